@@ -1,11 +1,19 @@
 //! This program is used as a testing ground for on chain compute and unsized type behavior
 
 use star_frame::{
-    account_set::{modifiers::MaybeMut, CheckKey as _},
+    account_set::{
+        modifiers::{MaybeMut, MaybeMutRuntime},
+        AccountSetDecode, CanAddLamports, CheckKey as _,
+    },
     borsh::{BorshDeserialize, BorshSerialize},
     pinocchio::syscalls::sol_remaining_compute_units,
     prelude::*,
 };
+use star_frame_spl::token::{
+    cpi,
+    state::{MintAccount, TokenAccount},
+    Token, TokenInterface,
+};
 
 #[allow(unused)]
 fn remaining_compute() -> u64 {
@@ -23,6 +31,37 @@ pub struct AccountTest;
 #[derive(InstructionSet)]
 pub enum AccountTestInstructionSet {
     Run(Run),
+    InitBorsh(InitBorsh),
+    CheckAddress(CheckAddress),
+    ResizeBorsh(ResizeBorsh),
+    PeekAccount(PeekAccount),
+    CloseTo(CloseTo),
+    InitOrGrow(InitOrGrow),
+    InvokeTransfer(InvokeTransfer),
+    ReadForeign(ReadForeign),
+    GrowFromVault(GrowFromVault),
+    CheckGrowData(CheckGrowData),
+    CountRemaining(CountRemaining),
+    WriteIf(WriteIf),
+    CheckOwner(CheckOwner),
+    WithdrawRentVault(WithdrawRentVault),
+    VerifyForeignSeeds(VerifyForeignSeeds),
+    NormalizeTargets(NormalizeTargets),
+    CloseWithConfig(CloseWithConfig),
+    CpiTransfer(CpiTransfer),
+    CpiTransferChecked(CpiTransferChecked),
+    CpiMintTo(CpiMintTo),
+    CpiBurn(CpiBurn),
+    CpiApprove(CpiApprove),
+    CpiRevoke(CpiRevoke),
+    CheckInterface(CheckInterface),
+    MigrateCounter(MigrateCounter),
+    ReadCounter(ReadCounter),
+    ResetCounter(ResetCounter),
+    InitHashed(InitHashed),
+    AddHashed(AddHashed),
+    CountAsVault(CountAsVault),
+    DecodeBorsh(DecodeBorsh),
 }
 
 #[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
@@ -64,140 +103,2322 @@ impl RunAccountsInnerInner {
     }
 }
 
-#[unsized_type(program_account)]
-pub struct AccountData {
-    #[unsized_start]
-    list: List<ListInner>,
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct InitBorsh {
+    #[ix_args(validate)]
+    space: u64,
 }
 
-#[derive(ProgramAccount, BorshSerialize, BorshDeserialize, Debug, Default)]
+#[derive(AccountSet)]
+#[validate(arg = u64)]
+pub struct InitBorshAccounts {
+    #[validate(funder)]
+    pub funder: Mut<Signer>,
+    #[validate(arg = Create(WithSpace(arg as usize)))]
+    pub borsh_account: Init<Signer<BorshAccount<MyBorshAccount>>>,
+    pub system_program: Program<System>,
+}
+
+#[star_frame_instruction]
+fn InitBorsh(accounts: &mut InitBorshAccounts) -> Result<()> {
+    accounts.borsh_account.vec.extend([1, 2, 3]);
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
 #[borsh(crate = "star_frame::borsh")]
-pub struct MyBorshAccount {
-    vec: Vec<u8>,
+pub struct ResizeBorsh {
+    #[ix_args(run)]
+    len: u16,
 }
 
-#[zero_copy(pod)]
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, TypeToIdl)]
-struct ListInner {
-    id: u64,
-    key: Pubkey,
+#[derive(AccountSet)]
+pub struct ResizeBorshAccounts {
+    #[validate(funder)]
+    pub funder: Mut<Signer>,
+    #[cleanup(arg = NormalizeRentAfterRealloc(()))]
+    pub borsh_account: Mut<BorshAccount<MyBorshAccount>>,
+    pub system_program: Program<System>,
 }
 
 #[star_frame_instruction]
-fn Run(accounts: &mut RunAccounts<true>, arg: Run) -> Result<()> {
-    let mut data = accounts.account.data_mut()?;
-    let before = remaining_compute();
-    let mut list = data.list();
-    let after = remaining_compute();
-    msg!("compute units: {}", before - after - 100);
+fn ResizeBorsh(accounts: &mut ResizeBorshAccounts, len: u16) -> Result<()> {
+    accounts.borsh_account.vec.resize(len.into(), 5);
+    Ok(())
+}
 
-    accounts
-        .borsh_account
-        .set_inner(MyBorshAccount { vec: vec![1, 2, 3] })?;
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CheckAddress {
+    #[ix_args(validate)]
+    expected: Pubkey,
+}
 
-    accounts.borsh_account.vec.push(4);
+#[derive(AccountSet)]
+#[validate(arg = Pubkey)]
+pub struct CheckAddressAccounts {
+    #[validate(address = arg)]
+    pub expected: AccountInfo,
+    #[validate(address = System::ID)]
+    pub system: AccountInfo,
+}
 
-    list.insert(
-        0,
-        ListInner {
-            id: 1,
-            key: arg.key_to_find,
+#[star_frame_instruction]
+fn CheckAddress(_accounts: &mut CheckAddressAccounts) -> Result<()> {
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CheckOwner {
+    #[ix_args(validate)]
+    expected: Pubkey,
+}
+
+#[derive(AccountSet)]
+#[validate(arg = Pubkey)]
+pub struct CheckOwnerAccounts {
+    #[validate(owner = arg)]
+    pub owned: AccountInfo,
+    #[validate(owner = System::ID)]
+    pub system_owned: AccountInfo,
+}
+
+#[star_frame_instruction]
+fn CheckOwner(_accounts: &mut CheckOwnerAccounts) -> Result<()> {
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct PeekAccount;
+
+#[derive(AccountSet)]
+pub struct PeekAccountAccounts {
+    pub account: AccountInfo,
+}
+
+/// Returns 0 for an [`AccountData`] account and 1 for a [`MyBorshAccount`] account, from the discriminant alone.
+#[star_frame_instruction]
+fn PeekAccount(accounts: &mut PeekAccountAccounts) -> Result<u8> {
+    if Account::<AccountData>::has_discriminant(&accounts.account) {
+        let account = Account::<AccountData>::from_account_info_unchecked(accounts.account);
+        account.data()?;
+        return Ok(0);
+    }
+    ensure!(
+        BorshAccount::<MyBorshAccount>::has_discriminant(&accounts.account),
+        ProgramError::InvalidAccountData,
+        "Account {} is not an AccountData or MyBorshAccount",
+        accounts.account.pubkey()
+    );
+    Ok(1)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct DecodeBorsh;
+
+#[derive(AccountSet)]
+pub struct DecodeBorshAccounts {
+    pub account: AccountInfo,
+}
+
+/// Decodes `account` as a [`MyBorshAccount`] without validating it, returning the length of its `vec`.
+#[star_frame_instruction]
+fn DecodeBorsh(accounts: &mut DecodeBorshAccounts, ctx: &mut Context) -> Result<u32> {
+    let mut infos: &[AccountInfo] = &[accounts.account];
+    let account = BorshAccount::<MyBorshAccount>::decode_accounts(&mut infos, (), ctx)?;
+    Ok(account.vec.len() as u32)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CloseTo;
+
+#[derive(AccountSet)]
+pub struct CloseToAccounts {
+    pub account: Mut<Account<AccountData>>,
+    pub recipient: Mut<AccountInfo>,
+}
+
+#[star_frame_instruction]
+fn CloseTo(accounts: &mut CloseToAccounts) -> Result<()> {
+    accounts.account.close_account(&accounts.recipient)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct InitOrGrow;
+
+#[derive(AccountSet)]
+pub struct InitOrGrowAccounts {
+    #[validate(funder)]
+    pub funder: Mut<Signer>,
+    #[validate(arg = ReinitOrRealloc(()))]
+    pub account: Init<Signer<Account<GrowData>>>,
+    pub system_program: Program<System>,
+}
+
+#[star_frame_instruction]
+fn InitOrGrow(accounts: &mut InitOrGrowAccounts) -> Result<bool> {
+    Ok(accounts.account.needed_init())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct InvokeTransfer {
+    lamports: u64,
+    /// Builds the transfer with this program's ID instead of the system program's.
+    wrong_program: bool,
+}
+
+#[derive(AccountSet)]
+pub struct InvokeTransferAccounts {
+    pub funder: Mut<Signer>,
+    pub recipient: Mut<AccountInfo>,
+    pub system_program: Program<System>,
+}
+
+#[star_frame_instruction]
+fn InvokeTransfer(accounts: &mut InvokeTransferAccounts, arg: InvokeTransfer) -> Result<()> {
+    let mut instruction = System::instruction(
+        &star_frame::program::system::Transfer {
+            lamports: arg.lamports,
+        },
+        star_frame::program::system::TransferClientAccounts {
+            funder: *accounts.funder.pubkey(),
+            recipient: *accounts.recipient.pubkey(),
         },
     )?;
+    if arg.wrong_program {
+        instruction.program_id = AccountTest::ID;
+    }
+    accounts.system_program.invoke(
+        &instruction,
+        &[
+            *accounts.funder.account_info(),
+            *accounts.recipient.account_info(),
+        ],
+    )
+}
 
-    Ok(())
+#[derive(Debug, GetSeeds, Clone)]
+#[get_seeds(seed_const = b"rent_vault")]
+pub struct RentVaultSeeds;
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct GrowFromVault;
+
+#[derive(AccountSet)]
+pub struct GrowFromVaultAccounts {
+    /// Pays for the account if present, otherwise the `rent_vault` PDA pays
+    #[validate(funder)]
+    pub funder: Option<Mut<Signer<SystemAccount>>>,
+    #[validate(funder, arg = Seeds(RentVaultSeeds))]
+    pub rent_vault: Mut<Seeded<SystemAccount, RentVaultSeeds>>,
+    #[validate(arg = ReinitOrRealloc(()))]
+    pub account: Init<Signer<Account<GrowData>>>,
+    pub system_program: Program<System>,
 }
 
-#[cfg(test)]
-#[allow(unused)]
-mod tests {
-    use super::*;
-    use mollusk_svm::{program::keyed_account_for_system_program, result::Check, Mollusk};
-    use pretty_assertions::assert_eq;
-    use solana_account::Account as SolanaAccount;
-    use star_frame::client::{DeserializeAccount, SerializeAccount};
-    use std::{collections::HashMap, env};
+#[star_frame_instruction]
+fn GrowFromVault(accounts: &mut GrowFromVaultAccounts) -> Result<bool> {
+    Ok(accounts.account.needed_init())
+}
 
-    #[test]
-    fn test_ix() -> Result<()> {
-        if env::var("SBF_OUT_DIR").is_err() {
-            println!("SBF_OUT_DIR is not set, skipping test");
-            return Ok(());
-        }
-        let mut mollusk = Mollusk::new(&AccountTest::ID, "account_test");
-        mollusk_svm_programs_token::token::add_program(&mut mollusk);
-        mollusk_svm_programs_token::associated_token::add_program(&mut mollusk);
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct ReadForeign;
 
-        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+#[derive(AccountSet)]
+pub struct ReadForeignAccounts {
+    pub account: Account<ForeignData>,
+}
 
-        let account = Pubkey::new_unique();
-        let borsh_account = Pubkey::new_unique();
+#[star_frame_instruction]
+fn ReadForeign(accounts: &mut ReadForeignAccounts) -> Result<u64> {
+    Ok(accounts.account.data()?.value)
+}
 
-        let list = std::iter::repeat_with(|| ListInner {
-            id: 2,
-            key: Pubkey::new_unique(),
-        })
-        .take(10000)
-        .collect::<Vec<_>>();
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CheckGrowData {
+    min_value: u64,
+}
 
-        let account_data = AccountData::serialize_account(AccountDataOwned { list })?;
+#[derive(AccountSet)]
+pub struct CheckGrowDataAccounts {
+    pub account: AccountInfo,
+}
 
-        let funder = Pubkey::new_unique();
+/// Checks that `account` is a [`GrowData`] with at least `min_value`, without decoding it into a [`ValidatedAccount`].
+#[star_frame_instruction]
+fn CheckGrowData(accounts: &mut CheckGrowDataAccounts, arg: CheckGrowData) -> Result<()> {
+    ValidatedAccount::<GrowData>::validate_only(&accounts.account, MinValue(arg.min_value))
+}
 
-        let mut account_store: HashMap<Pubkey, SolanaAccount> = HashMap::from_iter([
-            (
-                account,
-                SolanaAccount {
-                    lamports: 0,
-                    data: account_data,
-                    owner: AccountTest::ID,
-                    executable: false,
-                    rent_epoch: 0,
-                },
-            ),
-            (borsh_account, SolanaAccount::default()),
-            (
-                funder,
-                SolanaAccount {
-                    lamports: LAMPORTS_PER_SOL * 10,
-                    data: vec![],
-                    owner: System::ID,
-                    executable: false,
-                    rent_epoch: 0,
-                },
-            ),
-        ]);
-        let mollusk = mollusk.with_context(account_store);
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CountRemaining;
 
-        let res = mollusk.process_and_validate_instruction(
-            &AccountTest::instruction(
-                &Run {
-                    key_to_find: Pubkey::new_unique(),
-                    id_to_find: 1,
-                },
-                RunClientAccounts {
-                    account,
-                    borsh_account,
-                    funder,
-                    system_program: None,
-                    inner: RunAccountsInnerClientAccounts {
-                        inner2: Pubkey::new_unique(),
-                    },
-                },
-            )?,
-            &[Check::success()],
-        );
+#[derive(AccountSet)]
+pub struct CountRemainingAccounts {
+    pub authority: Signer,
+    #[account_set(remaining)]
+    pub others: Vec<AccountInfo>,
+}
 
-        let borsh_account_data = MyBorshAccount::deserialize_account(
-            &mollusk
-                .account_store
-                .borrow()
-                .get(&borsh_account)
-                .unwrap()
-                .data,
-        )?;
-        assert_eq!(borsh_account_data.vec, vec![1, 2, 3, 4]);
+/// Returns how many accounts were passed after `authority`.
+#[star_frame_instruction]
+fn CountRemaining(accounts: &mut CountRemainingAccounts) -> Result<u8> {
+    Ok(accounts.others.len() as u8)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct WriteIf {
+    #[ix_args(decode)]
+    write: bool,
+}
+
+#[derive(AccountSet)]
+#[decode(arg = bool)]
+pub struct WriteIfAccounts {
+    #[decode(arg = arg)]
+    pub account: MaybeMutRuntime<AccountInfo>,
+}
+
+/// Returns whether `account` was decoded as writable.
+#[star_frame_instruction]
+fn WriteIf(accounts: &mut WriteIfAccounts) -> Result<bool> {
+    Ok(accounts.account.runtime_writable())
+}
 
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct WithdrawRentVault {
+    lamports: u64,
+    /// Invokes the transfer without the `rent_vault` seeds when false.
+    sign: bool,
+}
+
+#[derive(AccountSet)]
+pub struct WithdrawRentVaultAccounts {
+    #[validate(arg = Seeds(RentVaultSeeds))]
+    pub rent_vault: Mut<Seeded<SystemAccount, RentVaultSeeds>>,
+    pub recipient: Mut<SystemAccount>,
+    pub system_program: Program<System>,
+}
+
+/// Transfers `lamports` out of the `rent_vault` PDA, signing with the seeds it was validated with.
+#[star_frame_instruction]
+fn WithdrawRentVault(
+    accounts: &mut WithdrawRentVaultAccounts,
+    WithdrawRentVault { lamports, sign }: WithdrawRentVault,
+) -> Result<()> {
+    let cpi = System::cpi(
+        star_frame::program::system::Transfer { lamports },
+        star_frame::program::system::TransferCpiAccounts {
+            funder: *accounts.rent_vault.account_info(),
+            recipient: *accounts.recipient.account_info(),
+        },
+        None,
+    );
+    if sign {
+        cpi.invoke_signed_by(&[&accounts.rent_vault])
+    } else {
+        cpi.invoke()
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct VerifyForeignSeeds;
+
+#[derive(AccountSet)]
+pub struct VerifyForeignSeedsAccounts {
+    pub seed_program: AccountInfo,
+    #[validate(arg = SeedsFor { seeds: RentVaultSeeds, program: *self.seed_program.pubkey() })]
+    pub vault: SeededFor<SystemAccount, RentVaultSeeds>,
+}
+
+/// Re-checks the seeds of a PDA of another program after it was validated with [`SeedsFor`].
+#[star_frame_instruction]
+fn VerifyForeignSeeds(accounts: &mut VerifyForeignSeedsAccounts) -> Result<()> {
+    accounts.vault.verify_seeds(&RentVaultSeeds)
+}
+
+#[derive(Debug, GetSeeds, Clone)]
+#[get_seeds(seed_const = b"authority_vault")]
+pub struct AuthorityVaultSeeds {
+    pub authority: Pubkey,
+}
+
+/// A PDA of [`AuthorityVaultSeeds`] that signs for its `authority`.
+#[zero_copy(pod)]
+#[derive(Default, Debug, PartialEq, Eq, ProgramAccount)]
+#[program_account(seeds = AuthorityVaultSeeds)]
+pub struct AuthorityVault {
+    pub authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CountAsVault {
+    /// Invokes [`CountRemaining`] without the `vault` seeds when false.
+    sign: bool,
+}
+
+#[derive(AccountSet)]
+pub struct CountAsVaultAccounts {
+    pub authority: Signer,
+    #[validate(arg = Seeds(AuthorityVaultSeeds { authority: *self.authority.pubkey() }))]
+    pub vault: Mut<Seeded<Account<AuthorityVault>>>,
+    pub program: Program<AccountTest>,
+}
+
+/// Invokes [`CountRemaining`] on this program with the `vault` PDA as its `authority`, signing once with seeds
+/// rebuilt from [`Seeded::access_seeds`] and once with the seeds stored during validation.
+#[star_frame_instruction]
+fn CountAsVault(
+    accounts: &mut CountAsVaultAccounts,
+    CountAsVault { sign }: CountAsVault,
+) -> Result<()> {
+    let seeds = AuthorityVaultSeeds {
+        authority: *accounts.authority.pubkey(),
+    };
+    accounts.vault.verify_seeds(&seeds)?;
+    let cpi = AccountTest::cpi(
+        CountRemaining,
+        CountRemainingCpiAccounts {
+            authority: *accounts.vault.account_info(),
+            others: vec![*accounts.authority.account_info()],
+        },
+        None,
+    );
+    if !sign {
+        return cpi.invoke();
+    }
+    let seeds = SeedsWithBump {
+        seeds,
+        bump: accounts.vault.access_seeds().bump,
+    };
+    cpi.invoke_signed(&[&seeds.seeds_with_bump()])?;
+    cpi.invoke_signed_by(&[&accounts.vault])
+}
+
+/// The lamports the `exact` account of [`NormalizeTargets`] is normalized to.
+pub const EXACT_RENT: u64 = 2_000_000;
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct NormalizeTargets;
+
+#[derive(AccountSet)]
+pub struct NormalizeTargetsAccounts {
+    #[validate(funder)]
+    pub funder: Mut<Signer<SystemAccount>>,
+    #[cleanup(arg = NormalizeRent(RentTarget::Minimum))]
+    pub minimum: Mut<Account<GrowData>>,
+    #[cleanup(arg = NormalizeRent(RentTarget::WithBuffer(1_000)))]
+    pub with_buffer: Mut<Account<GrowData>>,
+    #[cleanup(arg = NormalizeRent(RentTarget::Exact(EXACT_RENT)))]
+    pub exact: Mut<Account<GrowData>>,
+    #[cleanup(arg = NormalizeRent(RentTarget::MinimumForSpace(100)))]
+    pub for_space: Mut<Account<GrowData>>,
+    pub system_program: Program<System>,
+}
+
+#[star_frame_instruction]
+fn NormalizeTargets(_accounts: &mut NormalizeTargetsAccounts) -> Result<()> {
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(cleanup)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CloseWithConfig {
+    /// Closes to `other_recipient` instead of the cached `recipient` when true.
+    override_recipient: bool,
+    emit_log: bool,
+}
+
+#[derive(AccountSet)]
+#[cleanup(arg = CloseWithConfig)]
+pub struct CloseWithConfigAccounts {
+    #[validate(recipient)]
+    pub recipient: Mut<SystemAccount>,
+    pub other_recipient: Mut<SystemAccount>,
+    #[cleanup(arg = CloseAccount(CloseConfig {
+        recipient: arg
+            .override_recipient
+            .then_some(&self.other_recipient as &dyn CanAddLamports),
+        emit_log: arg.emit_log,
+    }))]
+    pub account: Mut<Account<GrowData>>,
+}
+
+#[star_frame_instruction]
+fn CloseWithConfig(_accounts: &mut CloseWithConfigAccounts) -> Result<()> {
+    Ok(())
+}
+
+/// Accounts for the instructions calling each [`cpi`] token helper. `destination` is the delegate for
+/// [`cpi::approve`].
+#[derive(AccountSet)]
+pub struct TokenCpiAccounts {
+    pub authority: Signer,
+    pub mint: Mut<MintAccount>,
+    pub source: Mut<TokenAccount>,
+    pub destination: Mut<TokenAccount>,
+    pub token_program: Program<Token>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CpiTransfer {
+    amount: u64,
+}
+
+#[star_frame_instruction]
+fn CpiTransfer(accounts: &mut TokenCpiAccounts, arg: CpiTransfer) -> Result<()> {
+    cpi::transfer(
+        &accounts.source,
+        &accounts.destination,
+        &accounts.authority,
+        arg.amount,
+    )?
+    .invoke()
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CpiTransferChecked {
+    amount: u64,
+    decimals: u8,
+}
+
+#[star_frame_instruction]
+fn CpiTransferChecked(accounts: &mut TokenCpiAccounts, arg: CpiTransferChecked) -> Result<()> {
+    cpi::transfer_checked(
+        &accounts.source,
+        &accounts.mint,
+        &accounts.destination,
+        &accounts.authority,
+        arg.amount,
+        arg.decimals,
+    )?
+    .invoke()
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CpiMintTo {
+    amount: u64,
+}
+
+#[star_frame_instruction]
+fn CpiMintTo(accounts: &mut TokenCpiAccounts, arg: CpiMintTo) -> Result<()> {
+    cpi::mint_to(
+        &accounts.mint,
+        &accounts.destination,
+        &accounts.authority,
+        arg.amount,
+    )?
+    .invoke()
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CpiBurn {
+    amount: u64,
+}
+
+#[star_frame_instruction]
+fn CpiBurn(accounts: &mut TokenCpiAccounts, arg: CpiBurn) -> Result<()> {
+    cpi::burn(
+        &accounts.source,
+        &accounts.mint,
+        &accounts.authority,
+        arg.amount,
+    )?
+    .invoke()
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CpiApprove {
+    amount: u64,
+}
+
+#[star_frame_instruction]
+fn CpiApprove(accounts: &mut TokenCpiAccounts, arg: CpiApprove) -> Result<()> {
+    cpi::approve(
+        &accounts.source,
+        &accounts.destination,
+        &accounts.authority,
+        arg.amount,
+    )?
+    .invoke()
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CpiRevoke;
+
+#[star_frame_instruction]
+fn CpiRevoke(accounts: &mut TokenCpiAccounts) -> Result<()> {
+    cpi::revoke(&accounts.source, &accounts.authority)?.invoke()
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CheckInterface {
+    expected: Pubkey,
+}
+
+#[derive(AccountSet)]
+pub struct CheckInterfaceAccounts {
+    pub program: Interface<TokenInterface>,
+}
+
+#[star_frame_instruction]
+fn CheckInterface(accounts: &mut CheckInterfaceAccounts, arg: CheckInterface) -> Result<()> {
+    ensure_eq!(
+        *accounts.program.program_id(),
+        arg.expected,
+        ProgramError::InvalidArgument
+    );
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct MigrateCounter;
+
+#[derive(AccountSet)]
+pub struct MigrateCounterAccounts {
+    #[validate(funder)]
+    pub funder: Mut<Signer<SystemAccount>>,
+    #[cleanup(arg = NormalizeRent(()))]
+    pub counter: Mut<Account<VersionedCounter>>,
+    pub system_program: Program<System>,
+}
+
+/// Migrates a [`VersionedCounterV1`] to a [`VersionedCounter`] during validation, funding the larger account.
+#[star_frame_instruction]
+fn MigrateCounter(_accounts: &mut MigrateCounterAccounts) -> Result<()> {
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct ReadCounter;
+
+#[derive(AccountSet)]
+pub struct ReadCounterAccounts {
+    pub counter: Account<VersionedCounter>,
+}
+
+/// Returns the count of a [`VersionedCounter`], which can't be migrated without being writable.
+#[star_frame_instruction]
+fn ReadCounter(accounts: &mut ReadCounterAccounts) -> Result<u64> {
+    Ok(accounts.counter.data()?.count)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct ResetCounter;
+
+#[derive(AccountSet)]
+pub struct ResetCounterAccounts {
+    #[validate(funder)]
+    pub funder: Mut<Signer<SystemAccount>>,
+    #[cleanup(arg = CloseAndReinit(()))]
+    pub counter: Mut<Account<VersionedCounter>>,
+    pub system_program: Program<System>,
+}
+
+#[star_frame_instruction]
+fn ResetCounter(_accounts: &mut ResetCounterAccounts) -> Result<()> {
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct InitHashed;
+
+#[derive(AccountSet)]
+pub struct InitHashedAccounts {
+    #[validate(funder)]
+    pub funder: Mut<Signer>,
+    #[validate(arg = Create(()))]
+    pub vault: Init<Signer<HashedAccount<HashedVault>>>,
+    pub system_program: Program<System>,
+}
+
+#[star_frame_instruction]
+fn InitHashed(_accounts: &mut InitHashedAccounts) -> Result<()> {
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, InstructionArgs, Copy, Clone)]
+#[ix_args(run)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct AddHashed {
+    amount: u64,
+}
+
+#[derive(AccountSet)]
+pub struct AddHashedAccounts {
+    pub vault: Mut<HashedAccount<HashedVault>>,
+}
+
+/// Adds to the balance through [`HashedAccount::data_mut`], then returns the balance read back through the hash check.
+#[star_frame_instruction]
+fn AddHashed(accounts: &mut AddHashedAccounts, arg: AddHashed) -> Result<u64> {
+    accounts.vault.data_mut()?.balance += arg.amount;
+    Ok(accounts.vault.data()?.balance)
+}
+
+/// A balance stored in a [`HashedAccount`].
+#[zero_copy(pod)]
+#[derive(Default, Debug, PartialEq, Eq, ProgramAccount)]
+pub struct HashedVault {
+    balance: u64,
+}
+
+/// The first version of [`VersionedCounter`].
+#[zero_copy(pod)]
+#[derive(Default, Debug, PartialEq, Eq, ProgramAccount)]
+#[program_account(version = 1)]
+pub struct VersionedCounterV1 {
+    count: u32,
+}
+
+/// A counter that is migrated from [`VersionedCounterV1`] when it is validated.
+#[zero_copy(pod)]
+#[derive(Default, Debug, PartialEq, Eq, ProgramAccount)]
+#[program_account(version = 2, migrate_from = VersionedCounterV1)]
+pub struct VersionedCounter {
+    count: u64,
+    /// The version the counter was migrated from, or 0 if it was created as this version.
+    migrated_from: u8,
+}
+
+impl MigrateFrom<VersionedCounterV1> for VersionedCounter {
+    fn migrate_from(previous: &VersionedCounterV1) -> Result<Self> {
+        Ok(Self {
+            count: previous.count.into(),
+            migrated_from: VersionedCounterV1::VERSION,
+        })
+    }
+}
+
+#[unsized_type(program_account)]
+pub struct AccountData {
+    #[unsized_start]
+    list: List<ListInner>,
+}
+
+#[derive(ProgramAccount, BorshSerialize, BorshDeserialize, Debug, Default)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct MyBorshAccount {
+    vec: Vec<u8>,
+}
+
+/// A zero-copy account that gained `extra` in a later version.
+#[zero_copy(pod)]
+#[derive(Default, Debug, PartialEq, Eq, ProgramAccount)]
+pub struct GrowData {
+    value: u64,
+    extra: u64,
+}
+
+/// Validates that a [`GrowData`] account's `value` is at least the given amount.
+pub struct MinValue(pub u64);
+
+impl AccountValidate<MinValue> for GrowData {
+    fn validate_account(self_ref: &Self::Ptr, arg: MinValue) -> Result<()> {
+        ensure!(
+            self_ref.value >= arg.0,
+            ProgramError::InvalidAccountData,
+            "GrowData value {} is less than {}",
+            self_ref.value,
+            arg.0
+        );
+        Ok(())
+    }
+}
+
+/// A zero-copy account that can be read from accounts owned by any program.
+#[zero_copy(pod)]
+#[derive(Default, Debug, PartialEq, Eq, ProgramAccount)]
+#[program_account(any_program)]
+pub struct ForeignData {
+    value: u64,
+}
+
+#[zero_copy(pod)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, TypeToIdl)]
+struct ListInner {
+    id: u64,
+    key: Pubkey,
+}
+
+#[star_frame_instruction]
+fn Run(accounts: &mut RunAccounts<true>, arg: Run) -> Result<()> {
+    let mut data = accounts.account.data_mut()?;
+    let before = remaining_compute();
+    let mut list = data.list();
+    let after = remaining_compute();
+    msg!("compute units: {}", before - after - 100);
+
+    accounts
+        .borsh_account
+        .set_inner(MyBorshAccount { vec: vec![1, 2, 3] })?;
+
+    accounts.borsh_account.vec.push(4);
+
+    list.insert(
+        0,
+        ListInner {
+            id: 1,
+            key: arg.key_to_find,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(unused)]
+mod tests {
+    use super::*;
+    use mollusk_svm::{
+        program::keyed_account_for_system_program,
+        result::{Check, InstructionResult, ProgramResult},
+        Mollusk,
+    };
+    use pretty_assertions::assert_eq;
+    use solana_account::Account as SolanaAccount;
+    use star_frame::{
+        account_set::modifiers::OwnerProgramDiscriminant,
+        client::{DeserializeAccount, SerializeAccount},
+        errors::{ErrorCode, StarFrameError},
+        SolanaInstruction,
+    };
+    use std::{collections::HashMap, env};
+
+    #[cfg(feature = "idl")]
+    #[test]
+    fn borsh_account_idl() -> Result<()> {
+        use star_frame::star_frame_idl::ty::{IdlStructField, IdlTypeDef};
+
+        let idl = StarFrameDeclaredProgram::program_to_idl()?;
+        let account = idl
+            .accounts
+            .values()
+            .find(|account| account.type_id.source.ends_with("::MyBorshAccount"))
+            .expect("MyBorshAccount should be in the IDL");
+        assert_eq!(account.space, None);
+        let ty = idl
+            .get_type(&account.type_id.source)
+            .expect("MyBorshAccount type should be in the IDL");
+        let IdlTypeDef::Struct(fields) = &ty.type_def else {
+            panic!("Expected a struct, got {:?}", ty.type_def);
+        };
+        assert!(matches!(
+            fields.as_slice(),
+            [IdlStructField {
+                path: Some(path),
+                type_def: IdlTypeDef::List { len_ty, item_ty },
+                ..
+            }] if path == "vec" && **len_ty == IdlTypeDef::U32 && **item_ty == IdlTypeDef::U8
+        ));
+
+        let _codama_idl: ProgramNode = idl.try_into()?;
+        Ok(())
+    }
+
+    #[cfg(feature = "idl")]
+    #[test]
+    fn validate_address_idl() -> Result<()> {
+        use star_frame::star_frame_idl::account_set::IdlAccountSetDef;
+
+        let idl = StarFrameDeclaredProgram::program_to_idl()?;
+        let account_set = idl
+            .account_sets
+            .values()
+            .find(|set| set.info.source.ends_with("::CheckAddressAccounts"))
+            .expect("CheckAddressAccounts should be in the IDL");
+        let IdlAccountSetDef::Struct(fields) = &account_set.account_set_def else {
+            panic!("Expected a struct, got {:?}", account_set.account_set_def);
+        };
+        let address = |index: usize| match &fields[index].account_set_def {
+            IdlAccountSetDef::Single(single) => single.address,
+            other => panic!("Expected a single account, got {other:?}"),
+        };
+        // `arg` is only known at runtime, `System::ID` is static
+        assert_eq!(address(0), None);
+        assert_eq!(address(1), Some(System::ID));
+        Ok(())
+    }
+
+    #[cfg(feature = "idl")]
+    #[test]
+    fn validate_owner_idl() -> Result<()> {
+        use star_frame::star_frame_idl::account_set::IdlAccountSetDef;
+
+        let idl = StarFrameDeclaredProgram::program_to_idl()?;
+        let account_set = idl
+            .account_sets
+            .values()
+            .find(|set| set.info.source.ends_with("::CheckOwnerAccounts"))
+            .expect("CheckOwnerAccounts should be in the IDL");
+        let IdlAccountSetDef::Struct(fields) = &account_set.account_set_def else {
+            panic!("Expected a struct, got {:?}", account_set.account_set_def);
+        };
+        let owner = |index: usize| match &fields[index].account_set_def {
+            IdlAccountSetDef::Single(single) => single.owner,
+            other => panic!("Expected a single account, got {other:?}"),
+        };
+        // `arg` is only known at runtime, `System::ID` is static
+        assert_eq!(owner(0), None);
+        assert_eq!(owner(1), Some(System::ID));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_owner() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let owner = Pubkey::new_unique();
+        let owned = Pubkey::new_unique();
+        let system_owned = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (owned, SolanaAccount::new(1_000_000_000, 0, &owner)),
+            (
+                system_owned,
+                SolanaAccount::new(1_000_000_000, 0, &System::ID),
+            ),
+        ]));
+        let instruction = |expected, system_owned| {
+            AccountTest::instruction(
+                &CheckOwner { expected },
+                CheckOwnerClientAccounts {
+                    owned,
+                    system_owned,
+                },
+            )
+        };
+
+        mollusk.process_and_validate_instruction(
+            &instruction(owner, system_owned)?,
+            &[Check::success()],
+        );
+        // The instruction arg doesn't match the owner
+        let res = mollusk.process_instruction(&instruction(Pubkey::new_unique(), system_owned)?);
+        assert_error(&res, ErrorCode::OwnerMismatch);
+        // The static owner doesn't match
+        let res = mollusk.process_instruction(&instruction(owner, owned)?);
+        assert_error(&res, ErrorCode::OwnerMismatch);
+        Ok(())
+    }
+
+    fn assert_error(result: &InstructionResult, error: impl StarFrameError) {
+        let ProgramResult::Failure(program_error) = &result.program_result else {
+            panic!("Expected {error:?}, got {:?}", result.program_result);
+        };
+        assert_eq!(u64::from(program_error.clone()), u64::from(error.code()));
+    }
+
+    #[test]
+    fn test_check_address() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let expected = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (expected, SolanaAccount::default()),
+            keyed_account_for_system_program(),
+        ]));
+        let instruction = |expected_arg, system| {
+            AccountTest::instruction(
+                &CheckAddress {
+                    expected: expected_arg,
+                },
+                CheckAddressClientAccounts { expected, system },
+            )
+        };
+
+        mollusk.process_and_validate_instruction(
+            &instruction(expected, System::ID)?,
+            &[Check::success()],
+        );
+        // The instruction arg doesn't match the account
+        let res = mollusk.process_instruction(&instruction(Pubkey::new_unique(), System::ID)?);
+        assert!(res.program_result.is_err());
+        // The static address doesn't match the account
+        let res = mollusk.process_instruction(&instruction(expected, expected)?);
+        assert!(res.program_result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_peek_account() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let account_data = Pubkey::new_unique();
+        let borsh_account = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let program_account = |data| SolanaAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner: AccountTest::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let mut account_data_bytes = AccountData::discriminant_bytes();
+        // An empty list
+        account_data_bytes.extend(0u32.to_le_bytes());
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (account_data, program_account(account_data_bytes)),
+            (
+                borsh_account,
+                program_account(MyBorshAccount::serialize_account(
+                    &MyBorshAccount::default(),
+                )?),
+            ),
+            (other, program_account(vec![0; 16])),
+        ]));
+
+        let peek = |account| -> Result<Option<u8>> {
+            let result = mollusk.process_instruction(&AccountTest::instruction(
+                &PeekAccount,
+                PeekAccountClientAccounts { account },
+            )?);
+            if result.program_result.is_err() {
+                return Ok(None);
+            }
+            Ok(Some(AccountTest::deserialize_return::<u8>(
+                &result.return_data,
+            )?))
+        };
+        assert_eq!(peek(account_data)?, Some(0));
+        assert_eq!(peek(borsh_account)?, Some(1));
+        assert_eq!(peek(other)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_close_to_self() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let account = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let mut account_data_bytes = AccountData::discriminant_bytes();
+        account_data_bytes.extend(0u32.to_le_bytes());
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (
+                account,
+                SolanaAccount {
+                    lamports: 1_000_000_000,
+                    data: account_data_bytes,
+                    owner: AccountTest::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+            (recipient, SolanaAccount::default()),
+        ]));
+        let instruction = |recipient| {
+            AccountTest::instruction(&CloseTo, CloseToClientAccounts { account, recipient })
+        };
+
+        // Closing an account to itself would burn its lamports
+        let res = mollusk.process_instruction(&instruction(account)?);
+        assert_error(&res, ErrorCode::CloseToSelf);
+        mollusk.process_and_validate_instruction(
+            &instruction(recipient)?,
+            &[
+                Check::success(),
+                Check::account(&recipient).lamports(1_000_000_000).build(),
+            ],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reinit_or_realloc() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let funder = Pubkey::new_unique();
+        let discriminant = GrowData::discriminant_bytes();
+        let space = discriminant.len() + size_of::<GrowData>();
+        let account_with = |data: Vec<u8>, owner| SolanaAccount {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        // The old layout only had `value`
+        let mut old_data = discriminant.clone();
+        old_data.extend(7u64.to_le_bytes());
+        let mut current_data = old_data.clone();
+        current_data.extend(9u64.to_le_bytes());
+        let mut grown_data = old_data.clone();
+        grown_data.extend([0; 8]);
+
+        let missing = Pubkey::new_unique();
+        let current = Pubkey::new_unique();
+        let old = Pubkey::new_unique();
+        let wrong_owner = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (
+                funder,
+                SolanaAccount {
+                    lamports: LAMPORTS_PER_SOL * 10,
+                    ..account_with(vec![], System::ID)
+                },
+            ),
+            (missing, SolanaAccount::default()),
+            (current, account_with(current_data.clone(), AccountTest::ID)),
+            (old, account_with(old_data, AccountTest::ID)),
+            (
+                wrong_owner,
+                account_with(current_data.clone(), Pubkey::new_unique()),
+            ),
+        ]));
+        let instruction = |account| {
+            AccountTest::instruction(
+                &InitOrGrow,
+                InitOrGrowClientAccounts {
+                    funder,
+                    account,
+                    system_program: None,
+                },
+            )
+        };
+        let needed_init = |account| -> Result<Option<bool>> {
+            let result = mollusk.process_instruction(&instruction(account)?);
+            if result.program_result.is_err() {
+                return Ok(None);
+            }
+            Ok(Some(AccountTest::deserialize_return::<bool>(
+                &result.return_data,
+            )?))
+        };
+
+        // Missing accounts are created
+        assert_eq!(needed_init(missing)?, Some(true));
+        let account_store = mollusk.account_store.borrow();
+        let created = account_store.get(&missing).unwrap();
+        assert_eq!(created.owner, AccountTest::ID);
+        assert_eq!(created.data.len(), space);
+        drop(account_store);
+
+        // Accounts with the current size are left as is
+        assert_eq!(needed_init(current)?, Some(false));
+        assert_eq!(
+            mollusk.account_store.borrow().get(&current).unwrap().data,
+            current_data
+        );
+
+        // Smaller accounts are grown, keeping their data
+        assert_eq!(needed_init(old)?, Some(false));
+        assert_eq!(
+            mollusk.account_store.borrow().get(&old).unwrap().data,
+            grown_data
+        );
+
+        // Accounts owned by another program are rejected
+        assert_eq!(needed_init(wrong_owner)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ix() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let mut mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        mollusk_svm_programs_token::token::add_program(&mut mollusk);
+        mollusk_svm_programs_token::associated_token::add_program(&mut mollusk);
+
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+        let account = Pubkey::new_unique();
+        let borsh_account = Pubkey::new_unique();
+
+        let list = std::iter::repeat_with(|| ListInner {
+            id: 2,
+            key: Pubkey::new_unique(),
+        })
+        .take(10000)
+        .collect::<Vec<_>>();
+
+        let account_data = AccountData::serialize_account(AccountDataOwned { list })?;
+
+        let funder = Pubkey::new_unique();
+
+        let mut account_store: HashMap<Pubkey, SolanaAccount> = HashMap::from_iter([
+            (
+                account,
+                SolanaAccount {
+                    lamports: 0,
+                    data: account_data,
+                    owner: AccountTest::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+            (borsh_account, SolanaAccount::default()),
+            (
+                funder,
+                SolanaAccount {
+                    lamports: LAMPORTS_PER_SOL * 10,
+                    data: vec![],
+                    owner: System::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+        ]);
+        let mollusk = mollusk.with_context(account_store);
+
+        let res = mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &Run {
+                    key_to_find: Pubkey::new_unique(),
+                    id_to_find: 1,
+                },
+                RunClientAccounts {
+                    account,
+                    borsh_account,
+                    funder,
+                    system_program: None,
+                    inner: RunAccountsInnerClientAccounts {
+                        inner2: Pubkey::new_unique(),
+                    },
+                },
+            )?,
+            &[Check::success()],
+        );
+
+        let borsh_account_data = MyBorshAccount::deserialize_account(
+            &mollusk
+                .account_store
+                .borrow()
+                .get(&borsh_account)
+                .unwrap()
+                .data,
+        )?;
+        assert_eq!(borsh_account_data.vec, vec![1, 2, 3, 4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resize_borsh() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        const SPACE: u64 = 100;
+
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let rent = mollusk.sysvars.rent.clone();
+        let borsh_account = Pubkey::new_unique();
+        let funder = Pubkey::new_unique();
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (borsh_account, SolanaAccount::default()),
+            (
+                funder,
+                SolanaAccount {
+                    lamports: LAMPORTS_PER_SOL * 10,
+                    data: vec![],
+                    owner: System::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+        ]));
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &InitBorsh { space: SPACE },
+                InitBorshClientAccounts {
+                    funder,
+                    borsh_account,
+                    system_program: None,
+                },
+            )?,
+            &[Check::success()],
+        );
+
+        let resize = |len: u16| {
+            mollusk.process_and_validate_instruction(
+                &AccountTest::instruction(
+                    &ResizeBorsh { len },
+                    ResizeBorshClientAccounts {
+                        funder,
+                        borsh_account,
+                        system_program: None,
+                    },
+                )
+                .unwrap(),
+                &[Check::success()],
+            );
+            let account_store = mollusk.account_store.borrow();
+            let account = account_store.get(&borsh_account).unwrap();
+            let data = MyBorshAccount::deserialize_account(&account.data).unwrap();
+            (account.data.len(), account.lamports, data.vec)
+        };
+        let expected_len =
+            |len: usize| size_of::<OwnerProgramDiscriminant<MyBorshAccount>>() + 4 + len;
+
+        // Growing past the initial space funds the extra rent
+        let (data_len, lamports, vec) = resize(200);
+        assert_eq!(data_len, expected_len(200));
+        assert_eq!(lamports, rent.minimum_balance(data_len));
+        assert_eq!(&vec[..3], &[1, 2, 3]);
+        assert!(vec[3..].iter().all(|&v| v == 5));
+
+        // Shrinking below the initial space refunds the excess rent
+        let funder_lamports = mollusk.account_store.borrow()[&funder].lamports;
+        let (data_len, lamports, vec) = resize(2);
+        assert_eq!(data_len, expected_len(2));
+        assert_eq!(lamports, rent.minimum_balance(data_len));
+        assert_eq!(vec, vec![1, 2]);
+        assert_eq!(
+            mollusk.account_store.borrow()[&funder].lamports,
+            funder_lamports + rent.minimum_balance(expected_len(200)) - lamports
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_init_borsh_with_space() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        const SPACE: u64 = 100;
+
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let borsh_account = Pubkey::new_unique();
+        let funder = Pubkey::new_unique();
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (borsh_account, SolanaAccount::default()),
+            (
+                funder,
+                SolanaAccount {
+                    lamports: LAMPORTS_PER_SOL * 10,
+                    data: vec![],
+                    owner: System::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+        ]));
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &InitBorsh { space: SPACE },
+                InitBorshClientAccounts {
+                    funder,
+                    borsh_account,
+                    system_program: None,
+                },
+            )?,
+            &[Check::success()],
+        );
+
+        let account_store = mollusk.account_store.borrow();
+        let data = &account_store.get(&borsh_account).unwrap().data;
+        assert_eq!(
+            data.len(),
+            size_of::<OwnerProgramDiscriminant<MyBorshAccount>>() + SPACE as usize
+        );
+        let borsh_account_data = MyBorshAccount::deserialize_account(data)?;
+        assert_eq!(borsh_account_data.vec, vec![1, 2, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_invoke() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let funder = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (funder, SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID)),
+            (
+                recipient,
+                SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID),
+            ),
+            keyed_account_for_system_program(),
+        ]));
+        let instruction = |wrong_program| {
+            AccountTest::instruction(
+                &InvokeTransfer {
+                    lamports: 100,
+                    wrong_program,
+                },
+                InvokeTransferClientAccounts {
+                    funder,
+                    recipient,
+                    system_program: None,
+                },
+            )
+        };
+
+        // The instruction's program must match the `Program` it is invoked through
+        let res = mollusk.process_instruction(&instruction(true)?);
+        assert!(res.program_result.is_err());
+
+        mollusk.process_and_validate_instruction(
+            &instruction(false)?,
+            &[
+                Check::success(),
+                Check::account(&funder)
+                    .lamports(LAMPORTS_PER_SOL - 100)
+                    .build(),
+                Check::account(&recipient)
+                    .lamports(LAMPORTS_PER_SOL + 100)
+                    .build(),
+            ],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_any_program_account() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let foreign_account = |data| SolanaAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        let mut foreign_data = ForeignData::discriminant_bytes();
+        foreign_data.extend(5u64.to_le_bytes());
+        let foreign = Pubkey::new_unique();
+        let wrong_discriminant = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (foreign, foreign_account(foreign_data)),
+            (wrong_discriminant, foreign_account(vec![0; 16])),
+        ]));
+
+        let read = |account| -> Result<Option<u64>> {
+            let result = mollusk.process_instruction(&AccountTest::instruction(
+                &ReadForeign,
+                ReadForeignClientAccounts { account },
+            )?);
+            if result.program_result.is_err() {
+                return Ok(None);
+            }
+            Ok(Some(AccountTest::deserialize_return::<u64>(
+                &result.return_data,
+            )?))
+        };
+        // The owner isn't checked, but the discriminant still is
+        assert_eq!(read(foreign)?, Some(5));
+        assert_eq!(read(wrong_discriminant)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_optional_funder() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let rent = mollusk.sysvars.rent.clone();
+        let funder = Pubkey::new_unique();
+        let (rent_vault, _) = Pubkey::find_program_address(&[b"rent_vault"], &AccountTest::ID);
+        // The old layout only had `value`
+        let mut old_data = GrowData::discriminant_bytes();
+        old_data.extend(7u64.to_le_bytes());
+        let old_account = || SolanaAccount {
+            lamports: rent.minimum_balance(old_data.len()),
+            data: old_data.clone(),
+            owner: AccountTest::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let grow_rent = rent.minimum_balance(old_data.len() + size_of::<u64>())
+            - rent.minimum_balance(old_data.len());
+
+        let vault_paid = Pubkey::new_unique();
+        let funder_paid = Pubkey::new_unique();
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (funder, SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID)),
+            (
+                rent_vault,
+                SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID),
+            ),
+            (vault_paid, old_account()),
+            (funder_paid, old_account()),
+            keyed_account_for_system_program(),
+        ]));
+        let instruction = |funder, account| {
+            AccountTest::instruction(
+                &GrowFromVault,
+                GrowFromVaultClientAccounts {
+                    funder,
+                    rent_vault,
+                    account,
+                    system_program: None,
+                },
+            )
+        };
+
+        // Without a funder, the PDA pays with its seeds
+        mollusk.process_and_validate_instruction(
+            &instruction(None, vault_paid)?,
+            &[
+                Check::success(),
+                Check::account(&rent_vault)
+                    .lamports(LAMPORTS_PER_SOL - grow_rent)
+                    .build(),
+                Check::account(&vault_paid)
+                    .space(old_data.len() + size_of::<u64>())
+                    .build(),
+            ],
+        );
+
+        // A provided funder takes precedence
+        mollusk.process_and_validate_instruction(
+            &instruction(Some(funder), funder_paid)?,
+            &[
+                Check::success(),
+                Check::account(&funder)
+                    .lamports(LAMPORTS_PER_SOL - grow_rent)
+                    .build(),
+                Check::account(&rent_vault)
+                    .lamports(LAMPORTS_PER_SOL - grow_rent)
+                    .build(),
+            ],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invoke_signed_by() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let (rent_vault, _) = Pubkey::find_program_address(&[b"rent_vault"], &AccountTest::ID);
+        let recipient = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (
+                rent_vault,
+                SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID),
+            ),
+            (
+                recipient,
+                SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID),
+            ),
+            keyed_account_for_system_program(),
+        ]));
+        let instruction = |sign| {
+            AccountTest::instruction(
+                &WithdrawRentVault {
+                    lamports: 100,
+                    sign,
+                },
+                WithdrawRentVaultClientAccounts {
+                    rent_vault,
+                    recipient,
+                    system_program: None,
+                },
+            )
+        };
+
+        // The system program requires the PDA's signature
+        let res = mollusk.process_instruction(&instruction(false)?);
+        assert!(res.program_result.is_err());
+
+        mollusk.process_and_validate_instruction(
+            &instruction(true)?,
+            &[
+                Check::success(),
+                Check::account(&rent_vault)
+                    .lamports(LAMPORTS_PER_SOL - 100)
+                    .build(),
+                Check::account(&recipient)
+                    .lamports(LAMPORTS_PER_SOL + 100)
+                    .build(),
+            ],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_for() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let seed_program = Pubkey::new_unique();
+        let (vault, _) = Pubkey::find_program_address(&[b"rent_vault"], &seed_program);
+        let (own_vault, _) = Pubkey::find_program_address(&[b"rent_vault"], &AccountTest::ID);
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (seed_program, SolanaAccount::default()),
+            (vault, SolanaAccount::new(1_000_000_000, 0, &System::ID)),
+            (own_vault, SolanaAccount::new(1_000_000_000, 0, &System::ID)),
+        ]));
+        let instruction = |vault| {
+            AccountTest::instruction(
+                &VerifyForeignSeeds,
+                VerifyForeignSeedsClientAccounts {
+                    seed_program,
+                    vault,
+                },
+            )
+        };
+
+        mollusk.process_and_validate_instruction(&instruction(vault)?, &[Check::success()]);
+
+        // A PDA of the executing program doesn't match the runtime seed program
+        let res = mollusk.process_instruction(&instruction(own_vault)?);
+        assert_error(&res, ErrorCode::AddressMismatch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_rent_targets() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let rent = mollusk.sysvars.rent.clone();
+        let mut data = GrowData::discriminant_bytes();
+        data.extend(1u64.to_le_bytes());
+        data.extend(2u64.to_le_bytes());
+        let minimum_rent = rent.minimum_balance(data.len());
+        let account = |lamports| SolanaAccount {
+            lamports,
+            data: data.clone(),
+            owner: AccountTest::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let [funder, minimum, with_buffer, exact, for_space] =
+            [(); 5].map(|()| Pubkey::new_unique());
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (funder, SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID)),
+            (minimum, account(LAMPORTS_PER_SOL)),
+            (with_buffer, account(LAMPORTS_PER_SOL)),
+            (exact, account(LAMPORTS_PER_SOL)),
+            // Starts at the minimum for its current size, so the funder pays to reach the larger target
+            (for_space, account(minimum_rent)),
+            keyed_account_for_system_program(),
+        ]));
+        let refunded = 3 * LAMPORTS_PER_SOL - 2 * minimum_rent - 1_000 - EXACT_RENT;
+        let funded = rent.minimum_balance(100) - minimum_rent;
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &NormalizeTargets,
+                NormalizeTargetsClientAccounts {
+                    funder,
+                    minimum,
+                    with_buffer,
+                    exact,
+                    for_space,
+                    system_program: None,
+                },
+            )?,
+            &[
+                Check::success(),
+                Check::account(&minimum).lamports(minimum_rent).build(),
+                Check::account(&with_buffer)
+                    .lamports(minimum_rent + 1_000)
+                    .build(),
+                Check::account(&exact).lamports(EXACT_RENT).build(),
+                Check::account(&for_space)
+                    .lamports(rent.minimum_balance(100))
+                    .build(),
+                Check::account(&funder)
+                    .lamports(LAMPORTS_PER_SOL + refunded - funded)
+                    .build(),
+            ],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_close_with_config() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let mut data = GrowData::discriminant_bytes();
+        data.extend(1u64.to_le_bytes());
+        data.extend(2u64.to_le_bytes());
+        let account = || SolanaAccount {
+            lamports: LAMPORTS_PER_SOL,
+            data: data.clone(),
+            owner: AccountTest::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let [recipient, other_recipient, cached_closed, overridden_closed] =
+            [(); 4].map(|()| Pubkey::new_unique());
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (recipient, SolanaAccount::default()),
+            (other_recipient, SolanaAccount::default()),
+            (cached_closed, account()),
+            (overridden_closed, account()),
+        ]));
+        let instruction = |account, override_recipient, emit_log| {
+            AccountTest::instruction(
+                &CloseWithConfig {
+                    override_recipient,
+                    emit_log,
+                },
+                CloseWithConfigClientAccounts {
+                    recipient,
+                    other_recipient,
+                    account,
+                },
+            )
+        };
+        let closed_data = vec![u8::MAX; GrowData::discriminant_bytes().len()];
+
+        // Without an override, the lamports go to the cached `recipient`
+        mollusk.process_and_validate_instruction(
+            &instruction(cached_closed, false, false)?,
+            &[
+                Check::success(),
+                Check::account(&cached_closed)
+                    .lamports(0)
+                    .data(&closed_data)
+                    .build(),
+                Check::account(&recipient)
+                    .lamports(LAMPORTS_PER_SOL)
+                    .build(),
+            ],
+        );
+
+        mollusk.process_and_validate_instruction(
+            &instruction(overridden_closed, true, true)?,
+            &[
+                Check::success(),
+                Check::account(&overridden_closed)
+                    .lamports(0)
+                    .data(&closed_data)
+                    .build(),
+                Check::account(&recipient)
+                    .lamports(LAMPORTS_PER_SOL)
+                    .build(),
+                Check::account(&other_recipient)
+                    .lamports(LAMPORTS_PER_SOL)
+                    .build(),
+            ],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_versioned_migration() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let rent = mollusk.sysvars.rent.clone();
+        let mut v1_data = VersionedCounterV1::discriminant_bytes();
+        v1_data.extend(4u32.to_le_bytes());
+        let v1_account = || SolanaAccount {
+            lamports: rent.minimum_balance(v1_data.len()),
+            data: v1_data.clone(),
+            owner: AccountTest::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let [funder, counter, read_only] = [(); 3].map(|()| Pubkey::new_unique());
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (funder, SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID)),
+            (counter, v1_account()),
+            (read_only, v1_account()),
+            keyed_account_for_system_program(),
+        ]));
+
+        // Migrating needs a writable account
+        let res = mollusk.process_instruction(&AccountTest::instruction(
+            &ReadCounter,
+            ReadCounterClientAccounts { counter: read_only },
+        )?);
+        assert_error(&res, ErrorCode::ExpectedWritable);
+
+        let mut migrated = VersionedCounter::discriminant_bytes();
+        migrated.extend(4u64.to_le_bytes());
+        migrated.push(VersionedCounterV1::VERSION);
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &MigrateCounter,
+                MigrateCounterClientAccounts {
+                    funder,
+                    counter,
+                    system_program: None,
+                },
+            )?,
+            &[
+                Check::success(),
+                Check::account(&counter)
+                    .data(&migrated)
+                    .lamports(rent.minimum_balance(migrated.len()))
+                    .build(),
+            ],
+        );
+
+        // Accounts at the current version are read without being migrated
+        let result = mollusk.process_instruction(&AccountTest::instruction(
+            &ReadCounter,
+            ReadCounterClientAccounts { counter },
+        )?);
+        assert!(result.program_result.is_ok());
+        assert_eq!(
+            AccountTest::deserialize_return::<u64>(&result.return_data)?,
+            4
+        );
+
+        let mut reset = VersionedCounter::discriminant_bytes();
+        reset.resize(migrated.len(), 0);
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &ResetCounter,
+                ResetCounterClientAccounts {
+                    funder,
+                    counter,
+                    system_program: None,
+                },
+            )?,
+            &[
+                Check::success(),
+                Check::account(&counter)
+                    .data(&reset)
+                    .lamports(rent.minimum_balance(reset.len()))
+                    .build(),
+            ],
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_signed_cpi_as_seeded_account() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let authority = Pubkey::new_unique();
+        let (vault, _) = AuthorityVault::find_program_address(&AuthorityVaultSeeds { authority });
+        let mut vault_data = AuthorityVault::discriminant_bytes();
+        vault_data.extend(authority.as_ref());
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (
+                authority,
+                SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID),
+            ),
+            (
+                vault,
+                SolanaAccount {
+                    lamports: LAMPORTS_PER_SOL,
+                    data: vault_data,
+                    owner: AccountTest::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+        ]));
+        let instruction = |sign| {
+            AccountTest::instruction(
+                &CountAsVault { sign },
+                CountAsVaultClientAccounts {
+                    authority,
+                    vault,
+                    program: None,
+                },
+            )
+        };
+
+        // `CountRemaining` requires the PDA's signature
+        let res = mollusk.process_instruction(&instruction(false)?);
+        assert!(res.program_result.is_err());
+
+        // The inner `CountRemaining` sets the return data
+        let result = mollusk.process_instruction(&instruction(true)?);
+        assert!(result.program_result.is_ok());
+        assert_eq!(
+            AccountTest::deserialize_return::<u8>(&result.return_data)?,
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hashed_account() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let rent = mollusk.sysvars.rent.clone();
+        let [funder, vault] = [(); 2].map(|()| Pubkey::new_unique());
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (funder, SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID)),
+            (vault, SolanaAccount::default()),
+            keyed_account_for_system_program(),
+        ]));
+
+        let space = HashedAccount::<HashedVault>::SPACE;
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &InitHashed,
+                InitHashedClientAccounts {
+                    funder,
+                    vault,
+                    system_program: None,
+                },
+            )?,
+            &[
+                Check::success(),
+                Check::account(&vault)
+                    .owner(&AccountTest::ID)
+                    .space(space)
+                    .lamports(rent.minimum_balance(space))
+                    .build(),
+            ],
+        );
+
+        // Each add is validated against the hash written by the previous instruction
+        for (amount, expected) in [(5, 5), (7, 12)] {
+            let result = mollusk.process_instruction(&AccountTest::instruction(
+                &AddHashed { amount },
+                AddHashedClientAccounts { vault },
+            )?);
+            assert!(result.program_result.is_ok());
+            assert_eq!(
+                AccountTest::deserialize_return::<u64>(&result.return_data)?,
+                expected
+            );
+        }
+        let data = mollusk.account_store.borrow()[&vault].data.clone();
+        let mut expected = HashedVault::discriminant_bytes();
+        expected.extend(12u64.to_le_bytes());
+        assert_eq!(data[..expected.len()], expected);
+
+        // Changing the balance without updating the hash is rejected
+        mollusk
+            .account_store
+            .borrow_mut()
+            .get_mut(&vault)
+            .expect("vault exists")
+            .data[expected.len() - 1] = 1;
+        let res = mollusk.process_instruction(&AccountTest::instruction(
+            &AddHashed { amount: 1 },
+            AddHashedClientAccounts { vault },
+        )?);
+        assert_error(&res, ErrorCode::HashMismatch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_cpi_helpers() -> Result<()> {
+        use star_frame_spl::token::state::{MintAccountData, TokenAccountData};
+
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let mut mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        mollusk_svm_programs_token::token::add_program(&mut mollusk);
+        let [authority, mint, source, destination] = [(); 4].map(|()| Pubkey::new_unique());
+        let token_account = |data: &[u8]| SolanaAccount {
+            lamports: LAMPORTS_PER_SOL,
+            data: data.to_vec(),
+            owner: Token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let token_data = |amount| TokenAccountData::new(KeyFor::new(mint), authority, amount);
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (authority, SolanaAccount::default()),
+            (
+                mint,
+                token_account(bytemuck::bytes_of(&MintAccountData::new(
+                    1_000,
+                    2,
+                    Some(authority),
+                    None,
+                ))),
+            ),
+            (
+                source,
+                token_account(bytemuck::bytes_of(&token_data(1_000))),
+            ),
+            (
+                destination,
+                token_account(bytemuck::bytes_of(&token_data(0))),
+            ),
+            mollusk_svm_programs_token::token::keyed_account(),
+        ]));
+        let accounts = TokenCpiClientAccounts {
+            authority,
+            mint,
+            source,
+            destination,
+            token_program: None,
+        };
+        let token = |key| -> TokenAccountData {
+            *bytemuck::checked::from_bytes(&mollusk.account_store.borrow()[&key].data)
+        };
+        let supply = || -> u64 {
+            bytemuck::checked::from_bytes::<MintAccountData>(
+                &mollusk.account_store.borrow()[&mint].data,
+            )
+            .supply
+        };
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(&CpiTransfer { amount: 100 }, accounts.clone())?,
+            &[Check::success()],
+        );
+        assert_eq!(token(source).balance(), 900);
+        assert_eq!(token(destination).balance(), 100);
+
+        // The helper checks the decimals before invoking the token program
+        let res = mollusk.process_instruction(&AccountTest::instruction(
+            &CpiTransferChecked {
+                amount: 50,
+                decimals: 3,
+            },
+            accounts.clone(),
+        )?);
+        assert!(res.program_result.is_err());
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(
+                &CpiTransferChecked {
+                    amount: 50,
+                    decimals: 2,
+                },
+                accounts.clone(),
+            )?,
+            &[Check::success()],
+        );
+        assert_eq!(token(source).balance(), 850);
+        assert_eq!(token(destination).balance(), 150);
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(&CpiMintTo { amount: 25 }, accounts.clone())?,
+            &[Check::success()],
+        );
+        assert_eq!(token(destination).balance(), 175);
+        assert_eq!(supply(), 1_025);
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(&CpiBurn { amount: 10 }, accounts.clone())?,
+            &[Check::success()],
+        );
+        assert_eq!(token(source).balance(), 840);
+        assert_eq!(supply(), 1_015);
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(&CpiApprove { amount: 30 }, accounts.clone())?,
+            &[Check::success()],
+        );
+        assert_eq!(token(source).delegate(), Some(destination));
+        assert_eq!(token(source).delegated_amount(), Some(30));
+
+        mollusk.process_and_validate_instruction(
+            &AccountTest::instruction(&CpiRevoke, accounts)?,
+            &[Check::success()],
+        );
+        assert_eq!(token(source).delegate(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_interface() -> Result<()> {
+        use star_frame_spl::token_2022::Token2022;
+
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            mollusk_svm_programs_token::token::keyed_account(),
+            mollusk_svm_programs_token::token2022::keyed_account(),
+            keyed_account_for_system_program(),
+        ]));
+
+        // Each program in `VALID_PROGRAM_IDS` is accepted, and `program_id` returns the one provided
+        for program in [Token::ID, Token2022::ID] {
+            mollusk.process_and_validate_instruction(
+                &AccountTest::instruction(
+                    &CheckInterface { expected: program },
+                    CheckInterfaceClientAccounts { program },
+                )?,
+                &[Check::success()],
+            );
+        }
+        let res = mollusk.process_instruction(&AccountTest::instruction(
+            &CheckInterface {
+                expected: Token2022::ID,
+            },
+            CheckInterfaceClientAccounts { program: Token::ID },
+        )?);
+        assert!(res.program_result.is_err());
+
+        let res = mollusk.process_instruction(&AccountTest::instruction(
+            &CheckInterface {
+                expected: System::ID,
+            },
+            CheckInterfaceClientAccounts {
+                program: System::ID,
+            },
+        )?);
+        let ProgramResult::Failure(err) = res.program_result else {
+            panic!("Expected IncorrectProgramId, got {:?}", res.program_result);
+        };
+        assert_eq!(u64::from(err), u64::from(ProgramError::IncorrectProgramId));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_only() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let program_account = |data| SolanaAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner: AccountTest::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let mut grow_data = GrowData::discriminant_bytes();
+        grow_data.extend(5u64.to_le_bytes());
+        grow_data.extend(0u64.to_le_bytes());
+        let grow_account = Pubkey::new_unique();
+        let borsh_account = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (grow_account, program_account(grow_data)),
+            (
+                borsh_account,
+                program_account(MyBorshAccount::serialize_account(
+                    &MyBorshAccount::default(),
+                )?),
+            ),
+        ]));
+        let check = |account, min_value| -> Result<bool> {
+            let result = mollusk.process_instruction(&AccountTest::instruction(
+                &CheckGrowData { min_value },
+                CheckGrowDataClientAccounts { account },
+            )?);
+            Ok(!result.program_result.is_err())
+        };
+        assert!(check(grow_account, 5)?);
+        assert!(!check(grow_account, 6)?);
+        // Fails on the discriminant before reaching the custom validation
+        assert!(!check(borsh_account, 0)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remaining_accounts() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let authority = Pubkey::new_unique();
+        let others = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter(
+            [authority]
+                .into_iter()
+                .chain(others)
+                .map(|key| (key, SolanaAccount::default())),
+        ));
+        let count = |others: &[Pubkey]| -> Result<u8> {
+            let instruction = AccountTest::instruction(
+                &CountRemaining,
+                CountRemainingClientAccounts {
+                    authority,
+                    others: others.to_vec(),
+                },
+            )?;
+            assert_eq!(instruction.accounts.len(), 1 + others.len());
+            let result = mollusk.process_instruction(&instruction);
+            assert!(result.program_result.is_ok(), "{:?}", result.program_result);
+            Ok(AccountTest::deserialize_return::<u8>(&result.return_data)?)
+        };
+
+        assert_eq!(count(&[])?, 0);
+        assert_eq!(count(&others[..1])?, 1);
+        assert_eq!(count(&others)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_maybe_mut_runtime() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let account = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk =
+            mollusk.with_context(HashMap::from_iter([(account, SolanaAccount::default())]));
+        let instruction = |write| {
+            AccountTest::instruction(
+                &WriteIf { write },
+                WriteIfClientAccounts {
+                    account: (account, write),
+                },
+            )
+        };
+        let write_if = |instruction: &SolanaInstruction| -> Result<Option<bool>> {
+            let result = mollusk.process_instruction(instruction);
+            if result.program_result.is_err() {
+                return Ok(None);
+            }
+            Ok(Some(AccountTest::deserialize_return::<bool>(
+                &result.return_data,
+            )?))
+        };
+
+        // The client meta follows the runtime flag
+        let read = instruction(false)?;
+        assert!(!read.accounts[0].is_writable);
+        assert_eq!(write_if(&read)?, Some(false));
+        let write = instruction(true)?;
+        assert!(write.accounts[0].is_writable);
+        assert_eq!(write_if(&write)?, Some(true));
+
+        // A read-only account is rejected when decoded as writable
+        let mut read_only_write = write;
+        read_only_write.accounts[0].is_writable = false;
+        assert_eq!(write_if(&read_only_write)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_borsh_wrong_discriminant() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let funder = Pubkey::new_unique();
+        let borsh_account = Pubkey::new_unique();
+        let valid_account = Pubkey::new_unique();
+        let program_account = |data| SolanaAccount {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner: AccountTest::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        // Valid `MyBorshAccount` data behind the discriminant of a different account type
+        let mut data = AccountData::discriminant_bytes();
+        data.extend(0u32.to_le_bytes());
+        let valid_data = MyBorshAccount::serialize_account(&MyBorshAccount { vec: vec![1, 2] })?;
+
+        let mollusk = Mollusk::new(&AccountTest::ID, "account_test");
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (borsh_account, program_account(data)),
+            (valid_account, program_account(valid_data)),
+            (funder, SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID)),
+        ]));
+        let decode =
+            |account| AccountTest::instruction(&DecodeBorsh, DecodeBorshClientAccounts { account });
+
+        // Decoding alone checks the discriminant before deserializing
+        let res = mollusk.process_instruction(&decode(borsh_account)?);
+        assert_error(&res, ErrorCode::DiscriminantMismatch);
+        let res =
+            mollusk.process_and_validate_instruction(&decode(valid_account)?, &[Check::success()]);
+        assert_eq!(AccountTest::deserialize_return::<u32>(&res.return_data)?, 2);
+
+        let res = mollusk.process_instruction(&AccountTest::instruction(
+            &ResizeBorsh { len: 1 },
+            ResizeBorshClientAccounts {
+                funder,
+                borsh_account,
+                system_program: None,
+            },
+        )?);
+        assert_error(&res, ErrorCode::DiscriminantMismatch);
         Ok(())
     }
 }
@@ -1,5 +1,8 @@
 use star_frame::{empty_star_frame_instruction, prelude::*};
-use star_frame_spl::token::state::{MintAccount, TokenAccount};
+use star_frame_spl::token::{
+    state::{MintAccount, TokenAccount},
+    TokenInterface,
+};
 
 #[derive(StarFrameProgram)]
 #[program(
@@ -77,10 +80,12 @@ pub enum BenchInstructionSet {
     InterfaceAccountToken1(InterfaceAccountToken1),
     InterfaceAccountToken2(InterfaceAccountToken2),
     InterfaceAccountToken4(InterfaceAccountToken4),
-    // Interface1(Interface1),
-    // Interface2(Interface2),
-    // Interface4(Interface4),
-    // Interface8(Interface8),
+    Interface1(Interface1),
+    Interface2(Interface2),
+    Interface4(Interface4),
+    Interface8(Interface8),
+    ListPush100(ListPush100),
+    ListExtend100(ListExtend100),
     Program1(Program1),
     Program2(Program2),
     Program4(Program4),
@@ -1023,44 +1028,79 @@ pub struct InterfaceAccountToken4Accounts {
 }
 empty_star_frame_instruction!(InterfaceAccountToken4, InterfaceAccountToken4Accounts);
 
-/*
-#[derive(Accounts)]
-pub struct Interface1<'info> {
-    pub account1: Interface<'info, TokenInterface>,
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct Interface1;
+#[derive(AccountSet, Debug)]
+pub struct Interface1Accounts {
+    pub account1: Interface<TokenInterface>,
+}
+empty_star_frame_instruction!(Interface1, Interface1Accounts);
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct Interface2;
+#[derive(AccountSet, Debug)]
+pub struct Interface2Accounts {
+    pub account1: Interface<TokenInterface>,
+    pub account2: Interface<TokenInterface>,
+}
+empty_star_frame_instruction!(Interface2, Interface2Accounts);
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct Interface4;
+#[derive(AccountSet, Debug)]
+pub struct Interface4Accounts {
+    pub account1: Interface<TokenInterface>,
+    pub account2: Interface<TokenInterface>,
+    pub account3: Interface<TokenInterface>,
+    pub account4: Interface<TokenInterface>,
+}
+empty_star_frame_instruction!(Interface4, Interface4Accounts);
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct Interface8;
+#[derive(AccountSet, Debug)]
+pub struct Interface8Accounts {
+    pub account1: Interface<TokenInterface>,
+    pub account2: Interface<TokenInterface>,
+    pub account3: Interface<TokenInterface>,
+    pub account4: Interface<TokenInterface>,
+    pub account5: Interface<TokenInterface>,
+    pub account6: Interface<TokenInterface>,
+    pub account7: Interface<TokenInterface>,
+    pub account8: Interface<TokenInterface>,
 }
-*/
+empty_star_frame_instruction!(Interface8, Interface8Accounts);
 
-/*
-#[derive(Accounts)]
-pub struct Interface2<'info> {
-    pub account1: Interface<'info, TokenInterface>,
-    pub account2: Interface<'info, TokenInterface>,
+// List insertion: 100 single pushes vs one bulk extend
+#[derive(AccountSet, Debug)]
+pub struct ListAccounts {
+    #[validate(funder)]
+    pub payer: Mut<Signer<SystemAccount>>,
+    pub system_program: Program<System>,
+    #[cleanup(arg = NormalizeRent(()))]
+    pub account: Mut<Account<Unsized>>,
 }
-*/
 
-/*
-#[derive(Accounts)]
-pub struct Interface4<'info> {
-    pub account1: Interface<'info, TokenInterface>,
-    pub account2: Interface<'info, TokenInterface>,
-    pub account3: Interface<'info, TokenInterface>,
-    pub account4: Interface<'info, TokenInterface>,
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct ListPush100;
+
+#[star_frame_instruction]
+fn ListPush100(accounts: &mut ListAccounts) -> Result<()> {
+    let mut data = accounts.account.data_mut()?;
+    for item in 0..100 {
+        data.field().push(item)?;
+    }
+    Ok(())
 }
-*/
 
-/*
-#[derive(Accounts)]
-pub struct Interface8<'info> {
-    pub account1: Interface<'info, TokenInterface>,
-    pub account2: Interface<'info, TokenInterface>,
-    pub account3: Interface<'info, TokenInterface>,
-    pub account4: Interface<'info, TokenInterface>,
-    pub account5: Interface<'info, TokenInterface>,
-    pub account6: Interface<'info, TokenInterface>,
-    pub account7: Interface<'info, TokenInterface>,
-    pub account8: Interface<'info, TokenInterface>,
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct ListExtend100;
+
+#[star_frame_instruction]
+fn ListExtend100(accounts: &mut ListAccounts) -> Result<()> {
+    accounts.account.data_mut()?.field().extend(0..100)?;
+    Ok(())
 }
-*/
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
 pub struct Program1;
@@ -1233,3 +1273,75 @@ pub struct UncheckedAccount8Accounts {
     pub account8: AccountInfo,
 }
 empty_star_frame_instruction!(UncheckedAccount8, UncheckedAccount8Accounts);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mollusk_svm::{program::keyed_account_for_system_program, result::Check, Mollusk};
+    use pretty_assertions::assert_eq;
+    use solana_account::Account as SolanaAccount;
+    use star_frame::client::{DeserializeAccount, SerializeAccount};
+    use std::{collections::HashMap, env};
+
+    #[test]
+    fn test_list_extend_compute_units() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+        let mollusk = Mollusk::new(&Bench::ID, "bench");
+        let rent = mollusk.sysvars.rent.clone();
+        let payer = Pubkey::new_unique();
+        let [push_account, extend_account] = [(); 2].map(|()| Pubkey::new_unique());
+        let empty_list = Unsized::serialize_account(UnsizedOwned { field: vec![] })?;
+        let list_account = SolanaAccount {
+            lamports: rent.minimum_balance(empty_list.len()),
+            data: empty_list,
+            owner: Bench::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (
+                payer,
+                SolanaAccount {
+                    lamports: LAMPORTS_PER_SOL,
+                    data: vec![],
+                    owner: System::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+            (push_account, list_account.clone()),
+            (extend_account, list_account),
+            keyed_account_for_system_program(),
+        ]));
+        let accounts = |account| ListClientAccounts {
+            payer,
+            system_program: None,
+            account,
+        };
+
+        let push = mollusk.process_and_validate_instruction(
+            &Bench::instruction(&ListPush100, accounts(push_account))?,
+            &[Check::success()],
+        );
+        let extend = mollusk.process_and_validate_instruction(
+            &Bench::instruction(&ListExtend100, accounts(extend_account))?,
+            &[Check::success()],
+        );
+        let expected = (0..100).collect::<Vec<u8>>();
+        for account in [push_account, extend_account] {
+            let account = mollusk.account_store.borrow()[&account].clone();
+            assert_eq!(Unsized::deserialize_account(&account.data)?.field, expected);
+            assert!(account.lamports >= rent.minimum_balance(account.data.len()));
+        }
+        println!(
+            "ListPush100: {} CUs, ListExtend100: {} CUs",
+            push.compute_units_consumed, extend.compute_units_consumed
+        );
+        assert!(extend.compute_units_consumed < push.compute_units_consumed);
+        Ok(())
+    }
+}
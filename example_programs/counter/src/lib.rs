@@ -16,6 +16,7 @@ pub enum CounterInstructionSet {
     UpdateSigner(UpdateCounterSigner),
     Count(Count),
     CloseCounter(CloseCounter),
+    MultisigCount(MultisigCount),
 }
 
 #[zero_copy(pod)]
@@ -49,8 +50,10 @@ pub struct CounterAccountSeeds {
     pub owner: Pubkey,
 }
 
+/// Creates a counter for the owner
 #[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
 pub struct CreateCounter {
+    /// The initial count, zero if not set
     #[ix_args(run)]
     pub start_at: Option<u64>,
 }
@@ -66,6 +69,7 @@ pub struct CreateCounterAccounts {
     ))]
     #[idl(arg = Seeds(FindCounterAccountSeeds { owner: seed_path("owner") }))]
     pub counter: Init<Seeded<WrappedCounter>>,
+    #[account_set(client_default = System::ID)]
     pub system_program: Program<System>,
 }
 
@@ -109,10 +113,13 @@ fn UpdateCounterSigner(accounts: &mut UpdateCounterSignerAccounts) -> Result<()>
     Ok(())
 }
 
+/// Adds to or subtracts from the count
 #[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
 #[ix_args(run)]
 pub struct Count {
+    /// The amount to change the count by
     pub amount: u64,
+    /// Subtracts `amount` instead of adding it
     pub subtract: bool,
 }
 
@@ -145,6 +152,44 @@ fn Count(accounts: &mut CountAccounts, Count { amount, subtract }: Count) -> Res
     Ok(new_count)
 }
 
+/// Adds to the count with the approval of both the counter's owner and signer
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+pub struct MultisigCount {
+    /// The amount to add to the count
+    #[ix_args(run)]
+    pub amount: u64,
+}
+
+#[derive(AccountSet, Debug)]
+#[validate(extra_validation = self.validate())]
+pub struct MultisigCountAccounts {
+    /// The counter's owner and signer, in any order
+    pub signers: MultiSigner<SystemAccount, 2>,
+    pub counter: Mut<Account<CounterAccount>>,
+}
+
+impl MultisigCountAccounts {
+    fn validate(&self) -> Result<()> {
+        let counter = self.counter.data()?;
+        for required in [counter.owner, counter.signer] {
+            ensure!(
+                self.signers
+                    .iter()
+                    .any(|signer| *signer.pubkey() == required),
+                CounterErrors::IncorrectSigner
+            );
+        }
+        Ok(())
+    }
+}
+
+#[star_frame_instruction]
+fn MultisigCount(accounts: &mut MultisigCountAccounts, amount: u64) -> Result<u64> {
+    let mut counter = accounts.counter.data_mut()?;
+    counter.count += amount;
+    Ok(counter.count)
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
 pub struct CloseCounter;
 
@@ -178,11 +223,15 @@ mod tests {
 
     use super::*;
     use mollusk_svm::{
-        account_store::AccountStore, program::keyed_account_for_system_program, result::Check, *,
+        account_store::AccountStore,
+        program::keyed_account_for_system_program,
+        result::{Check, ProgramResult},
+        *,
     };
     use solana_account::Account as SolanaAccount;
     use star_frame::{
         client::{DeserializeAccount, SerializeAccount},
+        errors::{ErrorCode, StarFrameError},
         solana_instruction::Instruction,
     };
 
@@ -196,6 +245,103 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "idl")]
+    #[test]
+    fn idl_includes_docs() -> Result<()> {
+        let idl = StarFrameDeclaredProgram::program_to_idl()?;
+        let codama_idl: ProgramNode = idl.try_into()?;
+        let json: star_frame::serde_json::Value =
+            star_frame::serde_json::from_str(&codama_idl.to_json()?)?;
+        let instruction = |name: &str| {
+            json["instructions"]
+                .as_array()
+                .and_then(|ixs| ixs.iter().find(|ix| ix["name"] == name))
+                .unwrap_or_else(|| panic!("Instruction {name} should be in the IDL"))
+        };
+        let argument = |ix: &star_frame::serde_json::Value, name: &str| {
+            ix["arguments"]
+                .as_array()
+                .and_then(|args| args.iter().find(|arg| arg["name"] == name))
+                .unwrap_or_else(|| panic!("Argument {name} should be in the IDL"))
+                .clone()
+        };
+
+        let create = instruction("createCounter");
+        assert_eq!(create["docs"][0], "Creates a counter for the owner");
+        assert_eq!(
+            argument(create, "startAt")["docs"][0],
+            "The initial count, zero if not set"
+        );
+
+        let count = instruction("count");
+        assert_eq!(count["docs"][0], "Adds to or subtracts from the count");
+        assert_eq!(
+            argument(count, "subtract")["docs"][0],
+            "Subtracts `amount` instead of adding it"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "idl")]
+    #[test]
+    fn idl_includes_pda_defaults() -> Result<()> {
+        let idl = StarFrameDeclaredProgram::program_to_idl()?;
+        let codama_idl: ProgramNode = idl.try_into()?;
+        let json: star_frame::serde_json::Value =
+            star_frame::serde_json::from_str(&codama_idl.to_json()?)?;
+        let create = json["instructions"]
+            .as_array()
+            .and_then(|ixs| ixs.iter().find(|ix| ix["name"] == "createCounter"))
+            .expect("createCounter should be in the IDL");
+        let counter = create["accounts"]
+            .as_array()
+            .and_then(|accounts| accounts.iter().find(|account| account["name"] == "counter"))
+            .expect("counter should be in the createCounter accounts");
+
+        // The counter address is derived from `FindCounterAccountSeeds`
+        let default_value = &counter["defaultValue"];
+        assert_eq!(default_value["kind"], "pdaValueNode");
+        let pda_seeds = &default_value["pda"]["seeds"];
+        assert_eq!(pda_seeds[0]["kind"], "constantPdaSeedNode");
+        assert_eq!(pda_seeds[1]["kind"], "variablePdaSeedNode");
+        assert_eq!(pda_seeds[1]["type"]["kind"], "publicKeyTypeNode");
+        let owner_seed = &default_value["seeds"][0];
+        assert_eq!(owner_seed["name"], pda_seeds[1]["name"]);
+        assert_eq!(owner_seed["value"]["kind"], "accountValueNode");
+        assert_eq!(owner_seed["value"]["name"], "owner");
+        Ok(())
+    }
+
+    /// Fails if the current IDL has breaking changes compared to the committed
+    /// `idl_definition.json`. Run with `UPDATE_IDL=1` to overwrite the snapshot instead.
+    #[cfg(feature = "idl")]
+    #[test]
+    fn check_idl_compat() -> Result<()> {
+        use star_frame::star_frame_idl::{diff, IdlDefinition};
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/idl_definition.json");
+        let idl = StarFrameDeclaredProgram::program_to_idl()?;
+        if env::var("UPDATE_IDL").is_ok_and(|update| update == "1") {
+            std::fs::write(path, star_frame::serde_json::to_string_pretty(&idl)?)?;
+            return Ok(());
+        }
+        let Ok(committed) = std::fs::read_to_string(path) else {
+            panic!("`{path}` is missing, run with `UPDATE_IDL=1` to create it");
+        };
+        let committed: IdlDefinition = star_frame::serde_json::from_str(&committed)?;
+        let breaking = diff::diff(&committed, &idl)
+            .into_iter()
+            .filter(diff::IdlChange::is_breaking)
+            .map(|change| change.to_string())
+            .collect::<Vec<_>>();
+        assert!(
+            breaking.is_empty(),
+            "Breaking IDL changes:\n{}",
+            breaking.join("\n")
+        );
+        Ok(())
+    }
+
     #[test]
     fn program_test() -> Result<()> {
         if env::var("SBF_OUT_DIR").is_err() {
@@ -211,8 +357,7 @@ mod tests {
 
         let start_at = Some(2u64);
         let seeds = CounterAccountSeeds { owner };
-        let (counter_account, bump) =
-            Pubkey::find_program_address(&seeds.seeds(), &StarFrameDeclaredProgram::ID);
+        let (counter_account, bump) = CounterAccount::find_program_address(&seeds);
 
         let mollusk = mollusk.with_context(HashMap::from_iter([
             (funder, SolanaAccount::new(1_000_000_000, 0, &System::ID)),
@@ -239,7 +384,6 @@ mod tests {
                     funder,
                     owner,
                     counter: counter_account,
-                    system_program: None,
                 },
             )?,
             &[
@@ -274,7 +418,7 @@ mod tests {
 
         expected_counter.count += COUNT_ADD;
         // Update count
-        mollusk.process_and_validate_instruction(
+        let result = mollusk.process_and_validate_instruction(
             &CounterProgram::instruction(
                 &Count {
                     amount: COUNT_ADD,
@@ -292,6 +436,11 @@ mod tests {
                     .build(),
             ],
         );
+        // `Count` returns the new count
+        assert_eq!(
+            CounterProgram::deserialize_return::<u64>(&result.return_data)?,
+            expected_counter.count
+        );
         const COUNT_SUB: u64 = 4;
         expected_counter.count -= COUNT_SUB;
         mollusk.process_and_validate_instruction(
@@ -313,6 +462,40 @@ mod tests {
             ],
         );
 
+        // Multisig count requires both the owner and the signer
+        let multisig_count = |signers| {
+            CounterProgram::instruction(
+                &MultisigCount { amount: 3 },
+                MultisigCountClientAccounts {
+                    signers,
+                    counter: counter_account,
+                },
+            )
+        };
+        // The same key in both slots only counts as one signer
+        let result = mollusk.process_instruction(&multisig_count([owner, owner])?);
+        let ProgramResult::Failure(error) = &result.program_result else {
+            panic!(
+                "Expected InsufficientSigners, got {:?}",
+                result.program_result
+            );
+        };
+        assert_eq!(
+            u64::from(error.clone()),
+            u64::from(ErrorCode::InsufficientSigners.code())
+        );
+
+        expected_counter.count += 3;
+        mollusk.process_and_validate_instruction(
+            &multisig_count([owner, signer2])?,
+            &[
+                Check::success(),
+                Check::account(&counter_account)
+                    .data(&CounterAccount::serialize_account(expected_counter)?)
+                    .build(),
+            ],
+        );
+
         let counter_lamports = mollusk
             .account_store
             .borrow()
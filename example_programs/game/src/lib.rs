@@ -0,0 +1,536 @@
+//! A small game program that combines several `star_frame` patterns:
+//!
+//! - [`TransferScore`] atomically updates two [`Seeded`] player PDAs, using `extra_validation` for
+//!   cross-field checks.
+//! - [`PlayAction`] records an action on the player and CPIs into the [`counter`] program.
+//! - [`ClaimReward`] uses the instructions sysvar to enforce that it directly follows a
+//!   [`PlayAction`] in the same transaction.
+//!
+//! See `README.md` for when to reach for each pattern.
+use counter::{Count, CountCpiAccounts, CounterAccount, CounterProgram};
+use star_frame::{
+    account_set::sysvar::InstructionsSysvar, instruction::InstructionDiscriminant, prelude::*,
+};
+
+#[derive(StarFrameProgram)]
+#[program(
+    instruction_set = GameInstructionSet,
+    id = "Game4Gfqv9kvPiq3n9ZK3ncLxeDtaq39DpFYy1eM1ggZ"
+)]
+pub struct GameProgram;
+
+#[derive(InstructionSet)]
+pub enum GameInstructionSet {
+    CreatePlayer(CreatePlayer),
+    TransferScore(TransferScore),
+    PlayAction(PlayAction),
+    ClaimReward(ClaimReward),
+}
+
+/// The points awarded by [`ClaimReward`].
+pub const REWARD_POINTS: u64 = 10;
+
+#[zero_copy(pod)]
+#[derive(Default, Debug, Eq, PartialEq, ProgramAccount)]
+#[program_account(seeds = PlayerSeeds)]
+pub struct PlayerAccount {
+    pub owner: Pubkey,
+    pub score: u64,
+    /// The number of [`PlayAction`]s this player has made.
+    pub actions: u64,
+    /// The value of `actions` when the last reward was claimed.
+    pub last_rewarded_action: u64,
+    pub bump: u8,
+}
+
+#[derive(Debug, GetSeeds, Clone)]
+#[get_seeds(seed_const = b"PLAYER")]
+pub struct PlayerSeeds {
+    pub owner: Pubkey,
+}
+
+#[star_frame_error]
+pub enum GameError {
+    #[msg("Cannot transfer score to yourself")]
+    SelfTransfer,
+    #[msg("Not enough score")]
+    InsufficientScore,
+    #[msg("ClaimReward must directly follow a PlayAction")]
+    MissingPlayAction,
+    #[msg("Reward already claimed for the latest action")]
+    AlreadyClaimed,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct CreatePlayer;
+
+#[derive(AccountSet)]
+pub struct CreatePlayerAccounts {
+    #[validate(funder)]
+    pub owner: Signer<Mut<SystemAccount>>,
+    #[validate(arg = (
+        Create(()),
+        Seeds(PlayerSeeds { owner: *self.owner.pubkey() }),
+    ))]
+    #[idl(arg = Seeds(FindPlayerSeeds { owner: seed_path("owner") }))]
+    pub player: Init<Seeded<Account<PlayerAccount>>>,
+    pub system_program: Program<System>,
+}
+
+#[star_frame_instruction]
+fn CreatePlayer(accounts: &mut CreatePlayerAccounts) -> Result<()> {
+    **accounts.player.data_mut()? = PlayerAccount {
+        owner: *accounts.owner.pubkey(),
+        bump: accounts.player.access_seeds().bump,
+        ..Default::default()
+    };
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+pub struct TransferScore {
+    #[ix_args(run)]
+    pub amount: u64,
+}
+
+/// Both player accounts are checked against their seeds during validation, and
+/// `extra_validation` runs after every field has been validated, so it can compare fields.
+#[derive(AccountSet, Debug)]
+#[validate(extra_validation = self.validate())]
+pub struct TransferScoreAccounts {
+    pub owner: Signer<SystemAccount>,
+    pub recipient: SystemAccount,
+    #[validate(arg = Seeds(PlayerSeeds { owner: *self.owner.pubkey() }))]
+    #[idl(arg = Seeds(FindPlayerSeeds { owner: seed_path("owner") }))]
+    pub from: Seeded<Mut<Account<PlayerAccount>>>,
+    #[validate(arg = Seeds(PlayerSeeds { owner: *self.recipient.pubkey() }))]
+    #[idl(arg = Seeds(FindPlayerSeeds { owner: seed_path("recipient") }))]
+    pub to: Seeded<Mut<Account<PlayerAccount>>>,
+}
+
+impl TransferScoreAccounts {
+    fn validate(&self) -> Result<()> {
+        ensure_ne!(
+            self.owner.pubkey(),
+            self.recipient.pubkey(),
+            GameError::SelfTransfer
+        );
+        Ok(())
+    }
+}
+
+/// Either both players are updated or, if anything fails, neither is.
+#[star_frame_instruction]
+fn TransferScore(accounts: &mut TransferScoreAccounts, amount: u64) -> Result<()> {
+    let mut from = accounts.from.data_mut()?;
+    let mut to = accounts.to.data_mut()?;
+    from.score = from
+        .score
+        .checked_sub(amount)
+        .ok_or_else(|| error!(GameError::InsufficientScore))?;
+    packed_checked_add!(to.score, amount)?;
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+pub struct PlayAction {
+    #[ix_args(run)]
+    pub points: u64,
+}
+
+#[derive(AccountSet, Debug)]
+pub struct PlayActionAccounts {
+    pub owner: Signer<SystemAccount>,
+    #[validate(arg = Seeds(PlayerSeeds { owner: *self.owner.pubkey() }))]
+    #[idl(arg = Seeds(FindPlayerSeeds { owner: seed_path("owner") }))]
+    pub player: Seeded<Mut<Account<PlayerAccount>>>,
+    /// A [`counter`] account owned by `owner`, incremented once per action.
+    pub action_counter: Mut<Account<CounterAccount>>,
+    pub counter_program: Program<CounterProgram>,
+}
+
+#[star_frame_instruction]
+fn PlayAction(accounts: &mut PlayActionAccounts, points: u64) -> Result<()> {
+    {
+        let mut player = accounts.player.data_mut()?;
+        packed_checked_add!(player.score, points)?;
+        packed_checked_add!(player.actions, 1)?;
+    }
+    // The player data borrow must be released before the CPI.
+    CounterProgram::cpi(
+        Count {
+            amount: 1,
+            subtract: false,
+        },
+        CountCpiAccounts {
+            owner: *accounts.owner.account_info(),
+            counter: *accounts.action_counter.account_info(),
+        },
+        None,
+    )
+    .invoke()?;
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, InstructionArgs)]
+pub struct ClaimReward;
+
+#[derive(AccountSet, Debug)]
+#[validate(extra_validation = self.validate(ctx))]
+pub struct ClaimRewardAccounts {
+    pub owner: Signer<SystemAccount>,
+    #[validate(arg = Seeds(PlayerSeeds { owner: *self.owner.pubkey() }))]
+    #[idl(arg = Seeds(FindPlayerSeeds { owner: seed_path("owner") }))]
+    pub player: Seeded<Mut<Account<PlayerAccount>>>,
+    pub instructions: Sysvar<InstructionsSysvar>,
+}
+
+impl ClaimRewardAccounts {
+    fn validate(&self, ctx: &Context) -> Result<()> {
+        let instructions = self.instructions.instructions()?;
+        let current = instructions.load_current_index();
+        ensure!(
+            current > 0,
+            GameError::MissingPlayAction,
+            "ClaimReward cannot be the first instruction"
+        );
+        let previous = instructions.load_instruction_at(usize::from(current - 1))?;
+        check_play_action(
+            ctx.current_program_id(),
+            bytemuck::cast_ref(previous.get_program_id()),
+            previous.get_instruction_data(),
+        )
+    }
+}
+
+/// Checks that an introspected instruction is a [`PlayAction`] for `program_id`.
+fn check_play_action(program_id: &Pubkey, ix_program_id: &Pubkey, ix_data: &[u8]) -> Result<()> {
+    ensure_eq!(
+        ix_program_id,
+        program_id,
+        GameError::MissingPlayAction,
+        "Previous instruction was for program `{ix_program_id}`"
+    );
+    let discriminant =
+        <PlayAction as InstructionDiscriminant<GameInstructionSet>>::discriminant_bytes();
+    ensure!(
+        ix_data.starts_with(&discriminant),
+        GameError::MissingPlayAction,
+        "Previous instruction was not a PlayAction"
+    );
+    Ok(())
+}
+
+#[star_frame_instruction]
+fn ClaimReward(accounts: &mut ClaimRewardAccounts) -> Result<()> {
+    accounts.player.data_mut()?.claim_reward()
+}
+
+impl PlayerAccount {
+    /// Awards [`REWARD_POINTS`] for the latest [`PlayAction`], once per action.
+    fn claim_reward(&mut self) -> Result<()> {
+        ensure!(
+            self.actions > self.last_rewarded_action,
+            GameError::AlreadyClaimed
+        );
+        packed_checked_add!(self.score, REWARD_POINTS)?;
+        self.last_rewarded_action = self.actions;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(unused)]
+mod tests {
+    use std::{collections::HashMap, env};
+
+    use super::*;
+    use counter::CounterAccountData;
+    use mollusk_svm::{
+        account_store::AccountStore,
+        program::keyed_account_for_system_program,
+        result::{Check, InstructionResult, ProgramResult},
+        *,
+    };
+    use solana_account::Account as SolanaAccount;
+    use star_frame::{
+        account_set::sysvar::SysvarId,
+        client::{star_frame_instruction_data, SerializeAccount},
+        errors::StarFrameError,
+        SolanaInstruction,
+    };
+
+    /// Builds the instructions sysvar account for a transaction made of `instructions`.
+    ///
+    /// The runtime sets the index of the executing instruction, so it is left as 0.
+    fn instructions_sysvar_account(instructions: &[SolanaInstruction]) -> SolanaAccount {
+        let mut data = (instructions.len() as u16).to_le_bytes().to_vec();
+        let offsets_start = data.len();
+        data.resize(offsets_start + 2 * instructions.len(), 0);
+        for (index, instruction) in instructions.iter().enumerate() {
+            let offset = (data.len() as u16).to_le_bytes();
+            data[offsets_start + 2 * index..][..2].copy_from_slice(&offset);
+            data.extend((instruction.accounts.len() as u16).to_le_bytes());
+            for meta in &instruction.accounts {
+                data.push(u8::from(meta.is_signer) | (u8::from(meta.is_writable) << 1));
+                data.extend(meta.pubkey.as_ref());
+            }
+            data.extend(instruction.program_id.as_ref());
+            data.extend((instruction.data.len() as u16).to_le_bytes());
+            data.extend(&instruction.data);
+        }
+        data.extend(0u16.to_le_bytes());
+        SolanaAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner: pubkey!("Sysvar1111111111111111111111111111111111111"),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn assert_error(result: &InstructionResult, error: impl StarFrameError) {
+        let ProgramResult::Failure(program_error) = &result.program_result else {
+            panic!("Expected {error:?}, got {:?}", result.program_result);
+        };
+        assert_eq!(u64::from(program_error.clone()), u64::from(error.code()));
+    }
+
+    #[cfg(feature = "idl")]
+    #[test]
+    fn generate_idl() -> Result<()> {
+        let idl = StarFrameDeclaredProgram::program_to_idl()?;
+        let codama_idl: ProgramNode = idl.try_into()?;
+        let idl_json = codama_idl.to_json()?;
+        std::fs::write("idl.json", &idl_json)?;
+        Ok(())
+    }
+
+    #[test]
+    fn check_play_action_ordering() -> Result<()> {
+        let program_id = GameProgram::ID;
+        let play_action =
+            star_frame_instruction_data::<GameInstructionSet, _>(&PlayAction { points: 1 })?;
+        let claim_reward = star_frame_instruction_data::<GameInstructionSet, _>(&ClaimReward)?;
+
+        check_play_action(&program_id, &program_id, &play_action)?;
+        assert!(check_play_action(&program_id, &program_id, &claim_reward).is_err());
+        assert!(check_play_action(&program_id, &CounterProgram::ID, &play_action).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn claim_reward_once_per_action() -> Result<()> {
+        let mut player = PlayerAccount::default();
+        assert!(player.claim_reward().is_err());
+
+        player.actions = 2;
+        player.claim_reward()?;
+        assert_eq!(player.score, REWARD_POINTS);
+        assert_eq!(player.last_rewarded_action, 2);
+        assert!(player.claim_reward().is_err());
+        assert_eq!(player.score, REWARD_POINTS);
+
+        // Overflowing the score fails without marking the action as rewarded
+        player.actions = 3;
+        player.score = u64::MAX;
+        assert!(player.claim_reward().is_err());
+        assert_eq!(player.score, u64::MAX);
+        assert_eq!(player.last_rewarded_action, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn program_test() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+        let mut mollusk = Mollusk::new(&GameProgram::ID, "game");
+        mollusk.add_program(
+            &CounterProgram::ID,
+            "counter",
+            &mollusk_svm::program::loader_keys::LOADER_V3,
+        );
+
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let (alice_player, alice_bump) =
+            PlayerAccount::find_program_address(&PlayerSeeds { owner: alice });
+        let (bob_player, bob_bump) =
+            PlayerAccount::find_program_address(&PlayerSeeds { owner: bob });
+
+        let action_counter = Pubkey::new_unique();
+        let counter_account = CounterAccount {
+            version: 0,
+            owner: alice,
+            signer: alice,
+            count: 0,
+            bump: 0,
+            data: CounterAccountData::default(),
+        };
+
+        let claim_reward = GameProgram::instruction(
+            &ClaimReward,
+            ClaimRewardClientAccounts {
+                owner: alice,
+                player: alice_player,
+                instructions: None,
+            },
+        )?;
+
+        let mollusk = mollusk.with_context(HashMap::from_iter([
+            (alice, SolanaAccount::new(1_000_000_000, 0, &System::ID)),
+            (bob, SolanaAccount::new(1_000_000_000, 0, &System::ID)),
+            (alice_player, SolanaAccount::new(0, 0, &System::ID)),
+            (bob_player, SolanaAccount::new(0, 0, &System::ID)),
+            (
+                action_counter,
+                SolanaAccount {
+                    lamports: 1_000_000_000,
+                    data: CounterAccount::serialize_account(counter_account)?,
+                    owner: CounterProgram::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+            (
+                InstructionsSysvar::id(),
+                instructions_sysvar_account(&[claim_reward.clone()]),
+            ),
+            keyed_account_for_system_program(),
+        ]));
+
+        let mut expected_alice = PlayerAccount {
+            owner: alice,
+            bump: alice_bump,
+            ..Default::default()
+        };
+        let mut expected_bob = PlayerAccount {
+            owner: bob,
+            bump: bob_bump,
+            ..Default::default()
+        };
+
+        for (owner, player, expected) in [
+            (alice, alice_player, expected_alice),
+            (bob, bob_player, expected_bob),
+        ] {
+            mollusk.process_and_validate_instruction(
+                &GameProgram::instruction(
+                    &CreatePlayer,
+                    CreatePlayerClientAccounts {
+                        owner,
+                        player,
+                        system_program: None,
+                    },
+                )?,
+                &[
+                    Check::success(),
+                    Check::account(&player)
+                        .data(&PlayerAccount::serialize_account(expected)?)
+                        .owner(&GameProgram::ID)
+                        .build(),
+                ],
+            );
+        }
+
+        // Play an action, which also increments the counter through a CPI
+        expected_alice.score += 5;
+        expected_alice.actions += 1;
+        mollusk.process_and_validate_instruction(
+            &GameProgram::instruction(
+                &PlayAction { points: 5 },
+                PlayActionClientAccounts {
+                    owner: alice,
+                    player: alice_player,
+                    action_counter,
+                    counter_program: None,
+                },
+            )?,
+            &[
+                Check::success(),
+                Check::account(&alice_player)
+                    .data(&PlayerAccount::serialize_account(expected_alice)?)
+                    .build(),
+                Check::account(&action_counter)
+                    .data(&CounterAccount::serialize_account(CounterAccount {
+                        count: 1,
+                        ..counter_account
+                    })?)
+                    .build(),
+            ],
+        );
+
+        // Transfer score between the two player PDAs
+        expected_alice.score -= 3;
+        expected_bob.score += 3;
+        mollusk.process_and_validate_instruction(
+            &GameProgram::instruction(
+                &TransferScore { amount: 3 },
+                TransferScoreClientAccounts {
+                    owner: alice,
+                    recipient: bob,
+                    from: alice_player,
+                    to: bob_player,
+                },
+            )?,
+            &[
+                Check::success(),
+                Check::account(&alice_player)
+                    .data(&PlayerAccount::serialize_account(expected_alice)?)
+                    .build(),
+                Check::account(&bob_player)
+                    .data(&PlayerAccount::serialize_account(expected_bob)?)
+                    .build(),
+            ],
+        );
+
+        // Transferring more than the player has fails, and neither account is changed
+        let result = mollusk.process_instruction(&GameProgram::instruction(
+            &TransferScore { amount: 100 },
+            TransferScoreClientAccounts {
+                owner: alice,
+                recipient: bob,
+                from: alice_player,
+                to: bob_player,
+            },
+        )?);
+        assert!(result.program_result.is_err());
+        for (player, expected) in [(alice_player, expected_alice), (bob_player, expected_bob)] {
+            let data = mollusk
+                .account_store
+                .borrow()
+                .get_account(&player)
+                .unwrap()
+                .data;
+            assert_eq!(data, PlayerAccount::serialize_account(expected)?);
+        }
+
+        // Transferring to yourself is rejected during validation
+        let result = mollusk.process_instruction(&GameProgram::instruction(
+            &TransferScore { amount: 1 },
+            TransferScoreClientAccounts {
+                owner: alice,
+                recipient: alice,
+                from: alice_player,
+                to: alice_player,
+            },
+        )?);
+        assert!(result.program_result.is_err());
+
+        // Mollusk runs each instruction as its own transaction, so ClaimReward is always the first instruction
+        // and is rejected without touching the player
+        let result = mollusk.process_instruction(&claim_reward);
+        assert_error(&result, GameError::MissingPlayAction);
+        let data = mollusk
+            .account_store
+            .borrow()
+            .get_account(&alice_player)
+            .unwrap()
+            .data;
+        assert_eq!(data, PlayerAccount::serialize_account(expected_alice)?);
+
+        Ok(())
+    }
+}
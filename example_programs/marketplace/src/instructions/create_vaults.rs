@@ -0,0 +1,147 @@
+use star_frame::prelude::*;
+use star_frame_spl::{
+    associated_token::{
+        state::{AssociatedTokenAccount, InitAta, ValidateAta},
+        FindAtaSeeds,
+    },
+    token::{state::MintAccount, Token},
+};
+
+#[cfg(feature = "idl")]
+use crate::state::FindMarketSeeds;
+use crate::state::{Market, ValidateCurrency, ValidateMarketToken};
+
+#[derive(AccountSet, Debug)]
+pub struct CreateVaultsAccounts {
+    #[validate(funder)]
+    pub payer: Mut<Signer<SystemAccount>>,
+    #[idl(arg = Seeds(FindMarketSeeds {
+        currency: seed_path("currency"),
+        market_token: seed_path("market_token")
+    }))]
+    #[validate(arg = (
+        ValidateCurrency(self.currency.key_for()),
+        ValidateMarketToken(self.market_token.key_for())
+    ))]
+    pub market: ValidatedAccount<Market>,
+    pub currency: MintAccount,
+    pub market_token: MintAccount,
+    #[validate(arg = (
+        CreateIfNeeded(InitAta::new(&self.market, &self.market_token, self.system_program, self.token_program)),
+        ValidateAta { mint: self.market_token.key_for(), wallet: self.market.pubkey() }
+    ))]
+    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("market_token"), wallet: seed_path("market"), token_program: None }))]
+    pub market_token_vault: Init<AssociatedTokenAccount>,
+    #[validate(arg = (
+        CreateIfNeeded(InitAta::new(&self.market, &self.currency, self.system_program, self.token_program)),
+        ValidateAta { mint: self.currency.key_for(), wallet: self.market.pubkey() }
+    ))]
+    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("currency"), wallet: seed_path("market"), token_program: None }))]
+    pub currency_vault: Init<AssociatedTokenAccount>,
+    pub system_program: Program<System>,
+    pub token_program: Program<Token>,
+}
+
+/// Creates the market's token vaults, skipping any that already exist
+#[derive(InstructionArgs, BorshSerialize, BorshDeserialize, Copy, Clone, Debug)]
+#[borsh(crate = "star_frame::borsh")]
+pub struct CreateVaults;
+
+#[star_frame_instruction]
+fn CreateVaults(_accounts: &mut CreateVaultsAccounts) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::{tests::default_market, Market, MarketOwned, MarketSeeds},
+        test_utils::{new_mint_account, new_mollusk, LAMPORTS_PER_SOL},
+        Marketplace,
+    };
+    use mollusk_svm::result::Check;
+    use solana_account::Account as SolanaAccount;
+    use star_frame::client::SerializeAccount;
+    use star_frame_spl::associated_token::AssociatedToken;
+    use std::{collections::HashMap, env};
+
+    #[test]
+    fn create_vaults_is_idempotent() -> Result<()> {
+        if env::var("SBF_OUT_DIR").is_err() {
+            println!("SBF_OUT_DIR is not set, skipping test");
+            return Ok(());
+        }
+
+        let payer = Pubkey::new_unique();
+        let currency = KeyFor::new(Pubkey::new_unique());
+        let market_token = KeyFor::new(Pubkey::new_unique());
+        let (market_pda, bump) = Market::find_program_address(&MarketSeeds {
+            currency,
+            market_token,
+        });
+        let currency_vault = AssociatedToken::find_address(&market_pda, &currency);
+        let market_token_vault = AssociatedToken::find_address(&market_pda, &market_token);
+
+        let market_data = Market::serialize_account(MarketOwned {
+            bump,
+            currency,
+            market_token,
+            ..default_market()
+        })?;
+        let account_store = HashMap::from_iter([
+            (payer, SolanaAccount::new(LAMPORTS_PER_SOL, 0, &System::ID)),
+            (
+                market_pda,
+                SolanaAccount {
+                    lamports: LAMPORTS_PER_SOL,
+                    data: market_data,
+                    owner: Marketplace::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+            (currency_vault, SolanaAccount::default()),
+            (market_token_vault, SolanaAccount::default()),
+            new_mint_account(currency),
+            new_mint_account(market_token),
+            mollusk_svm::program::keyed_account_for_system_program(),
+        ]);
+        let mollusk = new_mollusk().with_context(account_store);
+
+        let ix = Marketplace::instruction(
+            &CreateVaults,
+            CreateVaultsClientAccounts {
+                payer,
+                market: market_pda,
+                currency: *currency.pubkey(),
+                market_token: *market_token.pubkey(),
+                market_token_vault,
+                currency_vault,
+                system_program: None,
+                token_program: None,
+            },
+        )?;
+
+        let vault_checks = [
+            Check::success(),
+            Check::account(&currency_vault).owner(&Token::ID).build(),
+            Check::account(&market_token_vault)
+                .owner(&Token::ID)
+                .build(),
+        ];
+        mollusk.process_and_validate_instruction(&ix, &vault_checks);
+        let payer_lamports = mollusk.account_store.borrow().get(&payer).unwrap().lamports;
+
+        // The vaults already exist, so the second call should skip the create CPIs
+        mollusk.process_and_validate_instruction(
+            &ix,
+            &[
+                Check::success(),
+                Check::account(&payer).lamports(payer_lamports).build(),
+            ],
+        );
+
+        Ok(())
+    }
+}
@@ -1,18 +1,21 @@
 mod cancel_orders;
+mod create_vaults;
 mod initialize;
 mod place_order;
 
 pub use cancel_orders::*;
+pub use create_vaults::*;
 pub use initialize::*;
 pub use place_order::*;
 
 use star_frame::prelude::*;
-#[cfg(feature = "idl")]
-use star_frame_spl::associated_token::FindAtaSeeds;
 use star_frame_spl::{
-    associated_token::state::{AssociatedTokenAccount, ValidateAta},
+    associated_token::{
+        state::{AssociatedTokenAccount, ValidateAta},
+        FindAtaSeeds,
+    },
     token::{
-        instructions::{Transfer, TransferCpiAccounts},
+        cpi,
         state::{MintAccount, TokenAccount, ValidateToken},
         Token,
     },
@@ -44,16 +47,16 @@ pub struct ManageOrderAccounts {
     pub currency: MintAccount,
     pub market_token: MintAccount,
     #[validate(arg = ValidateAta { mint: self.market_token.key_for(), wallet: self.market.pubkey()})]
-    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("market_token"), wallet: seed_path("market") }))]
+    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("market_token"), wallet: seed_path("market"), token_program: None }))]
     pub market_token_vault: Mut<AssociatedTokenAccount>,
     #[validate(arg = ValidateAta { mint: self.currency.key_for(), wallet: self.market.pubkey()})]
-    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("currency"), wallet: seed_path("market") }))]
+    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("currency"), wallet: seed_path("market"), token_program: None }))]
     pub currency_vault: Mut<AssociatedTokenAccount>,
     #[validate(arg = ValidateToken { mint: Some(*self.market_token.key_for()), owner: Some(*self.user.pubkey())})]
-    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("market_token"), wallet: seed_path("user") }))]
+    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("market_token"), wallet: seed_path("user"), token_program: None }))]
     pub user_market_token_vault: Mut<TokenAccount>,
     #[validate(arg = ValidateToken { mint: Some(*self.currency.key_for()), owner: Some(*self.user.pubkey())})]
-    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("currency"), wallet: seed_path("user") }))]
+    #[idl(arg = Seeds(FindAtaSeeds{ mint: seed_path("currency"), wallet: seed_path("user"), token_program: None }))]
     pub user_currency_vault: Mut<TokenAccount>,
     pub token_program: Program<Token>,
 }
@@ -79,31 +82,21 @@ impl ManageOrderAccounts {
         };
         let signer_seeds = signer_seeds.as_ref().map(|seeds| seeds.seeds_with_bump());
         if market_tokens > ZERO_QUANTITY {
-            Token::cpi(
-                Transfer {
-                    amount: market_tokens.val().0,
-                },
-                TransferCpiAccounts {
-                    source: *self.market_token_vault.account_info(),
-                    destination: *self.user_market_token_vault.account_info(),
-                    owner: *self.market.account_info(),
-                },
-                None,
-            )
+            cpi::transfer(
+                &self.market_token_vault,
+                &self.user_market_token_vault,
+                &self.market,
+                market_tokens.val().0,
+            )?
             .invoke_signed(&[signer_seeds.as_ref().unwrap().as_slice()])?;
         }
         if currency > ZERO_PRICE {
-            Token::cpi(
-                Transfer {
-                    amount: currency.val().0,
-                },
-                TransferCpiAccounts {
-                    source: *self.currency_vault.account_info(),
-                    destination: *self.user_currency_vault.account_info(),
-                    owner: *self.market.account_info(),
-                },
-                None,
-            )
+            cpi::transfer(
+                &self.currency_vault,
+                &self.user_currency_vault,
+                &self.market,
+                currency.val().0,
+            )?
             .invoke_signed(&[signer_seeds.as_ref().unwrap().as_slice()])?;
         }
         Ok(())
@@ -115,31 +108,21 @@ impl ManageOrderAccounts {
             currency,
         } = totals;
         if market_tokens > ZERO_QUANTITY {
-            Token::cpi(
-                Transfer {
-                    amount: market_tokens.val().0,
-                },
-                TransferCpiAccounts {
-                    source: *self.user_market_token_vault.account_info(),
-                    destination: *self.market_token_vault.account_info(),
-                    owner: *self.user.account_info(),
-                },
-                None,
-            )
+            cpi::transfer(
+                &self.user_market_token_vault,
+                &self.market_token_vault,
+                &self.user,
+                market_tokens.val().0,
+            )?
             .invoke()?;
         }
         if currency > ZERO_PRICE {
-            Token::cpi(
-                Transfer {
-                    amount: currency.val().0,
-                },
-                TransferCpiAccounts {
-                    source: *self.user_currency_vault.account_info(),
-                    destination: *self.currency_vault.account_info(),
-                    owner: *self.user.account_info(),
-                },
-                None,
-            )
+            cpi::transfer(
+                &self.user_currency_vault,
+                &self.currency_vault,
+                &self.user,
+                currency.val().0,
+            )?
             .invoke()?;
         }
         Ok(())
@@ -1,6 +1,6 @@
 use star_frame::prelude::*;
 
-use instructions::{CancelOrders, Initialize, PlaceOrder};
+use instructions::{CancelOrders, CreateVaults, Initialize, PlaceOrder};
 mod instructions;
 pub mod state;
 
@@ -17,6 +17,7 @@ pub enum MarketplaceInstructionSet {
     Initialize(Initialize),
     PlaceOrder(PlaceOrder),
     CancelOrders(CancelOrders),
+    CreateVaults(CreateVaults),
 }
 
 #[star_frame_error]
@@ -46,7 +47,7 @@ pub mod test_utils {
     use mollusk_svm::Mollusk;
     use solana_account::Account as SolanaAccount;
     use star_frame::{data_types::PackedValue, solana_pubkey::Pubkey};
-    use star_frame_spl::token::{state::MintAccount, Token};
+    use star_frame_spl::prelude::{MintAccount, MintAccountData, Token, TokenAccountData};
 
     use crate::state::{Price, Quantity};
 
@@ -65,13 +66,12 @@ pub mod test_utils {
     pub fn new_mint_account(mint: KeyFor<MintAccount>) -> (Pubkey, SolanaAccount) {
         let acc = SolanaAccount {
             lamports: LAMPORTS_PER_SOL,
-            data: bytemuck::bytes_of(&star_frame_spl::token::state::MintAccountData {
-                mint_authority: star_frame_spl::pod::PodOption::none(),
-                supply: TOKEN_SUPPLY,
-                decimals: TOKEN_DECIMALS,
-                is_initialized: true,
-                freeze_authority: star_frame_spl::pod::PodOption::none(),
-            })
+            data: bytemuck::bytes_of(&MintAccountData::new(
+                TOKEN_SUPPLY,
+                TOKEN_DECIMALS,
+                None,
+                None,
+            ))
             .to_vec(),
             owner: Token::ID,
             executable: false,
@@ -81,17 +81,7 @@ pub mod test_utils {
     }
 
     pub fn token_account_data(owner: Pubkey, mint: KeyFor<MintAccount>, amount: u64) -> Vec<u8> {
-        bytemuck::bytes_of(&star_frame_spl::token::state::TokenAccountData {
-            mint,
-            owner,
-            amount,
-            delegate: star_frame_spl::pod::PodOption::none(),
-            state: star_frame_spl::token::state::AccountState::Initialized,
-            is_native: star_frame_spl::pod::PodOption::none(),
-            delegated_amount: 0,
-            close_authority: star_frame_spl::pod::PodOption::none(),
-        })
-        .to_vec()
+        bytemuck::bytes_of(&TokenAccountData::new(mint, owner, amount)).to_vec()
     }
 
     pub fn new_token_account(
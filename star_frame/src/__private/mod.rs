@@ -1,23 +1,31 @@
 pub mod macro_prelude {
     pub use crate::{
         account_set::cpi_const_wrapper::CpiConstWrapper,
+        account_set::versioned::{migrate_account, migrate_data_from, VersionedAccount},
         account_set::{
-            internal_reverse::{_account_set_cleanup_reverse, _account_set_validate_reverse},
+            internal_reverse::{
+                CacheFunder, CacheRequiredFunder, NoResultTemp, ValidateTemp,
+                _account_set_cleanup_reverse, _account_set_validate_reverse,
+            },
             modifiers::{
-                CanInitAccount, CanInitSeeds, GetSeeds, HasInnerType, HasOwnerProgram, HasSeeds,
-                Seed, SignedAccount, WritableAccount,
+                push_bytes_seed, push_fixed_len_seed, CanInitAccount, CanInitSeeds, GetSeeds,
+                HasInnerType, HasOwnerProgram, HasSeeds, Immutable, Seed, SignedAccount,
+                WritableAccount,
             },
             single_set::{SingleAccountSet, SingleSetMeta},
-            AccountSet, AccountSetValidate, CheckKey, ClientAccountSet, CpiAccountSet,
-            DynamicCpiAccountSetLen, ProgramAccount,
+            AccountDataSpace, AccountKey, AccountSet, AccountSetKeys, AccountSetValidate, CheckKey,
+            ClientAccountSet, CpiAccountSet, DynamicCpiAccountSetLen, ProgramAccount,
+            ProgramAccountSpaceFn, UnknownAccountDataSpace,
         },
         align1::Align1,
         bail,
-        client::MakeInstruction,
+        client::{FindProgramAddress, MakeInstruction},
         context::Context,
         cpi::{CpiBuilder, MakeCpi},
         errors::{ErrorCode, ErrorInfo, StarFrameError},
         instruction::{
+            assert_async_args, block_on_instruction, concat_discriminants,
+            deserialize_trailing_arg, has_duplicate_discriminants, AsyncContext, BlockingContext,
             Instruction, InstructionArgs, InstructionDiscriminant, InstructionSet, IxArgs,
             IxReturnType, StarFrameInstruction,
         },
@@ -33,12 +41,20 @@ pub mod macro_prelude {
         Result,
     };
 
+    #[cfg(not(target_os = "solana"))]
+    pub use crate::instruction::AsyncStarFrameInstruction;
+
+    // Deprecated with the `async` feature, to warn where the `InstructionArgs` derive uses it
+    #[allow(deprecated)]
+    pub use crate::instruction::BorrowedRunArg;
+
     #[cfg(all(feature = "idl", not(target_os = "solana")))]
     pub use crate::{
         crate_metadata,
         idl::{
-            seed_const, seed_path, AccountSetToIdl, AccountToIdl, ErrorsToIdl, FindIdlSeeds,
-            FindSeed, InstructionSetToIdl, InstructionToIdl, ProgramToIdl, SeedsToIdl, TypeToIdl,
+            arg_path, seed_const, seed_path, AccountSetToIdl, AccountToIdl, ErrorsToIdl,
+            FindIdlSeeds, FindSeed, InstructionSetToIdl, InstructionToIdl, ProgramToIdl,
+            SeedsToIdl, TypeToIdl,
         },
         IdlResult,
     };
@@ -57,6 +73,7 @@ pub mod macro_prelude {
     pub use star_frame_proc::{sighash, zero_copy, InstructionToIdl, TypeToIdl};
 
     pub use advancer::{Advance, AdvanceArray};
+    pub use borsh;
     pub use core::any::type_name;
     pub use derive_where::DeriveWhere;
     pub use pinocchio::{
@@ -14,12 +14,45 @@ use crate::{
 };
 use advancer::Advance;
 use bytemuck::bytes_of;
+use pinocchio::sysvars::rent::Rent;
 use std::marker::PhantomData;
 
 /// Increases or decreases the rent of self to be the minimum required using [`CanModifyRent::normalize_rent`](crate::account_set::CanModifyRent::normalize_rent).
+///
+/// Use a [`RentTarget`] as the argument (e.g. `NormalizeRent(RentTarget::WithBuffer(1000))`), or
+/// `NormalizeRent((target, funder))`, to normalize to a different target with
+/// [`CanModifyRent::normalize_rent_to`](crate::account_set::CanModifyRent::normalize_rent_to).
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct NormalizeRent<T>(pub T);
 
+/// The lamports an account is normalized to with [`NormalizeRent`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum RentTarget {
+    /// The minimum balance for rent exemption of the account's current size.
+    #[default]
+    Minimum,
+    /// The minimum balance for rent exemption plus this many extra lamports.
+    WithBuffer(u64),
+    /// Exactly this many lamports. Emits a warning with the `cleanup_rent_warning` feature if this is below the minimum.
+    Exact(u64),
+    /// The minimum balance for rent exemption of an account with this many bytes of data, for accounts that will be
+    /// reallocated soon.
+    MinimumForSpace(usize),
+}
+
+impl RentTarget {
+    /// The target lamports for an account with `data_len` bytes of data.
+    #[must_use]
+    pub fn lamports(self, data_len: usize, rent: &Rent) -> u64 {
+        match self {
+            Self::Minimum => rent.minimum_balance(data_len),
+            Self::WithBuffer(buffer) => rent.minimum_balance(data_len).saturating_add(buffer),
+            Self::Exact(lamports) => lamports,
+            Self::MinimumForSpace(space) => rent.minimum_balance(space),
+        }
+    }
+}
+
 /// Decreases the rent of self to be the minimum required using [`CanModifyRent::refund_rent`](crate::account_set::CanModifyRent::refund_rent).
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct RefundRent<T>(pub T);
@@ -29,21 +62,51 @@ pub struct RefundRent<T>(pub T);
 pub struct ReceiveRent<T>(pub T);
 
 /// Closes the account using [`CanCloseAccount::close_account`](crate::account_set::CanCloseAccount::close_account).
+///
+/// Use a [`CloseConfig`] as the argument (e.g. `CloseAccount(CloseConfig { emit_log: true, ..Default::default() })`)
+/// to override the recipient or log the closure.
+///
+/// A field can't be both the `#[validate(recipient)]` and closed with `CloseAccount(())`, since it would be closed
+/// to itself.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub struct CloseAccount<T>(pub T);
 
+/// Configures [`CloseAccount`] using [`CanCloseAccount::close_account_with_config`](crate::account_set::CanCloseAccount::close_account_with_config).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CloseConfig<'a> {
+    /// The recipient of the lamports. Defaults to the `recipient` in the [`Context`] cache.
+    pub recipient: Option<&'a dyn CanAddLamports>,
+    /// Logs the closed account key with `msg!` and emits a [`CloseConfig::EVENT_DISCRIMINANT`] event
+    /// using `sol_log_data`.
+    pub emit_log: bool,
+}
+
+/// Discards the account data and reinitializes it as the default current version using
+/// [`Account::close_and_reinit`].
+///
+/// This is a migration path for [`VersionedAccount`](crate::account_set::versioned::VersionedAccount)s whose old data
+/// doesn't need to be kept. Use a funder as the argument (e.g. `CloseAndReinit(&self.funder)`), or `CloseAndReinit(())`
+/// to use the `funder` in the [`Context`] cache.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct CloseAndReinit<T>(pub T);
+
+impl CloseConfig<'_> {
+    /// The first data segment of the `sol_log_data` event emitted when [`CloseConfig::emit_log`] is set.
+    /// The second segment is the closed account's key.
+    pub const EVENT_DISCRIMINANT: [u8; 8] = *b"sf_close";
+}
+
 /// A [`ProgramAccount`] that contains an [`UnsizedType`].
 ///
 /// Calls [`ProgramAccount::validate_account_info`] during validation to ensure the owner and discriminant match.
+/// Versioned accounts stored as an older version are first migrated with [`ProgramAccount::migrate_account_info`].
 #[derive(AccountSet, derive_where::DeriveWhere)]
 #[derive_where(Clone, Debug, Copy)]
 #[account_set(skip_default_idl, skip_default_cleanup)]
-#[cfg_attr(feature = "aggressive_inline",
-    validate(inline_always, extra_validation = T::validate_account_info(self.info))
-)]
-#[cfg_attr(not(feature = "aggressive_inline"),
-    validate(extra_validation = T::validate_account_info(self.info))
-)]
+#[validate(extra_validation = {
+    T::migrate_account_info(self.info)?;
+    T::validate_account_info(self.info)
+})]
 #[cleanup(
     generics = [],
     extra_cleanup = self.check_cleanup(ctx),
@@ -63,6 +126,21 @@ pub struct CloseAccount<T>(pub T);
         self.normalize_rent(funder, ctx)
     },
 )]
+#[cleanup(
+    id = "normalize_rent_to",
+    generics = [<'a, Funder> where Funder: CanFundRent],
+    arg = NormalizeRent<(RentTarget, &'a Funder)>,
+    extra_cleanup = self.normalize_rent_to(arg.0.0, arg.0.1, ctx)
+)]
+#[cleanup(
+    id = "normalize_rent_to_cached",
+    arg = NormalizeRent<RentTarget>,
+    generics = [],
+    extra_cleanup = {
+        let funder = ctx.get_funder().ok_or_else(|| error!(ErrorCode::EmptyFunderCache, "Missing `funder` in cache for `NormalizeRent`"))?;
+        self.normalize_rent_to(arg.0, funder, ctx)
+    },
+)]
 #[cleanup(
     id = "receive_rent",
     generics = [<'a, Funder> where Funder: CanFundRent],
@@ -108,6 +186,27 @@ pub struct CloseAccount<T>(pub T);
         self.close_account(recipient)
     }
 )]
+#[cleanup(
+    id = "close_account_config",
+    generics = [<'a>],
+    arg = CloseAccount<CloseConfig<'a>>,
+    extra_cleanup = self.close_account_with_config(arg.0, ctx)
+)]
+#[cleanup(
+    id = "close_and_reinit",
+    generics = [<'a, Funder> where Funder: CanFundRent, T: UnsizedInit<DefaultInit>],
+    arg = CloseAndReinit<&'a Funder>,
+    extra_cleanup = self.close_and_reinit(arg.0, ctx)
+)]
+#[cleanup(
+    id = "close_and_reinit_cached",
+    arg = CloseAndReinit<()>,
+    generics = [where T: UnsizedInit<DefaultInit>],
+    extra_cleanup = {
+        let funder = ctx.get_funder().ok_or_else(|| error!(ErrorCode::EmptyFunderCache, "Missing `funder` in cache for `CloseAndReinit`"))?;
+        self.close_and_reinit(funder, ctx)
+    }
+)]
 pub struct Account<T: ProgramAccount + UnsizedType + ?Sized> {
     #[single_account_set(
         skip_has_inner_type,
@@ -124,6 +223,40 @@ impl<T> Account<T>
 where
     T: ProgramAccount + UnsizedType + ?Sized,
 {
+    /// Checks that the data of `info` starts with the discriminant of `T`, without checking the owner or reading the
+    /// rest of the data.
+    ///
+    /// Useful for peeking at an [`AccountInfo`] to decide how to handle it, before decoding it as a specific account.
+    #[inline]
+    pub fn check_discriminant(info: &AccountInfo) -> Result<()> {
+        super::validate_discriminant::<T>(*info)
+    }
+
+    /// Returns whether the data of `info` starts with the discriminant of `T`. See [`Self::check_discriminant`].
+    #[inline]
+    #[must_use]
+    pub fn has_discriminant(info: &AccountInfo) -> bool {
+        Self::check_discriminant(info).is_ok()
+    }
+
+    /// Checks that the account data still starts with the discriminant of `T`. See [`Self::check_discriminant`].
+    #[inline]
+    pub fn verify_discriminant(&self) -> Result<()> {
+        Self::check_discriminant(&self.info)
+    }
+
+    /// Wraps `info` as an [`Account`] without checking the owner or discriminant.
+    ///
+    /// Only use this when the account has already been checked, like with [`Self::check_discriminant`].
+    #[doc(hidden)]
+    #[must_use]
+    pub fn from_account_info_unchecked(info: AccountInfo) -> Self {
+        Self {
+            info,
+            phantom_t: PhantomData,
+        }
+    }
+
     #[inline]
     pub fn data(&self) -> Result<SharedWrapper<'_, T::Ptr>> {
         // If the account is writable, changes could have been made after AccountSetValidate has been run
@@ -368,6 +501,35 @@ where
     }
 }
 
+impl<T> Account<T>
+where
+    T: ProgramAccount + UnsizedType + UnsizedInit<DefaultInit> + ?Sized,
+{
+    /// Discards the account data and reinitializes it with the current discriminant and the default `T`, resizing the
+    /// account and normalizing its rent with `funder`.
+    ///
+    /// Does nothing if the account is closed or no longer owned by this program.
+    pub fn close_and_reinit(
+        &self,
+        funder: &(impl CanFundRent + ?Sized),
+        ctx: &Context,
+    ) -> Result<()> {
+        if self.info.lamports() == 0 || !self.info.owner().fast_eq(&T::OwnerProgram::ID) {
+            return Ok(());
+        }
+        self.check_writable()?;
+        let space = <AccountDiscriminant<T>>::INIT_BYTES;
+        self.info.resize(space)?;
+        {
+            let mut data_bytes = self.account_data_mut()?;
+            data_bytes.fill(0);
+            let mut data_bytes = &mut *data_bytes;
+            <AccountDiscriminant<T>>::init(&mut data_bytes, DefaultInit)?;
+        }
+        self.normalize_rent(funder, ctx)
+    }
+}
+
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 mod idl_impl {
 
@@ -392,3 +554,156 @@ mod idl_impl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::discriminant::AccountDiscriminant;
+    use crate::prelude::*;
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(Debug, PartialEq, Eq)]
+    struct ActiveState {
+        count: u64,
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(Debug, PartialEq, Eq)]
+    struct ClosedState {
+        closed_at: i64,
+    }
+
+    #[allow(dead_code)]
+    #[derive(ProgramAccount, Align1, CheckedBitPattern, Copy, Clone, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl)]
+    #[repr(u8)]
+    enum StateAccount {
+        Active(ActiveState),
+        Closed(ClosedState),
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy]
+    #[derive(ProgramAccount, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl)]
+    enum ZeroCopyStateAccount {
+        Active(ActiveState),
+        Closed(ClosedState),
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount)]
+    #[program_account(skip_idl)]
+    struct PodAccount {
+        count: u64,
+        key: Pubkey,
+    }
+
+    #[allow(dead_code)]
+    #[derive(ProgramAccount, BorshSerialize, BorshDeserialize, Default)]
+    #[program_account(skip_idl, space = 100)]
+    struct FixedBorshAccount {
+        data: Vec<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(ProgramAccount, BorshSerialize, BorshDeserialize, Default)]
+    #[program_account(skip_idl)]
+    struct BorshAccountData {
+        data: Vec<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl, discriminant = [0x01, 0, 0, 0, 0, 0, 0, 0])]
+    struct CustomDiscriminantAccount {
+        count: u64,
+    }
+
+    const fn buffer_space(len: usize) -> usize {
+        4 + len
+    }
+
+    #[allow(dead_code)]
+    #[derive(ProgramAccount, BorshSerialize, BorshDeserialize, Default)]
+    #[program_account(skip_idl, space_fn = buffer_space)]
+    struct BufferAccount {
+        data: Vec<u8>,
+    }
+
+    static_assertions::const_assert_eq!(PodAccount::SPACE, 8 + 8 + 32);
+    static_assertions::const_assert_eq!(BufferAccount::space_for(10), 8 + 4 + 10);
+    static_assertions::const_assert_eq!(StateAccount::SPACE, 8 + 1 + 8);
+    static_assertions::const_assert_eq!(ZeroCopyStateAccount::SPACE, 8 + 1 + 8);
+    static_assertions::const_assert_eq!(FixedBorshAccount::SPACE, 8 + 100);
+
+    #[test]
+    fn test_data_space() {
+        assert_eq!(PodAccount::DATA_SPACE, Some(size_of::<PodAccount>()));
+        assert_eq!(StateAccount::DATA_SPACE, Some(9));
+        assert_eq!(FixedBorshAccount::DATA_SPACE, Some(100));
+        assert_eq!(BorshAccountData::DATA_SPACE, None);
+        assert_eq!(BufferAccount::DATA_SPACE, None);
+        assert_eq!(BufferAccount::data_space_for(10), 4 + 10);
+    }
+
+    #[test]
+    fn test_custom_discriminant() -> Result<()> {
+        assert_eq!(
+            CustomDiscriminantAccount::discriminant_bytes(),
+            [0x01, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_ne!(
+            CustomDiscriminantAccount::discriminant_bytes(),
+            PodAccount::discriminant_bytes()
+        );
+
+        let mut bytes = CustomDiscriminantAccount::discriminant_bytes();
+        bytes.extend(7u64.to_le_bytes());
+        assert_eq!(
+            CustomDiscriminantAccount::deserialize_account(&bytes)?,
+            CustomDiscriminantAccount { count: 7 }
+        );
+
+        bytes[..8].copy_from_slice(&PodAccount::discriminant_bytes());
+        assert!(CustomDiscriminantAccount::deserialize_account(&bytes).is_err());
+        Ok(())
+    }
+
+    fn account_bytes(tag: u8, data: u64) -> Vec<u8> {
+        let mut bytes = StateAccount::discriminant_bytes();
+        bytes.push(tag);
+        bytes.extend(data.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_enum_account_data() -> Result<()> {
+        static_assertions::assert_impl_all!(StateAccount: UnsizedType, NoUninit);
+
+        let active = AccountDiscriminant::<StateAccount>::owned(&account_bytes(0, 10))?;
+        assert_eq!(active, StateAccount::Active(ActiveState { count: 10 }));
+
+        let closed = AccountDiscriminant::<StateAccount>::owned(&account_bytes(1, 20))?;
+        assert_eq!(closed, StateAccount::Closed(ClosedState { closed_at: 20 }));
+
+        assert!(AccountDiscriminant::<StateAccount>::owned(&account_bytes(2, 0)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_copy_enum_account_data() -> Result<()> {
+        let mut bytes = ZeroCopyStateAccount::discriminant_bytes();
+        bytes.extend(bytemuck::bytes_of(&ZeroCopyStateAccount::Closed(
+            ClosedState { closed_at: 20 },
+        )));
+        assert_eq!(
+            AccountDiscriminant::<ZeroCopyStateAccount>::owned(&bytes)?,
+            ZeroCopyStateAccount::Closed(ClosedState { closed_at: 20 })
+        );
+        Ok(())
+    }
+}
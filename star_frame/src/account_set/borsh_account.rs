@@ -8,27 +8,37 @@ use crate::{
     account_set::{
         modifiers::{
             CanInitAccount, HasInnerType, HasOwnerProgram, HasSeeds, OwnerProgramDiscriminant,
+            WithSizeFn, WithSpace,
         },
         AccountSetDecode, CanAddLamports, CanFundRent, CanSystemCreateAccount as _,
+        ProgramAccountSpaceFn,
     },
     errors::ErrorCode,
     prelude::*,
 };
 
+/// Reallocates a [`BorshAccount`] to exactly fit its data, then normalizes its rent using
+/// [`BorshAccount::realloc`] and [`CanModifyRent::normalize_rent`](crate::account_set::CanModifyRent::normalize_rent).
+///
+/// Unlike [`NormalizeRent`], which only grows the account, this also shrinks it and refunds the excess rent. Use a
+/// funder as the argument (e.g. `NormalizeRentAfterRealloc(&self.funder)`), or `NormalizeRentAfterRealloc(())` to use
+/// the `funder` in the [`Context`] cache.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct NormalizeRentAfterRealloc<T>(pub T);
+
 /// A [`ProgramAccount`] that is serialized and deserialized using [`BorshSerialize`] and [`BorshDeserialize`].
 ///
 /// This is much less effecient than using [`Account`] because this is not zero-copy.
 ///
 /// Calls [`ProgramAccount::validate_account_info`] during validation to ensure the owner and discriminant match, and writes back the
 /// updated `T` to the account info when the account is writable during `AccountSetCleanup`
+///
+/// `T`'s fields are included in the IDL through its `AccountToIdl` implementation, which is generated by
+/// [`derive@ProgramAccount`] unless `skip_idl` is set. Borsh accounts have no fixed `space` in the IDL unless one is
+/// provided with `#[program_account(space = <expr>)]`.
 #[derive(AccountSet, Debug, Clone)]
 #[account_set(skip_default_decode, skip_default_idl)]
-#[cfg_attr(feature = "aggressive_inline",
-    validate(inline_always, extra_validation = T::validate_account_info(self.info))
-)]
-#[cfg_attr(not(feature = "aggressive_inline"),
-    validate(extra_validation = T::validate_account_info(self.info))
-)]
+#[validate(extra_validation = T::validate_account_info(self.info))]
 #[cleanup(generics = [], extra_cleanup = {
     self.serialize()?;
     self.check_cleanup(ctx)
@@ -52,6 +62,44 @@ use crate::{
         self.normalize_rent(funder, ctx)
     },
 )]
+#[cleanup(
+    id = "normalize_rent_to",
+    generics = [<'a, Funder> where Funder: CanFundRent],
+    arg = NormalizeRent<(RentTarget, &'a Funder)>,
+    extra_cleanup = {
+        self.serialize()?;
+        self.normalize_rent_to(arg.0.0, arg.0.1, ctx)
+    }
+)]
+#[cleanup(
+    id = "normalize_rent_to_cached",
+    arg = NormalizeRent<RentTarget>,
+    generics = [],
+    extra_cleanup = {
+        self.serialize()?;
+        let funder = ctx.get_funder().ok_or_else(|| error!(ErrorCode::EmptyFunderCache, "Missing `funder` in cache for `NormalizeRent`"))?;
+        self.normalize_rent_to(arg.0, funder, ctx)
+    },
+)]
+#[cleanup(
+    id = "normalize_rent_after_realloc",
+    generics = [<'a, Funder> where Funder: CanFundRent],
+    arg = NormalizeRentAfterRealloc<&'a Funder>,
+    extra_cleanup = {
+        self.realloc()?;
+        self.normalize_rent(arg.0, ctx)
+    }
+)]
+#[cleanup(
+    id = "normalize_rent_after_realloc_cached",
+    arg = NormalizeRentAfterRealloc<()>,
+    generics = [],
+    extra_cleanup = {
+        self.realloc()?;
+        let funder = ctx.get_funder().ok_or_else(|| error!(ErrorCode::EmptyFunderCache, "Missing `funder` in cache for `NormalizeRentAfterRealloc`"))?;
+        self.normalize_rent(funder, ctx)
+    },
+)]
 #[cleanup(
     id = "receive_rent",
     generics = [<'a, Funder> where Funder: CanFundRent],
@@ -109,6 +157,15 @@ use crate::{
         self.close_account(recipient)
     }
 )]
+#[cleanup(
+    id = "close_account_config",
+    generics = [<'a>],
+    arg = CloseAccount<CloseConfig<'a>>,
+    extra_cleanup = {
+        // We don't serialize here because we are about to close the account!
+        self.close_account_with_config(arg.0, ctx)
+    }
+)]
 pub struct BorshAccount<T: ProgramAccount + BorshSerialize + BorshDeserialize> {
     #[single_account_set(
         skip_has_inner_type,
@@ -172,8 +229,11 @@ where
     ) -> Result<Self> {
         let info = <AccountInfo as AccountSetDecode<'a, ()>>::decode_accounts(accounts, (), ctx)?;
         let data = if info.data_len() > size_of::<OwnerProgramDiscriminant<T>>() {
-            Some(T::try_from_slice(
-                &info.account_data()?[size_of::<OwnerProgramDiscriminant<T>>()..],
+            // Check the discriminant before deserializing, so data from another account type is never decoded as `T`
+            Self::verify_discriminant(&info)
+                .with_ctx(|| format!("Failed to decode BorshAccount {}", info.pubkey()))?;
+            Some(T::deserialize(
+                &mut &info.account_data()?[size_of::<OwnerProgramDiscriminant<T>>()..],
             )?)
         } else {
             None
@@ -183,19 +243,57 @@ where
 }
 
 impl<T: ProgramAccount + BorshSerialize + BorshDeserialize> BorshAccount<T> {
+    /// The discriminant of `T`, which the account data must start with.
+    pub const DISCRIMINANT: OwnerProgramDiscriminant<T> = T::DISCRIMINANT;
+
+    /// Checks that the data of `info` starts with the discriminant of `T`, returning
+    /// [`ErrorCode::DiscriminantMismatch`] if it doesn't. The owner isn't checked.
+    #[inline]
+    pub fn verify_discriminant(info: &AccountInfo) -> Result<()> {
+        super::validate_discriminant::<T>(*info)
+    }
+
+    /// Whether the data of `info` starts with the discriminant of `T`. The owner isn't checked.
+    ///
+    /// Useful for peeking at an [`AccountInfo`] to decide how to handle it, before decoding it as a specific account.
+    #[must_use]
+    pub fn has_discriminant(info: &AccountInfo) -> bool {
+        Self::verify_discriminant(info).is_ok()
+    }
+
     /// Serializes the inner data `T` back to the account info if the account is writable, still owned by this program, and not closed.
     ///
+    /// The account is grown if the data no longer fits, but is never shrunk, so any space pre-allocated with
+    /// [`WithSpace`] is kept.
+    ///
     /// This is called during `AccountSetCleanup` and can be useful to call manually if you need the data to be serialized prior to a CPI.
     pub fn serialize(&mut self) -> Result<()> {
+        self.serialize_with_resize(false)
+    }
+
+    /// Serializes the inner data `T` back to the account info like [`Self::serialize`], but resizes the account to
+    /// exactly fit the data, shrinking it if the data got smaller.
+    ///
+    /// This does not modify the account's lamports, so it should be followed by a rent normalization, like
+    /// [`NormalizeRentAfterRealloc`] does.
+    pub fn realloc(&mut self) -> Result<()> {
+        self.serialize_with_resize(true)
+    }
+
+    fn serialize_with_resize(&mut self, shrink: bool) -> Result<()> {
         if self.is_writable()
             && self.info.data_len() > size_of::<OwnerProgramDiscriminant<T>>()
             && self.owner_pubkey() == T::OwnerProgram::ID
         {
-            let new_size = size_of::<OwnerProgramDiscriminant<T>>() + object_length(&self.data)?;
-            self.info.resize(new_size)?;
+            let Some(data) = &self.data else {
+                return Ok(());
+            };
+            let new_size = size_of::<OwnerProgramDiscriminant<T>>() + object_length(data)?;
+            if new_size > self.info.data_len() || (shrink && new_size < self.info.data_len()) {
+                self.info.resize(new_size)?;
+            }
             let mut account_data = self.info.account_data_mut()?;
-            self.data
-                .serialize(&mut &mut account_data[size_of::<OwnerProgramDiscriminant<T>>()..])?;
+            data.serialize(&mut &mut account_data[size_of::<OwnerProgramDiscriminant<T>>()..])?;
         }
         Ok(())
     }
@@ -205,8 +303,8 @@ impl<T: ProgramAccount + BorshSerialize + BorshDeserialize> BorshAccount<T> {
     /// This is useful if the account data has been modified by another program through a CPI, which won't update
     /// `Self`'s deserialized data.
     pub fn reload(&mut self) -> Result<()> {
-        self.data = Some(T::try_from_slice(
-            &self.info.account_data()?[size_of::<OwnerProgramDiscriminant<T>>()..],
+        self.data = Some(T::deserialize(
+            &mut &self.info.account_data()?[size_of::<OwnerProgramDiscriminant<T>>()..],
         )?);
         Ok(())
     }
@@ -227,6 +325,21 @@ impl<T: ProgramAccount + BorshSerialize + BorshDeserialize> BorshAccount<T> {
         self.data = Some(data);
         Ok(())
     }
+
+    /// Sets the inner data `T`, reallocates the account to exactly fit it, and normalizes the account's rent with
+    /// `funder`.
+    ///
+    /// Returns an error if the account is not writable.
+    pub fn set_inner_with_realloc(
+        &mut self,
+        data: T,
+        funder: &(impl CanFundRent + ?Sized),
+        ctx: &Context,
+    ) -> Result<()> {
+        self.set_inner(data)?;
+        self.realloc()?;
+        self.normalize_rent(funder, ctx)
+    }
 }
 
 impl<T> HasSeeds for BorshAccount<T>
@@ -313,6 +426,99 @@ where
         arg: (InitValue, &Funder),
         account_seeds: Option<&[&[u8]]>,
         ctx: &Context,
+    ) -> Result<bool> {
+        let (init_value, funder) = arg;
+        self.init_with_space::<IF_NEEDED>(init_value, None, funder, account_seeds, ctx)
+    }
+}
+
+impl<T> CanInitAccount<WithSpace> for BorshAccount<T>
+where
+    T: BorshDeserialize + BorshSerialize + ProgramAccount + Default,
+{
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: WithSpace,
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let funder = ctx.get_funder().ok_or_else(|| {
+            error!(
+                ErrorCode::EmptyFunderCache,
+                "Missing tagged `funder` for Account `init_account`"
+            )
+        })?;
+        self.init_account::<IF_NEEDED>((arg, funder), account_seeds, ctx)
+    }
+}
+
+impl<T, Funder> CanInitAccount<(WithSpace, &Funder)> for BorshAccount<T>
+where
+    T: BorshDeserialize + BorshSerialize + ProgramAccount + Default,
+    Funder: CanFundRent + ?Sized,
+{
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: (WithSpace, &Funder),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let (WithSpace(space), funder) = arg;
+        self.init_with_space::<IF_NEEDED>(T::default, Some(space), funder, account_seeds, ctx)
+    }
+}
+
+impl<T> CanInitAccount<WithSizeFn> for BorshAccount<T>
+where
+    T: BorshDeserialize + BorshSerialize + ProgramAccountSpaceFn + Default,
+{
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: WithSizeFn,
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        self.init_account::<IF_NEEDED>(WithSpace(T::data_space_for(arg.0)), account_seeds, ctx)
+    }
+}
+
+impl<T, Funder> CanInitAccount<(WithSizeFn, &Funder)> for BorshAccount<T>
+where
+    T: BorshDeserialize + BorshSerialize + ProgramAccountSpaceFn + Default,
+    Funder: CanFundRent + ?Sized,
+{
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: (WithSizeFn, &Funder),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let (WithSizeFn(n), funder) = arg;
+        self.init_account::<IF_NEEDED>(
+            (WithSpace(T::data_space_for(n)), funder),
+            account_seeds,
+            ctx,
+        )
+    }
+}
+
+impl<T> BorshAccount<T>
+where
+    T: BorshDeserialize + BorshSerialize + ProgramAccount,
+{
+    /// Creates the account with `space` bytes after the discriminant. If `None`, uses enough for the initial value
+    /// and at least [`ProgramAccount::DATA_SPACE`].
+    fn init_with_space<const IF_NEEDED: bool>(
+        &mut self,
+        init_value: impl FnOnce() -> T,
+        space: Option<usize>,
+        funder: &(impl CanFundRent + ?Sized),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
     ) -> Result<bool> {
         if IF_NEEDED {
             let needs_init = self.account_info().owner().fast_eq(&System::ID)
@@ -324,9 +530,20 @@ where
             }
         }
         self.check_writable()?;
-        let (init_value, funder) = arg;
         let data = init_value();
-        let space = size_of::<OwnerProgramDiscriminant<T>>() + object_length(&data)?;
+        let data_len = object_length(&data)?;
+        let space = match space {
+            Some(space) => {
+                ensure!(
+                    space >= data_len,
+                    ProgramError::AccountDataTooSmall,
+                    "Space of {space} is too small for the initial value of {data_len} bytes"
+                );
+                space
+            }
+            None => T::DATA_SPACE.map_or(data_len, |space| space.max(data_len)),
+        };
+        let space = size_of::<OwnerProgramDiscriminant<T>>() + space;
         self.system_create_account(funder, T::OwnerProgram::ID, space, account_seeds, ctx)
             .ctx("system_create_account failed")?;
         self.account_data_mut()?[..size_of::<OwnerProgramDiscriminant<T>>()]
@@ -0,0 +1,364 @@
+//! A [`ProgramAccount`] with a SHA-256 integrity hash of its data, for tamper detection.
+//!
+//! The `HashedAccount<T>` type stores a `Pod` account followed by a 32 byte hash of the discriminant and data. Reads
+//! verify the hash, and writes through [`HashedAccount::set_data`] or [`HashedAccount::data_mut`] update it, so any
+//! modification to the account data that bypasses [`HashedAccount`] is detected on the next read.
+use crate::{
+    account_set::{
+        modifiers::{CanInitAccount, HasInnerType, HasOwnerProgram, OwnerProgramDiscriminant},
+        CanFundRent, CanSystemCreateAccount as _,
+    },
+    prelude::*,
+    ErrorCode,
+};
+use bytemuck::bytes_of;
+use derive_more::{Deref, DerefMut};
+use pinocchio::account_info::RefMut;
+use std::marker::PhantomData;
+
+/// The size of the integrity hash stored after the account data.
+pub const HASH_SIZE: usize = 32;
+
+/// A [`ProgramAccount`] followed by a SHA-256 hash of its discriminant and data, which is verified on every read.
+///
+/// The account data is laid out as `[discriminant][T][hash]`, for a total size of [`HashedAccount::SPACE`].
+/// [`HashedAccount::data`] returns an error if the hash doesn't match the data, and [`HashedAccount::set_data`] and
+/// [`HashedAccount::data_mut`] write the data and update the hash. The hash uses the `sol_sha256` syscall on chain.
+///
+/// [`HashedAccount`] can be initialized with [`Init`] and `Create(())` (the default `T`), `Create(|| value)`, or
+/// either with a funder, like `Create((|| value, &self.funder))`. The account is created with
+/// [`HashedAccount::SPACE`] bytes and the hash of its initial value.
+#[derive_where::derive_where(Clone, Copy, Debug)]
+#[derive(AccountSet)]
+#[account_set(skip_default_idl)]
+#[validate(extra_validation = self.validate())]
+pub struct HashedAccount<T: ProgramAccount + Pod> {
+    #[single_account_set(skip_has_inner_type, skip_has_seeds, skip_has_owner_program)]
+    info: AccountInfo,
+    #[account_set(skip = PhantomData)]
+    phantom: PhantomData<T>,
+}
+
+impl<T: ProgramAccount + Pod> HashedAccount<T> {
+    /// The total size of the account data, including the discriminant and hash.
+    pub const SPACE: usize = size_of::<OwnerProgramDiscriminant<T>>() + size_of::<T>() + HASH_SIZE;
+
+    fn validate(&self) -> Result<()> {
+        T::validate_account_info(self.info)?;
+        ensure!(
+            self.info.data_len() == Self::SPACE,
+            ProgramError::InvalidAccountData,
+            "HashedAccount {} data length {} does not match expected length {}",
+            self.info.pubkey(),
+            self.info.data_len(),
+            Self::SPACE
+        );
+        Ok(())
+    }
+
+    /// Reads the account data after verifying its hash.
+    ///
+    /// Returns [`ErrorCode::HashMismatch`] if the data was modified without updating the hash.
+    pub fn data(&self) -> Result<T> {
+        Self::read_data(&self.info.account_data()?)
+            .with_ctx(|| format!("Failed to read HashedAccount {}", self.info.pubkey()))
+    }
+
+    /// Reads the account data after verifying its hash, and returns a guard that writes it back and updates the hash
+    /// when it is dropped.
+    ///
+    /// The account data stays mutably borrowed until the guard is dropped. Returns an error if the account is not
+    /// writable or the hash doesn't match the data.
+    pub fn data_mut(&self) -> Result<HashedDataMut<'_, T>> {
+        ensure!(
+            self.info.is_writable(),
+            ErrorCode::ExpectedWritable,
+            "HashedAccount {} is not writable",
+            self.info.pubkey()
+        );
+        let bytes = self.info.account_data_mut()?;
+        let data = Self::read_data(&bytes)
+            .with_ctx(|| format!("Failed to read HashedAccount {}", self.info.pubkey()))?;
+        Ok(HashedDataMut { bytes, data })
+    }
+
+    /// Writes `data` and its discriminant to the account and updates the hash.
+    ///
+    /// Returns an error if the account is not writable.
+    pub fn set_data(&self, data: &T) -> Result<()> {
+        ensure!(
+            self.info.is_writable(),
+            ErrorCode::ExpectedWritable,
+            "HashedAccount {} is not writable",
+            self.info.pubkey()
+        );
+        Self::write_data(&mut self.info.account_data_mut()?, data)
+    }
+
+    /// Reads `T` from raw account `bytes` after verifying the hash.
+    pub fn read_data(bytes: &[u8]) -> Result<T> {
+        let (hashed, hash) = Self::split(bytes)?;
+        ensure!(
+            sha256(hashed) == hash,
+            ErrorCode::HashMismatch,
+            "Account data does not match its integrity hash"
+        );
+        let discriminant_size = size_of::<OwnerProgramDiscriminant<T>>();
+        ensure!(
+            hashed[..discriminant_size] == *bytes_of(&T::DISCRIMINANT),
+            ErrorCode::DiscriminantMismatch
+        );
+        Ok(bytemuck::pod_read_unaligned(&hashed[discriminant_size..]))
+    }
+
+    /// Writes the discriminant, `data`, and the hash of both to raw account `bytes`.
+    pub fn write_data(bytes: &mut [u8], data: &T) -> Result<()> {
+        ensure!(
+            bytes.len() == Self::SPACE,
+            ProgramError::AccountDataTooSmall,
+            "Expected {} bytes for HashedAccount, found {}",
+            Self::SPACE,
+            bytes.len()
+        );
+        write_hashed(bytes, data);
+        Ok(())
+    }
+
+    /// Creates the account with [`Self::SPACE`] bytes and writes the initial value and its hash.
+    fn init_with<const IF_NEEDED: bool>(
+        &mut self,
+        init_value: impl FnOnce() -> T,
+        funder: &(impl CanFundRent + ?Sized),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        if IF_NEEDED {
+            let needs_init = self.info.owner().fast_eq(&System::ID)
+                || self.account_data()?[..size_of::<OwnerProgramDiscriminant<T>>()]
+                    .iter()
+                    .all(|x| *x == 0);
+            if !needs_init {
+                return Ok(false);
+            }
+        }
+        self.check_writable()?;
+        self.system_create_account(funder, T::OwnerProgram::ID, Self::SPACE, account_seeds, ctx)
+            .ctx("system_create_account failed")?;
+        Self::write_data(&mut self.info.account_data_mut()?, &init_value())?;
+        Ok(true)
+    }
+
+    fn split(bytes: &[u8]) -> Result<(&[u8], [u8; HASH_SIZE])> {
+        ensure!(
+            bytes.len() == Self::SPACE,
+            ProgramError::InvalidAccountData,
+            "Expected {} bytes for HashedAccount, found {}",
+            Self::SPACE,
+            bytes.len()
+        );
+        let (hashed, hash) = bytes.split_at(Self::SPACE - HASH_SIZE);
+        Ok((hashed, hash.try_into()?))
+    }
+}
+
+/// Writes the discriminant, `data`, and the hash of both to `bytes`, which must be [`HashedAccount::SPACE`] bytes long.
+fn write_hashed<T: ProgramAccount + Pod>(bytes: &mut [u8], data: &T) {
+    let discriminant_size = size_of::<OwnerProgramDiscriminant<T>>();
+    let (hashed, hash) = bytes.split_at_mut(HashedAccount::<T>::SPACE - HASH_SIZE);
+    hashed[..discriminant_size].copy_from_slice(bytes_of(&T::DISCRIMINANT));
+    hashed[discriminant_size..].copy_from_slice(bytes_of(data));
+    hash.copy_from_slice(&sha256(hashed));
+}
+
+/// A mutable copy of a [`HashedAccount`]'s data, returned by [`HashedAccount::data_mut`].
+///
+/// The data is written back to the account and the hash is updated when the guard is dropped.
+#[derive(Deref, DerefMut)]
+pub struct HashedDataMut<'a, T: ProgramAccount + Pod> {
+    bytes: RefMut<'a, [u8]>,
+    #[deref]
+    #[deref_mut]
+    data: T,
+}
+
+impl<T: ProgramAccount + Pod> Drop for HashedDataMut<'_, T> {
+    fn drop(&mut self) {
+        // The length was checked when the data was read
+        write_hashed(&mut self.bytes, &self.data);
+    }
+}
+
+/// Hashes `data` with SHA-256, using the `sol_sha256` syscall on chain.
+fn sha256(data: &[u8]) -> [u8; HASH_SIZE] {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = [0; HASH_SIZE];
+        let slices = [data];
+        // SAFETY:
+        // `slices` is an array of one slice, which has the layout the syscall expects, and `hash` is 32 bytes
+        unsafe {
+            pinocchio::syscalls::sol_sha256(
+                slices.as_ptr().cast(),
+                slices.len() as u64,
+                hash.as_mut_ptr(),
+            );
+        }
+        hash
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(data).into()
+    }
+}
+
+impl<T: ProgramAccount + Pod> HasOwnerProgram for HashedAccount<T> {
+    type OwnerProgram = T::OwnerProgram;
+}
+
+impl<T: ProgramAccount + Pod + 'static> HasInnerType for HashedAccount<T> {
+    type Inner = T;
+}
+
+impl<T: ProgramAccount + Pod + Default> CanInitAccount<()> for HashedAccount<T> {
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        _arg: (),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        self.init_account::<IF_NEEDED>(T::default, account_seeds, ctx)
+    }
+}
+
+impl<T, InitFn> CanInitAccount<InitFn> for HashedAccount<T>
+where
+    T: ProgramAccount + Pod,
+    InitFn: FnOnce() -> T,
+{
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: InitFn,
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let funder = ctx.get_funder().ok_or_else(|| {
+            error!(
+                ErrorCode::EmptyFunderCache,
+                "Missing tagged `funder` for HashedAccount `init_account`"
+            )
+        })?;
+        self.init_with::<IF_NEEDED>(arg, funder, account_seeds, ctx)
+    }
+}
+
+impl<T, Funder> CanInitAccount<(&Funder,)> for HashedAccount<T>
+where
+    T: ProgramAccount + Pod + Default,
+    Funder: CanFundRent + ?Sized,
+{
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: (&Funder,),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        self.init_with::<IF_NEEDED>(T::default, arg.0, account_seeds, ctx)
+    }
+}
+
+impl<T, InitFn, Funder> CanInitAccount<(InitFn, &Funder)> for HashedAccount<T>
+where
+    T: ProgramAccount + Pod,
+    InitFn: FnOnce() -> T,
+    Funder: CanFundRent + ?Sized,
+{
+    #[inline]
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: (InitFn, &Funder),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let (init_value, funder) = arg;
+        self.init_with::<IF_NEEDED>(init_value, funder, account_seeds, ctx)
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use star_frame::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<T, A> AccountSetToIdl<A> for HashedAccount<T>
+    where
+        AccountInfo: AccountSetToIdl<A>,
+        T: ProgramAccount + Pod + AccountToIdl,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            let mut set = <AccountInfo>::account_set_to_idl(idl_definition, arg)?;
+            set.single()?
+                .program_accounts
+                .push(T::account_to_idl(idl_definition)?);
+            Ok(set)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::StarFrameError;
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl)]
+    struct Vault {
+        balance: u64,
+        authority: Pubkey,
+    }
+
+    #[test]
+    fn test_hashed_account_detects_corruption() -> Result<()> {
+        let vault = Vault {
+            balance: 100,
+            authority: Pubkey::new_from_array([1; 32]),
+        };
+        let mut bytes = vec![0; HashedAccount::<Vault>::SPACE];
+        HashedAccount::write_data(&mut bytes, &vault)?;
+        assert_eq!(HashedAccount::<Vault>::read_data(&bytes)?, vault);
+
+        // Modifying the data without updating the hash is detected
+        let mut corrupted = bytes.clone();
+        corrupted[size_of::<OwnerProgramDiscriminant<Vault>>()] ^= 1;
+        let err = HashedAccount::<Vault>::read_data(&corrupted).unwrap_err();
+        assert_eq!(
+            ProgramError::from(err),
+            ProgramError::Custom(ErrorCode::HashMismatch.code())
+        );
+
+        // So is modifying the hash
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(HashedAccount::<Vault>::read_data(&corrupted).is_err());
+
+        // Writing through `HashedAccount` keeps the hash valid
+        let updated = Vault {
+            balance: 50,
+            ..vault
+        };
+        HashedAccount::write_data(&mut bytes, &updated)?;
+        assert_eq!(HashedAccount::<Vault>::read_data(&bytes)?, updated);
+
+        assert!(HashedAccount::<Vault>::read_data(&bytes[1..]).is_err());
+        Ok(())
+    }
+}
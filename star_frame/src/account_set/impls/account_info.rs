@@ -4,8 +4,8 @@ use std::mem::MaybeUninit;
 
 use crate::{
     account_set::{
-        single_set::SingleSetMeta, AccountSetCleanup, AccountSetDecode, AccountSetValidate,
-        ClientAccountSet, CpiAccountSet,
+        single_set::SingleSetMeta, AccountKey, AccountSetCleanup, AccountSetDecode, AccountSetKeys,
+        AccountSetValidate, ClientAccountSet, CpiAccountSet,
     },
     prelude::*,
 };
@@ -164,6 +164,24 @@ impl SingleAccountSet for &AccountInfo {
         self
     }
 }
+
+impl AccountSetKeys for AccountInfo {
+    #[inline]
+    fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>) {
+        keys.push(AccountKey {
+            pubkey: SingleAccountSet::pubkey(self),
+            writable: false,
+            signer: false,
+        });
+    }
+}
+
+impl AccountSetKeys for &AccountInfo {
+    #[inline]
+    fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>) {
+        AccountInfo::collect_account_keys(self, keys);
+    }
+}
 impl<'a> AccountSetDecode<'a, ()> for AccountInfo {
     #[inline]
     fn decode_accounts(
@@ -4,7 +4,8 @@ use std::{mem::MaybeUninit, ops::Mul};
 
 use crate::{
     account_set::{
-        AccountSetCleanup, AccountSetDecode, AccountSetValidate, ClientAccountSet, CpiAccountSet,
+        AccountKey, AccountSetCleanup, AccountSetDecode, AccountSetKeys, AccountSetValidate,
+        ClientAccountSet, CpiAccountSet,
     },
     prelude::*,
 };
@@ -70,6 +71,18 @@ where
     }
 }
 
+impl<A, const N: usize> AccountSetKeys for [A; N]
+where
+    A: AccountSetKeys,
+{
+    #[inline]
+    fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>) {
+        for a in self {
+            a.collect_account_keys(keys);
+        }
+    }
+}
+
 impl<'a, A, const N: usize, DArg> AccountSetDecode<'a, [DArg; N]> for [A; N]
 where
     A: AccountSetDecode<'a, DArg>,
@@ -9,7 +9,8 @@ use crate::{
             WritableAccount,
         },
         single_set::SingleSetMeta,
-        AccountSetCleanup, AccountSetDecode, AccountSetValidate, ClientAccountSet, CpiAccountSet,
+        AccountKey, AccountSetCleanup, AccountSetDecode, AccountSetKeys, AccountSetValidate,
+        ClientAccountSet, CpiAccountSet,
     },
     prelude::*,
 };
@@ -29,6 +30,16 @@ where
     }
 }
 
+impl<T> AccountSetKeys for Box<T>
+where
+    T: AccountSetKeys,
+{
+    #[inline]
+    fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>) {
+        T::collect_account_keys(self, keys);
+    }
+}
+
 unsafe impl<T> CpiAccountSet for Box<T>
 where
     T: CpiAccountSet,
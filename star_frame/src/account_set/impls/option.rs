@@ -1,11 +1,16 @@
 //! `AccountSet` implementations for optional types. Enables conditional account presence using `Option<T>` syntax with automatic handling of None cases.
+//!
+//! A `None` account is represented by passing the executing program's id in its place, matching the
+//! `programId` optional account strategy used by Codama and Anchor. The system program is not used
+//! as the sentinel, since it is a valid account for many optional fields (e.g. `Option<Program<System>>`).
+//! Validation and cleanup arguments are only passed to the inner account set when the account is `Some`.
 
 use std::mem::MaybeUninit;
 
 use crate::{
     account_set::{
-        AccountSetCleanup, AccountSetDecode, AccountSetValidate, CheckKey, ClientAccountSet,
-        CpiAccountSet, DynamicCpiAccountSetLen,
+        AccountKey, AccountSetCleanup, AccountSetDecode, AccountSetKeys, AccountSetValidate,
+        CheckKey, ClientAccountSet, CpiAccountSet, DynamicCpiAccountSetLen,
     },
     prelude::*,
     ErrorCode,
@@ -149,6 +154,18 @@ where
     }
 }
 
+impl<T> AccountSetKeys for Option<T>
+where
+    T: AccountSetKeys,
+{
+    #[inline]
+    fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>) {
+        if let Some(inner) = self {
+            inner.collect_account_keys(keys);
+        }
+    }
+}
+
 impl<T> CheckKey for Option<T>
 where
     T: CheckKey,
@@ -4,7 +4,8 @@ use std::mem::MaybeUninit;
 
 use crate::{
     account_set::{
-        AccountSetCleanup, AccountSetDecode, AccountSetValidate, ClientAccountSet, CpiAccountSet,
+        AccountKey, AccountSetCleanup, AccountSetDecode, AccountSetKeys, AccountSetValidate,
+        ClientAccountSet, CpiAccountSet,
     },
     prelude::*,
 };
@@ -21,6 +22,11 @@ impl ClientAccountSet for () {
     }
 }
 
+impl AccountSetKeys for () {
+    #[inline]
+    fn collect_account_keys<'a>(&'a self, _keys: &mut Vec<AccountKey<'a>>) {}
+}
+
 unsafe impl CpiAccountSet for () {
     type ContainsOption = typenum::False;
     type CpiAccounts = ();
@@ -2,8 +2,8 @@
 
 use crate::{
     account_set::{
-        AccountSetCleanup, AccountSetDecode, AccountSetValidate, ClientAccountSet, CpiAccountSet,
-        DynamicCpiAccountSetLen,
+        AccountKey, AccountSetCleanup, AccountSetDecode, AccountSetKeys, AccountSetValidate,
+        ClientAccountSet, CpiAccountSet, DynamicCpiAccountSetLen,
     },
     prelude::*,
 };
@@ -63,6 +63,18 @@ where
     }
 }
 
+impl<T> AccountSetKeys for Vec<T>
+where
+    T: AccountSetKeys,
+{
+    #[inline]
+    fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>) {
+        for account in self {
+            account.collect_account_keys(keys);
+        }
+    }
+}
+
 impl<'a, T> AccountSetDecode<'a, usize> for Vec<T>
 where
     T: AccountSetDecode<'a, ()>,
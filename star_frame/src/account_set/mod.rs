@@ -2,21 +2,24 @@
 pub mod account;
 pub mod borsh_account;
 pub(crate) mod cpi_const_wrapper;
+pub mod hashed_account;
 mod impls; // Just impls, no need to re-export
 pub mod modifiers;
+pub mod multi_signer;
 pub mod program;
 pub mod rest;
 pub mod single_set;
 pub mod system_account;
 pub mod sysvar;
 pub mod validated_account;
+pub mod versioned;
 
 pub use star_frame_proc::{AccountSet, ProgramAccount};
 
 use crate::{prelude::*, ErrorCode};
 use bytemuck::bytes_of;
 use modifiers::{HasOwnerProgram, OwnerProgramDiscriminant};
-use std::{mem::MaybeUninit, slice};
+use std::{marker::PhantomData, mem::MaybeUninit, slice};
 
 /// An account that has a discriminant and is owned by a [`StarFrameProgram`].
 ///
@@ -24,6 +27,16 @@ use std::{mem::MaybeUninit, slice};
 pub trait ProgramAccount: HasOwnerProgram {
     /// The discriminant of the account. This should be unique for each account type in a program.
     const DISCRIMINANT: <Self::OwnerProgram as StarFrameProgram>::AccountDiscriminant;
+    /// The size of the account data after the discriminant, if it is fixed.
+    ///
+    /// The [`derive@ProgramAccount`] macro sets this to `size_of::<Self>()` for zero-copy types, or to the value of
+    /// `#[program_account(space = <expr>)]`. Borsh accounts are created with at least this much space.
+    const DATA_SPACE: Option<usize> = None;
+    /// Skips the owner check in [`Self::validate_account_info`], for accounts that can be owned by any program.
+    ///
+    /// Set with `#[program_account(any_program)]` on [`derive@ProgramAccount`].
+    const ANY_OWNER: bool = false;
+
     /// The discriminant of the account as bytes.
     #[must_use]
     #[inline]
@@ -31,13 +44,25 @@ pub trait ProgramAccount: HasOwnerProgram {
         bytes_of(&Self::DISCRIMINANT).into()
     }
 
+    /// Migrates the account data in place from an older version before it is validated.
+    ///
+    /// Does nothing by default. The [`derive@ProgramAccount`] macro overrides this for accounts with
+    /// `#[program_account(migrate_from = <ty>)]`, see [`versioned`].
+    #[allow(clippy::inline_always)]
+    #[inline(always)]
+    fn migrate_account_info(_info: AccountInfo) -> Result<()> {
+        Ok(())
+    }
+
     /// Validates the owner matches [`Self::OwnerProgram::ID`](`crate::program::StarFrameProgram::ID`) and the discriminant matches [`Self::DISCRIMINANT`].
+    ///
+    /// The owner check is skipped if [`Self::ANY_OWNER`] is set.
     #[allow(clippy::inline_always)]
     #[inline(always)]
     fn validate_account_info(info: AccountInfo) -> Result<()> {
         validate_discriminant::<Self>(info)?;
 
-        if !info.owner().fast_eq(&Self::OwnerProgram::ID) {
+        if !Self::ANY_OWNER && !info.owner().fast_eq(&Self::OwnerProgram::ID) {
             bail!(
                 ProgramError::InvalidAccountOwner,
                 "Account {} owner {} does not match expected program ID {}",
@@ -51,6 +76,36 @@ pub trait ProgramAccount: HasOwnerProgram {
     }
 }
 
+/// A [`ProgramAccount`] whose size is computed from an init argument, for accounts that are sized when created.
+///
+/// Derivable via `#[program_account(space_fn = <fn>)]` on [`derive@ProgramAccount`], and used by
+/// [`WithSizeFn`](modifiers::init::WithSizeFn).
+pub trait ProgramAccountSpaceFn: ProgramAccount {
+    /// The size of the account data after the discriminant for the init argument `n`.
+    fn data_space_for(n: usize) -> usize;
+}
+
+/// Resolves [`ProgramAccount::DATA_SPACE`] in the [`derive@ProgramAccount`] macro.
+///
+/// `AccountDataSpace::<T>::SPACE` resolves to the inherent constant for zero-copy types, and falls back to
+/// [`UnknownAccountDataSpace::SPACE`] for everything else.
+#[doc(hidden)]
+pub struct AccountDataSpace<T: ?Sized>(PhantomData<T>);
+
+impl<T> AccountDataSpace<T>
+where
+    T: CheckedBitPattern + NoUninit + Align1,
+{
+    pub const SPACE: Option<usize> = Some(size_of::<T>());
+}
+
+#[doc(hidden)]
+pub trait UnknownAccountDataSpace {
+    const SPACE: Option<usize> = None;
+}
+
+impl<T: ?Sized> UnknownAccountDataSpace for AccountDataSpace<T> {}
+
 /// Fast discriminant comparison, with fast path unaligned reads for small discriminants.
 ///
 /// Adapted from [Typhoon](https://github.com/exotic-markets-labs/typhoon/blob/60c5197cc632f1bce07ba27876669e4ca8580421/crates/accounts/src/discriminator.rs#L8)
@@ -194,6 +249,11 @@ pub trait AccountSetDecode<'a, A>: Sized {
 /// Evaluate wrapping as inner before outer.
 ///
 /// Derivable via [`derive@AccountSet`].
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be validated with an arg of type `{A}`",
+    label = "this expression returns `{A}`, but `{Self}` does not implement `AccountSetValidate<{A}>`",
+    note = "check the field's `#[validate(arg = ...)]` attribute, or the `#[validate(arg = ...)]` of `{Self}`"
+)]
 pub trait AccountSetValidate<A> {
     /// Validate the accounts using `validate_input`.
     #[rust_analyzer::completions(ignore_flyimport)]
@@ -255,6 +315,63 @@ pub trait ClientAccountSet {
     );
 }
 
+/// A single account key collected by [`AccountSetKeys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountKey<'a> {
+    pub pubkey: &'a Pubkey,
+    /// Whether the account set requires the account to be writable.
+    pub writable: bool,
+    /// Whether the account set requires the account to be a signer.
+    pub signer: bool,
+}
+
+impl AccountKey<'_> {
+    /// Returns true if both keys are the same account and the account set could alias mutable data: both are
+    /// writable, or one is a signer and the other is writable but not a signer.
+    #[must_use]
+    pub fn conflicts_with(&self, other: &AccountKey) -> bool {
+        self.pubkey.fast_eq(other.pubkey)
+            && ((self.writable && other.writable)
+                || (self.signer && other.writable && !other.signer)
+                || (other.signer && self.writable && !self.signer))
+    }
+}
+
+/// Collects the keys of the accounts in an [`AccountSet`], used for duplicate account detection.
+///
+/// Derivable via [`derive@AccountSet`], and required for the accounts of a [`StarFrameInstruction`].
+pub trait AccountSetKeys {
+    /// Pushes the key of every account in the set to `keys`, in order.
+    #[rust_analyzer::completions(ignore_flyimport)]
+    fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>);
+}
+
+/// Returns an error if two accounts in `set` conflict according to [`AccountKey::conflicts_with`].
+///
+/// Called once on the top-level accounts of each [`StarFrameInstruction`] after they are decoded. Sets with
+/// `#[account_set(skip_duplicate_check)]` don't collect any keys. The check only runs in debug builds, or with the
+/// `duplicate_account_check` feature.
+pub fn check_duplicate_accounts(set: &(impl AccountSetKeys + ?Sized)) -> Result<()> {
+    if !cfg!(any(debug_assertions, feature = "duplicate_account_check")) {
+        return Ok(());
+    }
+    let mut keys = Vec::new();
+    set.collect_account_keys(&mut keys);
+    for (index, key) in keys.iter().enumerate() {
+        if keys[index + 1..]
+            .iter()
+            .any(|other| key.conflicts_with(other))
+        {
+            bail!(
+                ErrorCode::DuplicateAccount,
+                "Account {} is used more than once in conflicting positions",
+                key.pubkey
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Used to check if the key matches the expected key.
 pub trait CheckKey {
     /// Checks if the key matches the expected key.
@@ -297,11 +414,22 @@ pub trait CanFundRent: CanAddLamports {
 pub trait CanCloseAccount {
     /// Closes the account by zeroing the lamports and replacing the discriminant with all `u8::MAX`,
     /// reallocating down to size.
+    ///
+    /// In debug builds or with the `close_validation` feature, returns [`ErrorCode::CloseToSelf`] if `recipient` is
+    /// the account being closed, which would burn its lamports.
     fn close_account(&self, recipient: &(impl CanAddLamports + ?Sized)) -> Result<()>
     where
         Self: HasOwnerProgram,
         Self: Sized;
 
+    /// Closes the account like [`Self::close_account`], configured by `config`.
+    ///
+    /// Uses the [`Context`] recipient if [`CloseConfig::recipient`](account::CloseConfig::recipient) is not set.
+    fn close_account_with_config(&self, config: account::CloseConfig, ctx: &Context) -> Result<()>
+    where
+        Self: HasOwnerProgram,
+        Self: Sized;
+
     /// Closes the account by reallocating to zero and assigning to the System program.
     /// This is the same as calling `close` but not abusable and harder for indexer detection.
     ///
@@ -316,6 +444,17 @@ pub trait CanModifyRent {
     /// If the account has 0 lamports (i.e., it is set to be closed), this will do nothing.
     fn normalize_rent(&self, funder: &(impl CanFundRent + ?Sized), ctx: &Context) -> Result<()>;
 
+    /// Normalizes the rent of an account to the lamports given by `target`, funding or refunding `funder`.
+    /// Assumes `Self` is mutable and owned by this program.
+    ///
+    /// If the account has 0 lamports (i.e., it is set to be closed), this will do nothing.
+    fn normalize_rent_to(
+        &self,
+        target: RentTarget,
+        funder: &(impl CanFundRent + ?Sized),
+        ctx: &Context,
+    ) -> Result<()>;
+
     /// Refunds rent to the funder so long as the account has more than the minimum rent.
     /// Assumes `Self` is owned by this program and is mutable.
     ///
@@ -377,36 +516,99 @@ pub(crate) mod internal_reverse {
     {
         this.cleanup_accounts(cleanup_input, ctx)
     }
+
+    /// Caches a `#[validate(funder)]` field in the [`Context`] if no funder is set yet. `Option` fields are only
+    /// cached when present, falling back to [`CacheRequiredFunder`] for everything else.
+    pub struct CacheFunder<'a, T>(pub &'a T);
+
+    impl<T: CanFundRent + Clone + 'static> CacheFunder<'_, Option<T>> {
+        #[inline]
+        pub fn cache_funder(self, ctx: &mut Context) {
+            if let Some(funder) = self.0 {
+                if ctx.get_funder().is_none() {
+                    ctx.set_funder(Box::new(funder.clone()));
+                }
+            }
+        }
+    }
+
+    pub trait CacheRequiredFunder {
+        fn cache_funder(self, ctx: &mut Context);
+    }
+
+    impl<T: CanFundRent + Clone + 'static> CacheRequiredFunder for CacheFunder<'_, T> {
+        #[inline]
+        fn cache_funder(self, ctx: &mut Context) {
+            if ctx.get_funder().is_none() {
+                ctx.set_funder(Box::new(self.0.clone()));
+            }
+        }
+    }
+
+    /// Unwraps a `#[validate(temp = ...)]` value that is a [`Result`], falling back to [`NoResultTemp`] to use any
+    /// other value as is.
+    pub struct ValidateTemp<T>(pub T);
+
+    impl<T, E: Into<Error>> ValidateTemp<core::result::Result<T, E>> {
+        #[inline]
+        pub fn into_temp(self) -> Result<T> {
+            self.0.map_err(Into::into)
+        }
+    }
+
+    pub trait NoResultTemp {
+        type Temp;
+
+        fn into_temp(self) -> Result<Self::Temp>;
+    }
+
+    impl<T> NoResultTemp for ValidateTemp<T> {
+        type Temp = T;
+
+        #[inline]
+        fn into_temp(self) -> Result<T> {
+            Ok(self.0)
+        }
+    }
 }
 
 pub(crate) mod prelude {
     use super::*;
     pub use super::{
         AccountSet, CanCloseAccount as _, CanModifyRent as _, CheckKey as _, ProgramAccount,
-        TryFromAccounts, TryFromAccountsWithArgs,
+        ProgramAccountSpaceFn, TryFromAccounts, TryFromAccountsWithArgs,
     };
     pub use account::{
-        discriminant, Account, CloseAccount, NormalizeRent, ReceiveRent, RefundRent,
+        discriminant, Account, CloseAccount, CloseAndReinit, CloseConfig, NormalizeRent,
+        ReceiveRent, RefundRent, RentTarget,
     };
-    pub use borsh_account::BorshAccount;
+    pub use borsh_account::{BorshAccount, NormalizeRentAfterRealloc};
+    pub use hashed_account::{HashedAccount, HashedDataMut};
     pub use modifiers::{
-        init::{Create, CreateIfNeeded, Init},
+        immutable::Immutable,
+        init::{Create, CreateIfNeeded, Init, ReinitOrRealloc, WithSizeFn, WithSpace},
         mutable::Mut,
-        seeded::{GetSeeds, Seed, Seeded, Seeds, SeedsWithBump},
+        seeded::{
+            arg_path, seed_const, seed_path, FindSeed, GetSeeds, Seed, Seeded, SeededFor, Seeds,
+            SeedsFor, SeedsWithBump,
+        },
         signer::Signer,
     };
-    pub use program::Program;
+    pub use multi_signer::MultiSigner;
+    pub use program::{Interface, Program, ProgramInterface};
     pub use rest::Rest;
     pub use single_set::SingleAccountSet;
     pub use system_account::SystemAccount;
     pub use sysvar::Sysvar;
-    pub use validated_account::{AccountValidate, ValidatedAccount};
+    pub use validated_account::{AccountValidate, AccountValidateWithContext, ValidatedAccount};
+    pub use versioned::{MigrateFrom, VersionedAccount};
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{account_set::AccountSetValidate, prelude::Context};
+    use crate::{account_set::AccountSetValidate, prelude::*};
     use star_frame_proc::AccountSet;
+    use std::cell::RefCell;
 
     #[derive(AccountSet)]
     #[validate(arg = &mut Vec<usize>, extra_validation = { arg.push(N); Ok(()) })]
@@ -456,6 +658,160 @@ mod test {
         c: InnerAccount<3>,
     }
 
+    #[derive(AccountSet)]
+    #[account_set(skip_client_account_set, skip_cpi_account_set)]
+    #[validate(arg = &mut Vec<usize>)]
+    struct OptionalAccountSet {
+        #[validate(arg = &mut *arg)]
+        a: Option<InnerAccount<1>>,
+        #[validate(arg = &mut *arg)]
+        b: Option<InnerAccount<2>>,
+        #[validate(arg = &mut *arg)]
+        c: InnerAccount<3>,
+    }
+
+    // `b` requires `a`, which is declared after it and requires `c`
+    #[derive(AccountSet)]
+    #[validate(arg = &mut Vec<usize>)]
+    struct AccountSetChained {
+        #[validate(arg = &mut *arg, requires = [c])]
+        a: InnerAccount<1>,
+        #[validate(arg = &mut *arg, requires = [a])]
+        b: InnerAccount<2>,
+        #[validate(arg = &mut *arg)]
+        c: InnerAccount<3>,
+    }
+
+    #[derive(AccountSet)]
+    #[validate(arg = &mut Vec<usize>)]
+    struct AccountSetDiamond {
+        #[validate(arg = &mut *arg, requires = [b, c])]
+        d: InnerAccount<4>,
+        #[validate(arg = &mut *arg, requires = [a])]
+        c: InnerAccount<3>,
+        #[validate(arg = &mut *arg, requires = [a])]
+        b: InnerAccount<2>,
+        #[validate(arg = &mut *arg)]
+        a: InnerAccount<1>,
+    }
+
+    // `b` has no `arg`, but still orders `a` after `c`
+    #[derive(AccountSet)]
+    #[validate(arg = &mut Vec<usize>)]
+    struct AccountSetNoArgRequires {
+        #[validate(arg = &mut *arg, requires = [b])]
+        a: InnerAccount<1>,
+        #[validate(skip, requires = [c])]
+        b: InnerAccount<2>,
+        #[validate(arg = &mut *arg)]
+        c: InnerAccount<3>,
+    }
+
+    trait TestId {
+        const ID: usize;
+    }
+
+    #[derive(AccountSet)]
+    struct IdAccount<const N: usize>;
+
+    impl<const N: usize> TestId for IdAccount<N> {
+        const ID: usize = N;
+    }
+
+    #[derive(AccountSet)]
+    #[validate(arg = &mut Vec<usize>, extra_validation = { arg.push(T::ID); Ok(()) })]
+    struct GenericAccountSet<T: TestId> {
+        inner: T,
+        optional: Option<T>,
+    }
+
+    #[derive(AccountSet)]
+    #[validate(arg = (&mut Vec<Pubkey>, &Pubkey), extra_validation = { arg.0.push(*arg.1); Ok(()) })]
+    struct KeyAccount;
+
+    /// Stands in for an expensive PDA derivation, counting how often it is called.
+    fn find_address(seed: u8, derivations: &mut usize) -> Result<(Pubkey, u8)> {
+        *derivations += 1;
+        ensure!(seed != 0, ProgramError::InvalidSeeds, "Seed cannot be zero");
+        Ok((Pubkey::new_from_array([seed; 32]), seed))
+    }
+
+    #[derive(AccountSet)]
+    #[validate(arg = (&mut Vec<Pubkey>, &mut usize, u8))]
+    struct TempAccountSet {
+        // The `Result` is unwrapped, and `arg` borrows from it
+        #[validate(temp = find_address(arg.2, arg.1), arg = (&mut *arg.0, &temp.0))]
+        a: KeyAccount,
+        // Each field has its own `temp`
+        #[validate(temp = Pubkey::new_from_array([arg.2 + 1; 32]), arg = (&mut *arg.0, &temp))]
+        b: KeyAccount,
+    }
+
+    #[test]
+    fn test_validate_temp() -> Result<()> {
+        let mut keys = Vec::new();
+        let mut derivations = 0;
+        let mut ctx = Context::default();
+        let mut set = TempAccountSet {
+            a: KeyAccount,
+            b: KeyAccount,
+        };
+        set.validate_accounts((&mut keys, &mut derivations, 1), &mut ctx)?;
+        assert_eq!(
+            keys,
+            [
+                Pubkey::new_from_array([1; 32]),
+                Pubkey::new_from_array([2; 32])
+            ]
+        );
+        assert_eq!(derivations, 1);
+
+        let err = set
+            .validate_accounts((&mut keys, &mut derivations, 0), &mut ctx)
+            .unwrap_err();
+        assert_eq!(ProgramError::from(err), ProgramError::InvalidSeeds);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_generic() {
+        let mut vec = Vec::new();
+        let mut ctx = Context::default();
+        let mut set = GenericAccountSet {
+            inner: IdAccount::<1>,
+            optional: None,
+        };
+        set.validate_accounts(&mut vec, &mut ctx).unwrap();
+        let mut set = GenericAccountSet {
+            inner: IdAccount::<2>,
+            optional: Some(IdAccount::<2>),
+        };
+        set.validate_accounts(&mut vec, &mut ctx).unwrap();
+        assert_eq!(vec, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_validate_optional() {
+        let mut vec = Vec::new();
+        let mut ctx = Context::default();
+        let mut set = OptionalAccountSet {
+            a: Some(InnerAccount::<1>),
+            b: None,
+            c: InnerAccount::<3>,
+        };
+        set.validate_accounts(&mut vec, &mut ctx).unwrap();
+        assert_eq!(vec, vec![1, 3]);
+
+        vec.clear();
+        let mut set = OptionalAccountSet {
+            a: None,
+            b: Some(InnerAccount::<2>),
+            c: InnerAccount::<3>,
+        };
+        set.validate_accounts(&mut vec, &mut ctx).unwrap();
+        assert_eq!(vec, vec![2, 3]);
+    }
+
     #[test]
     fn test_validate() {
         let mut vec = Vec::new();
@@ -495,4 +851,387 @@ mod test {
         set.validate_accounts(&mut vec, &mut ctx).unwrap();
         assert_eq!(vec, vec![2, 3, 1]);
     }
+
+    #[test]
+    fn test_validate_requires_order() {
+        let mut vec = Vec::new();
+        let mut ctx = Context::default();
+        let mut set = AccountSetChained {
+            a: InnerAccount::<1>,
+            b: InnerAccount::<2>,
+            c: InnerAccount::<3>,
+        };
+        set.validate_accounts(&mut vec, &mut ctx).unwrap();
+        assert_eq!(vec, vec![3, 1, 2]);
+
+        vec.clear();
+        let mut set = AccountSetDiamond {
+            d: InnerAccount::<4>,
+            c: InnerAccount::<3>,
+            b: InnerAccount::<2>,
+            a: InnerAccount::<1>,
+        };
+        set.validate_accounts(&mut vec, &mut ctx).unwrap();
+        assert_eq!(vec, vec![1, 3, 2, 4]);
+
+        vec.clear();
+        let mut set = AccountSetNoArgRequires {
+            a: InnerAccount::<1>,
+            b: InnerAccount::<2>,
+            c: InnerAccount::<3>,
+        };
+        set.validate_accounts(&mut vec, &mut ctx).unwrap();
+        assert_eq!(vec, vec![3, 1]);
+    }
+
+    /// Stands in for a sysvar that should only be fetched once per instruction.
+    #[derive(Default)]
+    struct CachedClock {
+        fetches: usize,
+        unix_timestamp: Option<i64>,
+        events: Vec<String>,
+    }
+
+    impl CachedClock {
+        fn load(&mut self) -> Result<()> {
+            self.fetches += 1;
+            self.unix_timestamp = Some(1_700_000_000);
+            self.events.push("before".to_string());
+            Ok(())
+        }
+
+        fn unix_timestamp(&self) -> Result<i64> {
+            self.unix_timestamp
+                .ok_or_else(|| error!(ProgramError::InvalidArgument, "Clock was not loaded"))
+        }
+    }
+
+    #[derive(AccountSet)]
+    #[validate(arg = (i64, &mut Vec<String>), extra_validation = {
+        ensure_eq!(arg.0, 1_700_000_000);
+        arg.1.push(format!("field {N}"));
+        Ok(())
+    })]
+    struct ClockCheckAccount<const N: usize>;
+
+    #[derive(AccountSet)]
+    #[validate(
+        arg = &mut CachedClock,
+        before_validation = arg.load(),
+        extra_validation = { arg.events.push("extra".to_string()); Ok(()) },
+        after_validation = { arg.events.push("after".to_string()); Ok(()) }
+    )]
+    struct ClockAccountSet {
+        #[validate(arg = (arg.unix_timestamp()?, &mut arg.events), requires = [b])]
+        a: ClockCheckAccount<1>,
+        #[validate(arg = (arg.unix_timestamp()?, &mut arg.events))]
+        b: ClockCheckAccount<2>,
+        #[validate(arg = (arg.unix_timestamp()?, &mut arg.events))]
+        c: ClockCheckAccount<3>,
+    }
+
+    #[test]
+    fn test_validate_struct_hook_order() -> Result<()> {
+        let mut clock = CachedClock::default();
+        let mut ctx = Context::default();
+        let mut set = ClockAccountSet {
+            a: ClockCheckAccount::<1>,
+            b: ClockCheckAccount::<2>,
+            c: ClockCheckAccount::<3>,
+        };
+        set.validate_accounts(&mut clock, &mut ctx)?;
+        assert_eq!(clock.fetches, 1);
+        assert_eq!(
+            clock.events,
+            ["before", "field 2", "field 1", "field 3", "extra", "after"]
+        );
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount)]
+    #[program_account(skip_idl)]
+    struct ConfigAccount {
+        value: u64,
+    }
+
+    #[derive(Debug, GetSeeds, Clone, Default)]
+    #[get_seeds(seed_const = b"CONFIG", skip_idl)]
+    struct ConfigSeeds {}
+
+    #[derive(AccountSet)]
+    #[account_set(skip_default_validate, skip_default_idl)]
+    struct ClientFieldsAccounts {
+        authority: Signer<SystemAccount>,
+        #[account_set(client_skip)]
+        config: Seeded<Mut<Account<ConfigAccount>>, ConfigSeeds>,
+        #[account_set(client_default = System::ID)]
+        system_program: Program<System>,
+    }
+
+    #[test]
+    fn test_client_fields() {
+        use crate::{account_set::ClientAccountSet, StarFrameDeclaredProgram};
+
+        let authority = Pubkey::new_unique();
+        let (config, _) = Pubkey::find_program_address(
+            &ConfigSeeds::default().seeds(),
+            &StarFrameDeclaredProgram::ID,
+        );
+        let mut metas = Vec::new();
+        ClientFieldsAccounts::extend_account_metas(
+            &StarFrameDeclaredProgram::ID,
+            &ClientFieldsClientAccounts { authority },
+            &mut metas,
+        );
+        assert_eq!(
+            metas,
+            vec![
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new(config, false),
+                AccountMeta::new_readonly(System::ID, false),
+            ]
+        );
+        assert_eq!(ClientFieldsAccounts::MIN_LEN, 3);
+    }
+
+    #[derive(AccountSet, Debug, PartialEq)]
+    struct PriceCache {
+        #[account_set(skip = 0)]
+        price: u64,
+    }
+
+    #[derive(AccountSet)]
+    #[decode(arg = u64)]
+    struct ComputedFieldAccounts<T> {
+        authority: T,
+        #[decode(skip = PriceCache { price: arg * 2 })]
+        cache: PriceCache,
+    }
+
+    #[test]
+    fn test_decode_skip() {
+        use crate::{
+            account_set::{AccountSetDecode, ClientAccountSet},
+            StarFrameDeclaredProgram,
+        };
+
+        let mut accounts: &[AccountInfo] = &[];
+        let mut ctx = Context::default();
+        let set =
+            ComputedFieldAccounts::<IdAccount<1>>::decode_accounts(&mut accounts, 21, &mut ctx)
+                .unwrap();
+        assert_eq!(set.cache, PriceCache { price: 42 });
+
+        // The computed field takes no account slot on the client
+        let authority = Pubkey::new_unique();
+        let mut metas = Vec::new();
+        ComputedFieldAccounts::<Signer<SystemAccount>>::extend_account_metas(
+            &StarFrameDeclaredProgram::ID,
+            &ComputedFieldClientAccounts { authority },
+            &mut metas,
+        );
+        assert_eq!(metas, vec![AccountMeta::new_readonly(authority, true)]);
+        assert_eq!(ComputedFieldAccounts::<Signer<SystemAccount>>::MIN_LEN, 1);
+    }
+
+    #[derive(AccountSet)]
+    #[validate(arg = (&RefCell<Vec<usize>>, usize), extra_validation = { arg.0.borrow_mut().push(arg.1); Ok(()) })]
+    #[cleanup(arg = (&RefCell<Vec<usize>>, usize), extra_cleanup = { arg.0.borrow_mut().push(arg.1); Ok(()) })]
+    struct IndexAccount;
+
+    #[derive(AccountSet)]
+    #[validate(arg = &RefCell<Vec<usize>>)]
+    #[cleanup(arg = &RefCell<Vec<usize>>)]
+    struct ArrayAccounts<const N: usize> {
+        // A one-tuple applies the same arg to every element
+        #[validate(arg = ((arg, 0),))]
+        #[cleanup(arg = ((arg, 0),))]
+        same: [IndexAccount; 2],
+        // An array gives each element its own arg
+        #[validate(arg = [(arg, 1), (arg, 2), (arg, 3)])]
+        #[cleanup(arg = [(arg, 3), (arg, 2), (arg, 1)])]
+        each: [IndexAccount; 3],
+        #[validate(arg = ((arg, 4),))]
+        #[cleanup(arg = ((arg, 4),))]
+        generic: [IndexAccount; N],
+    }
+
+    #[test]
+    fn test_array_fields() -> Result<()> {
+        use crate::account_set::AccountSetCleanup;
+
+        let indices = RefCell::new(Vec::new());
+        let mut ctx = Context::default();
+        let mut set = ArrayAccounts::<1> {
+            same: [IndexAccount, IndexAccount],
+            each: [IndexAccount, IndexAccount, IndexAccount],
+            generic: [IndexAccount],
+        };
+        set.validate_accounts(&indices, &mut ctx)?;
+        assert_eq!(indices.take(), [0, 0, 1, 2, 3, 4]);
+        set.cleanup_accounts(&indices, &mut ctx)?;
+        assert_eq!(indices.take(), [0, 0, 3, 2, 1, 4]);
+        Ok(())
+    }
+
+    #[derive(AccountSet)]
+    struct TrailingAccounts {
+        authority: Signer<SystemAccount>,
+        #[account_set(remaining)]
+        others: Vec<Mut<SystemAccount>>,
+    }
+
+    #[test]
+    fn test_remaining_client_accounts() {
+        use crate::{account_set::ClientAccountSet, StarFrameDeclaredProgram};
+
+        let authority = Pubkey::new_unique();
+        let others = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut metas = Vec::new();
+        TrailingAccounts::extend_account_metas(
+            &StarFrameDeclaredProgram::ID,
+            &TrailingClientAccounts {
+                authority,
+                others: others.clone(),
+            },
+            &mut metas,
+        );
+        assert_eq!(
+            metas,
+            vec![
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new(others[0], false),
+                AccountMeta::new(others[1], false),
+            ]
+        );
+        // The remaining accounts can be empty
+        assert_eq!(TrailingAccounts::MIN_LEN, 1);
+    }
+
+    static WRITABLE_COUNTER: std::sync::atomic::AtomicBool =
+        std::sync::atomic::AtomicBool::new(false);
+
+    fn counter_meta() -> crate::account_set::single_set::SingleSetMeta {
+        crate::account_set::single_set::SingleSetMeta {
+            writable: WRITABLE_COUNTER.load(std::sync::atomic::Ordering::Relaxed),
+            ..SystemAccount::meta()
+        }
+    }
+
+    #[derive(AccountSet)]
+    struct WrappedCounter {
+        #[single_account_set(meta = counter_meta())]
+        counter: SystemAccount,
+    }
+
+    #[test]
+    fn test_single_set_custom_meta() {
+        use crate::{account_set::ClientAccountSet, StarFrameDeclaredProgram};
+
+        let counter = Pubkey::new_unique();
+        let metas = || {
+            let mut metas = Vec::new();
+            WrappedCounter::extend_account_metas(
+                &StarFrameDeclaredProgram::ID,
+                &counter,
+                &mut metas,
+            );
+            metas
+        };
+
+        assert!(!WrappedCounter::meta().writable);
+        assert_eq!(metas(), vec![AccountMeta::new_readonly(counter, false)]);
+
+        // The meta is evaluated when the metas are built, not when the set is derived
+        WRITABLE_COUNTER.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(WrappedCounter::meta().writable);
+        assert_eq!(metas(), vec![AccountMeta::new(counter, false)]);
+        WRITABLE_COUNTER.store(false, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    #[test]
+    fn test_account_key_conflicts() {
+        use crate::{account_set::AccountKey, prelude::Pubkey};
+
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let key = |pubkey, writable, signer| AccountKey {
+            pubkey,
+            writable,
+            signer,
+        };
+
+        assert!(key(&a, true, false).conflicts_with(&key(&a, true, false)));
+        assert!(key(&a, true, true).conflicts_with(&key(&a, true, false)));
+        assert!(key(&a, false, true).conflicts_with(&key(&a, true, false)));
+        assert!(key(&a, true, false).conflicts_with(&key(&a, false, true)));
+        assert!(!key(&a, true, false).conflicts_with(&key(&b, true, false)));
+        assert!(!key(&a, false, false).conflicts_with(&key(&a, true, false)));
+        assert!(!key(&a, false, true).conflicts_with(&key(&a, false, false)));
+        assert!(!key(&a, false, true).conflicts_with(&key(&a, true, true)));
+    }
+
+    #[test]
+    fn test_check_duplicate_accounts() -> Result<()> {
+        use crate::{
+            account_set::{check_duplicate_accounts, AccountKey, AccountSetKeys},
+            errors::StarFrameError,
+            ErrorCode,
+        };
+
+        struct Key {
+            pubkey: Pubkey,
+            writable: bool,
+            signer: bool,
+        }
+
+        impl AccountSetKeys for Key {
+            fn collect_account_keys<'a>(&'a self, keys: &mut Vec<AccountKey<'a>>) {
+                keys.push(AccountKey {
+                    pubkey: &self.pubkey,
+                    writable: self.writable,
+                    signer: self.signer,
+                });
+            }
+        }
+
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let key = |pubkey, writable, signer| Key {
+            pubkey,
+            writable,
+            signer,
+        };
+
+        // A signer reused as a writable signer is allowed
+        check_duplicate_accounts(&[key(a, false, true), key(b, true, false), key(a, true, true)])?;
+        check_duplicate_accounts(&vec![Some(key(a, false, false)), Some(key(a, true, false))])?;
+
+        // Two writable accounts
+        let err = check_duplicate_accounts(&[
+            key(a, true, false),
+            key(b, false, true),
+            key(a, true, true),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            ProgramError::from(err),
+            ProgramError::Custom(ErrorCode::DuplicateAccount.code())
+        );
+
+        // A signer and a writable non-signer
+        let err = check_duplicate_accounts(&[
+            key(a, false, true),
+            key(b, true, false),
+            key(a, true, false),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            ProgramError::from(err),
+            ProgramError::Custom(ErrorCode::DuplicateAccount.code())
+        );
+        Ok(())
+    }
 }
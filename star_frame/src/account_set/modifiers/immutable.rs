@@ -0,0 +1,79 @@
+//! Account modifier for accounts that are only used read-only.
+//!
+//! The `Immutable<T>` modifier marks an account as read-only. The account is passed as not writable in
+//! [`CpiAccountSet`](crate::account_set::CpiAccountSet) and [`ClientAccountSet`](crate::account_set::ClientAccountSet)
+//! account metas, and only shared access to `T` is given out.
+//!
+//! Fields in a derived [`AccountSet`] can be treated as `Immutable<T>` with `#[validate(read_only)]`. `T` must not be
+//! [`Mut`], which is still checked to be writable during validation, so the derive rejects `Immutable<Mut<_>>` fields
+//! and `read_only` on `Mut` fields.
+use crate::{account_set::single_set::SingleSetMeta, prelude::*};
+use derive_more::Deref;
+use ref_cast::{ref_cast_custom, RefCastCustom};
+
+/// A read-only account, which is never marked as writable in CPIs or client instructions.
+///
+/// [`Immutable`] only dereferences to `&T` and does not implement
+/// [`WritableAccount`](crate::account_set::modifiers::WritableAccount), so APIs that require a writable account, like
+/// adding lamports or funding rent, are a compile error.
+///
+/// # Example
+/// ```
+/// # fn main() {}
+/// use star_frame::{account_set::modifiers::Immutable, prelude::*};
+///
+/// #[derive(AccountSet)]
+/// pub struct ReadOnlyAccounts {
+///     pub config: Immutable<SystemAccount>,
+///     // Equivalent to `Immutable<SystemAccount>` in CPIs, client instructions, and the IDL
+///     #[validate(read_only)]
+///     pub other: SystemAccount,
+/// }
+/// ```
+#[derive(AccountSet, Copy, Clone, Debug, Deref, RefCastCustom)]
+#[account_set(skip_default_idl)]
+#[repr(transparent)]
+pub struct Immutable<T>(
+    #[single_account_set(meta = SingleSetMeta { writable: false, ..T::meta() }, skip_writable_account)]
+    pub(crate) T,
+);
+
+impl<T> Immutable<T> {
+    /// Casts a reference to an account into a reference to the read-only account.
+    #[ref_cast_custom]
+    pub fn from_ref(inner: &T) -> &Self;
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use star_frame::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<T, A> AccountSetToIdl<A> for Immutable<T>
+    where
+        T: AccountSetToIdl<A> + SingleAccountSet,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            let mut set = T::account_set_to_idl(idl_definition, arg)?;
+            set.single()?.writable = false;
+            Ok(set)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_immutable_meta() {
+        assert!(<Mut<SystemAccount>>::meta().writable);
+        let meta = <Immutable<Mut<Signer<SystemAccount>>>>::meta();
+        assert!(!meta.writable);
+        assert!(meta.signer);
+    }
+}
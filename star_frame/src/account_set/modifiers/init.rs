@@ -7,16 +7,19 @@
 use std::cell::Cell;
 
 use crate::{
-    account_set::modifiers::{CanInitAccount, CanInitSeeds},
+    account_set::modifiers::{
+        CanInitAccount, CanInitSeeds, HasInnerType, HasOwnerProgram, OwnerProgramDiscriminant,
+    },
     prelude::*,
+    ErrorCode,
 };
 use derive_more::{Deref, DerefMut};
 
 /// A modifier that handles account initialization and creation during instruction execution.
 ///
 /// This wrapper enables accounts to be created or initialized as part of instruction processing.
-/// It supports different creation modes through validation arguments like `Create<T>` and
-/// `CreateIfNeeded<T>`, automatically handling seed initialization and account creation.
+/// It supports different creation modes through validation arguments like `Create<T>`,
+/// `CreateIfNeeded<T>`, and `ReinitOrRealloc<T>`, automatically handling seed initialization and account creation.
 #[derive(AccountSet, Clone, Debug, Deref, DerefMut)]
 #[account_set(skip_default_idl, skip_default_validate)]
 #[validate(
@@ -63,12 +66,35 @@ use derive_more::{Deref, DerefMut};
         Ok(())
     }
 )]
+#[validate(
+    id = "reinit_or_realloc",
+    generics = [<C> where T: CanInitSeeds<()> + CanInitAccount<C> + HasInnerType, T::Inner: ProgramAccount],
+    arg = ReinitOrRealloc<C>,
+    before_validation = {
+        self.init_seeds(&(), ctx).ctx("Failed to init seeds")?;
+        let needed_init = self.init_or_realloc(arg.0, ctx).ctx("Failed to init or realloc account")?;
+        self.needed_init.set(needed_init);
+        Ok(())
+    }
+)]
+#[validate(
+    id = "reinit_or_realloc_generic",
+    generics = [<C, A> where T: CanInitSeeds<A> + CanInitAccount<C> + HasInnerType, T::Inner: ProgramAccount],
+    arg = (ReinitOrRealloc<C>, A),
+    before_validation = {
+        self.init_seeds(&arg.1, ctx).ctx("Failed to init seeds")?;
+        let needed_init = self.init_or_realloc(arg.0.0, ctx).ctx("Failed to init or realloc account")?;
+        self.needed_init.set(needed_init);
+        Ok(())
+    }
+)]
 pub struct Init<T> {
     #[deref]
     #[deref_mut]
     #[single_account_set(writable, skip_can_init_seeds, skip_can_init_account)]
     #[validate(id = "create_generic", arg = arg.1)]
     #[validate(id = "create_if_needed_generic", arg = arg.1)]
+    #[validate(id = "reinit_or_realloc_generic", arg = arg.1)]
     inner: T,
     #[account_set(skip = Cell::new(false))]
     needed_init: Cell<bool>,
@@ -79,6 +105,42 @@ impl<T> Init<T> {
     pub fn needed_init(&self) -> bool {
         self.needed_init.get()
     }
+
+    /// Creates the account if it doesn't exist, or grows it to its [`ProgramAccount::DATA_SPACE`] if it is smaller,
+    /// zeroing the new bytes and funding rent with the [`Context`] funder. Returns whether the account was created.
+    fn init_or_realloc<C>(&mut self, arg: C, ctx: &Context) -> Result<bool>
+    where
+        T: CanInitAccount<C> + HasInnerType,
+        T::Inner: ProgramAccount,
+    {
+        let info = *self.inner.account_info();
+        if info.lamports() == 0 || info.owner().fast_eq(&System::ID) {
+            return self.inner.init_account::<false>(arg, None, ctx);
+        }
+        self.inner
+            .check_owner(&<T::Inner as HasOwnerProgram>::OwnerProgram::ID)?;
+        let Some(data_space) = T::Inner::DATA_SPACE else {
+            bail!(
+                ProgramError::InvalidArgument,
+                "`ReinitOrRealloc` requires a fixed `DATA_SPACE` for {}",
+                std::any::type_name::<T::Inner>()
+            );
+        };
+        let space = size_of::<OwnerProgramDiscriminant<T::Inner>>() + data_space;
+        let len = info.data_len();
+        if len < space {
+            info.resize(space)?;
+            info.account_data_mut()?[len..].fill(0);
+            let funder = ctx.get_funder().ok_or_else(|| {
+                error!(
+                    ErrorCode::EmptyFunderCache,
+                    "Missing tagged `funder` for `ReinitOrRealloc`"
+                )
+            })?;
+            self.inner.receive_rent(funder, ctx)?;
+        }
+        Ok(false)
+    }
 }
 
 /// Validation argument for `Init<T>` that requires account creation.
@@ -97,6 +159,33 @@ pub struct Create<T>(pub T);
 #[repr(transparent)]
 pub struct CreateIfNeeded<T>(pub T);
 
+/// Validation argument for `Init<T>` that creates the account if it doesn't exist, or grows it if it is too small.
+///
+/// Unlike [`CreateIfNeeded`], an existing account is checked to be owned by `T`'s program and reallocated to
+/// `T`'s [`ProgramAccount::DATA_SPACE`] if it is smaller, such as an account created with an older, smaller layout.
+/// The new bytes are zeroed and rent is funded by the `#[validate(funder)]`. Larger accounts are left as is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ReinitOrRealloc<T>(pub T);
+
+/// Init argument that creates the account with the given number of bytes after the discriminant.
+///
+/// Use this in place of the init argument (e.g. `Create(WithSpace(n))`) to pre-allocate space in accounts
+/// whose data can grow, such as [`BorshAccount`]s containing a `Vec`. Initialization fails if the space is
+/// less than the initial value needs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct WithSpace(pub usize);
+
+/// Init argument that creates the account with the space computed by its `#[program_account(space_fn = <fn>)]`
+/// from the given argument.
+///
+/// `Create(WithSizeFn(n))` is equivalent to `Create(WithSpace(T::data_space_for(n)))`, see
+/// [`ProgramAccountSpaceFn`](crate::account_set::ProgramAccountSpaceFn).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct WithSizeFn(pub usize);
+
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 mod idl_impl {
     use super::*;
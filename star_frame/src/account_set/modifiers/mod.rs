@@ -2,11 +2,13 @@
 
 use crate::{account_set::AccountSetValidate, prelude::*};
 
+pub mod immutable;
 pub mod init;
 pub mod mutable;
 pub mod seeded;
 pub mod signer;
 
+pub use immutable::*;
 pub use init::*;
 pub use mutable::*;
 pub use seeded::*;
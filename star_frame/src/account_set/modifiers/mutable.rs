@@ -4,13 +4,25 @@
 //! based on the `MUT` const generic. It provides type-safe access to the account's
 //! mutable state only when `MUT` is true, and automatically disables mutable operations
 //! when `MUT` is false.
+//!
+//! The `MaybeMutRuntime<T>` modifier instead decides whether the account is writable with a `bool`
+//! passed in during decode.
+use std::mem::MaybeUninit;
+
 use crate::{
-    account_set::{modifiers::WritableAccount, single_set::SingleSetMeta},
+    account_set::{
+        modifiers::WritableAccount, single_set::SingleSetMeta, AccountSetDecode, ClientAccountSet,
+        CpiAccountSet,
+    },
     prelude::*,
 };
 use derive_more::{Deref, DerefMut};
 
 /// A potentially mutable account, contingent on the `MUT` const generic being true.
+///
+/// `MaybeMut<false, T>` is a zero-cost pass-through to `T`: it has the same layout, decodes and validates exactly
+/// like `T`, is only writable in account metas and the IDL if `T` is, and only implements [`WritableAccount`] if `T`
+/// does.
 #[derive(AccountSet, Copy, Clone, Debug, Deref, DerefMut)]
 #[account_set(skip_default_idl)]
 #[validate(
@@ -18,7 +30,7 @@ use derive_more::{Deref, DerefMut};
 )]
 #[repr(transparent)]
 pub struct MaybeMut<const MUT: bool, T>(
-    #[single_account_set(meta = SingleSetMeta { writable: MUT, ..T::meta() }, skip_writable_account)]
+    #[single_account_set(meta = SingleSetMeta { writable: MUT || T::meta().writable, ..T::meta() }, skip_writable_account)]
     pub(crate) T,
 );
 
@@ -30,6 +42,146 @@ impl<T> WritableAccount for MaybeMut<true, T> where T: SingleAccountSet {}
 // A false MaybeMut just acts as a pass-through, so we need to pass this through!
 impl<T> WritableAccount for MaybeMut<false, T> where T: WritableAccount {}
 
+/// A potentially mutable account, where the mutability is decided at runtime by the `bool` decode arg.
+///
+/// When `writable` is true the account is checked to be writable during validation, and is marked as
+/// writable in [`CpiAccountSet`] and [`ClientAccountSet`] account metas. The inner account is always
+/// accessible, the flag only affects the checks and account metas.
+///
+/// # Example
+/// ```
+/// # fn main() {}
+/// use star_frame::{account_set::modifiers::MaybeMutRuntime, prelude::*};
+///
+/// #[derive(AccountSet)]
+/// #[decode(arg = bool)]
+/// pub struct MaybeWrite {
+///     #[decode(arg = arg)]
+///     pub account: MaybeMutRuntime<SystemAccount>,
+/// }
+/// ```
+#[derive(AccountSet, Copy, Clone, Debug, Deref, DerefMut)]
+#[account_set(
+    skip_default_decode,
+    skip_default_idl,
+    skip_cpi_account_set,
+    skip_client_account_set
+)]
+#[validate(
+    extra_validation = if self.writable { self.check_writable() } else { Ok(()) }
+)]
+pub struct MaybeMutRuntime<T> {
+    #[deref]
+    #[deref_mut]
+    #[single_account_set]
+    inner: T,
+    #[account_set(skip = false)]
+    writable: bool,
+}
+
+impl<T> MaybeMutRuntime<T> {
+    /// Returns whether the account was decoded as writable.
+    #[must_use]
+    pub fn runtime_writable(&self) -> bool {
+        self.writable
+    }
+}
+
+impl<'a, T> AccountSetDecode<'a, bool> for MaybeMutRuntime<T>
+where
+    T: AccountSetDecode<'a, ()>,
+{
+    #[inline]
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        decode_input: bool,
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        Self::decode_accounts(accounts, (decode_input, ()), ctx)
+    }
+}
+
+impl<'a, T, A> AccountSetDecode<'a, (bool, A)> for MaybeMutRuntime<T>
+where
+    T: AccountSetDecode<'a, A>,
+{
+    #[inline]
+    fn decode_accounts(
+        accounts: &mut &'a [AccountInfo],
+        (writable, arg): (bool, A),
+        ctx: &mut Context,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner: T::decode_accounts(accounts, arg, ctx)?,
+            writable,
+        })
+    }
+}
+
+unsafe impl<T> CpiAccountSet for MaybeMutRuntime<T>
+where
+    T: CpiAccountSet + SingleAccountSet,
+{
+    type ContainsOption = T::ContainsOption;
+    /// The account and whether it should be passed in as writable.
+    type CpiAccounts = (AccountInfo, bool);
+    type AccountLen = typenum::U1;
+
+    #[inline]
+    fn to_cpi_accounts(&self) -> Self::CpiAccounts {
+        (*self.account_info(), self.writable)
+    }
+
+    #[inline]
+    fn write_account_infos<'a>(
+        program: Option<&'a AccountInfo>,
+        accounts: &'a Self::CpiAccounts,
+        index: &mut usize,
+        infos: &mut [MaybeUninit<&'a AccountInfo>],
+    ) -> Result<()> {
+        <AccountInfo as CpiAccountSet>::write_account_infos(program, &accounts.0, index, infos)
+    }
+
+    #[inline]
+    fn write_account_metas<'a>(
+        _program_id: &'a Pubkey,
+        accounts: &'a Self::CpiAccounts,
+        index: &mut usize,
+        metas: &mut [MaybeUninit<PinocchioAccountMeta<'a>>],
+    ) {
+        let meta = T::meta();
+        metas[*index] = MaybeUninit::new(PinocchioAccountMeta {
+            pubkey: accounts.0.key(),
+            is_signer: meta.signer,
+            is_writable: meta.writable || accounts.1,
+        });
+        *index += 1;
+    }
+}
+
+impl<T> ClientAccountSet for MaybeMutRuntime<T>
+where
+    T: ClientAccountSet + SingleAccountSet,
+{
+    /// The account and whether it should be passed in as writable.
+    type ClientAccounts = (Pubkey, bool);
+    const MIN_LEN: usize = 1;
+
+    #[inline]
+    fn extend_account_metas(
+        _program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<AccountMeta>,
+    ) {
+        let meta = T::meta();
+        metas.push(AccountMeta {
+            pubkey: accounts.0,
+            is_signer: meta.signer,
+            is_writable: meta.writable || accounts.1,
+        });
+    }
+}
+
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 mod idl_impl {
     use super::*;
@@ -51,4 +203,100 @@ mod idl_impl {
             Ok(set)
         }
     }
+
+    /// Mutability is only known at runtime, so this is the same as the inner account.
+    impl<T, A> AccountSetToIdl<A> for MaybeMutRuntime<T>
+    where
+        T: AccountSetToIdl<A> + SingleAccountSet,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: A,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            T::account_set_to_idl(idl_definition, arg)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_set::modifiers::WritableAccount;
+    use static_assertions::{assert_eq_size, assert_impl_all, assert_not_impl_any};
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount)]
+    #[program_account(skip_idl)]
+    struct Data {
+        value: u64,
+    }
+
+    assert_eq_size!(MaybeMut<false, Account<Data>>, Account<Data>);
+    assert_eq_size!(MaybeMut<true, Account<Data>>, Account<Data>);
+    assert_not_impl_any!(MaybeMut<false, Account<Data>>: WritableAccount);
+    assert_impl_all!(MaybeMut<false, Mut<Account<Data>>>: WritableAccount);
+
+    #[test]
+    fn test_maybe_mut_meta() {
+        assert!(!<MaybeMut<false, Account<Data>>>::meta().writable);
+        assert!(<MaybeMut<true, Account<Data>>>::meta().writable);
+        // A false `MaybeMut` passes the inner account through
+        assert!(<MaybeMut<false, Mut<Account<Data>>>>::meta().writable);
+
+        let key = Pubkey::new_from_array([1; 32]);
+        let mut metas = Vec::new();
+        <MaybeMut<false, Signer<SystemAccount>>>::extend_account_metas(
+            &System::ID,
+            &key,
+            &mut metas,
+        );
+        <Mut<Signer<SystemAccount>>>::extend_account_metas(&System::ID, &key, &mut metas);
+        assert!(matches!(
+            metas.as_slice(),
+            [
+                AccountMeta {
+                    is_writable: false,
+                    is_signer: true,
+                    ..
+                },
+                AccountMeta {
+                    is_writable: true,
+                    is_signer: true,
+                    ..
+                }
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_maybe_mut_runtime_meta() {
+        let key = Pubkey::new_from_array([1; 32]);
+        let mut metas = Vec::new();
+        <MaybeMutRuntime<Signer<SystemAccount>>>::extend_account_metas(
+            &System::ID,
+            &(key, false),
+            &mut metas,
+        );
+        <MaybeMutRuntime<Signer<SystemAccount>>>::extend_account_metas(
+            &System::ID,
+            &(key, true),
+            &mut metas,
+        );
+        // An already writable inner account stays writable
+        <MaybeMutRuntime<Mut<SystemAccount>>>::extend_account_metas(
+            &System::ID,
+            &(key, false),
+            &mut metas,
+        );
+        assert_eq!(
+            metas,
+            vec![
+                AccountMeta::new_readonly(key, true),
+                AccountMeta::new(key, true),
+                AccountMeta::new(key, false),
+            ]
+        );
+        assert_eq!(<MaybeMutRuntime<SystemAccount>>::MIN_LEN, 1);
+    }
 }
@@ -47,6 +47,19 @@ pub use star_frame_proc::GetSeeds;
 /// ```
 pub trait GetSeeds: Debug {
     fn seeds(&self) -> Vec<&[u8]>;
+
+    /// Returns the seeds without the empty bump placeholder at the end of [`GetSeeds::seeds`].
+    ///
+    /// Used to splice these seeds into another set of seeds, such as with `#[get_seeds(nested)]`.
+    fn seeds_without_bump(&self) -> Vec<&[u8]> {
+        let mut seeds = self.seeds();
+        let bump = seeds.pop();
+        debug_assert!(
+            bump.is_some_and(<[u8]>::is_empty),
+            "`GetSeeds::seeds` must end with an empty bump placeholder"
+        );
+        seeds
+    }
 }
 impl<T> GetSeeds for T
 where
@@ -55,6 +68,10 @@ where
     fn seeds(&self) -> Vec<&[u8]> {
         vec![self.seed(), &[]]
     }
+
+    fn seeds_without_bump(&self) -> Vec<&[u8]> {
+        vec![self.seed()]
+    }
 }
 
 pub trait Seed {
@@ -69,6 +86,35 @@ where
     }
 }
 
+/// Pushes `bytes` onto `seeds`. Used by `#[get_seeds(as_bytes)]`.
+///
+/// # Panics
+/// Panics if `bytes` is longer than [`MAX_SEED_LEN`](pinocchio::pubkey::MAX_SEED_LEN), since no PDA can be derived
+/// from it. Use `#[get_seeds(fixed_len = ...)]` to truncate long values instead.
+#[doc(hidden)]
+pub fn push_bytes_seed<'a>(seeds: &mut Vec<&'a [u8]>, bytes: &'a [u8]) {
+    assert!(
+        bytes.len() <= pinocchio::pubkey::MAX_SEED_LEN,
+        "Seed is {} bytes, but seeds can be at most {} bytes",
+        bytes.len(),
+        pinocchio::pubkey::MAX_SEED_LEN
+    );
+    seeds.push(bytes);
+}
+
+/// Pushes `bytes` truncated or zero-padded to `len` onto `seeds`. Used by `#[get_seeds(fixed_len = ...)]`.
+///
+/// PDAs hash the concatenation of their seeds, so the padding is pushed as its own seed rather than copied.
+#[doc(hidden)]
+pub fn push_fixed_len_seed<'a>(seeds: &mut Vec<&'a [u8]>, bytes: &'a [u8], len: usize) {
+    static ZEROES: [u8; pinocchio::pubkey::MAX_SEED_LEN] = [0; pinocchio::pubkey::MAX_SEED_LEN];
+    let bytes = &bytes[..bytes.len().min(len)];
+    seeds.push(bytes);
+    if bytes.len() < len {
+        seeds.push(&ZEROES[..len - bytes.len()]);
+    }
+}
+
 /// A combination of seeds and bump value for deterministic PDA generation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Hash, PartialOrd, Ord)]
 pub struct SeedsWithBump<T: GetSeeds> {
@@ -99,6 +145,51 @@ where
 #[repr(transparent)]
 pub struct Seeds<T>(pub T);
 
+/// Validation argument for [`Seeded`] that derives the PDA from a program supplied at runtime, such as the key of a
+/// [`Program`] in the same account set, instead of the [`SeedProgram`].
+///
+/// ```ignore
+/// #[validate(arg = SeedsFor { seeds: MySeeds { key }, program: *self.other_program.pubkey() })]
+/// pub account: SeededFor<Account<MyAccount>>,
+/// ```
+///
+/// The seeds are not included in the IDL since the program is not known until runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct SeedsFor<T> {
+    pub seeds: T,
+    pub program: Pubkey,
+}
+
+/// A seed in the IDL seeds of an account, either a path to another account in the account set, a path to an
+/// instruction argument, or a constant.
+///
+/// Used in `#[idl(arg = Seeds(Find<Seeds> { .. }))]` attributes. The IDL conversion is only compiled with the `idl`
+/// feature, but the type exists on every target so types built from it can be imported unconditionally.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FindSeed<T: NoUninit> {
+    Path(String),
+    Arg(String),
+    Const(T),
+}
+
+#[must_use]
+pub fn seed_const<T: NoUninit>(seed: T) -> FindSeed<T> {
+    FindSeed::Const(seed)
+}
+/// Creates a seed that references an account path. For nested account sets, the fields should be split with a `.` (or a
+/// space), like `seed_path("inner.owner")`. If you want to specify that a path is from the root and not nested (even if
+/// it's nested in another account set), prefix the path with a colon.
+#[must_use]
+pub fn seed_path<T: NoUninit>(path: &str) -> FindSeed<T> {
+    FindSeed::Path(path.to_string())
+}
+/// Creates a seed that references a top-level field of the instruction arguments, like `arg_path("owner")`. Nested
+/// fields (like `arg_path("inner.owner")`) are rejected when converting the IDL to Codama.
+#[must_use]
+pub fn arg_path<T: NoUninit>(path: &str) -> FindSeed<T> {
+    FindSeed::Arg(path.to_string())
+}
+
 /// Allows generic [`crate::account_set`]s to be used in multiple programs by defaulting the [`SeedProgram`] to the current
 /// executing program. This is the default [`SeedProgram`] for [`Seeded`], and the only [`SeedProgram`] that can be used with
 /// the [`Init`] account set.
@@ -111,6 +202,36 @@ pub trait SeedProgram {
     fn idl_program() -> Option<Pubkey>;
 }
 
+/// A [`SeedProgram`] that is supplied at runtime with [`SeedsFor`]. See [`SeededFor`].
+///
+/// Validating with [`Seeds`] or [`SeedsWithBump`] instead of [`SeedsFor`] returns an error.
+#[derive(Debug, Clone, Copy)]
+pub struct RuntimeProgram;
+
+impl SeedProgram for RuntimeProgram {
+    fn id(_ctx: &Context) -> Result<Pubkey> {
+        bail!(
+            ProgramError::InvalidArgument,
+            "`RuntimeProgram` seeds must be validated with `SeedsFor`"
+        )
+    }
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    fn idl_program() -> Option<Pubkey> {
+        None
+    }
+}
+
+/// Implemented only by [`RuntimeProgram`], so [`SeedsFor`] can only validate [`SeededFor`] accounts.
+///
+/// Other [`SeedProgram`]s are always validated against [`SeedProgram::id`], so a `Seeded<_, _, CurrentProgram>` can't
+/// be validated or initialized with seeds from a program it doesn't claim.
+pub trait RuntimeSeedProgram: SeedProgram {}
+
+impl RuntimeSeedProgram for RuntimeProgram {}
+
+/// A [`Seeded`] account whose PDA program is supplied at runtime with [`SeedsFor`].
+pub type SeededFor<T, S = <T as HasSeeds>::Seeds> = Seeded<T, S, RuntimeProgram>;
+
 impl SeedProgram for CurrentProgram {
     fn id(ctx: &Context) -> Result<Pubkey> {
         Ok(*ctx.current_program_id())
@@ -154,6 +275,18 @@ where
     arg = (Seeds<S>, A),
     before_validation = self.validate_and_set_seeds(&arg.0, ctx)
 )]
+#[validate(
+    id = "seeds_for",
+    generics = [where T: AccountSetValidate<()> + SingleAccountSet, P: RuntimeSeedProgram],
+    arg = SeedsFor<S>,
+    before_validation = self.validate_and_set_seeds_for(&arg.seeds, &arg.program)
+)]
+#[validate(
+    id = "seeds_for_generic",
+    generics = [<A> where T: AccountSetValidate<A> + SingleAccountSet, P: RuntimeSeedProgram],
+    arg = (SeedsFor<S>, A),
+    before_validation = self.validate_and_set_seeds_for(&arg.0.seeds, &arg.0.program)
+)]
 #[validate(
     id = "seeds_with_bump",
     generics = [where T: AccountSetValidate<()> + SingleAccountSet],
@@ -177,6 +310,7 @@ where
         skip_can_init_account
     )]
     #[validate(id = "seeds_generic", arg = arg.1)]
+    #[validate(id = "seeds_for_generic", arg = arg.1)]
     #[validate(id = "seeds_with_bump_generic", arg = arg.1)]
     #[deref]
     #[deref_mut]
@@ -184,6 +318,9 @@ where
     /// Seeds of the account. Starts as `None`, and are set to `Some` after validation, during `AccountSetValidate`.
     #[account_set(skip = None)]
     pub(crate) seeds: Option<SeedsWithBump<S>>,
+    /// The program the seeds were validated against, set along with `seeds`.
+    #[account_set(skip = None)]
+    seeds_program: Option<Pubkey>,
     #[account_set(skip = PhantomData)]
     phantom_p: PhantomData<P>,
 }
@@ -210,6 +347,28 @@ where
     }
 }
 
+impl<T, S, P, A> CanInitSeeds<(SeedsFor<S>, A)> for Seeded<T, S, P>
+where
+    T: SingleAccountSet + AccountSetValidate<A>,
+    S: GetSeeds + Clone,
+    P: RuntimeSeedProgram,
+{
+    fn init_seeds(&mut self, arg: &(SeedsFor<S>, A), _ctx: &Context) -> Result<()> {
+        self.validate_and_set_seeds_for(&arg.0.seeds, &arg.0.program)
+    }
+}
+
+impl<T, S, P> CanInitSeeds<SeedsFor<S>> for Seeded<T, S, P>
+where
+    T: SingleAccountSet + AccountSetValidate<()>,
+    S: GetSeeds + Clone,
+    P: RuntimeSeedProgram,
+{
+    fn init_seeds(&mut self, arg: &SeedsFor<S>, _ctx: &Context) -> Result<()> {
+        self.validate_and_set_seeds_for(&arg.seeds, &arg.program)
+    }
+}
+
 impl<T, S, P, A> CanInitSeeds<(SeedsWithBump<S>, A)> for Seeded<T, S, P>
 where
     T: SingleAccountSet + AccountSetValidate<A>,
@@ -242,8 +401,15 @@ where
         if self.seeds.is_some() {
             return Ok(());
         }
-        let seeds = seeds.clone().0;
-        let (address, bump) = Pubkey::find_program_address(&seeds.seeds(), &P::id(ctx)?);
+        self.validate_and_set_seeds_for(&seeds.0, &P::id(ctx)?)
+    }
+
+    fn validate_and_set_seeds_for(&mut self, seeds: &S, program: &Pubkey) -> Result<()> {
+        if self.seeds.is_some() {
+            return Ok(());
+        }
+        let seeds = seeds.clone();
+        let (address, bump) = Pubkey::find_program_address(&seeds.seeds(), program);
         let expected = self.account.account_info().pubkey();
         ensure!(
             address.fast_eq(expected),
@@ -251,6 +417,7 @@ where
             "Seeds: {seeds:?} result in address `{address}` and bump `{bump}`, expected `{expected}`"
         );
         self.seeds = Some(SeedsWithBump { seeds, bump });
+        self.seeds_program = Some(*program);
         Ok(())
     }
 
@@ -262,15 +429,36 @@ where
         if self.seeds.is_some() {
             return Ok(());
         }
-        let arg_seeds = seeds.seeds_with_bump();
-        let address = Pubkey::create_program_address(&arg_seeds, &P::id(ctx)?)?;
-        let expected = self.account.account_info().pubkey();
+        let program = P::id(ctx)?;
+        Self::check_address(seeds, &program, self.account.account_info().pubkey())?;
+        self.seeds = Some(seeds.clone());
+        self.seeds_program = Some(program);
+        Ok(())
+    }
+
+    /// Checks that `seeds` with the bump and program stored during validation derive this account's address with
+    /// [`Pubkey::create_program_address`].
+    ///
+    /// Returns [`ErrorCode::SeedsNotSet`] if the account hasn't been validated, and [`ErrorCode::AddressMismatch`]
+    /// if the seeds don't match.
+    pub fn verify_seeds(&self, seeds: &S) -> Result<()> {
+        let (Some(validated), Some(program)) = (&self.seeds, &self.seeds_program) else {
+            bail!(ErrorCode::SeedsNotSet, "Seeds not set for `Seeded`");
+        };
+        let seeds = SeedsWithBump {
+            seeds: seeds.clone(),
+            bump: validated.bump,
+        };
+        Self::check_address(&seeds, program, self.account.account_info().pubkey())
+    }
+
+    fn check_address(seeds: &SeedsWithBump<S>, program: &Pubkey, expected: &Pubkey) -> Result<()> {
+        let address = Pubkey::create_program_address(&seeds.seeds_with_bump(), program)?;
         ensure!(
             address.fast_eq(expected),
             ErrorCode::AddressMismatch,
             "Seeds `{seeds:?}` result in address `{address}`, expected `{expected}`"
         );
-        self.seeds = Some(seeds.clone());
         Ok(())
     }
 }
@@ -280,6 +468,13 @@ where
     S: GetSeeds + Clone,
     P: SeedProgram,
 {
+    /// Gets the seeds and bump of the account, which are set during validation.
+    ///
+    /// Available for any [`Seeded`], including when wrapped in [`Init`] or [`Mut`]. The bump is the canonical bump
+    /// from [`Pubkey::find_program_address`] unless the account was validated with [`SeedsWithBump`].
+    ///
+    /// # Panics
+    /// Panics if the account hasn't been validated yet.
     pub fn access_seeds(&self) -> &SeedsWithBump<S> {
         self.seeds.as_ref().expect("Seeds not set!")
     }
@@ -379,6 +574,34 @@ mod idl_impl {
         }
     }
 
+    impl<T, A, S, P, F> AccountSetToIdl<(SeedsFor<F>, A)> for Seeded<T, S, P>
+    where
+        T: AccountSetToIdl<A> + SingleAccountSet,
+        S: GetSeeds + Clone,
+        P: RuntimeSeedProgram,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: (SeedsFor<F>, A),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            T::account_set_to_idl(idl_definition, arg.1)?.assert_single()
+        }
+    }
+
+    impl<T, S, P, F> AccountSetToIdl<SeedsFor<F>> for Seeded<T, S, P>
+    where
+        T: AccountSetToIdl<()> + SingleAccountSet,
+        S: GetSeeds + Clone,
+        P: RuntimeSeedProgram,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: SeedsFor<F>,
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            Self::account_set_to_idl(idl_definition, (arg, ()))
+        }
+    }
+
     impl<T, S, P, F> AccountSetToIdl<Seeds<F>> for Seeded<T, S, P>
     where
         T: AccountSetToIdl<()> + SingleAccountSet,
@@ -446,6 +669,21 @@ mod tests {
         assert_eq!(seeds, intended_seeds);
     }
 
+    #[test]
+    fn test_runtime_program_requires_seeds_for() {
+        use super::{RuntimeProgram, SeedProgram};
+        assert!(RuntimeProgram::id(&Context::default()).is_err());
+    }
+
+    // Only `SeededFor` accounts take their program at runtime
+    static_assertions::assert_impl_all!(
+        SeededFor<SystemAccount, SingleKey>: crate::account_set::AccountSetValidate<SeedsFor<SingleKey>>
+    );
+    static_assertions::assert_not_impl_any!(
+        Seeded<SystemAccount, SingleKey>: crate::account_set::AccountSetValidate<SeedsFor<SingleKey>>,
+        crate::account_set::modifiers::CanInitSeeds<SeedsFor<SingleKey>>
+    );
+
     #[derive(Debug, GetSeeds, Clone)]
     pub struct TwoKeys {
         key1: Pubkey,
@@ -519,4 +757,352 @@ mod tests {
         let intended_seeds = vec![b"TEST_CONST".as_ref(), &[]];
         assert_eq!(seeds, intended_seeds);
     }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"market")]
+    pub struct MarketSeeds {
+        authority: Pubkey,
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"order")]
+    pub struct OrderSeeds {
+        #[get_seeds(nested)]
+        market: MarketSeeds,
+        order_id: u64,
+    }
+
+    #[test]
+    fn test_nested_seeds() {
+        let account = OrderSeeds {
+            market: MarketSeeds {
+                authority: Pubkey::new_unique(),
+            },
+            order_id: 7,
+        };
+        let seeds = account.seeds();
+        let intended_seeds = vec![
+            b"order".as_ref(),
+            b"market".as_ref(),
+            account.market.authority.seed(),
+            account.order_id.seed(),
+            &[],
+        ];
+        assert_eq!(seeds, intended_seeds);
+
+        let with_bump = SeedsWithBump {
+            seeds: account.clone(),
+            bump: 255,
+        };
+        assert_eq!(with_bump.seeds_with_bump().len(), 5);
+        assert_eq!(with_bump.seeds_with_bump()[4], &[255]);
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"vault", idl_name = "FindVault")]
+    pub struct VaultAuthoritySeeds {
+        owner: Pubkey,
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"skipped", skip_idl)]
+    pub struct SkippedIdlSeeds {
+        owner: Pubkey,
+    }
+
+    #[test]
+    fn test_skip_idl_seeds() {
+        let owner = Pubkey::new_unique();
+        let seeds = SkippedIdlSeeds { owner };
+        assert_eq!(seeds.seeds(), vec![b"skipped".as_ref(), owner.seed(), &[]]);
+    }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    static_assertions::assert_not_impl_any!(SkippedIdlSeeds: crate::idl::SeedsToIdl);
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[test]
+    fn test_idl_name_seeds() -> crate::IdlResult<()> {
+        use crate::idl::{seed_path, FindIdlSeeds};
+        use star_frame_idl::seeds::IdlFindSeed;
+
+        let find_seeds = FindVault {
+            owner: seed_path("owner"),
+        }
+        .find_seeds()?;
+        assert_eq!(
+            find_seeds,
+            vec![
+                IdlFindSeed::Const(b"vault".to_vec()),
+                IdlFindSeed::AccountPath("owner".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[test]
+    fn test_seed_and_arg_paths() -> crate::IdlResult<()> {
+        use crate::idl::{arg_path, seed_path, FindIdlSeeds};
+        use star_frame_idl::seeds::IdlFindSeed;
+
+        let find_seeds = FindKeyAndNumber {
+            key: seed_path("inner.owner_account"),
+            number: arg_path("number"),
+        }
+        .find_seeds()?;
+        assert_eq!(
+            find_seeds,
+            vec![
+                IdlFindSeed::AccountPath("inner.owner_account".to_string()),
+                IdlFindSeed::ArgPath("number".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount, Debug)]
+    #[program_account(skip_idl, seeds = VaultSeeds)]
+    struct VaultAccount {
+        authority: Pubkey,
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"vault_account", skip_idl)]
+    pub struct VaultSeeds {
+        authority: Pubkey,
+    }
+
+    #[derive(AccountSet)]
+    struct WithdrawVaultAccounts {
+        authority: Signer,
+        #[validate(arg = Seeds(VaultSeeds { authority: *self.authority.pubkey() }))]
+        vault: Mut<Seeded<Account<VaultAccount>>>,
+        recipient: Mut<SystemAccount>,
+        system_program: Program<System>,
+    }
+
+    /// Type checks signing a CPI with the seeds of an existing `Mut<Seeded<_>>` account, since unit tests can't
+    /// run CPIs. The same flow is run on chain by `CountAsVault` in the `account_test` example program.
+    #[allow(dead_code)]
+    fn withdraw_vault(accounts: &WithdrawVaultAccounts, lamports: u64) -> Result<()> {
+        let seeds = VaultSeeds {
+            authority: *accounts.authority.pubkey(),
+        };
+        accounts.vault.verify_seeds(&seeds)?;
+        let seeds = SeedsWithBump {
+            seeds,
+            bump: accounts.vault.access_seeds().bump,
+        };
+        System::cpi(
+            crate::program::system::Transfer { lamports },
+            crate::program::system::TransferCpiAccounts {
+                funder: *accounts.vault.account_info(),
+                recipient: *accounts.recipient.account_info(),
+            },
+            None,
+        )
+        .invoke_signed(&[&seeds.seeds_with_bump()])?;
+        // Equivalent, using the seeds stored during validation
+        System::cpi(
+            crate::program::system::Transfer { lamports },
+            crate::program::system::TransferCpiAccounts {
+                funder: *accounts.vault.account_info(),
+                recipient: *accounts.recipient.account_info(),
+            },
+            None,
+        )
+        .invoke_signed_by(&[&accounts.vault])
+    }
+
+    #[test]
+    fn test_check_seeds_address() {
+        type Vault = Seeded<Account<VaultAccount>>;
+        let program = Pubkey::new_unique();
+        let seeds = VaultSeeds {
+            authority: Pubkey::new_unique(),
+        };
+        let (address, bump) = Pubkey::find_program_address(&seeds.seeds(), &program);
+        let mut seeds = SeedsWithBump { seeds, bump };
+        assert!(Vault::check_address(&seeds, &program, &address).is_ok());
+        assert!(Vault::check_address(&seeds, &program, &Pubkey::new_unique()).is_err());
+
+        seeds.seeds.authority = Pubkey::new_unique();
+        assert!(Vault::check_address(&seeds, &program, &address).is_err());
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"pool")]
+    pub struct PoolSeeds {
+        #[get_seeds(as_bytes)]
+        name: String,
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    pub struct NamespaceSeeds<'a> {
+        #[get_seeds(fixed_len = 8)]
+        namespace: &'a str,
+        id: u8,
+    }
+
+    #[test]
+    fn test_byte_seeds() {
+        let program = Pubkey::new_unique();
+        let pool = PoolSeeds {
+            name: "my pool".to_string(),
+        };
+        assert_eq!(pool.seeds(), vec![b"pool".as_ref(), b"my pool", &[]]);
+        assert_eq!(
+            Pubkey::find_program_address(&pool.seeds(), &program),
+            Pubkey::find_program_address(&[b"pool", b"my pool"], &program)
+        );
+
+        let short = NamespaceSeeds {
+            namespace: "abc",
+            id: 1,
+        };
+        assert_eq!(short.seeds(), vec![b"abc".as_ref(), &[0; 5], &[1], &[]]);
+        assert_eq!(
+            Pubkey::find_program_address(&short.seeds(), &program),
+            Pubkey::find_program_address(&[b"abc\0\0\0\0\0", &[1]], &program)
+        );
+
+        // Long strings are truncated
+        let long = NamespaceSeeds {
+            namespace: "a very long namespace",
+            id: 1,
+        };
+        assert_eq!(long.seeds(), vec![b"a very l".as_ref(), &[1], &[]]);
+        let exact = NamespaceSeeds {
+            namespace: "a very l",
+            id: 1,
+        };
+        assert_eq!(
+            Pubkey::find_program_address(&long.seeds(), &program),
+            Pubkey::find_program_address(&exact.seeds(), &program)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "seeds can be at most 32 bytes")]
+    fn test_long_byte_seed() {
+        let pool = PoolSeeds {
+            name: "a pool name that is over 32 bytes long".to_string(),
+        };
+        let _ = pool.seeds();
+    }
+
+    #[derive(Debug, GetSeeds, Clone)]
+    pub struct PoolEntrySeeds {
+        #[get_seeds(nested)]
+        pool: PoolSeeds,
+        #[get_seeds(nested)]
+        market: MarketSeeds,
+    }
+
+    #[test]
+    fn test_nested_empty_last_seed() {
+        let entry = PoolEntrySeeds {
+            pool: PoolSeeds {
+                name: String::new(),
+            },
+            market: MarketSeeds {
+                authority: Pubkey::new_unique(),
+            },
+        };
+        assert_eq!(entry.pool.seeds_without_bump(), vec![b"pool".as_ref(), b""]);
+        assert_eq!(
+            entry.seeds(),
+            vec![
+                b"pool".as_ref(),
+                b"",
+                b"market",
+                entry.market.authority.seed(),
+                &[],
+            ]
+        );
+        let mut seeds = entry.seeds();
+        seeds.pop();
+        assert_eq!(entry.seeds_without_bump(), seeds);
+    }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[test]
+    fn test_byte_seeds_idl() -> crate::IdlResult<()> {
+        use crate::idl::{seed_path, FindIdlSeeds, SeedsToIdl};
+        use star_frame_idl::{
+            seeds::{IdlFindSeed, IdlSeed},
+            IdlDefinition,
+        };
+
+        let mut idl_definition = IdlDefinition::default();
+        let seeds = NamespaceSeeds::seeds_to_idl(&mut idl_definition)?;
+        assert!(matches!(
+            &seeds[0],
+            IdlSeed::VariableString { name, fixed_len: Some(8), .. } if name == "namespace"
+        ));
+        let seeds = PoolSeeds::seeds_to_idl(&mut idl_definition)?;
+        assert!(matches!(
+            &seeds[1],
+            IdlSeed::VariableString { name, fixed_len: None, .. } if name == "name"
+        ));
+
+        let find_seeds = FindNamespaceSeeds {
+            namespace: "abc",
+            id: seed_path("id"),
+        }
+        .find_seeds()?;
+        assert_eq!(
+            find_seeds,
+            vec![
+                IdlFindSeed::Const(b"abc\0\0\0\0\0".to_vec()),
+                IdlFindSeed::AccountPath("id".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[test]
+    fn test_nested_seeds_idl() -> crate::IdlResult<()> {
+        use crate::idl::{seed_path, FindIdlSeeds, SeedsToIdl};
+        use star_frame_idl::{
+            seeds::{IdlFindSeed, IdlSeed},
+            IdlDefinition,
+        };
+
+        let mut idl_definition = IdlDefinition::default();
+        let seeds = OrderSeeds::seeds_to_idl(&mut idl_definition)?;
+        let names = seeds
+            .iter()
+            .map(|seed| match seed {
+                IdlSeed::Const(bytes) => String::from_utf8(bytes.clone()).unwrap(),
+                IdlSeed::Variable { name, .. } | IdlSeed::VariableString { name, .. } => {
+                    name.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["order", "market", "market_authority", "order_id"]);
+
+        let find_seeds = FindOrderSeeds {
+            market: FindMarketSeeds {
+                authority: seed_path("authority"),
+            }
+            .find_seeds()?,
+            order_id: seed_path("order_id"),
+        }
+        .find_seeds()?;
+        assert_eq!(
+            find_seeds,
+            vec![
+                IdlFindSeed::Const(b"order".to_vec()),
+                IdlFindSeed::Const(b"market".to_vec()),
+                IdlFindSeed::AccountPath("authority".to_string()),
+                IdlFindSeed::AccountPath("order_id".to_string()),
+            ]
+        );
+        Ok(())
+    }
 }
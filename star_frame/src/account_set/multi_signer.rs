@@ -0,0 +1,127 @@
+//! Fixed-size signer sets for multisig instructions.
+//!
+//! The `MultiSigner<T, K>` type consumes `K` consecutive accounts and requires `K` distinct signers among them,
+//! reporting how many were provided if there are too few.
+
+use crate::{
+    account_set::{AccountSetValidate, ClientAccountSet, CpiAccountSet},
+    errors::ErrorCode,
+    prelude::*,
+};
+use derive_more::{Deref, DerefMut};
+use std::mem::MaybeUninit;
+
+/// `K` consecutive [`Signer`] accounts that must all sign the transaction, for multisig instructions.
+///
+/// Unlike `[Signer<T>; K]`, this fails with [`ErrorCode::InsufficientSigners`] and the number of signers provided
+/// before validating the individual accounts, and the same key passed in multiple slots only counts as one signer.
+///
+/// The client accounts are a `[Pubkey; K]` for single account sets, and the IDL has `K` individually indexed signer
+/// accounts.
+#[derive(AccountSet, Debug, Deref, DerefMut, Clone)]
+#[account_set(skip_cpi_account_set, skip_client_account_set, skip_default_idl)]
+#[validate(
+    generics = [where T: SingleAccountSet, [Signer<T>; K]: AccountSetValidate<()>],
+    before_validation = self.check_signers()
+)]
+pub struct MultiSigner<T, const K: usize>([Signer<T>; K]);
+
+impl<T, const K: usize> MultiSigner<T, K>
+where
+    T: SingleAccountSet,
+{
+    /// Returns the number of distinct keys that signed the transaction.
+    #[must_use]
+    pub fn signer_count(&self) -> usize {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(index, signer)| {
+                signer.is_signer()
+                    && !self.0[..*index]
+                        .iter()
+                        .any(|other| other.pubkey().fast_eq(signer.pubkey()))
+            })
+            .count()
+    }
+
+    fn check_signers(&self) -> Result<()> {
+        let provided = self.signer_count();
+        ensure!(
+            provided >= K,
+            ErrorCode::InsufficientSigners,
+            "Required {K} signers, but {provided} were provided"
+        );
+        Ok(())
+    }
+}
+
+unsafe impl<T, const K: usize> CpiAccountSet for MultiSigner<T, K>
+where
+    [Signer<T>; K]: CpiAccountSet,
+{
+    type ContainsOption = <[Signer<T>; K] as CpiAccountSet>::ContainsOption;
+    type CpiAccounts = <[Signer<T>; K] as CpiAccountSet>::CpiAccounts;
+    type AccountLen = <[Signer<T>; K] as CpiAccountSet>::AccountLen;
+
+    #[inline]
+    fn to_cpi_accounts(&self) -> Self::CpiAccounts {
+        self.0.to_cpi_accounts()
+    }
+
+    #[inline]
+    fn write_account_infos<'a>(
+        program: Option<&'a AccountInfo>,
+        accounts: &'a Self::CpiAccounts,
+        index: &mut usize,
+        infos: &mut [MaybeUninit<&'a AccountInfo>],
+    ) -> Result<()> {
+        <[Signer<T>; K]>::write_account_infos(program, accounts, index, infos)
+    }
+
+    #[inline]
+    fn write_account_metas<'a>(
+        program_id: &'a Pubkey,
+        accounts: &'a Self::CpiAccounts,
+        index: &mut usize,
+        metas: &mut [MaybeUninit<PinocchioAccountMeta<'a>>],
+    ) {
+        <[Signer<T>; K]>::write_account_metas(program_id, accounts, index, metas);
+    }
+}
+
+impl<T, const K: usize> ClientAccountSet for MultiSigner<T, K>
+where
+    [Signer<T>; K]: ClientAccountSet,
+{
+    type ClientAccounts = <[Signer<T>; K] as ClientAccountSet>::ClientAccounts;
+    const MIN_LEN: usize = <[Signer<T>; K] as ClientAccountSet>::MIN_LEN;
+
+    #[inline]
+    fn extend_account_metas(
+        program_id: &Pubkey,
+        accounts: &Self::ClientAccounts,
+        metas: &mut Vec<AccountMeta>,
+    ) {
+        <[Signer<T>; K]>::extend_account_metas(program_id, accounts, metas);
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use crate::idl::AccountSetToIdl;
+    use star_frame_idl::{account_set::IdlAccountSetDef, IdlDefinition};
+
+    impl<T, const K: usize> AccountSetToIdl<()> for MultiSigner<T, K>
+    where
+        [Signer<T>; K]: AccountSetToIdl<()>,
+    {
+        fn account_set_to_idl(
+            idl_definition: &mut IdlDefinition,
+            arg: (),
+        ) -> crate::IdlResult<IdlAccountSetDef> {
+            <[Signer<T>; K]>::account_set_to_idl(idl_definition, arg)
+        }
+    }
+}
@@ -3,6 +3,9 @@
 //! The `Program<T>` type represents a reference to a specific Star Frame program within an instruction's
 //! account set. It automatically validates that the provided account matches the expected program ID
 //! and provides type-safe access to program-specific functionality.
+//!
+//! The `Interface<T>` type accepts any of several programs implementing the same interface, such as
+//! the Token and Token-2022 programs.
 
 use crate::{account_set::ClientAccountSet, prelude::*};
 use ref_cast::{ref_cast_custom, RefCastCustom};
@@ -55,3 +58,48 @@ impl<T: StarFrameProgram> Program<T> {
     #[ref_cast_custom]
     pub(crate) fn cast_info_unchecked<'a>(info: &'a AccountInfo) -> &'a Self;
 }
+
+/// A set of programs that share an instruction interface, used with [`Interface`].
+pub trait ProgramInterface: 'static {
+    /// The program IDs accepted by [`Interface`].
+    const VALID_PROGRAM_IDS: &'static [Pubkey];
+}
+
+/// A program account that validates the program ID is one of [`ProgramInterface::VALID_PROGRAM_IDS`].
+///
+/// Use [`Interface::program_id`] to find out which program was provided, e.g., to pass as the program
+/// override when building a CPI.
+#[derive(AccountSet, Debug, derive_where::DeriveWhere)]
+#[derive_where(Clone, Copy)]
+#[validate(
+    extra_validation = self.check_id(),
+)]
+#[repr(transparent)]
+pub struct Interface<T: ProgramInterface>(
+    #[single_account_set] pub(crate) AccountInfo,
+    #[account_set(skip = PhantomData)] pub(crate) PhantomData<T>,
+);
+
+impl<T: ProgramInterface> Interface<T> {
+    pub fn check_id(&self) -> Result<()> {
+        if T::VALID_PROGRAM_IDS
+            .iter()
+            .any(|id| self.0.pubkey().fast_eq(id))
+        {
+            Ok(())
+        } else {
+            bail!(
+                ProgramError::IncorrectProgramId,
+                "Program {} is not one of the valid interface programs {:?}",
+                self.0.pubkey(),
+                T::VALID_PROGRAM_IDS
+            )
+        }
+    }
+
+    /// Returns the ID of the program that was provided.
+    #[must_use]
+    pub fn program_id(&self) -> &Pubkey {
+        self.0.pubkey()
+    }
+}
@@ -100,6 +100,23 @@ pub trait SingleAccountSet {
         }
     }
 
+    /// Checks that this account is owned by `owner`. Returns an error if it is not.
+    #[inline]
+    fn check_owner(&self, owner: &Pubkey) -> Result<()> {
+        let account_owner = self.owner_pubkey();
+        if &account_owner == owner {
+            Ok(())
+        } else {
+            bail!(
+                ErrorCode::OwnerMismatch,
+                "Account {} is owned by {}, expected {}",
+                self.pubkey(),
+                account_owner,
+                owner
+            )
+        }
+    }
+
     /// Returns a reference to the public key of the contained account.
     #[inline]
     fn pubkey(&self) -> &Pubkey {
@@ -208,6 +225,7 @@ where
         Self: Sized,
     {
         let info = self.account_info();
+        check_close_recipient(info, recipient.account_to_modify())?;
         info.resize(size_of::<OwnerProgramDiscriminant<Self>>())?;
         info.account_data_mut()?.fill(u8::MAX);
         recipient.add_lamports(info.lamports())?;
@@ -215,26 +233,89 @@ where
         Ok(())
     }
 
+    #[inline]
+    fn close_account_with_config(&self, config: CloseConfig, ctx: &Context) -> Result<()>
+    where
+        Self: HasOwnerProgram,
+        Self: Sized,
+    {
+        let recipient = match config.recipient {
+            Some(recipient) => recipient,
+            None => ctx.get_recipient().ok_or_else(|| {
+                error!(
+                    ErrorCode::EmptyRecipientCache,
+                    "Missing `recipient` in cache for `CloseAccount`"
+                )
+            })?,
+        };
+        self.close_account(recipient)?;
+        if config.emit_log {
+            let info = self.account_info();
+            pinocchio::msg!("Closed account {}", info.pubkey());
+            pinocchio::log::sol_log_data(&[
+                &CloseConfig::EVENT_DISCRIMINANT,
+                info.pubkey().as_ref(),
+            ]);
+        }
+        Ok(())
+    }
+
     #[inline]
     fn close_account_full(&self, recipient: &dyn CanAddLamports) -> Result<()> {
         let info = self.account_info();
+        check_close_recipient(info, recipient.account_to_modify())?;
         recipient.add_lamports(info.lamports())?;
         info.close()?;
         Ok(())
     }
 }
 
+/// Closing an account to itself would burn its lamports, since they are added back before the balance is zeroed.
+///
+/// Only checked in debug builds or with the `close_validation` feature.
+#[inline]
+fn check_close_recipient(info: &AccountInfo, recipient: AccountInfo) -> Result<()> {
+    if !cfg!(any(debug_assertions, feature = "close_validation")) {
+        return Ok(());
+    }
+    ensure!(
+        !recipient.pubkey().fast_eq(info.pubkey()),
+        ErrorCode::CloseToSelf,
+        "Account {} cannot be closed to itself",
+        info.pubkey()
+    );
+    Ok(())
+}
+
 impl<T> CanModifyRent for T
 where
     T: SingleAccountSet + ?Sized,
 {
     #[inline]
     fn normalize_rent(&self, funder: &(impl CanFundRent + ?Sized), ctx: &Context) -> Result<()> {
+        self.normalize_rent_to(RentTarget::Minimum, funder, ctx)
+    }
+
+    #[inline]
+    fn normalize_rent_to(
+        &self,
+        target: RentTarget,
+        funder: &(impl CanFundRent + ?Sized),
+        ctx: &Context,
+    ) -> Result<()> {
         let account = self.account_info();
         let rent = ctx.get_rent()?;
         let lamports = *account.try_borrow_lamports()?;
         let data_len = account.data_len();
-        let rent_lamports = rent.minimum_balance(data_len);
+        let rent_lamports = target.lamports(data_len, &rent);
+        #[cfg(feature = "cleanup_rent_warning")]
+        if matches!(target, RentTarget::Exact(_)) && rent_lamports < rent.minimum_balance(data_len)
+        {
+            pinocchio::msg!(
+                "{} was normalized to fewer lamports than required by rent",
+                account.pubkey()
+            );
+        }
         match rent_lamports.cmp(&lamports) {
             Ordering::Equal => Ok(()),
             Ordering::Greater => {
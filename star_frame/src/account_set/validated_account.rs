@@ -8,18 +8,49 @@
 use crate::prelude::*;
 use derive_more::{Deref, DerefMut};
 
+/// Custom validation for an account's data, used by [`ValidatedAccount`].
+///
+/// Validators can be chained by passing a tuple of arguments (e.g. `#[validate(arg = (a, b, c))]`), which
+/// runs the validator for each element in order and stops at the first error.
 pub trait AccountValidate<ValidateArg>: UnsizedType {
     fn validate_account(self_ref: &Self::Ptr, arg: ValidateArg) -> Result<()>;
 }
 
+/// Custom validation for an account's data that also has access to the instruction's [`Context`] (e.g., to check
+/// the [`Clock`](pinocchio::sysvars::clock::Clock) with [`Context::get_clock`]), used by [`ValidatedAccount`].
+///
+/// This is implemented for every [`AccountValidate`] type, ignoring the context.
+pub trait AccountValidateWithContext<ValidateArg>: UnsizedType {
+    fn validate_account_with_context(
+        self_ref: &Self::Ptr,
+        arg: ValidateArg,
+        ctx: &Context,
+    ) -> Result<()>;
+}
+
+impl<T, ValidateArg> AccountValidateWithContext<ValidateArg> for T
+where
+    T: AccountValidate<ValidateArg> + ?Sized,
+{
+    #[inline]
+    fn validate_account_with_context(
+        self_ref: &Self::Ptr,
+        arg: ValidateArg,
+        _ctx: &Context,
+    ) -> Result<()> {
+        T::validate_account(self_ref, arg)
+    }
+}
+
 /// An account wrapper that performs additional custom validation during the validation phase.
 ///
 /// This type wraps an `Account<T>` and adds an extra validation step that calls the account's
-/// `AccountValidate::validate_account` method with the provided validation arguments. This is
-/// useful for accounts that need domain-specific validation beyond owner and discriminant checks.
+/// `AccountValidateWithContext::validate_account_with_context` method (or `AccountValidate::validate_account`)
+/// with the provided validation arguments and the instruction's [`Context`]. This is useful for accounts that need
+/// domain-specific validation beyond owner and discriminant checks.
 #[derive(AccountSet, Debug, Deref, DerefMut, derive_where::DeriveWhere)]
 #[derive_where(Clone)]
-#[validate(generics = [<ValidateArg> where T: AccountValidate<ValidateArg>], arg = ValidateArg, extra_validation = T::validate_account(&*self.account.data()?, arg))]
+#[validate(generics = [<ValidateArg> where T: AccountValidateWithContext<ValidateArg>], arg = ValidateArg, extra_validation = T::validate_account_with_context(&*self.account.data()?, arg, ctx))]
 #[idl(generics = [<A> where T: AccountToIdl, Account<T>: AccountSetToIdl<A>], arg = A)]
 pub struct ValidatedAccount<T>
 where
@@ -30,6 +61,25 @@ where
     account: Account<T>,
 }
 
+impl<T> ValidatedAccount<T>
+where
+    T: ProgramAccount + UnsizedType + ?Sized,
+{
+    /// Runs the owner, discriminant, and [`AccountValidate`] checks on `info` without constructing a
+    /// [`ValidatedAccount`] or decoding the rest of the account set.
+    ///
+    /// Useful as a cheap guard before doing more expensive work with the account. Unlike the validation phase, this
+    /// does not migrate versioned accounts, so accounts on an older version will fail the discriminant check.
+    pub fn validate_only<ValidateArg>(info: &AccountInfo, arg: ValidateArg) -> Result<()>
+    where
+        T: AccountValidate<ValidateArg>,
+    {
+        T::validate_account_info(*info)?;
+        let account = Account::<T>::from_account_info_unchecked(*info);
+        T::validate_account(&*account.data()?, arg)
+    }
+}
+
 macro_rules! account_validate_tuple {
     ($($idents:ident)*) => {
         account_validate_tuple!(| $($idents)*);
@@ -58,3 +108,109 @@ macro_rules! account_validate_tuple {
 }
 
 account_validate_tuple!(A B C D E F G H I J K L M N O P);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct Check<'a, const N: usize> {
+        calls: &'a RefCell<Vec<usize>>,
+        pass: bool,
+    }
+
+    impl<'a, const N: usize> Check<'a, N> {
+        fn new(calls: &'a RefCell<Vec<usize>>, pass: bool) -> Self {
+            Self { calls, pass }
+        }
+    }
+
+    impl<const N: usize> AccountValidate<Check<'_, N>> for u8 {
+        fn validate_account(_self_ref: &Self::Ptr, arg: Check<'_, N>) -> Result<()> {
+            arg.calls.borrow_mut().push(N);
+            ensure!(
+                arg.pass,
+                ProgramError::InvalidAccountData,
+                "Check {N} failed"
+            );
+            Ok(())
+        }
+    }
+
+    struct ProgramIdCheck(Pubkey);
+
+    impl AccountValidateWithContext<ProgramIdCheck> for u8 {
+        fn validate_account_with_context(
+            _self_ref: &Self::Ptr,
+            arg: ProgramIdCheck,
+            ctx: &Context,
+        ) -> Result<()> {
+            ensure!(
+                ctx.current_program_id() == &arg.0,
+                ProgramError::InvalidAccountData,
+                "Wrong program id"
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_validate_with_context() -> Result<()> {
+        let account = u8::new_byte_set(5)?;
+        let data = account.data()?;
+        let ctx = Context::default();
+
+        <u8 as AccountValidateWithContext<_>>::validate_account_with_context(
+            &data,
+            ProgramIdCheck(Pubkey::new_from_array([0; 32])),
+            &ctx,
+        )?;
+        assert!(
+            <u8 as AccountValidateWithContext<_>>::validate_account_with_context(
+                &data,
+                ProgramIdCheck(Pubkey::new_unique()),
+                &ctx,
+            )
+            .is_err()
+        );
+
+        // Plain `AccountValidate` implementations ignore the context
+        let calls = RefCell::new(vec![]);
+        <u8 as AccountValidateWithContext<_>>::validate_account_with_context(
+            &data,
+            Check::<1>::new(&calls, true),
+            &ctx,
+        )?;
+        assert_eq!(calls.take(), vec![1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_chain() -> Result<()> {
+        let account = u8::new_byte_set(5)?;
+        let data = account.data()?;
+
+        let calls = RefCell::new(vec![]);
+        <u8 as AccountValidate<_>>::validate_account(
+            &data,
+            (
+                Check::<1>::new(&calls, true),
+                Check::<2>::new(&calls, true),
+                Check::<3>::new(&calls, true),
+            ),
+        )?;
+        assert_eq!(calls.take(), vec![1, 2, 3]);
+
+        let res = <u8 as AccountValidate<_>>::validate_account(
+            &data,
+            (
+                Check::<1>::new(&calls, true),
+                Check::<2>::new(&calls, false),
+                Check::<3>::new(&calls, true),
+            ),
+        );
+        assert!(res.is_err());
+        assert_eq!(calls.take(), vec![1, 2]);
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! Versioned [`ProgramAccount`]s that are migrated from older layouts when they are validated.
+//!
+//! A versioned account is derived with `#[program_account(version = N)]`. The version doesn't change the discriminant,
+//! so each version is its own type and is distinguished on chain by its discriminant, the Anchor style sighash of the
+//! type name by default. Versions above 1 must set `migrate_from`, or reject older versions explicitly with
+//! `min_version = N, max_version = N`.
+//!
+//! Adding `migrate_from = <PreviousTy>` makes [`Account`] accept accounts stored as the previous version during
+//! validation. The data is migrated in place with [`MigrateFrom::migrate_from`], resizing the account if the layout
+//! changed, so the account must be writable. Previous versions can be migrated from their own previous versions,
+//! down to [`VersionedAccount::MIN_VERSION`]. Since the account may grow, combine it with a rent cleanup like
+//! [`NormalizeRent`].
+//!
+//! To version an existing account, rename the existing type to the first version and keep its discriminant with
+//! `discriminant = sighash!("account", "<OldName>")`, so accounts already on chain are migrated rather than rejected.
+//! The [`CloseAndReinit`] cleanup arg resets a validated account to the default of its current version.
+//!
+//! # Example
+//! ```
+//! # fn main() {}
+//! use star_frame::prelude::*;
+//!
+//! # #[derive(StarFrameProgram)]
+//! # #[program(instruction_set = (), id = System::ID, no_entrypoint)]
+//! # pub struct MyProgram;
+//! #[zero_copy(pod)]
+//! #[derive(ProgramAccount, Debug)]
+//! #[program_account(version = 1)]
+//! pub struct CounterV1 {
+//!     pub count: u32,
+//! }
+//!
+//! #[zero_copy(pod)]
+//! #[derive(ProgramAccount, Debug)]
+//! #[program_account(min_version = 1, max_version = 2, migrate_from = CounterV1)]
+//! pub struct Counter {
+//!     pub count: u64,
+//!     pub authority: Pubkey,
+//! }
+//!
+//! impl MigrateFrom<CounterV1> for Counter {
+//!     fn migrate_from(previous: &CounterV1) -> Result<Self> {
+//!         Ok(Self {
+//!             count: previous.count.into(),
+//!             authority: Pubkey::default(),
+//!         })
+//!     }
+//! }
+//! ```
+use crate::{account_set::modifiers::OwnerProgramDiscriminant, prelude::*, ErrorCode};
+use bytemuck::bytes_of;
+
+/// A [`ProgramAccount`] with a version.
+///
+/// Derivable via `#[program_account(version = N)]` or `#[program_account(min_version = M, max_version = N)]` on
+/// [`derive@ProgramAccount`].
+pub trait VersionedAccount: ProgramAccount {
+    /// The current version of the account.
+    const VERSION: u8;
+    /// The oldest version that is still accepted and migrated to [`Self::VERSION`].
+    const MIN_VERSION: u8;
+
+    /// Migrates the account `data`, including the discriminant, from an older version that is at least `min_version`.
+    ///
+    /// Returns the data of the account at [`Self::VERSION`], or [`None`] if `data` is not stored as an older version.
+    fn migrate_data(_data: &[u8], _min_version: u8) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// Migrates a [`VersionedAccount`] from its previous version.
+///
+/// Used by `#[program_account(migrate_from = <Previous>)]` on [`derive@ProgramAccount`].
+pub trait MigrateFrom<Previous>: Sized {
+    /// Creates the current version of the account from the `previous` version.
+    fn migrate_from(previous: &Previous) -> Result<Self>;
+}
+
+/// Migrates `data` stored as `Previous`, or any version `Previous` migrates from, to `T`.
+///
+/// This is the [`VersionedAccount::migrate_data`] of accounts with `#[program_account(migrate_from = <Previous>)]`.
+pub fn migrate_data_from<T, Previous>(data: &[u8], min_version: u8) -> Result<Option<Vec<u8>>>
+where
+    T: VersionedAccount + MigrateFrom<Previous> + NoUninit,
+    Previous: VersionedAccount<OwnerProgram = T::OwnerProgram> + Pod,
+{
+    const {
+        assert!(
+            !pod_bytes_eq(&Previous::DISCRIMINANT, &T::DISCRIMINANT),
+            "Each version of the account must have a different discriminant"
+        );
+    }
+    if min_version > Previous::VERSION {
+        return Ok(None);
+    }
+    let migrated = Previous::migrate_data(data, min_version)?;
+    let data = migrated.as_deref().unwrap_or(data);
+
+    let discriminant_size = size_of::<OwnerProgramDiscriminant<T>>();
+    if data.get(..discriminant_size) != Some(bytes_of(&Previous::DISCRIMINANT)) {
+        return Ok(None);
+    }
+    let previous_bytes = data
+        .get(discriminant_size..discriminant_size + size_of::<Previous>())
+        .ok_or_else(|| {
+            error!(
+                ProgramError::AccountDataTooSmall,
+                "Account data length {} is too small for version {} of the account",
+                data.len(),
+                Previous::VERSION
+            )
+        })?;
+    let previous: Previous = bytemuck::pod_read_unaligned(previous_bytes);
+    let current = T::migrate_from(&previous)?;
+
+    let mut bytes = T::discriminant_bytes();
+    bytes.extend_from_slice(bytes_of(&current));
+    Ok(Some(bytes))
+}
+
+/// Compares the bytes of two [`Pod`] values in a const context.
+const fn pod_bytes_eq<D: Pod>(a: &D, b: &D) -> bool {
+    let size = size_of::<D>();
+    // SAFETY: `D` is `Pod`, so all `size` bytes behind both references are initialized.
+    let (a, b) = unsafe {
+        (
+            core::slice::from_raw_parts((a as *const D).cast::<u8>(), size),
+            core::slice::from_raw_parts((b as *const D).cast::<u8>(), size),
+        )
+    };
+    let mut i = 0;
+    while i < size {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Migrates the data of `info` in place to the current version of `T`, resizing it if needed.
+///
+/// Does nothing if the account is not owned by `T`'s program or is not stored as an older version.
+pub fn migrate_account<T: VersionedAccount + ?Sized>(info: AccountInfo) -> Result<()> {
+    if !info.owner().fast_eq(&T::OwnerProgram::ID) {
+        return Ok(());
+    }
+    let migrated = T::migrate_data(&info.account_data()?, T::MIN_VERSION)?;
+    let Some(migrated) = migrated else {
+        return Ok(());
+    };
+    ensure!(
+        info.is_writable(),
+        ErrorCode::ExpectedWritable,
+        "Account {} must be writable to be migrated to version {}",
+        info.pubkey(),
+        T::VERSION
+    );
+    if migrated.len() != info.data_len() {
+        info.resize(migrated.len())?;
+    }
+    info.account_data_mut()?.copy_from_slice(&migrated);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_set::account::discriminant::AccountDiscriminant;
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl, version = 1)]
+    struct CounterV1 {
+        count: u32,
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl, version = 2, migrate_from = CounterV1)]
+    struct CounterV2 {
+        count: u64,
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl, min_version = 1, max_version = 3, migrate_from = CounterV2)]
+    struct Counter {
+        count: u64,
+        bumps: u8,
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount, Debug, PartialEq, Eq)]
+    #[program_account(skip_idl, min_version = 2, max_version = 3, migrate_from = CounterV2)]
+    struct RecentCounter {
+        count: u64,
+    }
+
+    impl MigrateFrom<CounterV1> for CounterV2 {
+        fn migrate_from(previous: &CounterV1) -> Result<Self> {
+            Ok(Self {
+                count: u64::from(previous.count) * 10,
+            })
+        }
+    }
+
+    impl MigrateFrom<CounterV2> for Counter {
+        fn migrate_from(previous: &CounterV2) -> Result<Self> {
+            Ok(Self {
+                count: previous.count + 1,
+                bumps: 1,
+            })
+        }
+    }
+
+    impl MigrateFrom<CounterV2> for RecentCounter {
+        fn migrate_from(previous: &CounterV2) -> Result<Self> {
+            Ok(Self {
+                count: previous.count,
+            })
+        }
+    }
+
+    fn account_bytes<T: ProgramAccount + NoUninit>(data: &T) -> Vec<u8> {
+        let mut bytes = T::discriminant_bytes();
+        bytes.extend_from_slice(bytes_of(data));
+        bytes
+    }
+
+    #[test]
+    fn test_versioned_discriminant() {
+        assert_eq!(CounterV1::VERSION, 1);
+        assert_eq!(CounterV2::MIN_VERSION, 1);
+        assert_eq!(Counter::VERSION, 3);
+        assert_eq!(Counter::MIN_VERSION, 1);
+        assert_eq!(RecentCounter::MIN_VERSION, 2);
+
+        // The version doesn't change the discriminant
+        assert_eq!(Counter::DISCRIMINANT, crate::sighash!("account", "Counter"));
+        assert_eq!(
+            CounterV1::DISCRIMINANT,
+            crate::sighash!("account", "CounterV1")
+        );
+    }
+
+    #[test]
+    fn test_migrate_v1_to_v2() -> Result<()> {
+        let v1 = account_bytes(&CounterV1 { count: 4 });
+        let migrated =
+            CounterV2::migrate_data(&v1, CounterV2::MIN_VERSION)?.expect("V1 data is migrated");
+        assert_eq!(
+            AccountDiscriminant::<CounterV2>::owned(&migrated)?,
+            CounterV2 { count: 40 }
+        );
+
+        // Data already at the current version is left alone
+        assert_eq!(CounterV2::migrate_data(&migrated, 1)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_chain() -> Result<()> {
+        let v1 = account_bytes(&CounterV1 { count: 4 });
+        let migrated =
+            Counter::migrate_data(&v1, Counter::MIN_VERSION)?.expect("V1 data is migrated");
+        assert_eq!(
+            AccountDiscriminant::<Counter>::owned(&migrated)?,
+            Counter {
+                count: 41,
+                bumps: 1
+            }
+        );
+
+        let v2 = account_bytes(&CounterV2 { count: 4 });
+        let migrated =
+            Counter::migrate_data(&v2, Counter::MIN_VERSION)?.expect("V2 data is migrated");
+        assert_eq!(
+            AccountDiscriminant::<Counter>::owned(&migrated)?,
+            Counter { count: 5, bumps: 1 }
+        );
+
+        // V1 is below the minimum version
+        assert_eq!(
+            RecentCounter::migrate_data(&v1, RecentCounter::MIN_VERSION)?,
+            None
+        );
+        assert!(RecentCounter::migrate_data(&v2, RecentCounter::MIN_VERSION)?.is_some());
+
+        // Truncated previous versions can't be migrated
+        assert!(Counter::migrate_data(&v2[..10], Counter::MIN_VERSION).is_err());
+        Ok(())
+    }
+}
@@ -64,3 +64,37 @@ impl_align1_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
 impl_align1_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
 impl_align1_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
 impl_align1_tuple!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
+
+#[cfg(test)]
+mod tests {
+    use super::Align1;
+
+    #[allow(dead_code)]
+    #[derive(Align1, Copy, Clone, Debug, PartialEq, Eq)]
+    #[repr(u8)]
+    enum RobotState {
+        Idle,
+        Working,
+        Charging,
+    }
+
+    #[allow(dead_code, clippy::upper_case_acronyms)]
+    #[derive(Align1, Copy, Clone, Debug, PartialEq, Eq)]
+    #[repr(u8)]
+    enum FactionId {
+        MUD = 1,
+        ONI = 2,
+        Ustur = 3,
+    }
+
+    fn assert_align1<T: Align1>() {
+        assert_eq!(core::mem::align_of::<T>(), 1);
+    }
+
+    #[test]
+    fn test_unit_enums() {
+        assert_align1::<RobotState>();
+        assert_align1::<FactionId>();
+        assert_align1::<[FactionId; 3]>();
+    }
+}
@@ -20,6 +20,7 @@ use crate::{
 use borsh::{object_length, BorshSerialize};
 use bytemuck::bytes_of;
 use solana_instruction::Instruction as SolanaInstruction;
+use std::io::Read as _;
 
 #[doc(hidden)]
 pub fn star_frame_instruction_data<S, I>(data: &I) -> Result<Vec<u8>>
@@ -51,15 +52,32 @@ pub trait MakeInstruction: StarFrameProgram {
             data,
         })
     }
+
+    /// Deserializes the return data of an instruction using [`BorshDeserialize`].
+    ///
+    /// The runtime strips trailing zeros from return data, so `data` is padded with zeros as needed.
+    fn deserialize_return<T>(data: &[u8]) -> Result<T>
+    where
+        T: BorshDeserialize,
+    {
+        let mut reader = data.chain(std::io::repeat(0));
+        T::deserialize_reader(&mut reader).ctx("Failed to deserialize return data")
+    }
 }
 
 impl<T> MakeInstruction for T where T: StarFrameProgram + ?Sized {}
 
+/// Derives the address of accounts with [`HasSeeds`] under their [`HasOwnerProgram::OwnerProgram`].
+///
+/// This is implemented for every [`ProgramAccount`] with `#[program_account(seeds = <type>)]`, so addresses can be
+/// found with `CounterAccount::find_program_address(&CounterAccountSeeds { owner })`.
 pub trait FindProgramAddress: HasSeeds + HasOwnerProgram {
+    /// Finds the address and bump of the account with `seeds`.
     fn find_program_address(seeds: &Self::Seeds) -> (Pubkey, u8) {
         Pubkey::find_program_address(&seeds.seeds(), &Self::OwnerProgram::ID)
     }
 
+    /// Creates the address of the account with `seeds` and a known `bump`.
     fn create_program_address(seeds: &Self::Seeds, bump: u8) -> Result<Pubkey> {
         let mut seeds = seeds.seeds();
         let bump = &[bump];
@@ -69,6 +87,17 @@ pub trait FindProgramAddress: HasSeeds + HasOwnerProgram {
             &Self::OwnerProgram::ID,
         )?)
     }
+
+    /// Errors if `key` is not the address found from `seeds`.
+    fn verify_address(key: &Pubkey, seeds: &Self::Seeds) -> Result<()> {
+        let (address, bump) = Self::find_program_address(seeds);
+        ensure!(
+            address.fast_eq(key),
+            ErrorCode::AddressMismatch,
+            "Seeds result in address `{address}` and bump `{bump}`, expected `{key}`"
+        );
+        Ok(())
+    }
 }
 
 impl<T> FindProgramAddress for T where T: HasSeeds + HasOwnerProgram {}
@@ -136,8 +165,9 @@ pub trait DeserializeBorshAccount: BorshDeserialize + ProgramAccount {
     fn deserialize_account(data: &[u8]) -> Result<Self> {
         check_discriminant::<Self>(data)
             .ctx("Failed to validate the discriminant in DeserializeBorshAccount")?;
-        let data = &data[size_of::<OwnerProgramDiscriminant<Self>>()..];
-        BorshDeserialize::try_from_slice(data).map_err(Into::into)
+        // Accounts may have trailing space pre-allocated with `WithSpace`
+        let mut data = &data[size_of::<OwnerProgramDiscriminant<Self>>()..];
+        BorshDeserialize::deserialize(&mut data).map_err(Into::into)
     }
 }
 
@@ -191,3 +221,47 @@ pub trait SerializeBorshAccount: BorshSerialize + ProgramAccount {
 }
 
 impl<T> SerializeBorshAccount for T where T: BorshSerialize + ProgramAccount + ?Sized {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StarFrameDeclaredProgram;
+
+    #[derive(Debug, GetSeeds, Clone)]
+    #[get_seeds(seed_const = b"SEEDED", skip_idl)]
+    struct SeededAccountSeeds {
+        owner: Pubkey,
+    }
+
+    #[allow(dead_code)]
+    #[zero_copy(pod)]
+    #[derive(ProgramAccount)]
+    #[program_account(skip_idl, seeds = SeededAccountSeeds)]
+    struct SeededAccount {
+        count: u64,
+    }
+
+    #[test]
+    fn test_find_program_address() -> Result<()> {
+        let seeds = SeededAccountSeeds {
+            owner: Pubkey::new_unique(),
+        };
+        let (address, bump) = SeededAccount::find_program_address(&seeds);
+        assert_eq!(
+            (address, bump),
+            Pubkey::find_program_address(&seeds.seeds(), &StarFrameDeclaredProgram::ID)
+        );
+        assert_eq!(
+            SeededAccount::create_program_address(&seeds, bump)?,
+            address
+        );
+
+        SeededAccount::verify_address(&address, &seeds)?;
+        assert!(SeededAccount::verify_address(&Pubkey::new_unique(), &seeds).is_err());
+        let other_seeds = SeededAccountSeeds {
+            owner: Pubkey::new_unique(),
+        };
+        assert!(SeededAccount::verify_address(&address, &other_seeds).is_err());
+        Ok(())
+    }
+}
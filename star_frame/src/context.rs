@@ -1,12 +1,12 @@
 //! Context for instruction execution.
 //!
-//! Caches sysvars, program id, and funder/recipients for account sets to use.
+//! Caches sysvars, program id, and funder/recipients for account sets to use, and exposes the remaining compute budget.
 use crate::{
     account_set::{CanAddLamports, CanFundRent},
     prelude::*,
 };
 use pinocchio::sysvars::{clock::Clock, rent::Rent, Sysvar};
-use std::cell::Cell;
+use std::cell::{Cell, OnceCell};
 
 /// Additional context given to [`crate::instruction::StarFrameInstruction`]s, enabling programs to cache and retrieve helpful information during instruction execution.
 #[derive(Debug)]
@@ -21,6 +21,19 @@ pub struct Context {
     recipient: Option<Box<dyn CanAddLamports>>,
     // Cached funder for rent. Usually set during `AccountSetValidate`
     funder: Option<Box<dyn CanFundRent>>,
+    // Remaining compute units the first time they were measured, the baseline for `ComputeBudget::used`
+    compute_units_start: OnceCell<u64>,
+}
+
+/// A snapshot of the compute budget of the current transaction, returned by [`Context::compute_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ComputeBudget {
+    /// The compute units consumed since the [`Context`] first measured compute units.
+    pub used: u64,
+    /// The compute units remaining in the transaction.
+    pub remaining: u64,
+    /// The compute units that were remaining when the [`Context`] first measured compute units.
+    pub start: u64,
 }
 
 impl Default for Context {
@@ -40,6 +53,7 @@ impl Context {
             clock_cache: Cell::new(None),
             recipient: None,
             funder: None,
+            compute_units_start: OnceCell::new(),
         }
     }
 
@@ -72,6 +86,37 @@ impl Context {
         }
     }
 
+    /// Gets the compute units remaining in the transaction. Always returns `0` off-chain.
+    ///
+    /// The first call records the baseline used by [`Self::compute_budget`]. Each call costs a syscall.
+    pub fn remaining_compute_units(&self) -> u64 {
+        #[cfg(target_os = "solana")]
+        // SAFETY: The syscall has no preconditions
+        let remaining = unsafe { pinocchio::syscalls::sol_remaining_compute_units() };
+        #[cfg(not(target_os = "solana"))]
+        let remaining = 0;
+        self.compute_units_start.get_or_init(|| remaining);
+        remaining
+    }
+
+    /// Gets a snapshot of the compute budget.
+    ///
+    /// `used` and `start` are measured from the first call to [`Self::remaining_compute_units`] or this function, so
+    /// call one of them early in the instruction to track its whole usage. There is no syscall for the transaction's
+    /// compute unit limit.
+    pub fn compute_budget(&self) -> ComputeBudget {
+        self.compute_budget_at(self.remaining_compute_units())
+    }
+
+    fn compute_budget_at(&self, remaining: u64) -> ComputeBudget {
+        let start = *self.compute_units_start.get_or_init(|| remaining);
+        ComputeBudget {
+            used: start.saturating_sub(remaining),
+            remaining,
+            start,
+        }
+    }
+
     /// Gets the cached funder for rent if it has been set.
     pub fn get_funder(&self) -> Option<&dyn CanFundRent> {
         self.funder.as_ref().map(std::convert::AsRef::as_ref)
@@ -92,3 +137,36 @@ impl Context {
         self.recipient.replace(recipient);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_budget_measures_from_first_call() {
+        let ctx = Context::default();
+        assert_eq!(
+            ctx.compute_budget_at(1_000),
+            ComputeBudget {
+                used: 0,
+                remaining: 1_000,
+                start: 1_000,
+            }
+        );
+        assert_eq!(
+            ctx.compute_budget_at(400),
+            ComputeBudget {
+                used: 600,
+                remaining: 400,
+                start: 1_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute_budget_off_chain() {
+        let ctx = Context::default();
+        assert_eq!(ctx.remaining_compute_units(), 0);
+        assert_eq!(ctx.compute_budget(), ComputeBudget::default());
+    }
+}
@@ -2,9 +2,10 @@
 use std::mem::MaybeUninit;
 
 use crate::{
-    account_set::{CpiAccountSet, DynamicCpiAccountSetLen},
+    account_set::{modifiers::SignedAccount, CpiAccountSet, DynamicCpiAccountSetLen},
     instruction::InstructionDiscriminant,
     prelude::*,
+    SolanaInstruction,
 };
 use borsh::object_length;
 use bytemuck::bytes_of;
@@ -20,7 +21,8 @@ use typenum::{False, True};
 
 /// A builder for creating a CPI instruction for a [`StarFrameProgram`].
 ///
-/// Returned from [`MakeCpi::cpi`], and can be invoked with [`CpiBuilder::invoke`] or [`CpiBuilder::invoke_signed`].
+/// Returned from [`MakeCpi::cpi`], and can be invoked with [`CpiBuilder::invoke`], [`CpiBuilder::invoke_signed`],
+/// or [`CpiBuilder::invoke_signed_by`].
 #[must_use = "Did you forget to invoke the builder?"]
 #[derive(Debug, Clone)]
 pub struct CpiBuilder<'program, P, Ix, A>
@@ -183,7 +185,92 @@ where
 
         Ok(())
     }
+
+    /// Invokes the instruction, signing with the seeds of each of the `signers` that has them.
+    ///
+    /// This saves building the signer seeds by hand for PDAs that are already validated in the
+    /// current instruction, such as [`Seeded`] accounts for the current program. Signers without seeds are skipped.
+    ///
+    /// # Example
+    /// ```ignore
+    /// MyProgram::cpi(&MyInstruction { .. }, MyInstructionCpiAccounts { .. }, None)
+    ///     .invoke_signed_by(&[&accounts.vault_authority])?;
+    /// ```
+    pub fn invoke_signed_by(&self, signers: &[&dyn SignedAccount]) -> Result<()> {
+        let signer_seeds = signers
+            .iter()
+            .filter_map(|signer| signer.signer_seeds())
+            .collect_vec();
+        let signer_seeds = signer_seeds.iter().map(Vec::as_slice).collect_vec();
+        self.invoke_signed(&signer_seeds)
+    }
 }
+
+impl<T: StarFrameProgram> Program<T> {
+    /// Invokes a prebuilt [`SolanaInstruction`] on this program.
+    ///
+    /// Prefer [`MakeCpi::cpi`] for star frame instructions. This is useful when the instruction comes from
+    /// elsewhere, such as a third party instruction builder.
+    ///
+    /// Errors with [`ProgramError::IncorrectProgramId`] if the instruction's program ID is not `T::ID`.
+    pub fn invoke(&self, instruction: &SolanaInstruction, accounts: &[AccountInfo]) -> Result<()> {
+        self.invoke_signed(instruction, accounts, &[])
+    }
+
+    /// Invokes a prebuilt [`SolanaInstruction`] on this program with the given signer seeds.
+    ///
+    /// See [`Program::invoke`].
+    pub fn invoke_signed(
+        &self,
+        instruction: &SolanaInstruction,
+        accounts: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        ensure!(
+            instruction.program_id.fast_eq(&T::ID),
+            ProgramError::IncorrectProgramId,
+            "Instruction program {} does not match program {}",
+            instruction.program_id,
+            T::ID
+        );
+        let metas = instruction
+            .accounts
+            .iter()
+            .map(|meta| PinocchioAccountMeta {
+                pubkey: meta.pubkey.as_array(),
+                is_writable: meta.is_writable,
+                is_signer: meta.is_signer,
+            })
+            .collect_vec();
+        let infos = accounts.iter().collect_vec();
+
+        let nested_seeds: Vec<Vec<PinocchioSeed>> = signers_seeds
+            .iter()
+            .map(|seeds| {
+                seeds
+                    .iter()
+                    .map(|seed| PinocchioSeed::from(*seed))
+                    .collect_vec()
+            })
+            .collect_vec();
+        let signers: Vec<PinocchioSigner> = nested_seeds
+            .iter()
+            .map(|seeds| seeds.as_slice().into())
+            .collect_vec();
+
+        pinocchio::cpi::slice_invoke_signed(
+            &PinocchioInstruction {
+                program_id: instruction.program_id.as_array(),
+                data: &instruction.data,
+                accounts: &metas,
+            },
+            &infos,
+            &signers,
+        )?;
+        Ok(())
+    }
+}
+
 /// Private trait to handle CPI w/ fixed size arrays
 #[doc(hidden)]
 pub trait HandleCpiArray {
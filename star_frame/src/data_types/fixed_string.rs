@@ -0,0 +1,207 @@
+use crate::{prelude::*, ErrorCode};
+use std::{
+    fmt::{Debug, Display, Formatter},
+    io::{Read, Write},
+    str::FromStr,
+};
+
+/// A UTF-8 string stored inline as exactly `N` bytes, padded with trailing null bytes.
+///
+/// Strings longer than `N` bytes are truncated to the last character boundary that fits. Borsh serializes a
+/// [`FixedString`] as its `N` padded bytes, without a length prefix, so its borsh layout matches its `Pod` layout and
+/// the IDL's fixed size string.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Align1)]
+#[repr(transparent)]
+pub struct FixedString<const N: usize>([u8; N]);
+
+impl<const N: usize> FixedString<N> {
+    /// Creates a new [`FixedString`], truncating `s` to at most `N` bytes.
+    #[must_use]
+    pub fn new(s: &str) -> Self {
+        let mut bytes = [0; N];
+        let len = floor_char_boundary(s, N);
+        bytes[..len].copy_from_slice(&s.as_bytes()[..len]);
+        Self(bytes)
+    }
+
+    /// Returns the string with trailing null bytes stripped.
+    ///
+    /// If the underlying bytes are not valid UTF-8, only the leading valid portion is returned.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        let bytes = self.as_bytes();
+        match std::str::from_utf8(bytes) {
+            Ok(s) => s,
+            Err(e) => {
+                // SAFETY:
+                // `valid_up_to` is the length of the longest valid UTF-8 prefix
+                unsafe { std::str::from_utf8_unchecked(&bytes[..e.valid_up_to()]) }
+            }
+        }
+    }
+
+    /// Returns the bytes of the string with trailing null bytes stripped.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self.0.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        &self.0[..len]
+    }
+
+    /// Returns the full `N` byte backing array, including padding.
+    #[must_use]
+    pub fn as_padded_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    /// Sets the string, truncating `s` to at most `N` bytes.
+    pub fn set(&mut self, s: &str) {
+        *self = Self::new(s);
+    }
+}
+
+/// Returns the largest index `<= max` that is on a character boundary of `s`.
+fn floor_char_boundary(s: &str, max: usize) -> usize {
+    if max >= s.len() {
+        return s.len();
+    }
+    (0..=max)
+        .rev()
+        .find(|&index| s.is_char_boundary(index))
+        .unwrap_or(0)
+}
+
+impl<const N: usize> Default for FixedString<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+// SAFETY:
+// `FixedString` is a transparent wrapper around a `[u8; N]` which is `Zeroable`
+unsafe impl<const N: usize> Zeroable for FixedString<N> {}
+// SAFETY:
+// `FixedString` is a transparent wrapper around a `[u8; N]` which is `Pod`
+unsafe impl<const N: usize> Pod for FixedString<N> {}
+
+impl<const N: usize> Debug for FixedString<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Display for FixedString<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> FromStr for FixedString<N> {
+    type Err = Error;
+
+    /// Parses a [`FixedString`], erroring instead of truncating if `s` is longer than `N` bytes.
+    fn from_str(s: &str) -> Result<Self> {
+        ensure!(
+            s.len() <= N,
+            ErrorCode::IndexOutOfBounds,
+            "String of {} bytes does not fit in FixedString<{N}>",
+            s.len()
+        );
+        Ok(Self::new(s))
+    }
+}
+
+impl<const N: usize> From<&str> for FixedString<N> {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl<const N: usize> PartialEq<str> for FixedString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for FixedString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> BorshSerialize for FixedString<N> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.0)
+    }
+}
+
+impl<const N: usize> BorshDeserialize for FixedString<N> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut bytes = [0; N];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use star_frame_idl::{ty::IdlTypeDef, IdlDefinition};
+
+    impl<const N: usize> TypeToIdl for FixedString<N> {
+        type AssociatedProgram = System;
+        fn type_to_idl(_idl_definition: &mut IdlDefinition) -> crate::IdlResult<IdlTypeDef> {
+            Ok(IdlTypeDef::FixedString(N))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_string_pads_and_strips() {
+        let s = FixedString::<8>::new("abc");
+        assert_eq!(s.as_padded_bytes(), b"abc\0\0\0\0\0");
+        assert_eq!(s.as_str(), "abc");
+        assert_eq!(s, "abc");
+        assert_eq!(&s, "abc");
+        assert_ne!(s, "abcd");
+        assert_eq!(s.to_string(), "abc");
+        assert_eq!(format!("{s:?}"), "\"abc\"");
+        assert_eq!(FixedString::<8>::default(), "");
+        assert_eq!(bytemuck::bytes_of(&s), b"abc\0\0\0\0\0");
+    }
+
+    #[test]
+    fn test_fixed_string_truncation() -> Result<()> {
+        assert_eq!(FixedString::<4>::new("abcdef"), "abcd");
+        // `é` is 2 bytes, so it can't be split
+        assert_eq!(FixedString::<4>::new("abcé"), "abc");
+        assert_eq!(FixedString::<5>::new("abcé"), "abcé");
+        assert!(FixedString::<4>::from_str("abcde").is_err());
+        assert_eq!(FixedString::<4>::from_str("abcd")?, "abcd");
+        // Invalid trailing bytes are ignored
+        let s: FixedString<4> = bytemuck::cast(*b"ab\xff\0");
+        assert_eq!(s.as_str(), "ab");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_string_borsh() -> Result<()> {
+        let s = FixedString::<8>::new("star");
+        let bytes = borsh::to_vec(&s)?;
+        // Exactly `N` bytes with no length prefix, matching the `Pod` layout and the IDL's `FixedString(N)`
+        assert_eq!(bytes, b"star\0\0\0\0");
+        assert_eq!(bytes, bytemuck::bytes_of(&s));
+        assert_eq!(FixedString::<8>::try_from_slice(&bytes)?, s);
+
+        let full = FixedString::<4>::new("star_frame");
+        assert_eq!(
+            FixedString::<4>::try_from_slice(&borsh::to_vec(&full)?)?,
+            "star"
+        );
+        assert!(FixedString::<8>::try_from_slice(b"star").is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Utility data types for Star Frame programs.
 // Just impls, no need to re-export
 mod fixed_point;
+mod fixed_string;
 mod key_for;
 mod optional_key_for;
 mod packed_value;
@@ -8,6 +9,7 @@ mod pod_bool;
 mod remaining_data;
 mod unit_system;
 
+pub use fixed_string::*;
 pub use key_for::*;
 pub use optional_key_for::*;
 pub use packed_value::*;
@@ -345,7 +345,24 @@ mod idl_impl {
         type AssociatedProgram = T::AssociatedProgram;
 
         fn type_to_idl(idl_definition: &mut IdlDefinition) -> crate::IdlResult<IdlTypeDef> {
-            T::type_to_idl(idl_definition)
+            Ok(IdlTypeDef::Newtype {
+                name: "PackedValue".to_string(),
+                inner: Box::new(T::type_to_idl(idl_definition)?),
+            })
+        }
+    }
+
+    impl<T> TypeToIdl for PackedValueChecked<T>
+    where
+        T: TypeToIdl,
+    {
+        type AssociatedProgram = T::AssociatedProgram;
+
+        fn type_to_idl(idl_definition: &mut IdlDefinition) -> crate::IdlResult<IdlTypeDef> {
+            Ok(IdlTypeDef::Newtype {
+                name: "PackedValueChecked".to_string(),
+                inner: Box::new(T::type_to_idl(idl_definition)?),
+            })
         }
     }
 }
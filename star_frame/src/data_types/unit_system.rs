@@ -617,7 +617,10 @@ mod idl {
         type AssociatedProgram = T::AssociatedProgram;
 
         fn type_to_idl(idl_definition: &mut IdlDefinition) -> crate::IdlResult<IdlTypeDef> {
-            T::type_to_idl(idl_definition)
+            Ok(IdlTypeDef::Newtype {
+                name: "UnitVal".to_string(),
+                inner: Box::new(T::type_to_idl(idl_definition)?),
+            })
         }
     }
 }
@@ -32,6 +32,16 @@ pub enum ErrorCode {
     ConflictingAccountSeeds,
     #[msg("Seeds not set during init")]
     SeedsNotSet,
+    #[msg("Account's owner does not match expected owner")]
+    OwnerMismatch,
+    #[msg("The same account is used in conflicting positions")]
+    DuplicateAccount,
+    #[msg("Account data does not match its integrity hash")]
+    HashMismatch,
+    #[msg("Account cannot be closed to itself")]
+    CloseToSelf,
+    #[msg("Not enough accounts signed")]
+    InsufficientSigners,
 
     // Unsized Type errors
     #[msg("An unexpected unsized type error occurred. This is a bug in star_frame")]
@@ -46,6 +56,8 @@ pub enum ErrorCode {
     IndexOutOfBounds = 3_000,
     #[msg("Invalid range")]
     InvalidRange,
+    #[msg("Async calls are not available in a blocking context")]
+    AsyncUnavailable,
 
     // Conversion from other errors
     #[msg("num_traits::cast::ToPrimitive")]
@@ -601,4 +613,28 @@ mod tests {
         let _: fn() -> Result<(), Error> = || bail!(ProgramError::IllegalOwner);
         let _: fn() -> Result<(), Error> = || bail!(ErrorCode::BorrowError, "Hello {}!", "world");
     }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[star_frame_error(offset = 1)]
+    enum OtherError {
+        /// Returned when something else goes wrong
+        #[msg("Other error")]
+        Other = 5,
+    }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[test]
+    fn test_errors_to_idl() -> Result<(), Error> {
+        use crate::{idl::ErrorsToIdl, star_frame_idl::IdlDefinition};
+
+        let mut idl = IdlDefinition::default();
+        <(ErrorCode, OtherError)>::errors_to_idl(&mut idl)?;
+        assert_eq!(idl.errors[0].code, ErrorCode::ExpectedWritable as usize);
+        assert_eq!(idl.errors[0].message, "Account is not writable");
+        let other = idl.errors.last().unwrap();
+        assert_eq!(other.code, (1 << 16) + 5);
+        assert_eq!(other.message, "Other error");
+        assert_eq!(idl.errors.iter().filter(|e| e.code >> 16 == 1).count(), 1);
+        Ok(())
+    }
 }
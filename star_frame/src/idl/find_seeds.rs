@@ -1,3 +1,4 @@
+pub use crate::account_set::modifiers::{arg_path, seed_const, seed_path, FindSeed};
 use bytemuck::NoUninit;
 use star_frame_idl::seeds::IdlFindSeed;
 
@@ -23,28 +24,11 @@ impl<const N: usize> FindIdlSeeds for [IdlFindSeed; N] {
     }
 }
 
-#[must_use]
-pub fn seed_const<T: NoUninit>(seed: T) -> FindSeed<T> {
-    FindSeed::Const(seed)
-}
-/// Creates a seed that references an account path. For nested account sets, the fields should be split with a space. If
-/// you want to specify that a path is from the root and not nested (even if it's nested in another account set), prefix
-/// the path with a colon.
-#[must_use]
-pub fn seed_path<T: NoUninit>(path: &str) -> FindSeed<T> {
-    FindSeed::Path(path.to_string())
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub enum FindSeed<T: NoUninit> {
-    Path(String),
-    Const(T),
-}
-
 impl<T: NoUninit> From<FindSeed<T>> for IdlFindSeed {
     fn from(seed: FindSeed<T>) -> Self {
         match seed {
             FindSeed::Path(path) => IdlFindSeed::AccountPath(path),
+            FindSeed::Arg(path) => IdlFindSeed::ArgPath(path),
             FindSeed::Const(constant) => {
                 IdlFindSeed::Const(bytemuck::bytes_of::<T>(&constant).to_vec())
             }
@@ -56,6 +40,7 @@ impl<T: NoUninit> From<&FindSeed<T>> for IdlFindSeed {
     fn from(seed: &FindSeed<T>) -> Self {
         match seed {
             FindSeed::Path(path) => IdlFindSeed::AccountPath(path.clone()),
+            FindSeed::Arg(path) => IdlFindSeed::ArgPath(path.clone()),
             FindSeed::Const(constant) => {
                 IdlFindSeed::Const(bytemuck::bytes_of::<T>(constant).to_vec())
             }
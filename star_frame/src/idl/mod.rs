@@ -2,8 +2,10 @@
 use crate::{instruction::Instruction, prelude::*};
 use star_frame_idl::{
     account::IdlAccountId, account_set::IdlAccountSetDef, instruction::IdlInstructionDef,
-    seeds::IdlSeeds, ty::IdlTypeDef, CrateMetadata, IdlDefinition, IdlMetadata,
+    seeds::IdlSeeds, ty::IdlTypeDef, CrateMetadata, IdlDefinition, IdlDefinitionReference,
+    IdlMetadata, ItemSource,
 };
+use std::collections::BTreeMap;
 
 mod find_seeds;
 mod ty;
@@ -66,6 +68,23 @@ impl ErrorsToIdl for () {
     }
 }
 
+macro_rules! errors_to_idl_tuple {
+    ($($error:ident),+) => {
+        /// Adds the errors of each element in order, for programs with multiple error enums.
+        impl<$($error: ErrorsToIdl),+> ErrorsToIdl for ($($error,)+) {
+            fn errors_to_idl(idl_definition: &mut IdlDefinition) -> crate::IdlResult<()> {
+                $($error::errors_to_idl(idl_definition)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+errors_to_idl_tuple!(A);
+errors_to_idl_tuple!(A, B);
+errors_to_idl_tuple!(A, B, C);
+errors_to_idl_tuple!(A, B, C, D);
+
 #[doc(hidden)]
 #[must_use]
 pub fn empty_env_option(env: &str) -> Option<String> {
@@ -105,10 +124,31 @@ pub trait ProgramToIdl: StarFrameProgram {
         }
     }
 
+    /// The reference recorded in other programs' [`IdlMetadata::required_idl_definitions`] when they use this
+    /// program's types.
+    #[must_use]
+    fn idl_definition_reference() -> IdlDefinitionReference {
+        IdlDefinitionReference {
+            version: Self::crate_metadata().version,
+            address: Self::ID,
+        }
+    }
+
+    /// Program IDs for external types by their [`ItemSource`], overriding the ID of the type's
+    /// [`TypeToIdl::AssociatedProgram`] in [`IdlMetadata::required_idl_definitions`].
+    #[must_use]
+    fn external_program_ids() -> BTreeMap<ItemSource, Pubkey> {
+        BTreeMap::new()
+    }
+
     fn modify_idl(_idl_definition: &mut IdlDefinition) -> crate::IdlResult<()> {
         Ok(())
     }
 
+    /// Generates the program's IDL.
+    ///
+    /// Returns [`Error::TypeNotFound`](star_frame_idl::Error::TypeNotFound) listing every
+    /// [`IdlDefinition::missing_types`] if a type is referenced but never added to the IDL.
     fn program_to_idl() -> crate::IdlResult<IdlDefinition>
     where
         <Self as StarFrameProgram>::InstructionSet: InstructionSetToIdl,
@@ -119,11 +159,13 @@ pub trait ProgramToIdl: StarFrameProgram {
                 crate_metadata: Self::crate_metadata(),
                 ..Default::default()
             },
+            external_program_ids: Self::external_program_ids(),
             ..Default::default()
         };
         <Self as StarFrameProgram>::InstructionSet::instruction_set_to_idl(&mut out)?;
         Self::Errors::errors_to_idl(&mut out)?;
         Self::modify_idl(&mut out)?;
+        out.check_missing_types()?;
         Ok(out)
     }
 }
@@ -153,6 +153,14 @@ macro_rules! impl_type_to_idl_for_tuple {
     };
 }
 
+/// The unit type is an empty struct, matching its empty [`borsh`] representation.
+impl TypeToIdl for () {
+    type AssociatedProgram = System;
+    fn type_to_idl(_idl_definition: &mut IdlDefinition) -> crate::IdlResult<IdlTypeDef> {
+        Ok(IdlTypeDef::Struct(vec![]))
+    }
+}
+
 // Implement for tuples of size 1 to 16
 impl_type_to_idl_for_tuple!(A);
 impl_type_to_idl_for_tuple!(A, B);
@@ -173,5 +181,115 @@ impl_type_to_idl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
 
 #[cfg(test)]
 mod tests {
-    // todo: add tests
+    use super::*;
+    use star_frame_idl::ty::IdlEnumVariant;
+
+    #[allow(dead_code)]
+    #[derive(TypeToIdl)]
+    struct Wrapped(u64);
+
+    #[allow(dead_code)]
+    #[derive(TypeToIdl)]
+    struct Pair(u8, Pubkey, #[type_to_idl(skip)] u64);
+
+    #[allow(dead_code)]
+    #[derive(TypeToIdl)]
+    struct CounterAccountData {
+        version: u8,
+        owner: Pubkey,
+        signer: Pubkey,
+        count: u64,
+        bump: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeToIdl)]
+    #[repr(u8)]
+    enum FactionId {
+        MUD = 1,
+        ONI = 2,
+        Ustur = 3,
+    }
+
+    fn defined_type_def<T: TypeToIdl>() -> IdlTypeDef {
+        let mut idl_definition = IdlDefinition::default();
+        let type_id = T::type_to_idl(&mut idl_definition)
+            .unwrap()
+            .assert_defined()
+            .unwrap()
+            .clone();
+        type_id
+            .get_defined(&idl_definition)
+            .unwrap()
+            .type_def
+            .clone()
+    }
+
+    fn field(path: &str, type_def: IdlTypeDef) -> IdlStructField {
+        IdlStructField {
+            path: Some(path.to_string()),
+            description: vec![],
+            type_def,
+        }
+    }
+
+    #[test]
+    fn test_tuple_struct_to_idl() {
+        assert_eq!(
+            defined_type_def::<Wrapped>(),
+            IdlTypeDef::Struct(vec![field("field_0", IdlTypeDef::U64)])
+        );
+        assert_eq!(
+            defined_type_def::<Pair>(),
+            IdlTypeDef::Struct(vec![
+                field("field_0", IdlTypeDef::U8),
+                field("field_1", IdlTypeDef::Pubkey),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_named_struct_to_idl() {
+        assert_eq!(
+            defined_type_def::<CounterAccountData>(),
+            IdlTypeDef::Struct(vec![
+                field("version", IdlTypeDef::U8),
+                field("owner", IdlTypeDef::Pubkey),
+                field("signer", IdlTypeDef::Pubkey),
+                field("count", IdlTypeDef::U64),
+                field("bump", IdlTypeDef::U8),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_newtype_to_idl() {
+        let mut idl_definition = IdlDefinition::default();
+        let packed = IdlTypeDef::Newtype {
+            name: "PackedValue".to_string(),
+            inner: Box::new(IdlTypeDef::U64),
+        };
+        assert_eq!(
+            PackedValue::<u64>::type_to_idl(&mut idl_definition).unwrap(),
+            packed
+        );
+        assert!(packed.same_layout(&IdlTypeDef::U64));
+    }
+
+    #[test]
+    fn test_enum_to_idl() {
+        let variant = |name: &str, discriminant: u8| IdlEnumVariant {
+            name: name.to_string(),
+            discriminant: vec![discriminant],
+            description: vec![],
+            type_def: None,
+        };
+        assert_eq!(
+            defined_type_def::<FactionId>(),
+            IdlTypeDef::Enum {
+                variants: vec![variant("MUD", 1), variant("ONI", 2), variant("Ustur", 3)],
+                size: Box::new(IdlTypeDef::U8),
+            }
+        );
+    }
 }
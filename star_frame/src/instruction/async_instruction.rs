@@ -0,0 +1,177 @@
+//! Async instruction processing for off-chain simulation.
+//!
+//! An `async fn` with [`star_frame_instruction`] implements [`AsyncStarFrameInstruction`] off chain, so it can await
+//! calls through an [`AsyncContext`], like fetching oracle prices over RPC. It also implements
+//! [`StarFrameInstruction`] by running the same body with a [`BlockingContext`], which can't make async calls.
+use crate::{prelude::*, ErrorCode};
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+    task::{Context as TaskContext, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+/// A boxed future returned by [`AsyncContext`] methods.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// The context of an async instruction, which can make async calls during off-chain simulation.
+pub trait AsyncContext {
+    /// The [`Context`] of the instruction.
+    fn context(&mut self) -> &mut Context;
+
+    /// Fetches the data of the account at `pubkey`, or [`None`] if the account doesn't exist.
+    fn fetch_account_data<'a>(
+        &'a self,
+        pubkey: &'a Pubkey,
+    ) -> BoxFuture<'a, Result<Option<Vec<u8>>>>;
+}
+
+/// An [`AsyncContext`] that can't make async calls, used when an async instruction is processed synchronously.
+///
+/// [`AsyncContext::fetch_account_data`] returns [`ErrorCode::AsyncUnavailable`].
+#[derive(Debug)]
+pub struct BlockingContext<'a>(pub &'a mut Context);
+
+impl AsyncContext for BlockingContext<'_> {
+    fn context(&mut self) -> &mut Context {
+        self.0
+    }
+
+    fn fetch_account_data<'a>(
+        &'a self,
+        pubkey: &'a Pubkey,
+    ) -> BoxFuture<'a, Result<Option<Vec<u8>>>> {
+        Box::pin(std::future::ready(Err(error!(
+            ErrorCode::AsyncUnavailable,
+            "Cannot fetch account {pubkey} in a blocking context"
+        ))))
+    }
+}
+
+/// An async [`StarFrameInstruction`], only available off chain.
+///
+/// Implemented by [`star_frame_instruction`] on an `async fn`.
+#[cfg(not(target_os = "solana"))]
+pub trait AsyncStarFrameInstruction: StarFrameInstruction {
+    /// Processes the instruction, awaiting any async calls made through `ctx`.
+    #[allow(async_fn_in_trait)]
+    async fn process_async(
+        accounts: &mut Self::Accounts<'_, '_>,
+        run_arg: Self::RunArg<'_>,
+        ctx: &mut dyn AsyncContext,
+    ) -> Result<Self::ReturnType>;
+}
+
+/// Runs an async instruction body without an executor. Used by [`star_frame_instruction`] for the
+/// [`StarFrameInstruction::process`] of an `async fn`.
+///
+/// The body is polled once, so it must not wait on anything other than a [`BlockingContext`].
+#[doc(hidden)]
+pub fn block_on_instruction<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(|_| RAW_WAKER, |_| {}, |_| {}, |_| {});
+    const RAW_WAKER: RawWaker = RawWaker::new(core::ptr::null(), &VTABLE);
+    // SAFETY:
+    // The vtable functions do nothing and never touch the data pointer
+    let waker = unsafe { Waker::from_raw(RAW_WAKER) };
+
+    match pin!(future).poll(&mut TaskContext::from_waker(&waker)) {
+        Poll::Ready(result) => result,
+        Poll::Pending => bail!(
+            ErrorCode::AsyncUnavailable,
+            "Async instruction is waiting on an async call in a blocking context"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::StarFrameError;
+
+    static ORACLE: Pubkey = Pubkey::new_from_array([7; 32]);
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+    #[ix_args(run)]
+    struct Quote {
+        amount: u64,
+        use_oracle: bool,
+    }
+
+    #[derive(AccountSet)]
+    struct QuoteAccounts;
+
+    #[star_frame_instruction]
+    async fn Quote(
+        _accounts: &mut QuoteAccounts,
+        Quote { amount, use_oracle }: Quote,
+        ctx: &mut dyn AsyncContext,
+    ) -> Result<u64> {
+        if !use_oracle {
+            return Ok(amount);
+        }
+        let price = match ctx.fetch_account_data(&ORACLE).await? {
+            Some(data) => u64::from_le_bytes(data[..8].try_into()?),
+            None => bail!(ProgramError::UninitializedAccount, "Oracle not found"),
+        };
+        Ok(amount * price)
+    }
+
+    struct OracleContext {
+        ctx: Context,
+        price: u64,
+    }
+
+    impl AsyncContext for OracleContext {
+        fn context(&mut self) -> &mut Context {
+            &mut self.ctx
+        }
+
+        fn fetch_account_data<'a>(
+            &'a self,
+            pubkey: &'a Pubkey,
+        ) -> BoxFuture<'a, Result<Option<Vec<u8>>>> {
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                Ok((*pubkey == ORACLE).then(|| self.price.to_le_bytes().to_vec()))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_async() -> Result<()> {
+        let mut ctx = OracleContext {
+            ctx: Context::default(),
+            price: 3,
+        };
+        let quote = Quote {
+            amount: 5,
+            use_oracle: true,
+        };
+        assert_eq!(
+            Quote::process_async(&mut QuoteAccounts, quote, &mut ctx).await?,
+            15
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_blocking() -> Result<()> {
+        let mut ctx = Context::default();
+        let quote = Quote {
+            amount: 5,
+            use_oracle: false,
+        };
+        assert_eq!(Quote::process(&mut QuoteAccounts, quote, &mut ctx)?, 5);
+
+        // Async calls aren't available synchronously
+        let quote = Quote {
+            use_oracle: true,
+            ..quote
+        };
+        let err = Quote::process(&mut QuoteAccounts, quote, &mut ctx).unwrap_err();
+        assert_eq!(
+            ProgramError::from(err),
+            ProgramError::Custom(ErrorCode::AsyncUnavailable.code())
+        );
+        Ok(())
+    }
+}
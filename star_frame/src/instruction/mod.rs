@@ -1,7 +1,10 @@
 //! Processing and handling of instructions from a [`StarFrameProgram::entrypoint`].
 
 use crate::{
-    account_set::{AccountSetCleanup, AccountSetDecode, AccountSetValidate},
+    account_set::{
+        check_duplicate_accounts, AccountSetCleanup, AccountSetDecode, AccountSetKeys,
+        AccountSetValidate,
+    },
     prelude::*,
 };
 use bytemuck::{bytes_of, Pod};
@@ -11,8 +14,14 @@ pub use star_frame_proc::{
     star_frame_instruction, InstructionArgs, InstructionSet, InstructionToIdl,
 };
 
+mod async_instruction;
 mod no_op;
 mod un_callable;
+#[doc(hidden)]
+pub use async_instruction::block_on_instruction;
+#[cfg(not(target_os = "solana"))]
+pub use async_instruction::AsyncStarFrameInstruction;
+pub use async_instruction::{AsyncContext, BlockingContext, BoxFuture};
 pub use un_callable::UnCallable;
 
 /// A set of instructions that can be used as input to a program.
@@ -22,6 +31,11 @@ pub trait InstructionSet {
     /// The discriminant type used by this program's instructions.
     type Discriminant: Pod;
 
+    /// Every discriminant this set dispatches on, including those of flattened sub-sets.
+    ///
+    /// Used to check sets flattened into a parent set with `#[ix_set(flatten = [...])]` for collisions.
+    const DISCRIMINANTS: &'static [Self::Discriminant];
+
     /// Dispatches the instruction data from the program entrypoint and then
     /// calls the appropriate [`Instruction::process_from_raw`] method.
     ///
@@ -33,6 +47,63 @@ pub trait InstructionSet {
     ) -> Result<()>;
 }
 
+/// Concatenates the discriminant lists of an instruction set and its flattened sub-sets. Used in the
+/// [`derive@InstructionSet`] macro.
+#[doc(hidden)]
+#[must_use]
+pub const fn concat_discriminants<D: Pod, const N: usize>(parts: &[&[D]]) -> [D; N] {
+    // SAFETY: `D` is `Pod`, so all zeroes is a valid value.
+    let mut out: [D; N] = unsafe { core::mem::zeroed() };
+    let mut index = 0;
+    let mut part = 0;
+    while part < parts.len() {
+        let mut i = 0;
+        while i < parts[part].len() {
+            out[index] = parts[part][i];
+            index += 1;
+            i += 1;
+        }
+        part += 1;
+    }
+    assert!(index == N, "Discriminant count does not match");
+    out
+}
+
+/// Returns true if any two discriminants in `discriminants` are equal. Used in the
+/// [`derive@InstructionSet`] macro to check flattened sub-sets for collisions at compile time.
+#[doc(hidden)]
+#[must_use]
+pub const fn has_duplicate_discriminants<D: Pod>(discriminants: &[D]) -> bool {
+    const fn bytes<D: Pod>(value: &D) -> &[u8] {
+        // SAFETY: `D` is `Pod`, so it has no padding and every byte is initialized.
+        unsafe {
+            core::slice::from_raw_parts(core::ptr::from_ref(value).cast::<u8>(), size_of::<D>())
+        }
+    }
+    let mut a = 0;
+    while a < discriminants.len() {
+        let mut b = a + 1;
+        while b < discriminants.len() {
+            let (left, right) = (bytes(&discriminants[a]), bytes(&discriminants[b]));
+            let mut i = 0;
+            let mut equal = true;
+            while i < left.len() {
+                if left[i] != right[i] {
+                    equal = false;
+                    break;
+                }
+                i += 1;
+            }
+            if equal {
+                return true;
+            }
+            b += 1;
+        }
+        a += 1;
+    }
+    false
+}
+
 /// A helper trait for the value of the instruction discriminant on an instruction.
 ///
 /// Since a single instruction can be in multiple [`InstructionSet`]s, this trait is generic over it
@@ -95,6 +166,42 @@ pub trait InstructionArgs: Sized {
     fn split_to_args(r: &mut Self) -> IxArgs<'_, Self>;
 }
 
+/// Deserializes a trailing instruction arg, returning `None` if the instruction data has already ended. Used in the
+/// [`derive@InstructionArgs`] macro for fields with `#[ix_args(default = ...)]` or `#[ix_args(optional)]`.
+#[doc(hidden)]
+pub fn deserialize_trailing_arg<T, R>(reader: &mut R) -> borsh::io::Result<Option<T>>
+where
+    T: BorshDeserialize,
+    R: borsh::io::Read,
+{
+    use borsh::io::Read as _;
+    let mut first = [0u8; 1];
+    if reader.read(&mut first)? == 0 {
+        return Ok(None);
+    }
+    T::deserialize_reader(&mut first.as_slice().chain(reader)).map(Some)
+}
+
+/// Checks that `T` can be held across await points by an `AsyncStarFrameInstruction`. Used in the
+/// [`derive@InstructionArgs`] macro, and only checks `T` when the `async` feature is enabled.
+#[doc(hidden)]
+#[cfg(feature = "async")]
+pub const fn assert_async_args<T: Send + 'static>() {}
+
+#[doc(hidden)]
+#[cfg(not(feature = "async"))]
+pub const fn assert_async_args<T: ?Sized>() {}
+
+/// Used in the [`derive@InstructionArgs`] macro to warn about `#[ix_args(&run)]` when the `async` feature is enabled.
+#[doc(hidden)]
+#[cfg_attr(
+    feature = "async",
+    deprecated(
+        note = "the run arg is borrowed with `#[ix_args(&run)]`, which can't be held across await points in an async instruction"
+    )
+)]
+pub struct BorrowedRunArg;
+
 #[doc(hidden)]
 #[diagnostic::on_unimplemented(
     message = "`StarFrameInstruction` requires the return type to be `Result<T>`"
@@ -113,18 +220,22 @@ impl<T, E> IxReturnType for Result<T, E> {
 /// 1. Decode Self from bytes using [`BorshDeserialize`].
 /// 2. Split Self into decode, validate, run, and cleanup args using [`InstructionArgs::split_to_args`].
 /// 3. Decode the accounts using [`Self::Accounts::decode_accounts`](AccountSetDecode::decode_accounts).
-/// 4. Validate the accounts using [`Self::Accounts::validate_accounts`](AccountSetValidate::validate_accounts).
-/// 5. Process the instruction using [`Self::process`].
-/// 6. Cleanup the accounts using [`Self::Accounts::cleanup_accounts`](AccountSetCleanup::cleanup_accounts).
-/// 7. Set the solana return data using [`bytemuck::bytes_of`] if it is not empty.
+/// 4. Check the accounts for writable duplicates using [`check_duplicate_accounts`].
+/// 5. Validate the accounts using [`Self::Accounts::validate_accounts`](AccountSetValidate::validate_accounts).
+/// 6. Process the instruction using [`Self::process`].
+/// 7. Cleanup the accounts using [`Self::Accounts::cleanup_accounts`](AccountSetCleanup::cleanup_accounts).
+/// 8. Set the solana return data using [`BorshSerialize`] if it is not empty.
+///
+/// Clients can read the return data with [`MakeInstruction::deserialize_return`](crate::client::MakeInstruction::deserialize_return).
 pub trait StarFrameInstruction: BorshDeserialize + InstructionArgs {
-    /// The return type of this instruction.
-    type ReturnType: NoUninit;
+    /// The return type of this instruction, set as the solana return data using [`BorshSerialize`].
+    type ReturnType: BorshSerialize;
 
     /// The [`AccountSet`] used by this instruction.
     type Accounts<'decode, 'arg>: AccountSetDecode<'decode, Self::DecodeArg<'arg>>
         + AccountSetValidate<Self::ValidateArg<'arg>>
-        + AccountSetCleanup<Self::CleanupArg<'arg>>;
+        + AccountSetCleanup<Self::CleanupArg<'arg>>
+        + AccountSetKeys;
 
     /// Processes the instruction.
     fn process(
@@ -160,6 +271,7 @@ where
                 &mut ctx,
             )
             .ctx("Failed to decode accounts")?;
+        check_duplicate_accounts(&account_set).ctx("Found duplicate accounts")?;
         account_set
             .validate_accounts(validate, &mut ctx)
             .ctx("Failed to validate accounts")?;
@@ -169,7 +281,10 @@ where
             .cleanup_accounts(cleanup, &mut ctx)
             .ctx("Failed to cleanup accounts")?;
         if size_of::<T::ReturnType>() > 0 {
-            set_return_data(bytemuck::bytes_of(&ret));
+            let return_data = borsh::to_vec(&ret).ctx("Failed to serialize return data")?;
+            if !return_data.is_empty() {
+                set_return_data(&return_data);
+            }
         }
         Ok(())
     }
@@ -229,6 +344,46 @@ mod test {
 
     impl_blank_ix!(Ix1, Ix2);
 
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    mod idl_impls {
+        use super::{Ix1, Ix2};
+        use crate::idl::InstructionToIdl;
+        use star_frame_idl::{
+            account_set::IdlAccountSetDef, instruction::IdlInstructionDef, ty::IdlTypeId,
+            IdlDefinition,
+        };
+
+        fn blank_ix_def(source: &str) -> IdlInstructionDef {
+            IdlInstructionDef {
+                account_set: IdlAccountSetDef::Struct(vec![]),
+                type_id: IdlTypeId {
+                    namespace: None,
+                    source: source.to_string(),
+                    provided_generics: vec![],
+                },
+                return_type: None,
+            }
+        }
+
+        impl InstructionToIdl<()> for Ix1 {
+            fn instruction_to_idl(
+                _idl_definition: &mut IdlDefinition,
+                _arg: (),
+            ) -> crate::IdlResult<IdlInstructionDef> {
+                Ok(blank_ix_def("Ix1"))
+            }
+        }
+
+        impl InstructionToIdl<()> for Ix2 {
+            fn instruction_to_idl(
+                _idl_definition: &mut IdlDefinition,
+                _arg: (),
+            ) -> crate::IdlResult<IdlInstructionDef> {
+                Ok(blank_ix_def("Ix2"))
+            }
+        }
+    }
+
     #[allow(dead_code)]
     #[derive(InstructionSet)]
     #[ix_set(skip_idl)]
@@ -236,4 +391,580 @@ mod test {
         Ix1(Ix1),
         Ix2(Ix2),
     }
+
+    #[allow(dead_code)]
+    #[derive(InstructionSet)]
+    #[ix_set(repr = u8)]
+    #[repr(u8)]
+    enum TestInstructionSetU8 {
+        Ix1(Ix1) = 100,
+        Ix2(Ix2),
+    }
+
+    #[allow(dead_code)]
+    #[derive(InstructionSet)]
+    #[ix_set(skip_idl, namespace = "custom_ns")]
+    enum TestInstructionSetNamespace {
+        Ix1(Ix1),
+        Ix2(Ix2),
+    }
+
+    #[allow(dead_code)]
+    #[derive(InstructionSet)]
+    #[ix_set(skip_idl, anchor_compat = false)]
+    enum TestInstructionSetCrateNamespace {
+        Ix1(Ix1),
+    }
+
+    #[allow(dead_code)]
+    #[derive(InstructionSet)]
+    #[ix_set(skip_idl_output)]
+    enum TestInstructionSetSkipOutput {
+        Ix1(Ix1),
+        Ix2(Ix2),
+    }
+
+    #[test]
+    fn test_skip_idl_discriminants() {
+        use super::InstructionDiscriminant;
+        assert_eq!(
+            <Ix1 as InstructionDiscriminant<TestInstructionSet3>>::DISCRIMINANT,
+            crate::sighash!("global", "ix1")
+        );
+        assert_eq!(
+            <Ix2 as InstructionDiscriminant<TestInstructionSetSkipOutput>>::DISCRIMINANT,
+            crate::sighash!("global", "ix2")
+        );
+    }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[test]
+    fn test_skip_idl_output() -> crate::IdlResult<()> {
+        use crate::idl::InstructionSetToIdl;
+        let mut idl_definition = star_frame_idl::IdlDefinition::default();
+        TestInstructionSetSkipOutput::instruction_set_to_idl(&mut idl_definition)?;
+        assert!(idl_definition.instructions.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_namespace_discriminants() {
+        use super::InstructionDiscriminant;
+        // First 8 bytes of sha256("custom_ns:ix1") and sha256("custom_ns:ix2")
+        assert_eq!(
+            <Ix1 as InstructionDiscriminant<TestInstructionSetNamespace>>::DISCRIMINANT,
+            [0x09, 0xb3, 0x03, 0x0e, 0xd9, 0x71, 0xfa, 0x03]
+        );
+        assert_eq!(
+            <Ix2 as InstructionDiscriminant<TestInstructionSetNamespace>>::DISCRIMINANT,
+            [0x6a, 0xec, 0x23, 0xa6, 0x6b, 0x82, 0xb7, 0xfa]
+        );
+        assert_eq!(
+            <Ix1 as InstructionDiscriminant<TestInstructionSetNamespace>>::DISCRIMINANT,
+            crate::sighash!(namespace: "custom_ns", "ix1")
+        );
+        // First 8 bytes of sha256("star_frame:ix1")
+        assert_eq!(
+            <Ix1 as InstructionDiscriminant<TestInstructionSetCrateNamespace>>::DISCRIMINANT,
+            [0xdd, 0xd7, 0xaf, 0x41, 0xe7, 0x5a, 0x34, 0x11]
+        );
+    }
+
+    #[test]
+    fn test_u8_discriminants() {
+        use super::{InstructionDiscriminant, InstructionSet};
+        static_assertions::assert_type_eq_all!(
+            <TestInstructionSetU8 as InstructionSet>::Discriminant,
+            u8
+        );
+        assert_eq!(
+            <Ix1 as InstructionDiscriminant<TestInstructionSetU8>>::DISCRIMINANT,
+            100
+        );
+        assert_eq!(
+            <Ix2 as InstructionDiscriminant<TestInstructionSetU8>>::DISCRIMINANT,
+            101
+        );
+        assert_eq!(
+            <Ix2 as InstructionDiscriminant<TestInstructionSetU8>>::discriminant_bytes(),
+            vec![101]
+        );
+    }
+
+    #[cfg(all(feature = "idl", not(target_os = "solana")))]
+    #[test]
+    fn test_u8_discriminants_idl() -> crate::IdlResult<()> {
+        use crate::idl::InstructionSetToIdl;
+        let mut idl_definition = star_frame_idl::IdlDefinition::default();
+        TestInstructionSetU8::instruction_set_to_idl(&mut idl_definition)?;
+        assert_eq!(idl_definition.instructions["Ix1"].discriminant, vec![100]);
+        assert_eq!(idl_definition.instructions["Ix2"].discriminant, vec![101]);
+        assert_eq!(
+            idl_definition.instructions["Ix2"].discriminant_namespace,
+            None
+        );
+        Ok(())
+    }
+
+    mod cfg_variants {
+        use crate::{
+            instruction::{Instruction, InstructionDiscriminant, InstructionSet},
+            prelude::*,
+        };
+        use core::sync::atomic::{AtomicU8, Ordering};
+        use star_frame_proc::InstructionSet;
+
+        static CALLED: AtomicU8 = AtomicU8::new(0);
+        static PROGRAM_ID: Pubkey = Pubkey::new_from_array([0; 32]);
+
+        macro_rules! recording_ix {
+            ($($ix:ident = $value:literal),*) => {
+                $(
+                    #[allow(dead_code)]
+                    struct $ix;
+                    impl Instruction for $ix {
+                        fn process_from_raw(
+                            _program_id: &'static Pubkey,
+                            _accounts: &[AccountInfo],
+                            _instruction_data: &[u8],
+                        ) -> Result<()> {
+                            CALLED.store($value, Ordering::SeqCst);
+                            Ok(())
+                        }
+                    }
+                )*
+            };
+        }
+
+        recording_ix!(UserIx = 1, AdminIx = 2, LastIx = 3);
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl)]
+        enum SighashSet {
+            User(UserIx),
+            #[cfg(any())]
+            Admin(AdminIx),
+            Last(LastIx),
+        }
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl, repr = u8)]
+        #[repr(u8)]
+        enum ReprSet {
+            User(UserIx) = 0,
+            #[cfg(any())]
+            Admin(AdminIx) = 1,
+            Last(LastIx) = 2,
+        }
+
+        #[test]
+        fn test_cfg_variants() -> Result<()> {
+            let admin_sighash = crate::sighash!("global", "admin");
+            let user_sighash = <UserIx as InstructionDiscriminant<SighashSet>>::DISCRIMINANT;
+            let last_sighash = <LastIx as InstructionDiscriminant<SighashSet>>::DISCRIMINANT;
+            assert_eq!(user_sighash, crate::sighash!("global", "user"));
+            assert_eq!(last_sighash, crate::sighash!("global", "last"));
+            assert_eq!(
+                <SighashSet as InstructionSet>::DISCRIMINANTS,
+                &[user_sighash, last_sighash]
+            );
+            SighashSet::dispatch(&PROGRAM_ID, &[], &last_sighash)?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), 3);
+            assert!(SighashSet::dispatch(&PROGRAM_ID, &[], &admin_sighash).is_err());
+
+            assert_eq!(<ReprSet as InstructionSet>::DISCRIMINANTS, &[0, 2]);
+            ReprSet::dispatch(&PROGRAM_ID, &[], &[2])?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), 3);
+            assert!(ReprSet::dispatch(&PROGRAM_ID, &[], &[1]).is_err());
+            Ok(())
+        }
+    }
+
+    mod trailing_args {
+        use crate::prelude::*;
+
+        #[derive(BorshSerialize)]
+        struct OldArgs {
+            amount: u64,
+        }
+
+        #[derive(BorshSerialize, InstructionArgs, Debug, PartialEq)]
+        #[instruction_args(skip_idl)]
+        struct NewArgs {
+            #[ix_args(run)]
+            amount: u64,
+            #[ix_args(run, optional)]
+            start_at: Option<u64>,
+            #[ix_args(default = 10)]
+            limit: u16,
+        }
+
+        #[test]
+        fn test_trailing_args() -> Result<()> {
+            let old = borsh::to_vec(&OldArgs { amount: 5 })?;
+            assert_eq!(
+                NewArgs::try_from_slice(&old)?,
+                NewArgs {
+                    amount: 5,
+                    start_at: None,
+                    limit: 10,
+                }
+            );
+
+            let new = NewArgs {
+                amount: 5,
+                start_at: Some(100),
+                limit: 3,
+            };
+            let data = borsh::to_vec(&new)?;
+            assert_eq!(NewArgs::try_from_slice(&data)?, new);
+
+            // Fields before the defaults are still required
+            assert!(NewArgs::try_from_slice(&[]).is_err());
+            // A partially present field is still an error
+            assert!(NewArgs::try_from_slice(&data[..data.len() - 1]).is_err());
+            Ok(())
+        }
+
+        #[derive(BorshSerialize, InstructionArgs, Debug, PartialEq)]
+        #[instruction_args(skip_idl)]
+        #[borsh(crate = "borsh")]
+        struct GenericArgs<T>
+        where
+            T: Copy,
+        {
+            #[ix_args(run)]
+            value: T,
+            #[borsh(skip)]
+            cached: u32,
+            #[ix_args(default = 7)]
+            count: u8,
+        }
+
+        #[test]
+        fn test_generic_trailing_args() -> Result<()> {
+            let old = borsh::to_vec(&OldArgs { amount: 5 })?;
+            assert_eq!(
+                GenericArgs::<u64>::try_from_slice(&old)?,
+                GenericArgs {
+                    value: 5,
+                    cached: 0,
+                    count: 7,
+                }
+            );
+
+            let new = GenericArgs {
+                value: 5u64,
+                cached: 3,
+                count: 1,
+            };
+            let data = borsh::to_vec(&new)?;
+            assert_eq!(data.len(), size_of::<u64>() + size_of::<u8>());
+            assert_eq!(
+                GenericArgs::<u64>::try_from_slice(&data)?,
+                GenericArgs { cached: 0, ..new }
+            );
+            Ok(())
+        }
+    }
+
+    mod fallback {
+        use crate::{
+            instruction::{Instruction, InstructionSet},
+            prelude::*,
+        };
+        use core::sync::atomic::{AtomicU8, Ordering};
+        use star_frame_proc::InstructionSet;
+
+        static CALLED: AtomicU8 = AtomicU8::new(0);
+        static FALLBACK_DATA_LEN: AtomicU8 = AtomicU8::new(0);
+        static PROGRAM_ID: Pubkey = Pubkey::new_from_array([0; 32]);
+
+        #[allow(dead_code)]
+        struct FallbackIx1;
+        #[allow(dead_code)]
+        struct FallbackIx2;
+
+        impl Instruction for FallbackIx1 {
+            fn process_from_raw(
+                _program_id: &'static Pubkey,
+                _accounts: &[AccountInfo],
+                _instruction_data: &[u8],
+            ) -> Result<()> {
+                CALLED.store(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        impl Instruction for FallbackIx2 {
+            fn process_from_raw(
+                _program_id: &'static Pubkey,
+                _accounts: &[AccountInfo],
+                _instruction_data: &[u8],
+            ) -> Result<()> {
+                CALLED.store(2, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        fn fallback(data: &[u8], _accounts: &[AccountInfo]) -> Result<()> {
+            CALLED.store(u8::MAX, Ordering::SeqCst);
+            FALLBACK_DATA_LEN.store(data.len() as u8, Ordering::SeqCst);
+            Ok(())
+        }
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl, repr = u8, fallback = fallback)]
+        enum FallbackSet {
+            Ix1(FallbackIx1),
+            Ix2(FallbackIx2),
+        }
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl, repr = u8)]
+        enum NoFallbackSet {
+            Ix1(FallbackIx1),
+            Ix2(FallbackIx2),
+        }
+
+        #[test]
+        fn test_fallback_dispatch() -> Result<()> {
+            FallbackSet::dispatch(&PROGRAM_ID, &[], &[0])?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), 1);
+            FallbackSet::dispatch(&PROGRAM_ID, &[], &[1, 5])?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), 2);
+
+            FallbackSet::dispatch(&PROGRAM_ID, &[], &[7, 1, 2])?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), u8::MAX);
+            assert_eq!(FALLBACK_DATA_LEN.load(Ordering::SeqCst), 3);
+
+            // Data too short for a discriminant goes to the fallback too
+            CALLED.store(0, Ordering::SeqCst);
+            FallbackSet::dispatch(&PROGRAM_ID, &[], &[])?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), u8::MAX);
+            assert_eq!(FALLBACK_DATA_LEN.load(Ordering::SeqCst), 0);
+
+            assert!(NoFallbackSet::dispatch(&PROGRAM_ID, &[], &[7]).is_err());
+            assert!(NoFallbackSet::dispatch(&PROGRAM_ID, &[], &[]).is_err());
+            Ok(())
+        }
+    }
+
+    mod flatten {
+        use crate::{
+            client::star_frame_instruction_data,
+            instruction::{Instruction, InstructionDiscriminant, InstructionSet},
+            prelude::*,
+        };
+        use core::sync::atomic::{AtomicU8, Ordering};
+        use star_frame_proc::InstructionSet;
+
+        static CALLED: AtomicU8 = AtomicU8::new(0);
+        static PROGRAM_ID: Pubkey = Pubkey::new_from_array([0; 32]);
+
+        macro_rules! recording_ix {
+            ($($ix:ident = $value:literal),*) => {
+                $(
+                    #[allow(dead_code)]
+                    #[derive(BorshSerialize)]
+                    struct $ix;
+                    impl Instruction for $ix {
+                        fn process_from_raw(
+                            _program_id: &'static Pubkey,
+                            _accounts: &[AccountInfo],
+                            _instruction_data: &[u8],
+                        ) -> Result<()> {
+                            CALLED.store($value, Ordering::SeqCst);
+                            Ok(())
+                        }
+                    }
+                )*
+            };
+        }
+
+        recording_ix!(
+            SubIx1 = 1,
+            SubIx2 = 2,
+            OtherIx = 3,
+            ParentIx = 4,
+            NestedIx = 5
+        );
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl, repr = u8)]
+        #[repr(u8)]
+        enum NestedSet {
+            Ix(NestedIx) = 30,
+        }
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl, repr = u8)]
+        #[repr(u8)]
+        enum SubSet {
+            Ix1(SubIx1),
+            Ix2(SubIx2),
+        }
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl, repr = u8)]
+        #[repr(u8)]
+        enum OtherSubSet {
+            Ix(OtherIx) = 20,
+            #[ix_set(flatten = [NestedIx])]
+            Nested(NestedSet),
+        }
+
+        #[allow(dead_code)]
+        #[derive(InstructionSet)]
+        #[ix_set(skip_idl, repr = u8)]
+        #[repr(u8)]
+        enum ParentSet {
+            Ix(ParentIx) = 10,
+            #[ix_set(flatten = [SubIx1, SubIx2])]
+            Sub(SubSet),
+            #[ix_set(flatten = [OtherIx, NestedIx])]
+            Other(OtherSubSet),
+        }
+
+        #[test]
+        fn test_flattened_discriminants() {
+            assert_eq!(<SubSet as InstructionSet>::DISCRIMINANTS, &[0, 1]);
+            assert_eq!(
+                <ParentSet as InstructionSet>::DISCRIMINANTS,
+                &[10, 0, 1, 20, 30]
+            );
+            assert_eq!(
+                <SubIx2 as InstructionDiscriminant<ParentSet>>::DISCRIMINANT,
+                1
+            );
+            assert_eq!(
+                <NestedIx as InstructionDiscriminant<ParentSet>>::DISCRIMINANT,
+                30
+            );
+        }
+
+        #[test]
+        fn test_flattened_dispatch() -> Result<()> {
+            for (discriminant, expected) in [(10, 4), (0, 1), (1, 2), (20, 3), (30, 5)] {
+                ParentSet::dispatch(&PROGRAM_ID, &[], &[discriminant])?;
+                assert_eq!(CALLED.load(Ordering::SeqCst), expected);
+            }
+            assert!(ParentSet::dispatch(&PROGRAM_ID, &[], &[2]).is_err());
+
+            // Instruction data built by clients against the parent set round-trips to the flattened instruction
+            let data = star_frame_instruction_data::<ParentSet, _>(&SubIx2)?;
+            assert_eq!(data, [1]);
+            ParentSet::dispatch(&PROGRAM_ID, &[], &data)?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), 2);
+
+            let data = star_frame_instruction_data::<ParentSet, _>(&NestedIx)?;
+            assert_eq!(data, [30]);
+            ParentSet::dispatch(&PROGRAM_ID, &[], &data)?;
+            assert_eq!(CALLED.load(Ordering::SeqCst), 5);
+            Ok(())
+        }
+    }
+
+    mod ctx_forms {
+        use crate::prelude::*;
+
+        #[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+        struct AliasCtx;
+
+        #[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+        struct PathCtx;
+
+        #[derive(AccountSet)]
+        struct EmptyAccounts;
+
+        type Ctx = Context;
+
+        // Aliases and paths of `Context` are left for the compiler to check
+        #[star_frame_instruction]
+        fn AliasCtx(_accounts: &mut EmptyAccounts, _run_arg: (), ctx: &mut Ctx) -> Result<()> {
+            let _ = ctx.current_program_id();
+            Ok(())
+        }
+
+        #[star_frame_instruction]
+        fn PathCtx(
+            _accounts: &mut EmptyAccounts,
+            _run_arg: (),
+            ctx: &mut crate::context::Context,
+        ) -> Result<()> {
+            let _ = ctx.current_program_id();
+            Ok(())
+        }
+
+        #[test]
+        fn test_ctx_forms() -> Result<()> {
+            let mut ctx = Context::default();
+            AliasCtx::process(&mut EmptyAccounts, (), &mut ctx)?;
+            PathCtx::process(&mut EmptyAccounts, (), &mut ctx)?;
+            Ok(())
+        }
+    }
+
+    mod arg_patterns {
+        use crate::prelude::*;
+
+        #[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+        struct Walk {
+            #[ix_args(run)]
+            distance: u64,
+            #[ix_args(run)]
+            speed: u64,
+        }
+
+        #[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+        #[ix_args(run)]
+        struct Jump {
+            height: u64,
+            twice: bool,
+        }
+
+        #[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+        struct Sit {
+            #[ix_args(run)]
+            minutes: u64,
+        }
+
+        #[derive(AccountSet)]
+        struct EmptyAccounts;
+
+        #[star_frame_instruction]
+        fn Walk(_accounts: &mut EmptyAccounts, (distance, speed): (u64, u64)) -> Result<u64> {
+            Ok(distance * speed)
+        }
+
+        #[star_frame_instruction]
+        fn Jump(_accounts: &mut EmptyAccounts, Jump { height, twice }: Jump) -> Result<u64> {
+            Ok(if twice { height * 2 } else { height })
+        }
+
+        #[star_frame_instruction]
+        fn Sit(_accounts: &mut EmptyAccounts, _: u64) -> Result<()> {
+            Ok(())
+        }
+
+        #[test]
+        fn test_arg_patterns() -> Result<()> {
+            let mut ctx = Context::default();
+            assert_eq!(Walk::process(&mut EmptyAccounts, (3, 4), &mut ctx)?, 12);
+            let jump = Jump {
+                height: 5,
+                twice: true,
+            };
+            assert_eq!(Jump::process(&mut EmptyAccounts, jump, &mut ctx)?, 10);
+            Sit::process(&mut EmptyAccounts, 30, &mut ctx)?;
+            Ok(())
+        }
+    }
 }
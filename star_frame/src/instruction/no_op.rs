@@ -2,6 +2,7 @@ use crate::{instruction::IxArgs, prelude::*};
 
 impl InstructionSet for () {
     type Discriminant = ();
+    const DISCRIMINANTS: &'static [()] = &[];
 
     fn dispatch(_program_id: &Pubkey, _accounts: &[AccountInfo], _ix_bytes: &[u8]) -> Result<()> {
         Ok(())
@@ -6,6 +6,7 @@ pub struct UnCallable;
 
 impl InstructionSet for UnCallable {
     type Discriminant = ();
+    const DISCRIMINANTS: &'static [()] = &[];
 
     fn dispatch(_program_id: &Pubkey, _accounts: &[AccountInfo], _ix_bytes: &[u8]) -> Result<()> {
         panic!("Cannot call dispatch on Uncallable")
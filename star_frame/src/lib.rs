@@ -330,8 +330,10 @@
 //! - `idl` - Enables IDL generation for client libraries
 //! - `test_helpers` - Provides utilities for testing programs and the unsized type system
 //! - `cleanup_rent_warning` - Emits a warning message if the account has more lamports than required by rent on cleanup
-//! - `aggressive_inline` - Adds `#[inline(always)]` to more functions. Can be beneficial in some cases, but will likely increase binary size and may even reduce performance.
-//!   This should only be used when you have thorough benchmarks and are confident in the performance impact.
+//! - `aggressive_inline` - Adds `#[inline(always)]` to more functions, including every function generated by the
+//!   [`AccountSet`](derive@crate::account_set::AccountSet) derive. Binary size will likely increase, and the compute unit
+//!   impact depends on the program, so it may even reduce performance. This should only be used when you have
+//!   thorough benchmarks (e.g. `example_programs/bench`) and are confident in the performance impact.
 #![warn(
     clippy::pedantic,
     missing_copy_implementations,
@@ -409,6 +411,8 @@ pub fn Ok<T>(value: T) -> Result<T> {
 
 #[doc(hidden)]
 pub use solana_instruction::Instruction as SolanaInstruction;
+#[cfg(feature = "expand")]
+pub use star_frame_proc::star_frame_expand;
 pub use star_frame_proc::{pubkey, sighash, zero_copy};
 
 #[allow(unused_imports)]
@@ -2,8 +2,7 @@
 
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 pub use crate::idl::{
-    seed_const, seed_path, AccountSetToIdl, AccountToIdl, InstructionSetToIdl, InstructionToIdl,
-    ProgramToIdl, TypeToIdl,
+    AccountSetToIdl, AccountToIdl, InstructionSetToIdl, InstructionToIdl, ProgramToIdl, TypeToIdl,
 };
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 pub use star_frame_idl::{NodeToJson, ProgramNode};
@@ -20,19 +19,20 @@ pub use crate::{
         FindProgramAddress as _, MakeInstruction as _, SerializeAccount as _,
         SerializeBorshAccount as _, SerializeType as _,
     },
-    context::Context,
+    context::{ComputeBudget, Context},
     cpi::MakeCpi as _,
     create_unit_system,
     data_types::{
-        ClockExt, GetKeyFor as _, GetOptionalKeyFor as _, KeyFor, OptionalKeyFor, OptionalPubkey,
-        PackedValue, SetKeyFor as _, UnitVal,
+        ClockExt, FixedString, GetKeyFor as _, GetOptionalKeyFor as _, KeyFor, OptionalKeyFor,
+        OptionalPubkey, PackedValue, SetKeyFor as _, UnitVal,
     },
     ensure, ensure_eq, ensure_ne, error,
     errors::{star_frame_error, Error, ErrorInfo as _},
     instruction::{
-        star_frame_instruction, InstructionArgs, InstructionDiscriminant as _, InstructionSet,
-        StarFrameInstruction,
+        star_frame_instruction, AsyncContext, InstructionArgs, InstructionDiscriminant as _,
+        InstructionSet, StarFrameInstruction,
     },
+    packed_checked_add, packed_checked_mul, packed_checked_sub,
     program::{system::System, StarFrameProgram},
     pubkey,
     unsize::prelude::*,
@@ -41,6 +41,8 @@ pub use crate::{
 };
 
 // ensure derive macros are in scope
+#[cfg(feature = "expand")]
+pub use star_frame_proc::star_frame_expand;
 pub use star_frame_proc::{zero_copy, InstructionToIdl, TypeToIdl};
 
 // Solana stuff
@@ -0,0 +1,564 @@
+//! Unordered key-value map type for the unsized type system.
+//!
+//! This module provides [`UnsizedHashMap<K, V, L>`], a hash map that stores pairs of fixed-size keys and values
+//! with O(1) expected lookup, insertion, and removal. Keys are hashed by running 64 bit FNV-1a over their bytes, so
+//! the layout of the table is the same on every platform and every run, which [`std::collections::HashMap`]'s
+//! randomized hashing can't guarantee.
+
+use crate::{
+    prelude::*,
+    unsize::{
+        impls::{ListItemSized, ListIter, ListIterMut, ListLength, UnsizedGenerics},
+        FromOwned,
+    },
+    ErrorCode,
+};
+use num_traits::ToPrimitive;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+const MIN_BUCKETS: usize = 8;
+
+/// A [`Hasher`] implementing 64 bit FNV-1a, which hashes the same on every platform and run.
+#[derive(Debug, Copy, Clone)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for FnvHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+/// Returns the number of buckets needed to hold `len` entries with a load factor of at most 3/4.
+fn bucket_count(len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    (len * 4).div_ceil(3).next_power_of_two().max(MIN_BUCKETS)
+}
+
+/// Hashes the bytes of `key`. [`Hash`] impls may feed the hasher differently across versions and platforms (e.g.,
+/// `usize` lengths), so they aren't used for the on-chain layout.
+fn hash_key<K: NoUninit>(key: &K) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytemuck::bytes_of(key));
+    hasher.finish()
+}
+
+/// A resizable hash map of fixed-size keys and values. The [`UnsizedType`] version of [`HashMap`].
+///
+/// Under the hood, an `UnsizedHashMap` is a [`List`] of entries in no particular order and a [`List`] of buckets
+/// indexing into it, probed linearly. Lookups take O(1) expected time, compared to the O(log n) binary search of
+/// [`Map`]. Use [`Map`] if the entries need to be sorted or the map is small enough for the bucket overhead to matter.
+///
+/// ## Unsized Type System
+/// For exclusive methods that change the underlying data size, see [`UnsizedHashMapExclusiveImpl`].
+#[unsized_type(skip_idl, owned_type = HashMap<K, V>, owned_from_ptr = hash_map_owned_from_ptr::<K, V, L>, skip_init_struct)]
+pub struct UnsizedHashMap<K, V, L = u32>
+where
+    K: UnsizedGenerics + Hash + Eq,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    #[unsized_start]
+    entries: List<ListItemSized<K, V>, L>,
+    // Each bucket is empty (zero) or holds the index of an entry plus one
+    buckets: List<PackedValue<L>>,
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn hash_map_owned_from_ptr<K, V, L>(r: &UnsizedHashMap<K, V, L>) -> Result<HashMap<K, V>>
+where
+    K: UnsizedGenerics + Hash + Eq,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    Ok(r.entries
+        .iter()
+        .map(|item| (item.key, item.value))
+        .collect())
+}
+
+impl<K, V, L> FromOwned for UnsizedHashMap<K, V, L>
+where
+    K: UnsizedGenerics + Hash + Eq,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    fn byte_size(owned: &Self::Owned) -> usize {
+        List::<ListItemSized<K, V>, L>::byte_size_from_len(owned.len())
+            + List::<PackedValue<L>>::byte_size_from_len(bucket_count(owned.len()))
+    }
+
+    fn from_owned(owned: Self::Owned, bytes: &mut &mut [u8]) -> Result<usize> {
+        let mut entries = owned
+            .into_iter()
+            .map(|(key, value)| ListItemSized { key, value })
+            .collect::<Vec<_>>();
+        // `HashMap` iteration order is random, so sort for the same layout every time
+        entries.sort_unstable_by(|a, b| bytemuck::bytes_of(&a.key).cmp(bytemuck::bytes_of(&b.key)));
+        let mut bucket_entries = vec![None; bucket_count(entries.len())];
+        let mask = bucket_entries.len().wrapping_sub(1);
+        for (index, entry) in entries.iter().enumerate() {
+            let mut bucket = hash_key(&entry.key) as usize & mask;
+            while bucket_entries[bucket].is_some() {
+                bucket = (bucket + 1) & mask;
+            }
+            bucket_entries[bucket] = Some(index);
+        }
+        let buckets = bucket_entries
+            .into_iter()
+            .map(|index| match index {
+                Some(index) => entry_index_to_bucket(index).map(PackedValue),
+                None => Ok(PackedValue(L::zeroed())),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(
+            List::<ListItemSized<K, V>, L>::from_owned_from_iter(entries, bytes)?
+                + List::<PackedValue<L>>::from_owned_from_iter(buckets, bytes)?,
+        )
+    }
+}
+
+fn entry_index_to_bucket<L: ListLength>(index: usize) -> Result<L> {
+    L::from_usize(index + 1).ok_or_else(|| {
+        error!(
+            ErrorCode::ToPrimitiveError,
+            "Failed to convert entry index {index} to L"
+        )
+    })
+}
+
+impl<K, V, L> UnsizedHashMap<K, V, L>
+where
+    K: UnsizedGenerics + Hash + Eq,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    fn bucket_mask(&self) -> usize {
+        self.buckets.len() - 1
+    }
+
+    #[inline]
+    fn ideal_bucket(&self, key: &K) -> usize {
+        hash_key(key) as usize & self.bucket_mask()
+    }
+
+    /// Returns the entry index stored in `bucket`, or `None` if it is empty.
+    #[inline]
+    fn bucket_entry(&self, bucket: usize) -> Option<usize> {
+        let PackedValue(value) = self.buckets[bucket];
+        value.to_usize().and_then(|value| value.checked_sub(1))
+    }
+
+    /// Returns the bucket holding `key` and its entry index, or the empty bucket it would be inserted into. There
+    /// must be at least one empty bucket.
+    fn probe(&self, key: &K) -> Result<(usize, usize), usize> {
+        let mut bucket = self.ideal_bucket(key);
+        loop {
+            match self.bucket_entry(bucket) {
+                None => return Err(bucket),
+                Some(index) if self.entries[index].key == *key => return Ok((bucket, index)),
+                Some(_) => bucket = (bucket + 1) & self.bucket_mask(),
+            }
+        }
+    }
+
+    /// Returns the index of the entry for `key`.
+    fn get_index(&self, key: &K) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        self.probe(key).ok().map(|(_, index)| index)
+    }
+
+    /// Refills the buckets from the entries, e.g., after the bucket list was resized.
+    fn rebuild_buckets(&mut self) -> Result<()> {
+        self.buckets
+            .iter_mut()
+            .for_each(|bucket| *bucket = PackedValue(L::zeroed()));
+        for index in 0..self.len() {
+            let Err(bucket) = self.probe(&self.entries[index].key) else {
+                bail!(
+                    ProgramError::InvalidAccountData,
+                    "Duplicate key at entry {index} in UnsizedHashMap"
+                );
+            };
+            self.buckets[bucket] = PackedValue(entry_index_to_bucket(index)?);
+        }
+        Ok(())
+    }
+
+    /// Empties `hole`, shifting back later entries of its probe sequence so they stay reachable.
+    fn clear_bucket(&mut self, mut hole: usize) {
+        let mask = self.bucket_mask();
+        let mut next = (hole + 1) & mask;
+        while let Some(index) = self.bucket_entry(next) {
+            let ideal = self.ideal_bucket(&self.entries[index].key);
+            // The entry can fill the hole if the hole is between its ideal bucket and its current bucket
+            if next.wrapping_sub(ideal) & mask >= next.wrapping_sub(hole) & mask {
+                self.buckets[hole] = self.buckets[next];
+                hole = next;
+            }
+            next = (next + 1) & mask;
+        }
+        self.buckets[hole] = PackedValue(L::zeroed());
+    }
+
+    #[must_use]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get_index(key).is_some()
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.get_index(key).map(|index| &self.entries[index].value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_index(key)
+            .map(|index| &mut self.entries[index].value)
+    }
+
+    /// Returns an iterator over the entries. The order is unspecified and changes when entries are removed.
+    #[must_use]
+    #[inline]
+    pub fn iter(&self) -> UnsizedHashMapIter<'_, K, V, L> {
+        UnsizedHashMapIter {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the entries with mutable values. The order is unspecified and changes when entries
+    /// are removed.
+    #[must_use]
+    #[inline]
+    pub fn iter_mut(&mut self) -> UnsizedHashMapIterMut<'_, K, V, L> {
+        UnsizedHashMapIterMut {
+            iter: self.entries.iter_mut(),
+        }
+    }
+}
+
+#[unsized_impl]
+impl<K, V, L> UnsizedHashMap<K, V, L>
+where
+    K: UnsizedGenerics + Hash + Eq,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    /// Inserts `value` at `key`, returning the previous value if there was one. Resizes the map if the key is new,
+    /// growing the buckets when the load factor would exceed 3/4.
+    ///
+    /// # Example
+    /// ```
+    /// use star_frame::prelude::*;
+    /// # fn main() -> Result<()> {
+    /// let bytes = <UnsizedHashMap<u8, u8>>::new_default_byte_set()?;
+    /// let mut map = bytes.data_mut()?;
+    /// assert_eq!(map.insert(1, 10)?, None);
+    /// assert_eq!(map.insert(1, 11)?, Some(10));
+    /// assert_eq!(map.get(&1), Some(&11));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>> {
+        if let Some(index) = self.get_index(&key) {
+            let old = core::mem::replace(&mut self.entries[index].value, value);
+            return Ok(Some(old));
+        }
+        let index = self.len();
+        let buckets = bucket_count(index + 1);
+        if buckets > self.buckets.len() {
+            let additional = buckets - self.buckets.len();
+            self.buckets()
+                .push_all((0..additional).map(|_| PackedValue(L::zeroed())))?;
+            self.rebuild_buckets()?;
+        }
+        // The key is not in the map, so probing finds an empty bucket
+        let Err(bucket) = self.probe(&key) else {
+            bail!(
+                ProgramError::InvalidAccountData,
+                "Key is already in the UnsizedHashMap buckets"
+            );
+        };
+        self.entries().push(ListItemSized { key, value })?;
+        self.buckets[bucket] = PackedValue(entry_index_to_bucket(index)?);
+        Ok(None)
+    }
+
+    /// Removes `key` from the map, returning its value if it was present. The last entry is moved into the removed
+    /// entry's place, so the buckets are never resized.
+    pub fn remove(&mut self, key: &K) -> Result<Option<V>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let Ok((bucket, index)) = self.probe(key) else {
+            return Ok(None);
+        };
+        let value = self.entries[index].value;
+        self.clear_bucket(bucket);
+
+        let last = self.len() - 1;
+        if index != last {
+            let last_entry = self.entries[last];
+            let Ok((last_bucket, _)) = self.probe(&last_entry.key) else {
+                bail!(
+                    ProgramError::InvalidAccountData,
+                    "Entry {last} is missing from the UnsizedHashMap buckets"
+                );
+            };
+            self.buckets[last_bucket] = PackedValue(entry_index_to_bucket(index)?);
+            self.entries[index] = last_entry;
+        }
+        self.entries().pop()?;
+        Ok(Some(value))
+    }
+
+    pub fn clear(&mut self) -> Result<()> {
+        self.entries().clear()?;
+        self.buckets().clear()
+    }
+}
+
+macro_rules! hash_map_iter {
+    ($name:ident $(: $extra_derive:path)?, $iter:ident, $item:ty, $next_arg:ident => $next:expr)  => {
+        #[derive(Debug, $($extra_derive)*)]
+        pub struct $name<'a, K, V, L>
+        where
+            K: UnsizedGenerics + Hash + Eq,
+            V: UnsizedGenerics,
+            L: ListLength,
+        {
+            iter: $iter<'a, ListItemSized<K, V>, L>,
+        }
+
+        impl<'a, K, V, L> Iterator for $name<'a, K, V, L>
+        where
+            K: UnsizedGenerics + Hash + Eq,
+            V: UnsizedGenerics,
+            L: ListLength,
+        {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let $next_arg = self;
+                $next_arg.iter.next().map($next)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+
+        impl<K, V, L> ExactSizeIterator for $name<'_, K, V, L>
+        where
+            K: UnsizedGenerics + Hash + Eq,
+            V: UnsizedGenerics,
+            L: ListLength,
+        {
+            fn len(&self) -> usize {
+                self.iter.len()
+            }
+        }
+
+        impl<K, V, L> FusedIterator for $name<'_, K, V, L>
+        where
+            K: UnsizedGenerics + Hash + Eq,
+            V: UnsizedGenerics,
+            L: ListLength,
+        {
+        }
+    };
+}
+
+hash_map_iter!(UnsizedHashMapIter: Clone, ListIter, (&'a K, &'a V), this => |item| (&item.key, &item.value));
+hash_map_iter!(UnsizedHashMapIterMut, ListIterMut, (&'a K, &'a mut V), this => |item| (&item.key, &mut item.value));
+
+impl<'a, K, V, L> IntoIterator for &'a UnsizedHashMap<K, V, L>
+where
+    K: UnsizedGenerics + Hash + Eq,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = UnsizedHashMapIter<'a, K, V, L>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, L> IntoIterator for &'a mut UnsizedHashMap<K, V, L>
+where
+    K: UnsizedGenerics + Hash + Eq,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = UnsizedHashMapIterMut<'a, K, V, L>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    type Key = PackedValue<u32>;
+    type Value = PackedValue<u64>;
+
+    fn keys() -> impl Iterator<Item = Key> {
+        // Spread out keys so the hash map and map see different insertion orders than their sorted order
+        (0..1000u32).map(|i| PackedValue(i.wrapping_mul(2_654_435_761)))
+    }
+
+    #[test]
+    fn test_fnv_hasher() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = FnvHasher::default();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        // Reference values from the FNV-1a specification
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_hash_key_is_stable() {
+        // The FNV-1a hash of the key's little endian bytes, independent of its `Hash` impl
+        assert_eq!(hash_key(&PackedValue(0x6261_6f6fu32)), {
+            let mut hasher = FnvHasher::default();
+            hasher.write(b"ooab");
+            hasher.finish()
+        });
+        assert_eq!(hash_key(&0u8), 0xaf63_bd4c_8601_b7df);
+    }
+
+    #[test]
+    fn test_hash_map_from_owned_is_deterministic() -> Result<()> {
+        let forward: HashMap<Key, Value> = keys().map(|key| (key, PackedValue(1))).collect();
+        let mut reverse: HashMap<Key, Value> = HashMap::new();
+        for key in keys().collect::<Vec<_>>().into_iter().rev() {
+            reverse.insert(key, PackedValue(1));
+        }
+        let forward = UnsizedHashMap::<Key, Value>::new_byte_set(forward)?;
+        let reverse = UnsizedHashMap::<Key, Value>::new_byte_set(reverse)?;
+        assert_eq!(forward.underlying_data()?, reverse.underlying_data()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_map_from_owned() -> Result<()> {
+        let owned: HashMap<u8, u8> = (0..20).map(|i| (i, i * 2)).collect();
+        let map = UnsizedHashMap::<u8, u8>::new_byte_set(owned.clone())?;
+        let data = map.data()?;
+        assert_eq!(data.len(), 20);
+        assert!((0..20).all(|i| data.get(&i) == Some(&(i * 2))));
+        assert_eq!(data.get(&20), None);
+        drop(data);
+        assert_eq!(map.owned()?, owned);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_map_matches_map() -> Result<()> {
+        let hash_map = UnsizedHashMap::<Key, Value>::new_default_byte_set()?;
+        let mut hash_map = hash_map.data_mut()?;
+        let map = Map::<Key, Value>::new_default_byte_set()?;
+        let mut map = map.data_mut()?;
+
+        for (i, key) in keys().enumerate() {
+            let value = PackedValue(i as u64);
+            assert_eq!(hash_map.insert(key, value)?, map.insert(key, value)?);
+        }
+        for key in keys() {
+            assert_eq!(hash_map.get(&key), map.get(&key));
+            assert!(hash_map.contains_key(&key));
+        }
+        assert_eq!(hash_map.len(), map.len());
+
+        // Every lookup should be close to its ideal bucket, unlike the ~10 comparisons of a binary search over 1000
+        let displacement: usize = keys()
+            .map(|key| {
+                let (bucket, _) = hash_map
+                    .probe(&key)
+                    .unwrap_or_else(|_| panic!("{key:?} is missing"));
+                bucket.wrapping_sub(hash_map.ideal_bucket(&key)) & hash_map.bucket_mask()
+            })
+            .sum();
+        assert!(
+            displacement < 2 * hash_map.len(),
+            "Average displacement is too high: {displacement}"
+        );
+
+        for key in keys().step_by(3) {
+            assert_eq!(hash_map.remove(&key)?, map.remove(&key)?);
+            assert_eq!(hash_map.remove(&key)?, None);
+        }
+        for key in keys() {
+            assert_eq!(hash_map.get(&key), map.get(&key));
+        }
+        let collected: BTreeMap<Key, Value> = hash_map.iter().map(|(k, v)| (*k, *v)).collect();
+        assert!(collected.into_iter().eq(map.iter().map(|(k, v)| (*k, *v))));
+
+        hash_map.clear()?;
+        assert!(hash_map.is_empty());
+        assert_eq!(hash_map.get(&PackedValue(0)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_map_iter_mut() -> Result<()> {
+        let map = UnsizedHashMap::<u8, u8>::new_byte_set((0..10).map(|i| (i, i)).collect())?;
+        let mut map = map.data_mut()?;
+        for (key, value) in map.iter_mut() {
+            *value = key * 3;
+        }
+        *map.get_mut(&9).expect("9 is in the map") = 0;
+        let mut entries = map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+        entries.sort_unstable();
+        assert_eq!(
+            entries,
+            (0..10)
+                .map(|i| (i, if i == 9 { 0 } else { i * 3 }))
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+}
@@ -129,6 +129,12 @@ where
         cast_slice_mut(&mut self.bytes)
     }
 
+    /// Returns the raw bytes of the list's elements, excluding the length prefix.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
     #[inline]
     pub fn as_checked_slice(&self) -> Result<&[T]> {
         checked::try_cast_slice(&self.bytes).map_err(Into::into)
@@ -162,6 +168,17 @@ where
         Self::binary_search_by(self, |p| p.cmp(x))
     }
 
+    /// Returns `true` if the sorted list contains `x`, using [`Self::binary_search`].
+    ///
+    /// The result is meaningless if the list is not sorted.
+    #[inline]
+    pub fn contains_sorted(&self, x: &T) -> bool
+    where
+        T: Ord,
+    {
+        self.binary_search(x).is_ok()
+    }
+
     /// See [`<[T]>::binary_search_by`]
     /// ```
     /// # use star_frame::unsize::{impls::List, TestByteSet};
@@ -471,11 +488,60 @@ where
     {
         self.insert_all(self.len(), items)
     }
+    /// Appends every item in `items` with a single resize, for iterators without an exact size.
+    ///
+    /// The items are collected into a temporary buffer first, so the list is only resized once instead of once per
+    /// item. Prefer [`Self::push_all`] when the iterator is [`ExactSizeIterator`].
+    ///
+    /// ```
+    /// use star_frame::prelude::*;
+    /// # fn main() -> Result<()> {
+    /// let bytes = <List<u8>>::new_default_byte_set()?;
+    /// let mut list = bytes.data_mut()?;
+    /// list.extend((0..10).filter(|i| i % 3 == 0))?;
+    /// assert_eq!(list.as_slice(), &[0, 3, 6, 9]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn extend<I>(&mut self, items: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let items = items.into_iter().collect::<Vec<T>>();
+        if items.is_empty() {
+            return Ok(());
+        }
+        self.push_all(items)
+    }
     #[inline]
     pub fn insert(&mut self, index: usize, item: T) -> Result<()> {
         self.insert_all(index, iter::once(item))
     }
 
+    /// Inserts `item` into a sorted list, keeping it sorted. Equal items are allowed.
+    ///
+    /// ```
+    /// use star_frame::prelude::*;
+    /// # fn main() -> Result<()> {
+    /// let bytes = <List<u8>>::new_default_byte_set()?;
+    /// let mut list = bytes.data_mut()?;
+    /// list.insert_sorted(3)?;
+    /// list.insert_sorted(1)?;
+    /// list.insert_sorted(2)?;
+    /// assert_eq!(list.as_slice(), &[1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn insert_sorted(&mut self, item: T) -> Result<()>
+    where
+        T: Ord,
+    {
+        let index = self.binary_search(&item).unwrap_or_else(|index| index);
+        self.insert(index, item)
+    }
+
     pub fn insert_all<I>(&mut self, index: usize, items: I) -> Result<()>
     where
         I: IntoIterator,
@@ -577,6 +643,44 @@ where
     pub fn clear(&mut self) -> Result<()> {
         self.remove_range(..)
     }
+
+    /// Keeps only the items for which `f` returns `true`, preserving their order.
+    ///
+    /// The kept items are compacted in place, and the list is then shrunk with a single resize.
+    ///
+    /// ```
+    /// use star_frame::prelude::*;
+    /// # fn main() -> Result<()> {
+    /// let bytes = <List<u8>>::new_default_byte_set()?;
+    /// let mut list = bytes.data_mut()?;
+    /// list.push_all([1, 2, 3, 4, 5])?;
+    /// list.retain(|item| item % 2 == 1)?;
+    /// assert_eq!(list.as_slice(), &[1, 3, 5]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len();
+        let mut kept = 0;
+        {
+            let items = self.as_checked_mut_slice()?;
+            for index in 0..len {
+                if f(&items[index]) {
+                    if kept != index {
+                        items[kept] = items[index];
+                    }
+                    kept += 1;
+                }
+            }
+        }
+        if kept == len {
+            return Ok(());
+        }
+        self.remove_range(kept..)
+    }
 }
 impl<T, L> UnsizedInit<DefaultInit> for List<T, L>
 where
@@ -817,6 +921,80 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_list_sorted() -> Result<()> {
+        let list_byte_set = List::<u8>::new_default_byte_set()?;
+        let mut list = list_byte_set.data_mut()?;
+        assert_eq!(list.binary_search(&1), Err(0));
+        assert!(!list.contains_sorted(&1));
+
+        list.insert_sorted(5)?;
+        assert_eq!(list.binary_search(&5), Ok(0));
+        assert_eq!(list.binary_search(&4), Err(0));
+        assert_eq!(list.binary_search(&6), Err(1));
+        assert!(list.contains_sorted(&5));
+
+        for item in [3, 9, 1, 5, 7, 5] {
+            list.insert_sorted(item)?;
+        }
+        assert_eq!(list.as_slice(), &[1, 3, 5, 5, 5, 7, 9]);
+        assert!(matches!(list.binary_search(&5), Ok(2..=4)));
+        assert_eq!(list.binary_search(&0), Err(0));
+        assert_eq!(list.binary_search(&8), Err(6));
+        assert_eq!(list.binary_search(&10), Err(7));
+        assert!(list.contains_sorted(&9));
+        assert!(!list.contains_sorted(&4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_bulk() -> Result<()> {
+        let list_byte_set = List::<PackedValue<u16>>::new_default_byte_set()?;
+        let mut list = list_byte_set.data_mut()?;
+        list.extend((0..100).map(PackedValue))?;
+        assert_eq!(list.len(), 100);
+        assert_eq!(list[99], PackedValue(99));
+
+        list.extend(iter::empty())?;
+        assert_eq!(list.len(), 100);
+
+        list.retain(|item| item.0 % 10 == 0)?;
+        let expected = (0..100).step_by(10).map(PackedValue).collect::<Vec<_>>();
+        assert_eq!(&*expected, &***list);
+
+        list.retain(|_| true)?;
+        assert_eq!(&*expected, &***list);
+
+        list.retain(|_| false)?;
+        assert!(list.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_slice_access() -> Result<()> {
+        let list_byte_set = List::<PackedValue<u16>>::new_default_byte_set()?;
+        let mut list = list_byte_set.data_mut()?;
+        list.push_all([1, 2, 3].map(PackedValue))?;
+        assert_eq!(list.as_bytes(), &[1, 0, 2, 0, 3, 0]);
+
+        for item in list.as_mut_slice() {
+            item.0 *= 10;
+        }
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.as_slice().iter().map(|item| item.0).sum::<u16>(), 60);
+        drop(list);
+
+        assert_eq!(
+            list_byte_set.underlying_data()?[..10],
+            [3, 0, 0, 0, 10, 0, 20, 0, 30, 0]
+        );
+        assert_eq!(
+            list_byte_set.owned()?,
+            [10, 20, 30].map(PackedValue).to_vec()
+        );
+        Ok(())
+    }
+
     #[unsized_type(skip_idl)]
     struct InnerList {
         #[unsized_start]
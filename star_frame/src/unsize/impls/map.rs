@@ -231,9 +231,190 @@ where
         }
     }
 
+    /// Removes all entries for which `f` returns `false`. The remaining entries stay in sorted order.
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &V) -> bool) -> Result<()> {
+        let mut index = 0;
+        while index < self.len() {
+            let item = &self.list[index];
+            if f(&item.key, &item.value) {
+                index += 1;
+            } else {
+                self.list().remove(index)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn clear(&mut self) -> Result<()> {
         self.list().remove_range(..)
     }
+
+    /// Gets the entry for `key` for in-place manipulation. Only inserting into a [`VacantEntry`] resizes the map.
+    ///
+    /// # Example
+    /// ```
+    /// use star_frame::prelude::*;
+    /// # fn main() -> Result<()> {
+    /// let bytes = <Map<u8, u8>>::new_byte_set(Default::default())?;
+    /// let mut map = bytes.data_mut()?;
+    /// for key in [1, 2, 1] {
+    ///     *map.entry(key).or_insert(0)? += 1;
+    /// }
+    /// assert_eq!(map.get(&1), Some(&2));
+    /// assert_eq!(map.get(&2), Some(&1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: K) -> MapEntry<'_, 'parent, 'top, K, V, L, P> {
+        match self.get_index(&key) {
+            Ok(index) => MapEntry::Occupied(OccupiedEntry { map: self, index }),
+            Err(index) => MapEntry::Vacant(VacantEntry {
+                map: self,
+                index,
+                key,
+            }),
+        }
+    }
+}
+
+type MapWrapper<'parent, 'top, K, V, L, P> =
+    ExclusiveWrapper<'parent, 'top, <Map<K, V, L> as UnsizedType>::Ptr, P>;
+
+/// A view into a single entry of a [`Map`], returned by [`MapExclusiveImpl::entry`].
+pub enum MapEntry<'a, 'parent, 'top, K, V, L, P>
+where
+    K: UnsizedGenerics + Ord,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    Occupied(OccupiedEntry<'a, 'parent, 'top, K, V, L, P>),
+    Vacant(VacantEntry<'a, 'parent, 'top, K, V, L, P>),
+}
+
+/// An entry for a key that is in the [`Map`].
+pub struct OccupiedEntry<'a, 'parent, 'top, K, V, L, P>
+where
+    K: UnsizedGenerics + Ord,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    map: &'a mut MapWrapper<'parent, 'top, K, V, L, P>,
+    index: usize,
+}
+
+/// An entry for a key that is not in the [`Map`].
+pub struct VacantEntry<'a, 'parent, 'top, K, V, L, P>
+where
+    K: UnsizedGenerics + Ord,
+    V: UnsizedGenerics,
+    L: ListLength,
+{
+    map: &'a mut MapWrapper<'parent, 'top, K, V, L, P>,
+    index: usize,
+    key: K,
+}
+
+impl<'a, 'parent, 'top, K, V, L, P> MapEntry<'a, 'parent, 'top, K, V, L, P>
+where
+    K: UnsizedGenerics + Ord,
+    V: UnsizedGenerics,
+    L: ListLength,
+    MapWrapper<'parent, 'top, K, V, L, P>: ExclusiveRecurse,
+{
+    #[must_use]
+    pub fn key(&self) -> &K {
+        match self {
+            MapEntry::Occupied(entry) => entry.key(),
+            MapEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> Result<&'a mut V> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> Result<&'a mut V> {
+        match self {
+            MapEntry::Occupied(entry) => Ok(entry.into_mut()),
+            MapEntry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` on the value if the entry is occupied.
+    #[must_use]
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let MapEntry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, 'parent, 'top, K, V, L, P> OccupiedEntry<'a, 'parent, 'top, K, V, L, P>
+where
+    K: UnsizedGenerics + Ord,
+    V: UnsizedGenerics,
+    L: ListLength,
+    MapWrapper<'parent, 'top, K, V, L, P>: ExclusiveRecurse,
+{
+    #[must_use]
+    pub fn key(&self) -> &K {
+        &self.map.list[self.index].key
+    }
+
+    #[must_use]
+    pub fn get(&self) -> &V {
+        &self.map.list[self.index].value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.list[self.index].value
+    }
+
+    /// Converts the entry into a mutable reference to its value that lives as long as the map borrow.
+    #[must_use]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.list[self.index].value
+    }
+
+    /// Replaces the value of the entry, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map, returning its value.
+    pub fn remove(self) -> Result<V> {
+        let value = *self.get();
+        self.map.list().remove(self.index)?;
+        Ok(value)
+    }
+}
+
+impl<'a, 'parent, 'top, K, V, L, P> VacantEntry<'a, 'parent, 'top, K, V, L, P>
+where
+    K: UnsizedGenerics + Ord,
+    V: UnsizedGenerics,
+    L: ListLength,
+    MapWrapper<'parent, 'top, K, V, L, P>: ExclusiveRecurse,
+{
+    #[must_use]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Inserts `value` at the entry's key, resizing the map, and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> Result<&'a mut V> {
+        self.map.list().insert(
+            self.index,
+            ListItemSized {
+                key: self.key,
+                value,
+            },
+        )?;
+        Ok(&mut self.map.list[self.index].value)
+    }
 }
 
 macro_rules! map_iter {
@@ -355,4 +536,58 @@ mod tests {
         assert_eq!(map_owned, owned);
         Ok(())
     }
+
+    #[test]
+    fn test_map_iter_retain() -> Result<()> {
+        let owned: BTreeMap<u8, u8> = (0..10).map(|i| (9 - i, i)).collect();
+        let map = Map::<u8, u8>::new_byte_set(owned.clone())?;
+        let mut map = map.data_mut()?;
+        assert!(map.iter().map(|(k, v)| (*k, *v)).eq(owned.clone()));
+        assert!(map.keys().copied().eq(owned.keys().copied()));
+        assert!(map.values().copied().eq(owned.values().copied()));
+
+        for (_, value) in map.iter_mut() {
+            *value *= 2;
+        }
+        map.retain(|key, value| key % 3 != 0 && *value != 2)?;
+        let expected: BTreeMap<u8, u8> = owned
+            .into_iter()
+            .map(|(k, v)| (k, v * 2))
+            .filter(|(k, v)| k % 3 != 0 && *v != 2)
+            .collect();
+        assert!(map.iter().map(|(k, v)| (*k, *v)).eq(expected));
+
+        map.retain(|_, _| false)?;
+        assert!(map.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_entry() -> Result<()> {
+        let map = Map::<u8, u8>::new_byte_set([(1, 10)].into_iter().collect())?;
+        let mut map = map.data_mut()?;
+        *map.entry(1).or_insert(0)? += 1;
+        *map.entry(2).or_insert(20)? += 1;
+        for _ in 0..2 {
+            map.entry(3).and_modify(|value| *value += 1).or_insert(30)?;
+        }
+        assert!(map
+            .iter()
+            .map(|(k, v)| (*k, *v))
+            .eq([(1, 11), (2, 21), (3, 31)]));
+
+        let MapEntry::Occupied(mut entry) = map.entry(2) else {
+            panic!("Entry 2 should be occupied");
+        };
+        assert_eq!((entry.key(), entry.get()), (&2, &21));
+        assert_eq!(entry.insert(22), 21);
+        assert_eq!(entry.remove()?, 22);
+
+        let MapEntry::Vacant(entry) = map.entry(2) else {
+            panic!("Entry 2 should be vacant");
+        };
+        assert_eq!(entry.key(), &2);
+        assert!(map.iter().map(|(k, v)| (*k, *v)).eq([(1, 11), (3, 31)]));
+        Ok(())
+    }
 }
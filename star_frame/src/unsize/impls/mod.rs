@@ -1,4 +1,5 @@
 pub mod checked;
+pub mod hash_map;
 pub mod list;
 pub mod map;
 pub mod remaining_bytes;
@@ -8,6 +9,7 @@ pub mod unsized_map;
 pub mod unsized_string;
 
 pub use checked::*;
+pub use hash_map::*;
 pub use list::*;
 pub use map::*;
 pub use remaining_bytes::*;
@@ -18,6 +20,7 @@ pub use unsized_string::*;
 
 pub(crate) mod prelude {
     use super::*;
+    pub use hash_map::{UnsizedHashMap, UnsizedHashMapExclusiveImpl as _};
     pub use list::{List, ListExclusiveImpl as _};
     pub use map::{Map, MapExclusiveImpl as _};
     pub use remaining_bytes::{RemainingBytes, RemainingBytesExclusiveImpl as _};
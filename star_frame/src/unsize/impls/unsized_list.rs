@@ -1248,6 +1248,12 @@ where
         Some(Ok((Ptr(ptr, PhantomData), offset)))
     }
 
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Skipped items don't need to be read, so jump straight to the requested index
+        self.index = self.index.saturating_add(n).min(self.list.len());
+        self.next()
+    }
+
     fn size_hint(&self) -> (usize, Option<usize>) {
         let len = self.list.len() - self.index;
         (len, Some(len))
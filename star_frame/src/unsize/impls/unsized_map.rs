@@ -16,7 +16,10 @@ use crate::{
         FromOwned, UnsizedTypePtr,
     },
 };
-use std::{collections::BTreeMap, iter::FusedIterator};
+use std::{
+    collections::BTreeMap,
+    iter::{FusedIterator, Skip, Take},
+};
 
 #[derive(Align1, Zeroable, Debug, Copy, Clone)]
 #[repr(C)]
@@ -175,6 +178,29 @@ where
         }
     }
 
+    /// Iterates over at most `limit` items in key order, starting at the `offset`th item.
+    ///
+    /// The skipped items are not read, so this can be used to return a bounded page of a large map. Use
+    /// [`Self::cursor_after`] to find the `offset` of the next page.
+    #[inline]
+    #[must_use]
+    pub fn page(&self, offset: usize, limit: usize) -> Take<Skip<UnsizedMapIter<'_, K, V>>> {
+        self.iter().skip(offset).take(limit)
+    }
+
+    /// Returns the offset of the first item with a key greater than `last_key`, or `None` if there are no more items.
+    ///
+    /// `last_key` does not need to be in the map, so a cursor stays accurate if items are inserted or removed
+    /// between pages.
+    #[must_use]
+    pub fn cursor_after(&self, last_key: &K) -> Option<usize> {
+        let next = match self.get_index(last_key) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        };
+        (next < self.len()).then_some(next)
+    }
+
     #[inline]
     #[must_use]
     pub fn keys(&self) -> UnsizedMapKeys<'_, K, V> {
@@ -286,6 +312,11 @@ macro_rules! map_iter {
                 $next_arg.iter.next().map(|item| item.map($next))
             }
 
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                let $next_arg = self;
+                $next_arg.iter.nth(n).map(|item| item.map($next))
+            }
+
             fn size_hint(&self) -> (usize, Option<usize>) {
                 self.iter.size_hint()
             }
@@ -427,4 +458,68 @@ mod tests {
         );
         Ok(())
     }
+
+    fn page_keys(map: &UnsizedMap<u8, List<u8>>, offset: usize, limit: usize) -> Result<Vec<u8>> {
+        map.page(offset, limit)
+            .map(|item| item.map(|(key, _value)| key))
+            .collect()
+    }
+
+    #[test]
+    fn test_unsized_map_page() -> Result<()> {
+        let owned_map = (0..5u8).map(|key| (key * 10, vec![key])).collect();
+        let map = UnsizedMap::<u8, List<u8>>::new_byte_set(owned_map)?;
+        let data = map.data()?;
+
+        assert_eq!(page_keys(&data, 0, 2)?, vec![0, 10]);
+        assert_eq!(page_keys(&data, 2, 2)?, vec![20, 30]);
+        // The last page is cut short
+        assert_eq!(page_keys(&data, 4, 2)?, vec![40]);
+        assert_eq!(page_keys(&data, 5, 2)?, Vec::<u8>::new());
+        assert_eq!(page_keys(&data, usize::MAX, 2)?, Vec::<u8>::new());
+        assert_eq!(page_keys(&data, 0, 0)?, Vec::<u8>::new());
+
+        let (key, value) = data.page(3, 1).next().expect("Page has an item")?;
+        assert_eq!(key, 30);
+        assert_eq!(value.as_slice(), &[3]);
+        assert_eq!(data.page(1, 3).len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsized_map_cursor_after() -> Result<()> {
+        let owned_map = (0..5u8).map(|key| (key * 10, vec![key])).collect();
+        let map = UnsizedMap::<u8, List<u8>>::new_byte_set(owned_map)?;
+
+        let data = map.data()?;
+        assert_eq!(data.cursor_after(&10), Some(2));
+        // Keys that aren't in the map point at the next greater key
+        assert_eq!(data.cursor_after(&15), Some(2));
+        assert_eq!(data.cursor_after(&30), Some(4));
+        assert_eq!(data.cursor_after(&40), None);
+        assert_eq!(data.cursor_after(&u8::MAX), None);
+        let first_page = page_keys(&data, 0, 2)?;
+        assert_eq!(first_page, vec![0, 10]);
+        let cursor = data.cursor_after(first_page.last().expect("Page is not empty"));
+        drop(data);
+
+        // Insert items before and after the cursor between pages
+        let mut data = map.data_mut()?;
+        data.insert(5, [])?;
+        data.insert(25, [])?;
+        drop(data);
+
+        let data = map.data()?;
+        // The old offset is stale, but a new cursor from the last key skips the earlier insertion
+        assert_eq!(
+            page_keys(&data, cursor.expect("More items"), 2)?,
+            vec![10, 20]
+        );
+        let cursor = data.cursor_after(&10).expect("More items");
+        assert_eq!(page_keys(&data, cursor, 2)?, vec![20, 25]);
+        let cursor = data.cursor_after(&25).expect("More items");
+        assert_eq!(page_keys(&data, cursor, 2)?, vec![30, 40]);
+        assert_eq!(data.cursor_after(&40), None);
+        Ok(())
+    }
 }
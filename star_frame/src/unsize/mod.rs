@@ -192,6 +192,54 @@ pub trait FromOwned: UnsizedType {
 #[cfg(doctest)]
 struct TestUnsizedZst;
 
+/// # Test `pub(crate)` unsized_impl methods outside their module
+/// ```
+/// use star_frame::prelude::*;
+/// use star_frame::unsize::TestByteSet;
+/// mod inner {
+///     use star_frame::prelude::*;
+///     #[unsized_type(skip_idl)]
+///     pub struct Bytes {
+///         #[unsized_start]
+///         pub list: List<u8>,
+///     }
+///     #[unsized_impl]
+///     impl Bytes {
+///         pub(crate) fn push_one(&mut self) -> Result<()> {
+///             self.list().push(1)
+///         }
+///     }
+/// }
+/// use inner::BytesExclusiveImplCrate;
+/// let test_bytes = TestByteSet::<inner::Bytes>::new_default().unwrap();
+/// test_bytes.data_mut().unwrap().push_one().unwrap();
+/// ```
+///
+/// # Test private unsized_impl methods outside their module
+/// ```compile_fail
+/// use star_frame::prelude::*;
+/// use star_frame::unsize::TestByteSet;
+/// mod inner {
+///     use star_frame::prelude::*;
+///     #[unsized_type(skip_idl)]
+///     pub struct Bytes {
+///         #[unsized_start]
+///         pub list: List<u8>,
+///     }
+///     #[unsized_impl]
+///     impl Bytes {
+///         fn push_one(&mut self) -> Result<()> {
+///             self.list().push(1)
+///         }
+///     }
+/// }
+/// use inner::BytesExclusiveImplPrivate;
+/// let test_bytes = TestByteSet::<inner::Bytes>::new_default().unwrap();
+/// test_bytes.data_mut().unwrap().push_one().unwrap();
+/// ```
+#[cfg(doctest)]
+struct TestUnsizedImplVisibility;
+
 #[allow(unused)]
 #[cfg(miri)]
 extern "Rust" {
@@ -175,3 +175,49 @@ fn unsized_enum_test() -> Result<()> {
     assert_eq!(owned, new_owned);
     Ok(())
 }
+
+#[test]
+fn set_variant_then_mutate() -> Result<()> {
+    // The top level enum
+    let bytes = TestByteSet::<UnsizedEnumTest<Unsized1>>::new_default()?;
+    let mut data = bytes.data_mut()?;
+    let mut unsized2 = data.set_unsized2(DefaultInit)?;
+    unsized2.list().push(1.into())?;
+    unsized2.list().push(2.into())?;
+    unsized2.sized = 5.into();
+    assert_eq!(
+        UnsizedEnumTest::<Unsized1>::owned_from_ptr(&data)?,
+        UnsizedEnumTestOwned::Unsized2(Unsized2Owned {
+            sized: 5.into(),
+            list: vec![1.into(), 2.into()],
+        })
+    );
+
+    // Nested between other unsized fields
+    let bytes = TestByteSet::<EnumTestStruct>::new_default()?;
+    let mut data = bytes.data_mut()?;
+    data.list_after().push(7)?;
+    data.enum_test()
+        .set_unsized3(DefaultInit)?
+        .unsized1()
+        .list()
+        .push(3.into())?;
+    data.list_before().push(6)?;
+    let mut enum_test = data.enum_test();
+    let mut unsized1 = enum_test.set_unsized1(DefaultInit)?;
+    unsized1.list().push(8)?;
+    unsized1.sized = 9;
+    data.list_after().push(10)?;
+    assert_eq!(
+        EnumTestStruct::owned_from_ptr(&data)?,
+        EnumTestStructOwned {
+            list_before: vec![6],
+            enum_test: UnsizedEnumTestOwned::Unsized1(Unsized1Owned {
+                sized: 9,
+                list: vec![8],
+            }),
+            list_after: vec![7, 10],
+        }
+    );
+    Ok(())
+}
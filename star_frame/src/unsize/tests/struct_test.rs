@@ -155,6 +155,37 @@ fn test_single_unsized() -> Result<()> {
     Ok(())
 }
 
+#[unsized_type(skip_idl, program_account)]
+struct UnsizedProgramAccount {
+    authority: Pubkey,
+    #[unsized_start]
+    items: List<PackedValue<u16>>,
+    names: List<u8>,
+}
+
+#[test]
+fn test_unsized_account_serialization() -> Result<()> {
+    let owned = UnsizedProgramAccountOwned {
+        authority: Pubkey::new_unique(),
+        items: [1, 2, 3].map(Into::into).to_vec(),
+        names: b"star".to_vec(),
+    };
+    let bytes = UnsizedProgramAccount::serialize_account(owned.clone())?;
+
+    // The discriminant prefixes the same layout `TestByteSet` writes
+    let (discriminant, data) = bytes.split_at(8);
+    assert_eq!(discriminant, UnsizedProgramAccount::discriminant_bytes());
+    let test_bytes = TestByteSet::<UnsizedProgramAccount>::new(owned.clone())?;
+    assert_eq!(data, test_bytes.underlying_data()?);
+
+    assert_eq!(UnsizedProgramAccount::deserialize_account(&bytes)?, owned);
+
+    let mut wrong_discriminant = bytes.clone();
+    wrong_discriminant[0] ^= 1;
+    assert!(UnsizedProgramAccount::deserialize_account(&wrong_discriminant).is_err());
+    Ok(())
+}
+
 mod many_unsized {
     use super::*;
     #[unsized_type()]
@@ -195,6 +226,29 @@ impl many_unsized::ManyUnsized {
     }
 }
 
+mod crate_visible {
+    use super::*;
+
+    #[unsized_impl(tag = "2")]
+    impl many_unsized::ManyUnsized {
+        pub(crate) fn push_unsized5(&mut self, val: u8) -> Result<()> {
+            self.unsized5().push(TestStruct {
+                val1: val.into(),
+                val2: val.into(),
+            })
+        }
+    }
+}
+
+#[test]
+fn test_crate_visible_unsized_impl() -> Result<()> {
+    use crate_visible::ManyUnsizedExclusiveImplCrate2;
+    let r = TestByteSet::<many_unsized::ManyUnsized>::new_default()?;
+    r.data_mut()?.push_unsized5(3)?;
+    assert_eq!(r.owned()?.unsized5, vec![TestStruct { val1: 3, val2: 3 }]);
+    Ok(())
+}
+
 #[test]
 fn test_many_unsized() -> Result<()> {
     TestByteSet::<many_unsized::ManyUnsized>::new_default()?;
@@ -437,6 +491,41 @@ fn test_with_sized_and_unsized_generics() -> Result<()> {
     Ok(())
 }
 
+#[unsized_type(skip_idl)]
+pub struct TypedList<T: UnsizedGenerics> {
+    #[unsized_start]
+    pub items: List<T>,
+}
+
+#[test]
+fn test_typed_list_instantiations() -> Result<()> {
+    let numbers = TestByteSet::<TypedList<PackedValueChecked<u64>>>::new(TypedListOwned {
+        items: vec![PackedValueChecked(1), PackedValueChecked(2)],
+    })?;
+    numbers.data_mut()?.items().push(PackedValueChecked(3))?;
+    assert_eq!(
+        numbers.owned()?,
+        TypedListOwned {
+            items: vec![
+                PackedValueChecked(1),
+                PackedValueChecked(2),
+                PackedValueChecked(3)
+            ],
+        }
+    );
+
+    let key = Pubkey::new_unique();
+    let keys = TestByteSet::<TypedList<Pubkey>>::new(TypedListOwned { items: vec![key] })?;
+    keys.data_mut()?.items().push(key)?;
+    assert_eq!(
+        keys.owned()?,
+        TypedListOwned {
+            items: vec![key, key],
+        }
+    );
+    Ok(())
+}
+
 #[unsized_type]
 struct MutliList {
     #[unsized_start]
@@ -468,3 +557,33 @@ fn test_multi_list() {
     same_mut_slice[0] = 100;
 }
 //todo: make a single very complex struct and test it with a watcher on owned like list
+
+#[unsized_type(skip_idl, serde)]
+pub struct PlayerFactionData {
+    pub owner: Pubkey,
+    pub faction_id: u8,
+    #[unsized_start]
+    pub members: List<Pubkey>,
+    pub name: List<u8>,
+}
+
+#[test]
+fn test_owned_serde_round_trip() -> Result<()> {
+    let owned = PlayerFactionDataOwned {
+        owner: Pubkey::new_from_array([1; 32]),
+        faction_id: 2,
+        members: vec![
+            Pubkey::new_from_array([3; 32]),
+            Pubkey::new_from_array([4; 32]),
+        ],
+        name: b"MUD".to_vec(),
+    };
+    let json = serde_json::to_string(&owned).expect("Owned data serializes");
+    let deserialized: PlayerFactionDataOwned =
+        serde_json::from_str(&json).expect("Owned data deserializes");
+    assert_eq!(deserialized, owned);
+
+    let bytes = TestByteSet::<PlayerFactionData>::new(deserialized)?;
+    assert_eq!(bytes.owned()?, owned);
+    Ok(())
+}
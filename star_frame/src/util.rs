@@ -211,9 +211,225 @@ pub mod borsh_bytemuck {
     }
 }
 
+/// Unaligned reads, writes, and checked arithmetic for fields of `#[repr(C, packed)]` structs.
+///
+/// The compiler rejects references to packed fields since they may be unaligned, so anything that goes through a
+/// reference, like [`num_traits::CheckedAdd`], can't be used on them directly. The functions here work on the raw
+/// pointers from [`core::ptr::addr_of!`] and [`core::ptr::addr_of_mut!`] instead, and the
+/// [`packed_checked_add!`](crate::packed_checked_add), [`packed_checked_sub!`](crate::packed_checked_sub), and
+/// [`packed_checked_mul!`](crate::packed_checked_mul) macros do the same arithmetic on a copy of the field without
+/// `unsafe`.
+///
+/// # Example
+/// ```
+/// use star_frame::prelude::*;
+///
+/// #[derive(Align1, Pod, Zeroable, Copy, Clone)]
+/// #[repr(C, packed)]
+/// pub struct Counter {
+///     pub bump: u8,
+///     pub count: u64,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let mut counter = Counter { bump: 0, count: 1 };
+/// packed_checked_add!(counter.count, 2)?;
+/// assert_eq!({ counter.count }, 3);
+/// assert!(packed_checked_sub!(counter.count, 4).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub mod packed {
+    use crate::prelude::*;
+    use core::ptr;
+    use num_traits::{CheckedAdd, CheckedMul, CheckedSub};
+
+    /// Reads the value at `src`, which does not need to be aligned.
+    ///
+    /// # Safety
+    /// `src` must be valid for reads, such as a pointer to a field from [`ptr::addr_of!`].
+    #[inline]
+    pub unsafe fn read<T: Copy>(src: *const T) -> T {
+        ptr::read_unaligned(src)
+    }
+
+    /// Writes `val` to `dst`, which does not need to be aligned.
+    ///
+    /// # Safety
+    /// `dst` must be valid for writes, such as a pointer to a field from [`ptr::addr_of_mut!`].
+    #[inline]
+    pub unsafe fn write<T: Copy>(dst: *mut T, val: T) {
+        ptr::write_unaligned(dst, val);
+    }
+
+    /// Adds `rhs` to the value at `dst`, erroring with [`ProgramError::ArithmeticOverflow`] on overflow.
+    ///
+    /// # Safety
+    /// `dst` must be valid for reads and writes, such as a pointer to a field from [`ptr::addr_of_mut!`].
+    #[inline]
+    pub unsafe fn checked_add_packed<T: CheckedAdd + Copy>(dst: *mut T, rhs: T) -> Result<()> {
+        let value = read(dst)
+            .checked_add(&rhs)
+            .ok_or_else(|| error!(ProgramError::ArithmeticOverflow))?;
+        write(dst, value);
+        Ok(())
+    }
+
+    /// Subtracts `rhs` from the value at `dst`, erroring with [`ProgramError::ArithmeticOverflow`] on overflow.
+    ///
+    /// # Safety
+    /// `dst` must be valid for reads and writes, such as a pointer to a field from [`ptr::addr_of_mut!`].
+    #[inline]
+    pub unsafe fn checked_sub_packed<T: CheckedSub + Copy>(dst: *mut T, rhs: T) -> Result<()> {
+        let value = read(dst)
+            .checked_sub(&rhs)
+            .ok_or_else(|| error!(ProgramError::ArithmeticOverflow))?;
+        write(dst, value);
+        Ok(())
+    }
+
+    /// Multiplies the value at `dst` by `rhs`, erroring with [`ProgramError::ArithmeticOverflow`] on overflow.
+    ///
+    /// # Safety
+    /// `dst` must be valid for reads and writes, such as a pointer to a field from [`ptr::addr_of_mut!`].
+    #[inline]
+    pub unsafe fn checked_mul_packed<T: CheckedMul + Copy>(dst: *mut T, rhs: T) -> Result<()> {
+        let value = read(dst)
+            .checked_mul(&rhs)
+            .ok_or_else(|| error!(ProgramError::ArithmeticOverflow))?;
+        write(dst, value);
+        Ok(())
+    }
+
+    /// Adds `rhs` to a packed field like [`checked_add_packed`](crate::util::packed::checked_add_packed), without `unsafe`.
+    ///
+    /// See [`util::packed`](crate::util::packed) for an example.
+    #[macro_export]
+    macro_rules! packed_checked_add {
+        ($place:expr, $rhs:expr $(,)?) => {{
+            let value = $place;
+            match $crate::num_traits::CheckedAdd::checked_add(&value, &$rhs) {
+                ::core::option::Option::Some(value) => {
+                    $place = value;
+                    ::core::result::Result::Ok(())
+                }
+                ::core::option::Option::None => ::core::result::Result::Err($crate::error!(
+                    $crate::prelude::ProgramError::ArithmeticOverflow
+                )),
+            }
+        }};
+    }
+
+    /// Subtracts `rhs` from a packed field like [`checked_sub_packed`](crate::util::packed::checked_sub_packed), without `unsafe`.
+    ///
+    /// See [`util::packed`](crate::util::packed) for an example.
+    #[macro_export]
+    macro_rules! packed_checked_sub {
+        ($place:expr, $rhs:expr $(,)?) => {{
+            let value = $place;
+            match $crate::num_traits::CheckedSub::checked_sub(&value, &$rhs) {
+                ::core::option::Option::Some(value) => {
+                    $place = value;
+                    ::core::result::Result::Ok(())
+                }
+                ::core::option::Option::None => ::core::result::Result::Err($crate::error!(
+                    $crate::prelude::ProgramError::ArithmeticOverflow
+                )),
+            }
+        }};
+    }
+
+    /// Multiplies a packed field by `rhs` like [`checked_mul_packed`](crate::util::packed::checked_mul_packed), without `unsafe`.
+    ///
+    /// See [`util::packed`](crate::util::packed) for an example.
+    #[macro_export]
+    macro_rules! packed_checked_mul {
+        ($place:expr, $rhs:expr $(,)?) => {{
+            let value = $place;
+            match $crate::num_traits::CheckedMul::checked_mul(&value, &$rhs) {
+                ::core::option::Option::Some(value) => {
+                    $place = value;
+                    ::core::result::Result::Ok(())
+                }
+                ::core::option::Option::None => ::core::result::Result::Err($crate::error!(
+                    $crate::prelude::ProgramError::ArithmeticOverflow
+                )),
+            }
+        }};
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[derive(Align1, Pod, Zeroable, Copy, Clone)]
+    #[repr(C, packed)]
+    struct PackedCounter {
+        bump: u8,
+        count: u64,
+    }
+
+    #[test]
+    fn test_packed_arithmetic() -> Result<()> {
+        let mut counter = PackedCounter { bump: 1, count: 10 };
+        packed_checked_add!(counter.count, 5)?;
+        packed_checked_mul!(counter.count, 2)?;
+        packed_checked_sub!(counter.count, 30)?;
+        assert_eq!({ counter.count }, 0);
+        assert!(packed_checked_sub!(counter.count, 1).is_err());
+        packed_checked_add!(counter.count, u64::MAX)?;
+        assert!(packed_checked_add!(counter.count, 1).is_err());
+        assert_eq!({ counter.count }, u64::MAX);
+
+        // SAFETY: The pointers are to fields of `counter`
+        unsafe {
+            packed::write(core::ptr::addr_of_mut!(counter.count), 7);
+            assert_eq!(packed::read(core::ptr::addr_of!(counter.count)), 7);
+        }
+        assert_eq!(counter.bump, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_packed_helpers_unaligned() -> Result<()> {
+        // Backed by `u64`s so the buffer is aligned and a `u64` at offset 1 is not
+        let mut backing = [u64::MAX; 2];
+        let buffer = bytemuck::bytes_of_mut(&mut backing);
+        let ptr = buffer[1..].as_mut_ptr().cast::<u64>();
+
+        // SAFETY: `ptr` points to `size_of::<u64>()` bytes within `buffer`
+        unsafe {
+            packed::write(ptr, 10);
+            assert_eq!(packed::read(ptr), 10);
+
+            packed::checked_add_packed(ptr, 5)?;
+            assert_eq!(packed::read(ptr), 15);
+            packed::checked_mul_packed(ptr, 3)?;
+            assert_eq!(packed::read(ptr), 45);
+            packed::checked_sub_packed(ptr, 44)?;
+            assert_eq!(packed::read(ptr), 1);
+
+            // Overflow errors and leaves the value unchanged
+            for res in [
+                packed::checked_sub_packed(ptr, 2),
+                packed::checked_add_packed(ptr, u64::MAX),
+            ] {
+                assert_eq!(
+                    ProgramError::from(res.unwrap_err()),
+                    ProgramError::ArithmeticOverflow
+                );
+            }
+            packed::write(ptr, u64::MAX / 2 + 1);
+            assert!(packed::checked_mul_packed(ptr, 2).is_err());
+            assert_eq!(packed::read(ptr), u64::MAX / 2 + 1);
+        }
+        assert_eq!(buffer[1..9], (u64::MAX / 2 + 1).to_ne_bytes());
+        // The surrounding bytes are untouched
+        assert_eq!(buffer[0], 0xff);
+        assert_eq!(buffer[9..], [0xff; 7]);
+        Ok(())
+    }
+
     #[test]
     fn test_compare_strings() {
         assert!(compare_strings("hello", "hello"));
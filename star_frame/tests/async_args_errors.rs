@@ -0,0 +1,7 @@
+/// The `async` feature checks that `InstructionArgs` are `Send + 'static`, and warns on borrowed run args.
+#[cfg(feature = "async")]
+#[test]
+fn async_args_errors() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/trybuild/async_args_*.rs");
+}
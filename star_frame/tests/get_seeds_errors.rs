@@ -0,0 +1,7 @@
+/// `skip_idl` omits the generated IDL types, even with the `idl` feature enabled.
+#[cfg(feature = "idl")]
+#[test]
+fn get_seeds_errors() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/trybuild/get_seeds_*.rs");
+}
@@ -0,0 +1,5 @@
+#[test]
+fn star_frame_instruction_errors() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/trybuild/star_frame_instruction_*.rs");
+}
@@ -0,0 +1,11 @@
+#![deny(deprecated)]
+use star_frame::prelude::*;
+
+#[derive(Copy, Clone, InstructionArgs)]
+#[instruction_args(skip_idl)]
+#[ix_args(&run)]
+pub struct BorrowedRun {
+    pub amount: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use star_frame::prelude::*;
+
+#[derive(Copy, Clone, InstructionArgs)]
+#[instruction_args(skip_idl)]
+#[ix_args(run)]
+pub struct NotSend {
+    pub data: *const u8,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use star_frame::prelude::*;
+
+#[derive(Debug, GetSeeds, Clone)]
+#[get_seeds(seed_const = b"pda", skip_idl)]
+pub struct Pda {
+    owner: Pubkey,
+}
+
+fn main() {
+    let _ = FindPda {
+        owner: star_frame::idl::seed_path("owner"),
+    };
+}
@@ -0,0 +1,16 @@
+use star_frame::prelude::*;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, InstructionArgs)]
+#[instruction_args(skip_idl)]
+pub struct Ix;
+
+#[derive(AccountSet)]
+pub struct IxAccounts;
+
+#[star_frame_instruction]
+fn Ix(_accounts: &mut IxAccounts, _run_arg: (), ctx: &mut impl Context) -> Result<()> {
+    let _ = ctx;
+    Ok(())
+}
+
+fn main() {}
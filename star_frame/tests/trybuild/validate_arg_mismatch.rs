@@ -0,0 +1,15 @@
+use star_frame::prelude::*;
+
+#[derive(AccountSet)]
+#[validate(arg = u64)]
+pub struct Inner {
+    pub account: AccountInfo,
+}
+
+#[derive(AccountSet)]
+pub struct Outer {
+    #[validate(arg = true)]
+    pub inner: Inner,
+}
+
+fn main() {}
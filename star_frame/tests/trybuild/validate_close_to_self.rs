@@ -0,0 +1,10 @@
+use star_frame::prelude::*;
+
+#[derive(AccountSet)]
+pub struct Accounts {
+    #[validate(recipient)]
+    #[cleanup(arg = CloseAccount(()))]
+    pub account: Mut<AccountInfo>,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use star_frame::{account_set::modifiers::Immutable, prelude::*};
+
+#[derive(AccountSet)]
+pub struct ReadOnlyMut {
+    #[validate(read_only)]
+    pub account: Mut<SystemAccount>,
+}
+
+#[derive(AccountSet)]
+pub struct ImmutableMut {
+    pub account: Immutable<Mut<SystemAccount>>,
+}
+
+fn main() {}
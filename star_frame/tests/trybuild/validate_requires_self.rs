@@ -0,0 +1,9 @@
+use star_frame::prelude::*;
+
+#[derive(AccountSet)]
+pub struct Accounts {
+    #[validate(requires = [account])]
+    pub account: AccountInfo,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use star_frame::prelude::*;
+
+#[derive(AccountSet)]
+#[validate(arg = Pubkey)]
+pub struct Accounts {
+    #[validate(temp = arg, address = &temp, arg = ())]
+    pub a: AccountInfo,
+    // `a`'s `temp` isn't visible to other fields
+    #[validate(address = &temp)]
+    pub b: AccountInfo,
+}
+
+fn main() {}
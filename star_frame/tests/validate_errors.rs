@@ -0,0 +1,5 @@
+#[test]
+fn validate_errors() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/trybuild/validate_*.rs");
+}
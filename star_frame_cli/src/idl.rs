@@ -0,0 +1,365 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use clap::{Parser, Subcommand};
+use colored::*;
+use eyre::{bail, eyre, WrapErr};
+use star_frame_idl::{diff::diff, IdlDefinition};
+
+#[derive(Parser, Debug)]
+pub struct ProgramDirArgs {
+    /// The directory of the program crate
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub program_dir: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+pub struct IdlArgs {
+    #[command(subcommand)]
+    pub command: IdlCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum IdlCommand {
+    #[command(about = "Generate the program's IDL into target/idl without running its tests")]
+    Generate(ProgramDirArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub program: ProgramDirArgs,
+    /// The committed IDL to compare against, relative to the program directory
+    #[arg(long, value_name = "PATH", default_value = "idl.json")]
+    pub baseline: PathBuf,
+}
+
+pub fn idl(args: IdlArgs) -> eyre::Result<()> {
+    match args.command {
+        IdlCommand::Generate(args) => {
+            let path = generate_idl(&args.program_dir)?;
+            println!(
+                "{}",
+                format!("IDL written to {}", path.display()).green().bold()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Runs the `generate_idl` test and compares the IDL to the committed baseline, exiting with code 1 on breaking
+/// changes.
+pub fn check(args: CheckArgs) -> eyre::Result<()> {
+    let program_dir = &args.program.program_dir;
+    let baseline_path = program_dir.join(&args.baseline);
+    if !baseline_path.exists() {
+        bail!(
+            "Baseline IDL `{}` does not exist. Run `sf idl generate` and commit the generated `*.star_frame.json` as `{}`.",
+            baseline_path.display(),
+            args.baseline.display()
+        );
+    }
+    let generated_path = run_generate_idl_test(program_dir)?;
+    let baseline = read_idl(&baseline_path)?;
+    let generated = read_idl(&generated_path)?;
+
+    if !report_changes(&baseline, &generated) {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Prints the changes from `old` to `new`, returning `false` if any are breaking.
+fn report_changes(old: &IdlDefinition, new: &IdlDefinition) -> bool {
+    let changes = diff(old, new);
+    for change in &changes {
+        if change.is_breaking() {
+            println!("{}", change.to_string().red());
+        } else {
+            println!("{change}");
+        }
+    }
+    let breaking = changes.iter().filter(|change| change.is_breaking()).count();
+    if breaking == 0 {
+        println!("{}", "IDL is backwards compatible".green().bold());
+    } else {
+        println!(
+            "{}",
+            format!("IDL has {breaking} breaking change(s)")
+                .red()
+                .bold()
+        );
+    }
+    breaking == 0
+}
+
+/// Runs the program's `generate_idl` test, returning the path of the written [`IdlDefinition`].
+///
+/// The test writes to `CARGO_TARGET_DIR`, which is set to the target directory from `cargo metadata` so workspace
+/// members write to the workspace's `target` directory.
+fn run_generate_idl_test(program_dir: &Path) -> eyre::Result<PathBuf> {
+    let metadata = cargo_metadata(program_dir)?;
+    let target_dir = target_directory(&metadata)?;
+    let (program, _) = program_package(&metadata, program_dir)?;
+    let package_name = program["name"].as_str().unwrap_or_default();
+
+    let status = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .args(["test", "--features", "idl", "--", "generate_idl"])
+        .current_dir(program_dir)
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .status()
+        .wrap_err("Failed to run `cargo test`")?;
+    if !status.success() {
+        bail!("IDL generation failed: `cargo test --features idl -- generate_idl` exited with {status}");
+    }
+
+    let path = target_dir
+        .join("idl")
+        .join(format!("{package_name}.star_frame.json"));
+    if !path.exists() {
+        bail!(
+            "`generate_idl` did not write `{}`. Make sure it writes the `IdlDefinition` as well as the Codama IDL.",
+            path.display()
+        );
+    }
+    Ok(path)
+}
+
+/// Generates the IDL by building and running a small binary that depends on the program with the `idl` feature,
+/// returning the path of the written [`IdlDefinition`]. The Codama IDL is written next to it.
+fn generate_idl(program_dir: &Path) -> eyre::Result<PathBuf> {
+    let metadata = cargo_metadata(program_dir)?;
+    let target_dir = target_directory(&metadata)?;
+    let (program, manifest) = program_package(&metadata, program_dir)?;
+    let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+    let package_name = program["name"].as_str().unwrap_or_default();
+    let has_lib = program["targets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|target| {
+            target["crate_types"]
+                .as_array()
+                .is_some_and(|types| types.iter().any(|ty| ty == "lib" || ty == "rlib"))
+        });
+    if !has_lib {
+        bail!("`{package_name}` has no `lib` crate type, so its IDL can't be generated");
+    }
+    let star_frame = packages
+        .iter()
+        .find(|package| package["name"] == "star_frame")
+        .ok_or_else(|| eyre!("`{package_name}` does not depend on star_frame"))?;
+
+    let generator_dir = target_dir.join("sf_idl").join(package_name);
+    fs::create_dir_all(generator_dir.join("src"))?;
+    fs::write(
+        generator_dir.join("Cargo.toml"),
+        generator_manifest(
+            package_name,
+            manifest.parent().unwrap_or(program_dir),
+            &star_frame_dependency(star_frame)?,
+        ),
+    )?;
+    fs::write(
+        generator_dir.join("src").join("main.rs"),
+        generator_main(package_name),
+    )?;
+    // Reuse the program's lockfile so the generator builds against the same dependency versions
+    if let Some(workspace_root) = metadata["workspace_root"].as_str() {
+        let lockfile = Path::new(workspace_root).join("Cargo.lock");
+        if lockfile.exists() {
+            fs::copy(&lockfile, generator_dir.join("Cargo.lock"))?;
+        }
+    }
+
+    let idl_dir = target_dir.join("idl");
+    let status = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .arg("run")
+        .arg("--manifest-path")
+        .arg(generator_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .arg("--")
+        .arg(&idl_dir)
+        .status()
+        .wrap_err("Failed to run the IDL generator")?;
+    if !status.success() {
+        bail!("IDL generation failed: the IDL generator exited with {status}");
+    }
+    Ok(idl_dir.join(format!("{package_name}.star_frame.json")))
+}
+
+fn cargo_metadata(program_dir: &Path) -> eyre::Result<serde_json::Value> {
+    let output = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .args(["metadata", "--format-version", "1"])
+        .current_dir(program_dir)
+        .output()
+        .wrap_err("Failed to run `cargo metadata`")?;
+    if !output.status.success() {
+        bail!(
+            "`cargo metadata` failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    serde_json::from_slice(&output.stdout).wrap_err("Failed to parse `cargo metadata` output")
+}
+
+/// Returns the target directory from `cargo metadata`, which is the workspace's for workspace members.
+fn target_directory(metadata: &serde_json::Value) -> eyre::Result<PathBuf> {
+    metadata["target_directory"]
+        .as_str()
+        .map(PathBuf::from)
+        .ok_or_else(|| eyre!("`cargo metadata` is missing `target_directory`"))
+}
+
+/// Returns the `cargo metadata` package whose manifest is in `program_dir`, and the canonical path of that manifest.
+fn program_package<'a>(
+    metadata: &'a serde_json::Value,
+    program_dir: &Path,
+) -> eyre::Result<(&'a serde_json::Value, PathBuf)> {
+    let manifest = fs::canonicalize(program_dir.join("Cargo.toml"))
+        .wrap_err_with(|| format!("No Cargo.toml in `{}`", program_dir.display()))?;
+    let program = metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|package| {
+            package["manifest_path"]
+                .as_str()
+                .is_some_and(|path| fs::canonicalize(path).is_ok_and(|path| path == manifest))
+        })
+        .ok_or_else(|| eyre!("No package found for `{}`", manifest.display()))?;
+    Ok((program, manifest))
+}
+
+/// Returns the dependency specification for the `star_frame` package resolved by `cargo metadata`.
+fn star_frame_dependency(package: &serde_json::Value) -> eyre::Result<String> {
+    let version = package["version"].as_str().unwrap_or_default();
+    match package["source"].as_str() {
+        None => {
+            let manifest = Path::new(package["manifest_path"].as_str().unwrap_or_default());
+            Ok(format!(
+                "{{ path = {:?} }}",
+                manifest.parent().unwrap_or(manifest).display().to_string()
+            ))
+        }
+        Some(source) if source.starts_with("registry+") => Ok(format!("\"={version}\"")),
+        Some(source) => {
+            let Some((url, rev)) = source
+                .strip_prefix("git+")
+                .and_then(|source| source.split_once('#'))
+            else {
+                bail!("Unsupported star_frame source `{source}`");
+            };
+            let url = url.split_once('?').map_or(url, |(url, _)| url);
+            Ok(format!("{{ git = {url:?}, rev = {rev:?} }}"))
+        }
+    }
+}
+
+fn generator_manifest(package_name: &str, program_dir: &Path, star_frame: &str) -> String {
+    format!(
+        r#"[package]
+name = "sf-idl-{package_name}"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[workspace]
+
+[dependencies]
+sf_idl_program = {{ package = {package_name:?}, path = {:?}, features = ["idl"] }}
+star_frame = {star_frame}
+"#,
+        program_dir.display().to_string()
+    )
+}
+
+fn generator_main(package_name: &str) -> String {
+    format!(
+        r#"use star_frame::prelude::*;
+use std::path::PathBuf;
+
+fn main() -> Result<()> {{
+    let idl_dir = PathBuf::from(std::env::args().nth(1).expect("Missing IDL directory"));
+    let idl = <sf_idl_program::StarFrameDeclaredProgram as ProgramToIdl>::program_to_idl()?;
+    let star_frame_idl_json = star_frame::serde_json::to_string_pretty(&idl)?;
+    let codama_idl: ProgramNode = idl.try_into()?;
+    std::fs::create_dir_all(&idl_dir)?;
+    std::fs::write(idl_dir.join("{package_name}.json"), codama_idl.to_json()?)?;
+    std::fs::write(idl_dir.join("{package_name}.star_frame.json"), star_frame_idl_json)?;
+    Ok(())
+}}
+"#
+    )
+}
+
+fn read_idl(path: &Path) -> eyre::Result<IdlDefinition> {
+    let json = fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read `{}`", path.display()))?;
+    serde_json::from_str(&json)
+        .wrap_err_with(|| format!("`{}` is not a valid IdlDefinition", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use star_frame_idl::{account::IdlAccount, ty::IdlTypeId};
+
+    #[test]
+    fn resolves_star_frame_dependency() -> eyre::Result<()> {
+        let package = |source: serde_json::Value| {
+            serde_json::json!({
+                "name": "star_frame",
+                "version": "0.30.0",
+                "manifest_path": "/work/star_frame/star_frame/Cargo.toml",
+                "source": source,
+            })
+        };
+        assert_eq!(
+            star_frame_dependency(&package(serde_json::Value::Null))?,
+            r#"{ path = "/work/star_frame/star_frame" }"#
+        );
+        assert_eq!(
+            star_frame_dependency(&package(
+                "registry+https://github.com/rust-lang/crates.io-index".into()
+            ))?,
+            r#""=0.30.0""#
+        );
+        assert_eq!(
+            star_frame_dependency(&package(
+                "git+https://github.com/staratlasmeta/star_frame?branch=main#0123abc".into()
+            ))?,
+            r#"{ git = "https://github.com/staratlasmeta/star_frame", rev = "0123abc" }"#
+        );
+        assert!(star_frame_dependency(&package("sparse+https://example.com".into())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reports_breaking_changes() {
+        let new = IdlDefinition::default();
+        assert!(report_changes(&new, &new));
+
+        let mut old = new.clone();
+        old.accounts.insert(
+            "Counter".to_owned(),
+            IdlAccount {
+                discriminant: vec![1],
+                type_id: IdlTypeId {
+                    namespace: None,
+                    source: "Counter".to_owned(),
+                    provided_generics: vec![],
+                },
+                seeds: None,
+                space: None,
+                version: None,
+            },
+        );
+        assert!(report_changes(&new, &old));
+        assert!(!report_changes(&old, &new));
+    }
+}
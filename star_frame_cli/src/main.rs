@@ -1,11 +1,17 @@
 use clap::{Parser, Subcommand};
+pub mod idl;
 pub mod new_project;
+use idl::*;
 use new_project::*;
 
 #[derive(Subcommand, Debug)]
 enum CliCommand {
     #[command(about = "Create new Solana program")]
     New(NewArgs),
+    #[command(about = "Check the generated IDL for breaking changes against the committed IDL")]
+    Check(CheckArgs),
+    #[command(about = "Work with the program's IDL")]
+    Idl(IdlArgs),
 }
 
 #[derive(Parser)]
@@ -18,5 +24,7 @@ fn main() -> eyre::Result<()> {
     let cli = Cli::parse();
     match cli.command {
         CliCommand::New(args) => new_project(args),
+        CliCommand::Check(args) => check(args),
+        CliCommand::Idl(args) => idl(args),
     }
 }
@@ -5,7 +5,7 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use colored::*;
 use convert_case::{Case, Casing};
 use eyre::{bail, eyre, WrapErr};
@@ -17,6 +17,19 @@ pub struct NewArgs {
     /// The name of the program
     #[arg(value_name = "NAME")]
     pub name: String,
+    /// How the scaffolded instructions are written
+    #[arg(long, value_enum, default_value_t = InstructionStyle::ProcMacro)]
+    pub style: InstructionStyle,
+}
+
+/// The style of the scaffolded instructions. Both styles generate the same program.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstructionStyle {
+    /// `#[star_frame_instruction]` functions
+    #[default]
+    ProcMacro,
+    /// Explicit `impl StarFrameInstruction` blocks
+    Explicit,
 }
 
 pub fn new_project(args: NewArgs) -> eyre::Result<()> {
@@ -27,7 +40,7 @@ fn new_project_in(output_dir: &Path, args: NewArgs) -> eyre::Result<()> {
     let project_name = validate_program_name(args.name.trim())?;
     let destination = output_dir.join(&project_name);
 
-    scaffold_project(&destination, &project_name)?;
+    scaffold_project(&destination, &project_name, args.style)?;
     let keypair_path = program_keypair_relative_path(&project_name);
 
     println!(
@@ -46,12 +59,18 @@ fn new_project_in(output_dir: &Path, args: NewArgs) -> eyre::Result<()> {
         "  cargo test --features idl  # writes target/idl/{}.json",
         project_name
     );
+    println!("  sf idl generate && cp target/idl/{project_name}.star_frame.json idl.json");
+    println!("  sf check  # fails on breaking IDL changes");
     println!("  # Program keypair: {}", keypair_path.display());
 
     Ok(())
 }
 
-fn scaffold_project(destination: &Path, project_name: &str) -> eyre::Result<()> {
+fn scaffold_project(
+    destination: &Path,
+    project_name: &str,
+    style: InstructionStyle,
+) -> eyre::Result<()> {
     if destination.exists() {
         bail!(
             "Target path `{}` already exists. Choose a different name or remove the existing path.",
@@ -70,7 +89,7 @@ fn scaffold_project(destination: &Path, project_name: &str) -> eyre::Result<()>
                 staging_dir.display()
             )
         })?;
-        write_project_files(&staging_dir, &values).wrap_err_with(|| {
+        write_project_files(&staging_dir, &values, style).wrap_err_with(|| {
             format!(
                 "Failed to write scaffold files in `{}`",
                 staging_dir.display()
@@ -183,7 +202,11 @@ fn write_program_keypair(base: &Path, project_name: &str, keypair: &Keypair) ->
     Ok(())
 }
 
-fn write_project_files(base: &Path, values: &TemplateValues) -> io::Result<()> {
+fn write_project_files(
+    base: &Path,
+    values: &TemplateValues,
+    style: InstructionStyle,
+) -> io::Result<()> {
     const CARGO_TOML: &str = include_str!("template/cargo_toml");
     const GITIGNORE: &str = include_str!("template/gitignore");
     const README_MD: &str = include_str!("template/readme_md");
@@ -192,10 +215,17 @@ fn write_project_files(base: &Path, values: &TemplateValues) -> io::Result<()> {
     const STATES_RS: &str = include_str!("template/states_rs");
     const INCREMENT_RS: &str = include_str!("template/increment_rs");
     const INITIALIZE_RS: &str = include_str!("template/initialize_rs");
+    const INCREMENT_EXPLICIT_RS: &str = include_str!("template/increment_explicit_rs");
+    const INITIALIZE_EXPLICIT_RS: &str = include_str!("template/initialize_explicit_rs");
     const INSTRUCTION_MOD_RS: &str = include_str!("template/instruction_mod_rs");
     const TEST_RS: &str = include_str!("template/counter_test_rs");
     const TEST_MOD_RS: &str = include_str!("template/test_mod_rs");
 
+    let (increment_rs, initialize_rs) = match style {
+        InstructionStyle::ProcMacro => (INCREMENT_RS, INITIALIZE_RS),
+        InstructionStyle::Explicit => (INCREMENT_EXPLICIT_RS, INITIALIZE_EXPLICIT_RS),
+    };
+
     let files = [
         (CARGO_TOML, base.join("Cargo.toml")),
         (GITIGNORE, base.join(".gitignore")),
@@ -203,8 +233,8 @@ fn write_project_files(base: &Path, values: &TemplateValues) -> io::Result<()> {
         (CONFIG_TOML, base.join(".cargo/config.toml")),
         (LIB_RS, base.join("src/lib.rs")),
         (STATES_RS, base.join("src/states.rs")),
-        (INCREMENT_RS, base.join("src/instructions/increment.rs")),
-        (INITIALIZE_RS, base.join("src/instructions/initialize.rs")),
+        (increment_rs, base.join("src/instructions/increment.rs")),
+        (initialize_rs, base.join("src/instructions/initialize.rs")),
         (INSTRUCTION_MOD_RS, base.join("src/instructions/mod.rs")),
         (TEST_RS, base.join("src/tests/counter.rs")),
         (TEST_MOD_RS, base.join("src/tests/mod.rs")),
@@ -426,6 +456,7 @@ mod tests {
             temp_dir.path(),
             NewArgs {
                 name: "counter".to_owned(),
+                style: InstructionStyle::default(),
             },
         )
         .unwrap_err();
@@ -444,6 +475,7 @@ mod tests {
             temp_dir.path(),
             NewArgs {
                 name: "counter-program".to_owned(),
+                style: InstructionStyle::default(),
             },
         )
         .unwrap();
@@ -475,6 +507,37 @@ mod tests {
         let id = extract_program_id(&lib_rs).expect("missing generated program id");
         let keypair = read_keypair_file(keypair_path).unwrap();
         assert_eq!(id, keypair.pubkey().to_string());
+
+        let states_rs = fs::read_to_string(project_dir.join("src/states.rs")).unwrap();
+        assert!(states_rs.contains("self_ref: &Self::Ptr"));
+        assert!(!states_rs.contains("Self::Ref"));
+        let initialize_rs =
+            fs::read_to_string(project_dir.join("src/instructions/initialize.rs")).unwrap();
+        assert!(initialize_rs.contains("#[star_frame_instruction]"));
+    }
+
+    #[test]
+    fn scaffolds_explicit_instruction_style() {
+        let temp_dir = TestDir::new("sf-new-explicit");
+
+        new_project_in(
+            temp_dir.path(),
+            NewArgs {
+                name: "counter".to_owned(),
+                style: InstructionStyle::Explicit,
+            },
+        )
+        .unwrap();
+
+        let ixs_dir = temp_dir.path().join("counter/src/instructions");
+        for (file, instruction) in [
+            ("initialize.rs", "InitializeCounter"),
+            ("increment.rs", "Increment"),
+        ] {
+            let ix_rs = fs::read_to_string(ixs_dir.join(file)).unwrap();
+            assert!(ix_rs.contains(&format!("impl StarFrameInstruction for {instruction} {{")));
+            assert!(!ix_rs.contains("#[star_frame_instruction]"));
+        }
     }
 
     #[test]
@@ -485,6 +548,7 @@ mod tests {
             temp_dir.path(),
             NewArgs {
                 name: "alpha".to_owned(),
+                style: InstructionStyle::default(),
             },
         )
         .unwrap();
@@ -492,6 +556,7 @@ mod tests {
             temp_dir.path(),
             NewArgs {
                 name: "beta".to_owned(),
+                style: InstructionStyle::default(),
             },
         )
         .unwrap();
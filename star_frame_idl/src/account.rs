@@ -1,11 +1,17 @@
 use crate::{seeds::IdlSeeds, ty::IdlTypeId, IdlDiscriminant, IdlNamespace, ItemSource};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct IdlAccount {
     pub discriminant: IdlDiscriminant,
     pub type_id: IdlTypeId,
     pub seeds: Option<IdlSeeds>,
+    /// The total size of the account including the discriminant, if it is fixed.
+    #[serde(skip_serializing_if = "crate::is_default", default)]
+    pub space: Option<usize>,
+    /// The current version of a versioned account.
+    #[serde(skip_serializing_if = "crate::is_default", default)]
+    pub version: Option<u8>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -1,5 +1,8 @@
 use crate::{
-    account::IdlAccountId, seeds::IdlFindSeeds, serde_base58_pubkey_option, ty::IdlTypeDef,
+    account::IdlAccountId,
+    seeds::IdlFindSeeds,
+    serde_base58_pubkey_option,
+    ty::{IdlTypeDef, IdlTypeId},
     IdlDefinition, IdlGeneric, ItemDescription, ItemInfo, ItemSource, Result,
 };
 use serde::{Deserialize, Serialize};
@@ -14,7 +17,7 @@ pub struct IdlAccountSetId {
     pub provided_account_generics: Vec<IdlAccountSetDef>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct IdlAccountSet {
     #[serde(flatten)]
     pub info: ItemInfo,
@@ -50,6 +53,12 @@ pub struct IdlSingleAccountSet {
         default
     )]
     pub address: Option<Pubkey>,
+    #[serde(
+        with = "serde_base58_pubkey_option",
+        skip_serializing_if = "crate::is_default",
+        default
+    )]
+    pub owner: Option<Pubkey>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -69,6 +78,59 @@ pub enum IdlAccountSetDef {
 }
 
 impl IdlAccountSetDef {
+    /// Calls `f` with every [`IdlTypeId`] in the provided type generics of this account set
+    /// and its nested account sets.
+    pub fn visit_type_ids<'a>(&'a self, f: &mut impl FnMut(&'a IdlTypeId)) {
+        match self {
+            IdlAccountSetDef::Defined(id) => {
+                for generic in &id.provided_type_generics {
+                    generic.visit_type_ids(f);
+                }
+                for generic in &id.provided_account_generics {
+                    generic.visit_type_ids(f);
+                }
+            }
+            IdlAccountSetDef::Single(_) => {}
+            IdlAccountSetDef::Struct(fields) => {
+                for field in fields {
+                    field.account_set_def.visit_type_ids(f);
+                }
+            }
+            IdlAccountSetDef::Many { account_set, .. } => account_set.visit_type_ids(f),
+            IdlAccountSetDef::Or(sets) => {
+                for set in sets {
+                    set.visit_type_ids(f);
+                }
+            }
+        }
+    }
+
+    /// Like [`Self::visit_type_ids`], but with mutable access to each type ID.
+    pub fn visit_type_ids_mut(&mut self, f: &mut impl FnMut(&mut IdlTypeId)) {
+        match self {
+            IdlAccountSetDef::Defined(id) => {
+                for generic in &mut id.provided_type_generics {
+                    generic.visit_type_ids_mut(f);
+                }
+                for generic in &mut id.provided_account_generics {
+                    generic.visit_type_ids_mut(f);
+                }
+            }
+            IdlAccountSetDef::Single(_) => {}
+            IdlAccountSetDef::Struct(fields) => {
+                for field in fields {
+                    field.account_set_def.visit_type_ids_mut(f);
+                }
+            }
+            IdlAccountSetDef::Many { account_set, .. } => account_set.visit_type_ids_mut(f),
+            IdlAccountSetDef::Or(sets) => {
+                for set in sets {
+                    set.visit_type_ids_mut(f);
+                }
+            }
+        }
+    }
+
     pub fn assert_defined(&self) -> Result<&IdlAccountSetId> {
         match self {
             IdlAccountSetDef::Defined(id) => Ok(id),
@@ -108,4 +170,13 @@ impl IdlAccountSetDef {
         self.single()?.address = Some(address);
         Ok(self)
     }
+
+    pub fn with_single_owner(mut self, owner: Pubkey) -> Result<Self> {
+        let single = self.single()?;
+        if let Some(old_owner) = single.owner {
+            eprintln!("Warning: Overwriting owner `{old_owner}` in single account set with owner `{owner}`");
+        }
+        single.owner = Some(owner);
+        Ok(self)
+    }
 }
@@ -66,7 +66,7 @@ impl TryFrom<IdlDefinition> for ProgramNode {
     type Error = crate::Error;
 
     fn try_from(def: IdlDefinition) -> Result<Self> {
-        let ctx = &mut TryToCodamaContext;
+        let ctx = &mut TryToCodamaContext::default();
 
         let (accounts, maybe_pdas): (_, Vec<_>) = def
             .accounts
@@ -119,7 +119,11 @@ impl TryFrom<IdlDefinition> for ProgramNode {
 }
 
 // todo: potentially add some error handling "context" info that gets passed around
-pub struct TryToCodamaContext;
+#[derive(Debug, Clone, Default)]
+pub struct TryToCodamaContext {
+    /// The arguments of the instruction currently being converted, used to resolve [`IdlFindSeed::ArgPath`] seeds
+    instruction_arguments: Vec<StructFieldTypeNode>,
+}
 
 trait TryToCodama<Output> {
     fn try_to_codama(
@@ -153,6 +157,9 @@ impl TryToCodama<InstructionNode> for IdlInstruction {
             ensure_struct_node(idl_type.type_def.try_to_codama(idl_definition, context)?)?;
 
         let (discriminator_field, discriminator_node) = discriminator_info(&self.discriminant);
+        context
+            .instruction_arguments
+            .clone_from(&struct_node.fields);
         let mut arguments = vec![discriminator_field.into()];
         arguments.extend(struct_node.fields.into_iter().map(Into::into));
         let (accounts, remaining_accounts) = self
@@ -195,42 +202,77 @@ impl PathInfo {
     }
 }
 
-fn seeds_to_pda_value_node(seeds: &IdlFindSeeds, paths: &PathInfo) -> PdaValueNode {
+fn seeds_to_pda_value_node(
+    seeds: &IdlFindSeeds,
+    paths: &PathInfo,
+    context: &TryToCodamaContext,
+) -> Result<PdaValueNode> {
     let mut paths = paths.clone();
     let name = paths.name();
     paths.paths.pop();
-    let (pda_node_seeds, lookup_seeds): (_, Vec<_>) = seeds
+    let seed_nodes: Vec<(PdaSeedNode, Option<PdaSeedValueNode>)> = seeds
         .seeds
         .iter()
         .enumerate()
-        .map(|(index, seed)| match seed {
-            IdlFindSeed::Const(bytes) => {
-                let value: PdaSeedNode = ConstantPdaSeedNode::new(
-                    BytesTypeNode {},
-                    BytesValueNode::base16(hex::encode(bytes)),
-                )
-                .into();
-                (value, None)
-            }
-            IdlFindSeed::AccountPath(account_path) => {
-                let name = format!("{account_path}{index}");
-                let value = VariablePdaSeedNode::new(name.clone(), PublicKeyTypeNode {}).into();
-                // Account paths that start with a colon are interpreted as root paths
-                let path_name = if let Some(stripped) = account_path.strip_prefix(':') {
-                    stripped.into()
-                } else {
-                    paths.create_next(Some(account_path), index).name()
-                };
-                let lookup = PdaSeedValueNode {
-                    name: name.into(),
-                    value: AccountValueNode { name: path_name }.into(),
-                };
-                (value, Some(lookup))
+        .map(|(index, seed)| -> Result<_> {
+            match seed {
+                IdlFindSeed::Const(bytes) => {
+                    let value: PdaSeedNode = ConstantPdaSeedNode::new(
+                        BytesTypeNode {},
+                        BytesValueNode::base16(hex::encode(bytes)),
+                    )
+                    .into();
+                    Ok((value, None))
+                }
+                IdlFindSeed::AccountPath(account_path) => {
+                    // Nested paths can be split with either a `.` or a space
+                    let account_path = account_path.replace('.', " ");
+                    let name = format!("{account_path}{index}");
+                    let value = VariablePdaSeedNode::new(name.clone(), PublicKeyTypeNode {}).into();
+                    // Account paths that start with a colon are interpreted as root paths
+                    let path_name = if let Some(stripped) = account_path.strip_prefix(':') {
+                        stripped.into()
+                    } else {
+                        paths.create_next(Some(&account_path), index).name()
+                    };
+                    let lookup = PdaSeedValueNode {
+                        name: name.into(),
+                        value: AccountValueNode { name: path_name }.into(),
+                    };
+                    Ok((value, Some(lookup)))
+                }
+                IdlFindSeed::ArgPath(arg_path) => {
+                    // Codama argument values can only reference whole instruction arguments
+                    if arg_path.contains('.') {
+                        return Err(crate::Error::CodamaConversion(format!(
+                            "Seed argument `{arg_path}` must be a top-level instruction argument"
+                        )));
+                    }
+                    let arg_name: CamelCaseString = arg_path.as_str().into();
+                    let argument = context
+                        .instruction_arguments
+                        .iter()
+                        .find(|argument| argument.name == arg_name)
+                        .ok_or_else(|| {
+                            crate::Error::CodamaConversion(format!(
+                                "Seed argument `{arg_path}` is not an instruction argument"
+                            ))
+                        })?;
+                    let name = format!("{arg_path}{index}");
+                    let value =
+                        VariablePdaSeedNode::new(name.clone(), argument.r#type.clone()).into();
+                    let lookup = PdaSeedValueNode {
+                        name: name.into(),
+                        value: ArgumentValueNode { name: arg_name }.into(),
+                    };
+                    Ok((value, Some(lookup)))
+                }
             }
         })
-        .collect();
+        .try_collect()?;
+    let (pda_node_seeds, lookup_seeds): (_, Vec<_>) = seed_nodes.into_iter().unzip();
 
-    PdaValueNode {
+    Ok(PdaValueNode {
         pda: PdaNode {
             name,
             docs: Default::default(),
@@ -239,27 +281,28 @@ fn seeds_to_pda_value_node(seeds: &IdlFindSeeds, paths: &PathInfo) -> PdaValueNo
         }
         .into(),
         seeds: lookup_seeds.into_iter().flatten().collect(),
-    }
+    })
 }
 
 fn single_set_to_account_node(
     single_set: &IdlSingleAccountSet,
     paths: &PathInfo,
     description: &ItemDescription,
-) -> InstructionAccountNode {
+    context: &TryToCodamaContext,
+) -> Result<InstructionAccountNode> {
     let default_value = match (single_set.address, &single_set.seeds) {
         (Some(address), _) => Some(PublicKeyValueNode::new(address.to_string()).into()),
-        (None, Some(seeds)) => Some(seeds_to_pda_value_node(seeds, paths).into()),
+        (None, Some(seeds)) => Some(seeds_to_pda_value_node(seeds, paths, context)?.into()),
         _ => None,
     };
-    InstructionAccountNode {
+    Ok(InstructionAccountNode {
         name: paths.name(),
         is_writable: single_set.writable,
         is_signer: single_set.signer.into(),
         is_optional: single_set.optional,
         docs: (*description).clone().into(),
         default_value,
-    }
+    })
 }
 
 fn instruction_account_to_remaining(
@@ -296,18 +339,60 @@ impl TryToCodama<CodamaInstructionInfo> for (&IdlAccountSetStructField, &PathInf
                 (&set.account_set_def, *paths).try_to_codama(idl_definition, context)?
             }
             IdlAccountSetDef::Single(single_set) => {
-                let single = single_set_to_account_node(single_set, paths, &field.description);
+                let single =
+                    single_set_to_account_node(single_set, paths, &field.description, context)?;
                 (vec![single], vec![])
             }
+            // Fixed-size sets are emitted as individual accounts with an index suffix
+            IdlAccountSetDef::Many {
+                account_set,
+                min,
+                max: Some(max),
+            } if min == max => {
+                let mut set: IdlAccountSetDef = account_set.as_ref().clone();
+                let single = set
+                    .single()
+                    .map_err(|_| crate::Error::ManySetsMustBeSingle)?;
+                let accounts = (0..*max)
+                    .map(|index| {
+                        single_set_to_account_node(
+                            single,
+                            &paths.create_next(None, index),
+                            &field.description,
+                            context,
+                        )
+                    })
+                    .try_collect()?;
+                (accounts, vec![])
+            }
             IdlAccountSetDef::Many { account_set, .. } => {
                 let mut set: IdlAccountSetDef = account_set.as_ref().clone();
                 let single = set
                     .single()
                     .map_err(|_| crate::Error::ManySetsMustBeSingle)?;
-                let single = single_set_to_account_node(single, paths, &field.description);
+                let single =
+                    single_set_to_account_node(single, paths, &field.description, context)?;
                 let remaining = instruction_account_to_remaining(single)?;
                 (vec![], vec![remaining])
             }
+            // `Option` of a multi-account set is an `Or` with an empty struct, so each of its accounts is optional
+            IdlAccountSetDef::Or(sets)
+                if sets.len() == 2 && sets.contains(&IdlAccountSetDef::empty_struct()) =>
+            {
+                let set = sets
+                    .iter()
+                    .find(|set| **set != IdlAccountSetDef::empty_struct())
+                    .unwrap_or(&sets[0]);
+                let (mut accounts, mut remaining) =
+                    (set, *paths).try_to_codama(idl_definition, context)?;
+                accounts
+                    .iter_mut()
+                    .for_each(|account| account.is_optional = true);
+                remaining
+                    .iter_mut()
+                    .for_each(|remaining| remaining.is_optional = true);
+                (accounts, remaining)
+            }
             IdlAccountSetDef::Struct(_) | IdlAccountSetDef::Or(_) => {
                 return (account_set_def, *paths).try_to_codama(idl_definition, context)
             }
@@ -386,6 +471,19 @@ impl TryToCodama<PdaSeedNode> for IdlSeed {
                 r#type: ty.try_to_codama(idl_definition, context)?,
             }
             .into(),
+            IdlSeed::VariableString {
+                name,
+                description,
+                fixed_len,
+            } => VariablePdaSeedNode {
+                name: name.as_str().into(),
+                docs: description.clone().into(),
+                r#type: match fixed_len {
+                    Some(len) => FixedSizeTypeNode::new(StringTypeNode::utf8(), *len).into(),
+                    None => StringTypeNode::utf8().into(),
+                },
+            }
+            .into(),
         };
         Ok(res)
     }
@@ -435,7 +533,7 @@ impl TryToCodama<(AccountNode, Option<PdaNode>)> for IdlAccount {
         Ok((
             AccountNode {
                 name: info.codama_name(),
-                size: None,
+                size: self.space,
                 docs: info.codama_docs(),
                 data: struct_node.into(),
                 pda: pda_node.is_some().then(|| PdaLinkNode {
@@ -490,8 +588,12 @@ impl TryToCodama<TypeNode> for IdlTypeDef {
                 StringTypeNode::utf8(),
                 NumberTypeNode::le(Num::U32),
             ).into_type_node(),
+            IdlTypeDef::FixedString(len) => FixedSizeTypeNode::<TypeNode>::new(
+                StringTypeNode::utf8(),
+                *len,
+            ).into_type_node(),
             IdlTypeDef::Pubkey => PublicKeyTypeNode {}.into_type_node(),
-            IdlTypeDef::FixedPoint { ty, .. } => ty.try_to_codama(idl_def, _context)?,
+            IdlTypeDef::FixedPoint { ty, .. } | IdlTypeDef::Newtype { inner: ty, .. } => ty.try_to_codama(idl_def, _context)?,
             IdlTypeDef::Option { ty, fixed } =>
                 OptionTypeNode {
                     fixed: *fixed,
@@ -652,3 +754,310 @@ impl NodeToJson for ProgramNode {
         serde_json::to_string_pretty(self).map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instruction::IdlInstructionDef,
+        ty::{IdlStructField, IdlType, IdlTypeId},
+    };
+
+    fn field(path: &str, type_def: IdlTypeDef) -> IdlStructField {
+        IdlStructField {
+            path: Some(path.to_string()),
+            description: vec![],
+            type_def,
+        }
+    }
+
+    fn account(path: &str, account_set_def: IdlAccountSetDef) -> IdlAccountSetStructField {
+        IdlAccountSetStructField {
+            path: Some(path.to_string()),
+            description: vec![],
+            account_set_def,
+        }
+    }
+
+    fn optional(ty: IdlTypeDef) -> IdlTypeDef {
+        IdlTypeDef::Option {
+            ty: Box::new(ty),
+            fixed: false,
+        }
+    }
+
+    #[test]
+    fn test_optional_types_and_accounts() -> Result<()> {
+        let mut definition = IdlDefinition::default();
+        definition.instructions.insert(
+            "Start".to_string(),
+            IdlInstruction {
+                discriminant: vec![0],
+                discriminant_namespace: None,
+                definition: IdlInstructionDef {
+                    return_type: None,
+                    account_set: IdlAccountSetDef::Struct(vec![
+                        account(
+                            "authority",
+                            IdlAccountSetDef::Single(IdlSingleAccountSet {
+                                signer: true,
+                                ..Default::default()
+                            }),
+                        ),
+                        // `Option<Account<T>>`
+                        account(
+                            "config",
+                            IdlAccountSetDef::Single(IdlSingleAccountSet {
+                                optional: true,
+                                ..Default::default()
+                            }),
+                        ),
+                        // `Option` of a multi-account set
+                        account(
+                            "extra",
+                            IdlAccountSetDef::Or(vec![
+                                IdlAccountSetDef::Struct(vec![account(
+                                    "vault",
+                                    IdlAccountSetDef::Single(IdlSingleAccountSet {
+                                        writable: true,
+                                        ..Default::default()
+                                    }),
+                                )]),
+                                IdlAccountSetDef::empty_struct(),
+                            ]),
+                        ),
+                    ]),
+                    type_id: IdlTypeId {
+                        source: "Start".to_string(),
+                        namespace: None,
+                        provided_generics: vec![],
+                    },
+                },
+            },
+        );
+        definition.types.insert(
+            "Start".to_string(),
+            IdlType {
+                info: ItemInfo {
+                    name: "Start".to_string(),
+                    source: "Start".to_string(),
+                    description: vec![],
+                },
+                generics: vec![],
+                type_def: IdlTypeDef::Struct(vec![
+                    field("start_at", optional(IdlTypeDef::U64)),
+                    field("delegate", optional(IdlTypeDef::Pubkey)),
+                ]),
+            },
+        );
+
+        let program: ProgramNode = definition.try_into()?;
+        let instruction = &program.instructions[0];
+        let optional_accounts = instruction
+            .accounts
+            .iter()
+            .map(|account| (account.name.to_string(), account.is_optional))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            optional_accounts,
+            [
+                ("authority".to_string(), false),
+                ("config".to_string(), true),
+                ("extraVault".to_string(), true),
+            ]
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&program.to_json()?)?;
+        let arguments = json["instructions"][0]["arguments"]
+            .as_array()
+            .expect("arguments should be an array");
+        let start_at = &arguments[1]["type"];
+        assert_eq!(start_at["kind"], "optionTypeNode");
+        assert_eq!(start_at["item"]["kind"], "numberTypeNode");
+        assert_eq!(start_at["item"]["format"], "u64");
+        let delegate = &arguments[2]["type"];
+        assert_eq!(delegate["kind"], "optionTypeNode");
+        assert_eq!(delegate["item"]["kind"], "publicKeyTypeNode");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_string_is_fixed_size() -> Result<()> {
+        let node = IdlTypeDef::FixedString(8).try_to_codama(
+            &IdlDefinition::default(),
+            &mut TryToCodamaContext::default(),
+        )?;
+        let json = serde_json::to_value(&node)?;
+        assert_eq!(json["kind"], "fixedSizeTypeNode");
+        assert_eq!(json["size"], 8);
+        assert_eq!(json["type"]["kind"], "stringTypeNode");
+        Ok(())
+    }
+
+    #[test]
+    fn test_instruction_docs() -> Result<()> {
+        let mut definition = IdlDefinition::default();
+        definition.instructions.insert(
+            "Enlist".to_string(),
+            IdlInstruction {
+                discriminant: vec![0],
+                discriminant_namespace: None,
+                definition: IdlInstructionDef {
+                    return_type: None,
+                    account_set: IdlAccountSetDef::Struct(vec![IdlAccountSetStructField {
+                        description: vec!["The player enlisting".to_string()],
+                        ..account(
+                            "player",
+                            IdlAccountSetDef::Single(IdlSingleAccountSet {
+                                signer: true,
+                                ..Default::default()
+                            }),
+                        )
+                    }]),
+                    type_id: IdlTypeId {
+                        source: "Enlist".to_string(),
+                        namespace: None,
+                        provided_generics: vec![],
+                    },
+                },
+            },
+        );
+        definition.types.insert(
+            "Enlist".to_string(),
+            IdlType {
+                info: ItemInfo {
+                    name: "Enlist".to_string(),
+                    source: "Enlist".to_string(),
+                    description: vec!["Enlists a player".to_string(), "in a faction".to_string()],
+                },
+                generics: vec![],
+                type_def: IdlTypeDef::Struct(vec![IdlStructField {
+                    description: vec!["New faction id for the player".to_string()],
+                    ..field("faction_id", IdlTypeDef::U8)
+                }]),
+            },
+        );
+
+        let program: ProgramNode = definition.try_into()?;
+        let json: serde_json::Value = serde_json::from_str(&program.to_json()?)?;
+        let instruction = &json["instructions"][0];
+        assert_eq!(
+            instruction["docs"],
+            serde_json::json!(["Enlists a player", "in a faction"])
+        );
+        assert_eq!(
+            instruction["accounts"][0]["docs"],
+            serde_json::json!(["The player enlisting"])
+        );
+        // The first argument is the discriminant
+        assert_eq!(
+            instruction["arguments"][1]["docs"],
+            serde_json::json!(["New faction id for the player"])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_seed_paths() -> Result<()> {
+        let seeded = |seeds: Vec<IdlFindSeed>| {
+            IdlAccountSetDef::Single(IdlSingleAccountSet {
+                seeds: Some(IdlFindSeeds {
+                    seeds,
+                    program: None,
+                }),
+                ..Default::default()
+            })
+        };
+        let mut definition = IdlDefinition::default();
+        definition.instructions.insert(
+            "Deposit".to_string(),
+            IdlInstruction {
+                discriminant: vec![0],
+                discriminant_namespace: None,
+                definition: IdlInstructionDef {
+                    return_type: None,
+                    account_set: IdlAccountSetDef::Struct(vec![
+                        account("owner", IdlAccountSetDef::Single(Default::default())),
+                        account(
+                            "inner",
+                            IdlAccountSetDef::Struct(vec![account(
+                                "owner_account",
+                                IdlAccountSetDef::Single(Default::default()),
+                            )]),
+                        ),
+                        account(
+                            "vault",
+                            seeded(vec![
+                                IdlFindSeed::Const(b"vault".to_vec()),
+                                IdlFindSeed::AccountPath("owner".to_string()),
+                            ]),
+                        ),
+                        account(
+                            "nested_vault",
+                            seeded(vec![
+                                IdlFindSeed::AccountPath("inner.owner_account".to_string()),
+                                IdlFindSeed::ArgPath("amount".to_string()),
+                            ]),
+                        ),
+                    ]),
+                    type_id: IdlTypeId {
+                        source: "Deposit".to_string(),
+                        namespace: None,
+                        provided_generics: vec![],
+                    },
+                },
+            },
+        );
+        definition.types.insert(
+            "Deposit".to_string(),
+            IdlType {
+                info: ItemInfo {
+                    name: "Deposit".to_string(),
+                    source: "Deposit".to_string(),
+                    description: vec![],
+                },
+                generics: vec![],
+                type_def: IdlTypeDef::Struct(vec![field("amount", IdlTypeDef::U64)]),
+            },
+        );
+
+        let program: ProgramNode = definition.clone().try_into()?;
+        let json: serde_json::Value = serde_json::from_str(&program.to_json()?)?;
+        let accounts = &json["instructions"][0]["accounts"];
+        let vault_seeds = &accounts[2]["defaultValue"]["seeds"];
+        assert_eq!(vault_seeds[0]["value"]["kind"], "accountValueNode");
+        assert_eq!(vault_seeds[0]["value"]["name"], "owner");
+
+        let nested_seeds = &accounts[3]["defaultValue"]["seeds"];
+        assert_eq!(nested_seeds[0]["value"]["kind"], "accountValueNode");
+        assert_eq!(nested_seeds[0]["value"]["name"], "innerOwnerAccount");
+        assert_eq!(nested_seeds[1]["value"]["kind"], "argumentValueNode");
+        assert_eq!(nested_seeds[1]["value"]["name"], "amount");
+        let pda_seeds = &accounts[3]["defaultValue"]["pda"]["seeds"];
+        assert_eq!(pda_seeds[1]["type"]["kind"], "numberTypeNode");
+        assert_eq!(pda_seeds[1]["type"]["format"], "u64");
+
+        // Argument seeds must reference a top-level instruction argument
+        let mut nested_arg = definition.clone();
+        let IdlAccountSetDef::Struct(accounts) = &mut nested_arg
+            .instructions
+            .get_mut("Deposit")
+            .unwrap()
+            .definition
+            .account_set
+        else {
+            unreachable!()
+        };
+        accounts[3].account_set_def =
+            seeded(vec![IdlFindSeed::ArgPath("amount.inner".to_string())]);
+        assert!(matches!(
+            ProgramNode::try_from(nested_arg),
+            Err(crate::Error::CodamaConversion(message)) if message.contains("top-level")
+        ));
+
+        let mut missing_arg = definition;
+        missing_arg.types.get_mut("Deposit").unwrap().type_def = IdlTypeDef::Struct(vec![]);
+        assert!(ProgramNode::try_from(missing_arg).is_err());
+        Ok(())
+    }
+}
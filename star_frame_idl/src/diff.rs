@@ -0,0 +1,701 @@
+//! Compares two versions of an [`IdlDefinition`] and reports what changed between them.
+//!
+//! Each [`IdlChange`] is classified as [`Compatibility::Breaking`] when clients built against the
+//! old definition could stop working against the new one (removed items, changed discriminants,
+//! new required accounts or signers, changed type layouts), and [`Compatibility::NonBreaking`]
+//! otherwise (new items, renames, description changes, appended enum variants).
+//!
+//! This is intended for CI: keep a serialized copy of the last released [`IdlDefinition`] in the
+//! repository and assert [`is_backwards_compatible`] against a freshly generated one.
+
+use std::{collections::BTreeMap, fmt};
+
+use solana_pubkey::Pubkey;
+
+use crate::{
+    account::IdlAccount,
+    account_set::{IdlAccountSetDef, IdlSingleAccountSet},
+    instruction::IdlInstruction,
+    ty::{IdlEnumVariant, IdlStructField, IdlType, IdlTypeDef},
+    IdlDefinition, ItemSource,
+};
+
+/// Whether a change can break existing clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Compatibility {
+    NonBreaking,
+    Breaking,
+}
+
+/// The kind of item an [`IdlChange`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IdlItemKind {
+    Instruction,
+    Account,
+    Type,
+}
+
+impl fmt::Display for IdlItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdlItemKind::Instruction => write!(f, "instruction"),
+            IdlItemKind::Account => write!(f, "account"),
+            IdlItemKind::Type => write!(f, "type"),
+        }
+    }
+}
+
+/// A single difference between two [`IdlDefinition`]s, as returned by [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdlChange {
+    /// The program address changed.
+    AddressChanged { old: Pubkey, new: Pubkey },
+    /// An item only exists in the new definition.
+    Added {
+        kind: IdlItemKind,
+        source: ItemSource,
+    },
+    /// An item only exists in the old definition.
+    Removed {
+        kind: IdlItemKind,
+        source: ItemSource,
+    },
+    /// An item exists in both definitions but differs.
+    Modified {
+        kind: IdlItemKind,
+        source: ItemSource,
+        compatibility: Compatibility,
+        description: String,
+    },
+}
+
+impl IdlChange {
+    #[must_use]
+    pub fn compatibility(&self) -> Compatibility {
+        match self {
+            IdlChange::AddressChanged { .. } | IdlChange::Removed { .. } => Compatibility::Breaking,
+            IdlChange::Added { .. } => Compatibility::NonBreaking,
+            IdlChange::Modified { compatibility, .. } => *compatibility,
+        }
+    }
+
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility() == Compatibility::Breaking
+    }
+}
+
+impl fmt::Display for IdlChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.compatibility() {
+            Compatibility::Breaking => "breaking",
+            Compatibility::NonBreaking => "non-breaking",
+        };
+        match self {
+            IdlChange::AddressChanged { old, new } => {
+                write!(
+                    f,
+                    "[{label}] program address changed from `{old}` to `{new}`"
+                )
+            }
+            IdlChange::Added { kind, source } => write!(f, "[{label}] added {kind} `{source}`"),
+            IdlChange::Removed { kind, source } => {
+                write!(f, "[{label}] removed {kind} `{source}`")
+            }
+            IdlChange::Modified {
+                kind,
+                source,
+                description,
+                ..
+            } => write!(f, "[{label}] {kind} `{source}`: {description}"),
+        }
+    }
+}
+
+/// Returns every change between `old` and `new`.
+///
+/// Instructions, accounts, and types are matched up by their [`ItemSource`]. Instruction account
+/// sets are compared account by account in order, resolving defined account sets through each
+/// definition's `account_sets`.
+#[must_use]
+pub fn diff(old: &IdlDefinition, new: &IdlDefinition) -> Vec<IdlChange> {
+    let mut changes = vec![];
+    if old.address != new.address {
+        changes.push(IdlChange::AddressChanged {
+            old: old.address,
+            new: new.address,
+        });
+    }
+    diff_items(
+        IdlItemKind::Instruction,
+        &old.instructions,
+        &new.instructions,
+        &mut changes,
+        |item, old_ix, new_ix| diff_instruction(item, (old, old_ix), (new, new_ix)),
+    );
+    diff_items(
+        IdlItemKind::Account,
+        &old.accounts,
+        &new.accounts,
+        &mut changes,
+        diff_account,
+    );
+    diff_items(
+        IdlItemKind::Type,
+        &old.types,
+        &new.types,
+        &mut changes,
+        diff_type,
+    );
+    changes
+}
+
+/// Returns `true` if none of the changes between `old` and `new` are breaking.
+#[must_use]
+pub fn is_backwards_compatible(old: &IdlDefinition, new: &IdlDefinition) -> bool {
+    diff(old, new).iter().all(|change| !change.is_breaking())
+}
+
+struct ItemDiff<'a> {
+    kind: IdlItemKind,
+    source: &'a ItemSource,
+    changes: &'a mut Vec<IdlChange>,
+}
+
+impl ItemDiff<'_> {
+    fn push(&mut self, compatibility: Compatibility, description: impl Into<String>) {
+        self.changes.push(IdlChange::Modified {
+            kind: self.kind,
+            source: self.source.clone(),
+            compatibility,
+            description: description.into(),
+        });
+    }
+
+    fn breaking(&mut self, description: impl Into<String>) {
+        self.push(Compatibility::Breaking, description);
+    }
+
+    fn non_breaking(&mut self, description: impl Into<String>) {
+        self.push(Compatibility::NonBreaking, description);
+    }
+}
+
+fn diff_items<T>(
+    kind: IdlItemKind,
+    old: &BTreeMap<ItemSource, T>,
+    new: &BTreeMap<ItemSource, T>,
+    changes: &mut Vec<IdlChange>,
+    mut diff_item: impl FnMut(&mut ItemDiff, &T, &T),
+) {
+    for (source, old_item) in old {
+        match new.get(source) {
+            Some(new_item) => {
+                let mut item = ItemDiff {
+                    kind,
+                    source,
+                    changes: &mut *changes,
+                };
+                diff_item(&mut item, old_item, new_item);
+            }
+            None => changes.push(IdlChange::Removed {
+                kind,
+                source: source.clone(),
+            }),
+        }
+    }
+    for source in new.keys().filter(|source| !old.contains_key(*source)) {
+        changes.push(IdlChange::Added {
+            kind,
+            source: source.clone(),
+        });
+    }
+}
+
+fn diff_instruction(
+    item: &mut ItemDiff,
+    (old_idl, old): (&IdlDefinition, &IdlInstruction),
+    (new_idl, new): (&IdlDefinition, &IdlInstruction),
+) {
+    if old.discriminant != new.discriminant {
+        item.breaking(format!(
+            "discriminant changed from {:?} to {:?}",
+            old.discriminant, new.discriminant
+        ));
+    }
+    if old.definition.type_id != new.definition.type_id {
+        item.breaking("instruction data type changed");
+    }
+
+    let mut old_accounts = vec![];
+    flatten_accounts(
+        &old.definition.account_set,
+        old_idl,
+        String::new(),
+        &mut old_accounts,
+    );
+    let mut new_accounts = vec![];
+    flatten_accounts(
+        &new.definition.account_set,
+        new_idl,
+        String::new(),
+        &mut new_accounts,
+    );
+
+    for (index, (old_path, old_account)) in old_accounts.iter().enumerate() {
+        let Some((new_path, new_account)) = new_accounts.get(index) else {
+            item.breaking(format!("account `{old_path}` was removed"));
+            continue;
+        };
+        if old_path != new_path {
+            item.non_breaking(format!("account `{old_path}` was renamed to `{new_path}`"));
+        }
+        match (old_account, new_account) {
+            (FlatAccount::Single(old), FlatAccount::Single(new)) => {
+                diff_single_account(item, new_path, old, new);
+            }
+            (old, new) if old == new => {}
+            _ => item.breaking(format!("account `{new_path}` changed shape")),
+        }
+    }
+    for (path, account) in new_accounts.iter().skip(old_accounts.len()) {
+        match account {
+            FlatAccount::Single(single) if single.optional => {
+                item.non_breaking(format!("optional account `{path}` was added"));
+            }
+            _ => item.breaking(format!("required account `{path}` was added")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FlatAccount<'a> {
+    Single(&'a IdlSingleAccountSet),
+    /// `Many` and `Or` sets, which are only compared by equality.
+    Other(&'a IdlAccountSetDef),
+}
+
+fn flatten_accounts<'a>(
+    set: &'a IdlAccountSetDef,
+    idl: &'a IdlDefinition,
+    path: String,
+    accounts: &mut Vec<(String, FlatAccount<'a>)>,
+) {
+    match set {
+        IdlAccountSetDef::Single(single) => accounts.push((path, FlatAccount::Single(single))),
+        IdlAccountSetDef::Struct(fields) => {
+            for (index, field) in fields.iter().enumerate() {
+                let name = field.path.clone().unwrap_or_else(|| index.to_string());
+                let field_path = if path.is_empty() {
+                    name
+                } else {
+                    format!("{path}.{name}")
+                };
+                flatten_accounts(&field.account_set_def, idl, field_path, accounts);
+            }
+        }
+        IdlAccountSetDef::Defined(_) => match set.get_defined(idl) {
+            Ok(defined) => flatten_accounts(&defined.account_set_def, idl, path, accounts),
+            Err(_) => accounts.push((path, FlatAccount::Other(set))),
+        },
+        IdlAccountSetDef::Many { .. } | IdlAccountSetDef::Or(_) => {
+            accounts.push((path, FlatAccount::Other(set)));
+        }
+    }
+}
+
+fn diff_single_account(
+    item: &mut ItemDiff,
+    path: &str,
+    old: &IdlSingleAccountSet,
+    new: &IdlSingleAccountSet,
+) {
+    match (old.writable, new.writable) {
+        (false, true) => item.breaking(format!("account `{path}` is now writable")),
+        (true, false) => item.non_breaking(format!("account `{path}` is no longer writable")),
+        _ => {}
+    }
+    match (old.signer, new.signer) {
+        (false, true) => item.breaking(format!("account `{path}` is now a signer")),
+        (true, false) => item.non_breaking(format!("account `{path}` is no longer a signer")),
+        _ => {}
+    }
+    match (old.optional, new.optional) {
+        (true, false) => item.breaking(format!("account `{path}` is no longer optional")),
+        (false, true) => item.non_breaking(format!("account `{path}` is now optional")),
+        _ => {}
+    }
+    if old.is_init != new.is_init {
+        item.breaking(format!(
+            "account `{path}` changed whether it is initialized"
+        ));
+    }
+    if old.address != new.address {
+        item.breaking(format!("account `{path}` address changed"));
+    }
+    if old.owner != new.owner {
+        item.breaking(format!("account `{path}` owner changed"));
+    }
+    if old.seeds != new.seeds {
+        item.breaking(format!("account `{path}` seeds changed"));
+    }
+    if old.program_accounts != new.program_accounts {
+        item.breaking(format!("account `{path}` program accounts changed"));
+    }
+}
+
+fn diff_account(item: &mut ItemDiff, old: &IdlAccount, new: &IdlAccount) {
+    if old.discriminant != new.discriminant {
+        item.breaking(format!(
+            "discriminant changed from {:?} to {:?}",
+            old.discriminant, new.discriminant
+        ));
+    }
+    if old.type_id != new.type_id {
+        item.breaking("account data type changed");
+    }
+    if old.seeds != new.seeds {
+        item.breaking("seeds changed");
+    }
+    if old.space != new.space {
+        item.breaking(format!(
+            "space changed from {:?} to {:?}",
+            old.space, new.space
+        ));
+    }
+    if old.version != new.version {
+        item.non_breaking(format!(
+            "version changed from {:?} to {:?}",
+            old.version, new.version
+        ));
+    }
+}
+
+fn diff_type(item: &mut ItemDiff, old: &IdlType, new: &IdlType) {
+    if old.info.name != new.info.name {
+        item.non_breaking(format!(
+            "renamed from `{}` to `{}`",
+            old.info.name, new.info.name
+        ));
+    }
+    if old.info.description != new.info.description {
+        item.non_breaking("description changed");
+    }
+    if old.generics != new.generics {
+        item.breaking("generics changed");
+    }
+    match (&old.type_def, &new.type_def) {
+        (IdlTypeDef::Struct(old_fields), IdlTypeDef::Struct(new_fields)) => {
+            diff_struct_fields(item, old_fields, new_fields);
+        }
+        (
+            IdlTypeDef::Enum {
+                size: old_size,
+                variants: old_variants,
+            },
+            IdlTypeDef::Enum {
+                size: new_size,
+                variants: new_variants,
+            },
+        ) => {
+            if old_size != new_size {
+                item.breaking("enum discriminant size changed");
+            }
+            diff_enum_variants(item, old_variants, new_variants);
+        }
+        (old, new) if old == new => {}
+        (old, new) if old.same_layout(new) => item.non_breaking("newtype wrappers changed"),
+        _ => item.breaking("type definition changed"),
+    }
+}
+
+fn diff_struct_fields(item: &mut ItemDiff, old: &[IdlStructField], new: &[IdlStructField]) {
+    if old.len() != new.len() {
+        item.breaking(format!(
+            "field count changed from {} to {}",
+            old.len(),
+            new.len()
+        ));
+        return;
+    }
+    for (index, (old, new)) in old.iter().zip(new).enumerate() {
+        let name = new.path.clone().unwrap_or_else(|| index.to_string());
+        if !old.type_def.same_layout(&new.type_def) {
+            item.breaking(format!("field `{name}` changed type"));
+            continue;
+        }
+        if old.type_def != new.type_def {
+            item.non_breaking(format!("field `{name}` changed newtype wrappers"));
+        }
+        if old.path != new.path {
+            item.non_breaking(format!(
+                "field `{}` was renamed to `{name}`",
+                old.path.clone().unwrap_or_else(|| index.to_string())
+            ));
+        }
+    }
+}
+
+fn same_variant_layout(old: &IdlEnumVariant, new: &IdlEnumVariant) -> bool {
+    match (&old.type_def, &new.type_def) {
+        (Some(old), Some(new)) => old.same_layout(new),
+        (old, new) => old == new,
+    }
+}
+
+fn diff_enum_variants(item: &mut ItemDiff, old: &[IdlEnumVariant], new: &[IdlEnumVariant]) {
+    for (index, old_variant) in old.iter().enumerate() {
+        let Some(new_variant) = new.get(index) else {
+            item.breaking(format!("variant `{}` was removed", old_variant.name));
+            continue;
+        };
+        if old_variant.discriminant != new_variant.discriminant
+            || !same_variant_layout(old_variant, new_variant)
+        {
+            item.breaking(format!("variant `{}` changed", old_variant.name));
+        } else if old_variant.name != new_variant.name {
+            item.non_breaking(format!(
+                "variant `{}` was renamed to `{}`",
+                old_variant.name, new_variant.name
+            ));
+        }
+    }
+    for variant in new.iter().skip(old.len()) {
+        item.non_breaking(format!("variant `{}` was added", variant.name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        account_set::IdlAccountSetStructField,
+        instruction::IdlInstructionDef,
+        ty::{IdlType, IdlTypeId},
+        ItemInfo,
+    };
+
+    fn type_id(source: &str) -> IdlTypeId {
+        IdlTypeId {
+            source: source.to_string(),
+            namespace: None,
+            provided_generics: vec![],
+        }
+    }
+
+    fn field(path: &str, type_def: IdlTypeDef) -> IdlStructField {
+        IdlStructField {
+            path: Some(path.to_string()),
+            description: vec![],
+            type_def,
+        }
+    }
+
+    fn account(path: &str, single: IdlSingleAccountSet) -> IdlAccountSetStructField {
+        IdlAccountSetStructField {
+            path: Some(path.to_string()),
+            description: vec![],
+            account_set_def: IdlAccountSetDef::Single(single),
+        }
+    }
+
+    fn definition(
+        accounts: Vec<IdlAccountSetStructField>,
+        fields: Vec<IdlStructField>,
+    ) -> IdlDefinition {
+        let mut definition = IdlDefinition::default();
+        definition.instructions.insert(
+            "DoThing".to_string(),
+            IdlInstruction {
+                discriminant: vec![0],
+                discriminant_namespace: None,
+                definition: IdlInstructionDef {
+                    return_type: None,
+                    account_set: IdlAccountSetDef::Struct(accounts),
+                    type_id: type_id("DoThing"),
+                },
+            },
+        );
+        definition.types.insert(
+            "DoThing".to_string(),
+            IdlType {
+                info: ItemInfo {
+                    name: "DoThing".to_string(),
+                    source: "DoThing".to_string(),
+                    description: vec![],
+                },
+                generics: vec![],
+                type_def: IdlTypeDef::Struct(fields),
+            },
+        );
+        definition
+    }
+
+    fn authority() -> IdlSingleAccountSet {
+        IdlSingleAccountSet {
+            signer: true,
+            ..Default::default()
+        }
+    }
+
+    fn base() -> IdlDefinition {
+        definition(
+            vec![account("authority", authority())],
+            vec![field("amount", IdlTypeDef::U64)],
+        )
+    }
+
+    #[test]
+    fn identical_definitions_have_no_changes() {
+        assert_eq!(diff(&base(), &base()), vec![]);
+    }
+
+    #[test]
+    fn added_and_removed_items() {
+        let old = base();
+        let mut new = base();
+        let instruction = new.instructions.remove("DoThing").unwrap();
+        new.instructions
+            .insert("DoOtherThing".to_string(), instruction);
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                IdlChange::Removed {
+                    kind: IdlItemKind::Instruction,
+                    source: "DoThing".to_string(),
+                },
+                IdlChange::Added {
+                    kind: IdlItemKind::Instruction,
+                    source: "DoOtherThing".to_string(),
+                },
+            ]
+        );
+        assert!(!is_backwards_compatible(&old, &new));
+        assert!(is_backwards_compatible(&new, &{
+            let mut newer = new.clone();
+            newer
+                .types
+                .insert("Extra".to_string(), old.types["DoThing"].clone());
+            newer
+        }));
+    }
+
+    #[test]
+    fn changed_discriminant_is_breaking() {
+        let mut new = base();
+        new.instructions.get_mut("DoThing").unwrap().discriminant = vec![1];
+        let changes = diff(&base(), &new);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is_breaking());
+    }
+
+    #[test]
+    fn account_changes() {
+        let old = base();
+
+        let new = definition(
+            vec![account(
+                "authority",
+                IdlSingleAccountSet {
+                    writable: true,
+                    ..authority()
+                },
+            )],
+            vec![field("amount", IdlTypeDef::U64)],
+        );
+        assert!(!is_backwards_compatible(&old, &new));
+
+        let new = definition(
+            vec![account("owner", IdlSingleAccountSet::default())],
+            vec![field("amount", IdlTypeDef::U64)],
+        );
+        let changes = diff(&old, &new);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|change| !change.is_breaking()));
+
+        let optional = IdlSingleAccountSet {
+            optional: true,
+            ..Default::default()
+        };
+        let new = definition(
+            vec![
+                account("authority", authority()),
+                account("extra", optional),
+            ],
+            vec![field("amount", IdlTypeDef::U64)],
+        );
+        assert!(is_backwards_compatible(&old, &new));
+
+        let new = definition(
+            vec![
+                account("authority", authority()),
+                account("extra", IdlSingleAccountSet::default()),
+            ],
+            vec![field("amount", IdlTypeDef::U64)],
+        );
+        assert!(!is_backwards_compatible(&old, &new));
+        assert!(!is_backwards_compatible(&new, &old));
+    }
+
+    #[test]
+    fn type_changes() {
+        let old = base();
+
+        let renamed = definition(
+            vec![account("authority", authority())],
+            vec![field("count", IdlTypeDef::U64)],
+        );
+        assert!(is_backwards_compatible(&old, &renamed));
+
+        let retyped = definition(
+            vec![account("authority", authority())],
+            vec![field("amount", IdlTypeDef::U32)],
+        );
+        assert!(!is_backwards_compatible(&old, &retyped));
+
+        let added_field = definition(
+            vec![account("authority", authority())],
+            vec![
+                field("amount", IdlTypeDef::U64),
+                field("memo", IdlTypeDef::String),
+            ],
+        );
+        assert!(!is_backwards_compatible(&old, &added_field));
+
+        let wrapped = definition(
+            vec![account("authority", authority())],
+            vec![field(
+                "amount",
+                IdlTypeDef::Newtype {
+                    name: "PackedValue".to_string(),
+                    inner: Box::new(IdlTypeDef::U64),
+                },
+            )],
+        );
+        let changes = diff(&old, &wrapped);
+        assert_eq!(changes.len(), 1);
+        assert!(!changes[0].is_breaking());
+    }
+
+    #[test]
+    fn appending_enum_variants_is_non_breaking() {
+        let variant = |name: &str, discriminant: u8| IdlEnumVariant {
+            name: name.to_string(),
+            discriminant: vec![discriminant],
+            ..Default::default()
+        };
+        let enum_def = |variants| IdlTypeDef::Enum {
+            size: Box::new(IdlTypeDef::U8),
+            variants,
+        };
+        let mut old = definition(vec![], vec![]);
+        old.types.get_mut("DoThing").unwrap().type_def = enum_def(vec![variant("Open", 0)]);
+        let mut new = old.clone();
+        new.types.get_mut("DoThing").unwrap().type_def =
+            enum_def(vec![variant("Open", 0), variant("Closed", 1)]);
+        assert!(is_backwards_compatible(&old, &new));
+        assert!(!is_backwards_compatible(&new, &old));
+    }
+}
@@ -1,15 +1,26 @@
-use crate::{account_set::IdlAccountSetDef, ty::IdlTypeId, IdlDiscriminant};
+use crate::{
+    account_set::IdlAccountSetDef,
+    ty::{IdlTypeDef, IdlTypeId},
+    IdlDiscriminant,
+};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct IdlInstruction {
     pub discriminant: IdlDiscriminant,
+    /// The namespace the discriminant was hashed with (e.g., `global` for Anchor-compatible sighashes), or `None`
+    /// for integer discriminants.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminant_namespace: Option<String>,
     #[serde(flatten)]
     pub definition: IdlInstructionDef,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct IdlInstructionDef {
     pub account_set: IdlAccountSetDef,
     pub type_id: IdlTypeId,
+    /// The type of the instruction's return data, if it returns anything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<IdlTypeDef>,
 }
@@ -6,7 +6,9 @@ mod codama;
 pub use codama::*;
 pub mod account;
 pub mod account_set;
+pub mod diff;
 pub mod instruction;
+pub mod resolver;
 pub mod seeds;
 pub mod serde_impls;
 pub mod ty;
@@ -23,8 +25,11 @@ use instruction::IdlInstruction;
 pub use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use solana_pubkey::Pubkey;
-use std::{any::type_name, collections::BTreeMap};
-use ty::IdlType;
+use std::{
+    any::type_name,
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+};
+use ty::{IdlType, IdlTypeId};
 
 pub fn idl_spec_version() -> Version {
     Version::parse(env!("CARGO_PKG_VERSION"))
@@ -40,7 +45,7 @@ pub type ItemSource = String;
 pub type ItemDescription = Vec<String>;
 pub type IdlNamespace = String;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct ItemInfo {
     pub name: String,
     #[serde(skip)]
@@ -64,9 +69,9 @@ pub struct IdlMetadata {
     pub idl_spec: Version,
     #[serde(flatten)]
     pub crate_metadata: CrateMetadata,
+    /// The IDLs of other programs that `external_types` come from, by namespace.
     #[serde(skip_serializing_if = "crate::is_default", default)]
-    // todo: figure out required_idl_definitions
-    pub required_idl_definitions: BTreeMap<String, IdlDefinitionReference>,
+    pub required_idl_definitions: BTreeMap<IdlNamespace, IdlDefinitionReference>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -121,6 +126,10 @@ pub struct IdlDefinition {
     pub types: BTreeMap<ItemSource, IdlType>,
     pub external_types: BTreeMap<ItemSource, IdlType>,
     pub errors: Vec<ErrorNode>,
+    /// Overrides the program ID recorded in [`IdlMetadata::required_idl_definitions`] for external types, by the
+    /// type's source. Only used while generating the IDL.
+    #[serde(skip)]
+    pub external_program_ids: BTreeMap<ItemSource, Pubkey>,
 }
 
 impl IdlDefinition {
@@ -132,11 +141,13 @@ impl IdlDefinition {
         &mut self,
         definition: IdlInstructionDef,
         discriminant: IdlDiscriminant,
+        discriminant_namespace: Option<String>,
     ) -> Result<()> {
         let source = definition.type_id.source.clone();
         let idl_instruction = IdlInstruction {
             definition,
             discriminant,
+            discriminant_namespace,
         };
         self.instructions.entry(source).or_insert(idl_instruction);
         Ok(())
@@ -172,17 +183,216 @@ impl IdlDefinition {
         }
     }
 
+    /// Records that the item `source` from the program in `namespace` requires that program's IDL, if `namespace`
+    /// isn't this program.
+    ///
+    /// The reference's address is replaced with the ID in [`Self::external_program_ids`] for `source`, if any.
+    pub fn add_required_definition(
+        &mut self,
+        namespace: IdlNamespace,
+        source: &str,
+        mut reference: IdlDefinitionReference,
+    ) {
+        if namespace == self.namespace() {
+            return;
+        }
+        if let Some(address) = self.external_program_ids.get(source) {
+            reference.address = *address;
+        }
+        self.metadata
+            .required_idl_definitions
+            .entry(namespace)
+            .or_insert(reference);
+    }
+
     pub fn get_type(&self, source: &ItemSource) -> Option<&IdlType> {
         self.types
             .get(source)
             .or_else(|| self.external_types.get(source))
     }
+
+    /// Returns the sources of every type referenced by this IDL that is in neither [`Self::types`] nor
+    /// [`Self::external_types`], usually from a manual `TypeToIdl` implementation that doesn't add its type.
+    #[must_use]
+    pub fn missing_types(&self) -> Vec<ItemSource> {
+        let mut missing = BTreeSet::new();
+        let mut check = |type_id: &IdlTypeId| {
+            if self.get_type(&type_id.source).is_none() {
+                missing.insert(type_id.source.clone());
+            }
+        };
+        for ty in self.types.values().chain(self.external_types.values()) {
+            ty.type_def.visit_type_ids(&mut check);
+        }
+        for account in self.accounts.values() {
+            account.type_id.visit_type_ids(&mut check);
+        }
+        for set in self.account_sets.values() {
+            set.account_set_def.visit_type_ids(&mut check);
+        }
+        for instruction in self.instructions.values() {
+            let definition = &instruction.definition;
+            definition.type_id.visit_type_ids(&mut check);
+            if let Some(return_type) = &definition.return_type {
+                return_type.visit_type_ids(&mut check);
+            }
+            definition.account_set.visit_type_ids(&mut check);
+        }
+        missing.into_iter().collect()
+    }
+
+    /// Returns [`Error::TypeNotFound`] listing every type in [`Self::missing_types`], if any are missing.
+    pub fn check_missing_types(&self) -> Result<()> {
+        let missing = self.missing_types();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::TypeNotFound(missing.join(", ")))
+        }
+    }
+
+    /// Calls `f` with every [`IdlTypeId`] referenced by this IDL, including account seeds and instruction return
+    /// types.
+    pub fn visit_type_ids_mut(&mut self, f: &mut impl FnMut(&mut IdlTypeId)) {
+        for ty in self
+            .types
+            .values_mut()
+            .chain(self.external_types.values_mut())
+        {
+            ty.type_def.visit_type_ids_mut(f);
+        }
+        for account in self.accounts.values_mut() {
+            account.type_id.visit_type_ids_mut(f);
+            for seed in account.seeds.iter_mut().flat_map(|seeds| seeds.iter_mut()) {
+                if let seeds::IdlSeed::Variable { ty, .. } = seed {
+                    ty.visit_type_ids_mut(f);
+                }
+            }
+        }
+        for set in self.account_sets.values_mut() {
+            set.account_set_def.visit_type_ids_mut(f);
+        }
+        for instruction in self.instructions.values_mut() {
+            let definition = &mut instruction.definition;
+            definition.type_id.visit_type_ids_mut(f);
+            if let Some(return_type) = &mut definition.return_type {
+                return_type.visit_type_ids_mut(f);
+            }
+            definition.account_set.visit_type_ids_mut(f);
+        }
+    }
+}
+
+/// Combines `other` into `base`, so one IDL can describe programs or libraries spread across multiple crates.
+///
+/// Instructions, account sets, accounts, types, external types, errors, and required IDL definitions from both IDLs
+/// are combined. Items with the same [`ItemSource`] (or errors with the same code) in both IDLs are deduplicated if
+/// they are identical, and return [`MergeError::ConflictingDefinition`] otherwise. The address and metadata of `base`
+/// are kept.
+pub fn merge(
+    mut base: IdlDefinition,
+    other: IdlDefinition,
+) -> std::result::Result<IdlDefinition, MergeError> {
+    merge_items("instruction", &mut base.instructions, other.instructions)?;
+    merge_items("account set", &mut base.account_sets, other.account_sets)?;
+    merge_items("account", &mut base.accounts, other.accounts)?;
+    merge_items("type", &mut base.types, other.types)?;
+    merge_items("type", &mut base.external_types, other.external_types)?;
+    merge_items(
+        "required IDL definition",
+        &mut base.metadata.required_idl_definitions,
+        other.metadata.required_idl_definitions,
+    )?;
+    for error in other.errors {
+        match base
+            .errors
+            .iter()
+            .find(|existing| existing.code == error.code)
+        {
+            Some(existing) if *existing == error => {}
+            Some(_) => {
+                return Err(MergeError::ConflictingDefinition {
+                    kind: "error",
+                    item: error.code.to_string(),
+                })
+            }
+            None => base.errors.push(error),
+        }
+    }
+    for (source, address) in other.external_program_ids {
+        base.external_program_ids.entry(source).or_insert(address);
+    }
+    Ok(base)
+}
+
+/// Promotes the types of `external` into the internal types of `base`, for programs that vendor another crate's types
+/// instead of depending on its IDL.
+///
+/// The promoted types are removed from `base`'s external types, references to them no longer have a namespace, and
+/// `external` is no longer a required IDL definition of `base`. Internal types already in `base` are kept.
+pub fn merge_external_as_internal(base: &mut IdlDefinition, external: IdlDefinition) {
+    base.metadata
+        .required_idl_definitions
+        .remove(&external.namespace());
+    let mut promoted = BTreeSet::new();
+    for (source, ty) in external.types.into_iter().chain(external.external_types) {
+        base.external_types.remove(&source);
+        promoted.insert(source.clone());
+        base.types.entry(source).or_insert(ty);
+    }
+    base.visit_type_ids_mut(&mut |type_id| {
+        if promoted.contains(&type_id.source) {
+            type_id.namespace = None;
+        }
+    });
+}
+
+fn merge_items<T: PartialEq>(
+    kind: &'static str,
+    base: &mut BTreeMap<String, T>,
+    other: BTreeMap<String, T>,
+) -> std::result::Result<(), MergeError> {
+    for (source, item) in other {
+        match base.entry(source) {
+            Entry::Vacant(entry) => {
+                entry.insert(item);
+            }
+            Entry::Occupied(entry) if *entry.get() == item => {}
+            Entry::Occupied(entry) => {
+                return Err(MergeError::ConflictingDefinition {
+                    kind,
+                    item: entry.key().clone(),
+                })
+            }
+        }
+    }
+    Ok(())
 }
 
+/// A reference to another program's IDL that this IDL depends on.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct IdlDefinitionReference {
+    /// The version of the program the IDL was generated against. Compatible (`^version`) IDLs are accepted.
     pub version: Version,
-    // todo: package name here too?
+    /// The program ID of the referenced program.
+    #[serde(with = "serde_base58_pubkey", default)]
+    pub address: Pubkey,
+}
+
+impl IdlDefinitionReference {
+    /// The versions of the referenced IDL that are compatible with this reference.
+    #[must_use]
+    pub fn version_req(&self) -> VersionReq {
+        VersionReq {
+            comparators: vec![semver::Comparator {
+                op: semver::Op::Caret,
+                major: self.version.major,
+                minor: Some(self.version.minor),
+                patch: Some(self.version.patch),
+                pre: self.version.pre.clone(),
+            }],
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -232,6 +442,10 @@ pub enum Error {
     ExpectedSingleAccountSet(String),
     #[error("Type not found in IDL definition: {0}")]
     TypeNotFound(String),
+    #[error("Required IDL definition not found for namespace: {0}")]
+    RequiredIdlNotFound(String),
+    #[error("Required IDL definition for namespace {namespace} does not match: {reason}")]
+    RequiredIdlMismatch { namespace: String, reason: String },
     #[error("Account set not found in IDL definition: {0}")]
     AccountSetNotFound(String),
     #[error("Missing name on named field for struct")]
@@ -260,9 +474,18 @@ pub enum Error {
     Custom(String),
 }
 
+/// An error combining two [`IdlDefinition`]s with [`merge`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// Both IDLs define the item differently. `item` is the item's source, or the code of an error.
+    #[error("Conflicting {kind} definitions for {item}")]
+    ConflictingDefinition { kind: &'static str, item: String },
+}
+
 #[cfg(test)]
 mod test {
-    use crate::idl_spec_version;
+    use super::*;
+    use crate::ty::IdlTypeDef;
 
     /// Tests that the idl_spec_version function doesn't panic
     #[test]
@@ -271,4 +494,164 @@ mod test {
     }
 
     // todo: add example idl maybe?
+
+    fn definition(namespace: &str) -> IdlDefinition {
+        IdlDefinition {
+            address: Pubkey::new_unique(),
+            metadata: IdlMetadata {
+                crate_metadata: CrateMetadata {
+                    name: namespace.to_string(),
+                    ..CrateMetadata::default()
+                },
+                ..IdlMetadata::default()
+            },
+            ..IdlDefinition::default()
+        }
+    }
+
+    fn ty(source: &str, type_def: IdlTypeDef) -> IdlType {
+        IdlType {
+            info: ItemInfo {
+                name: source.rsplit("::").next().unwrap().to_string(),
+                source: source.to_string(),
+                description: vec![],
+            },
+            generics: vec![],
+            type_def,
+        }
+    }
+
+    #[test]
+    fn test_merge() -> std::result::Result<(), MergeError> {
+        let mut base = definition("game");
+        base.add_type(ty("game::Shared", IdlTypeDef::U64), base.namespace());
+        base.add_type(ty("game::Player", IdlTypeDef::U8), base.namespace());
+        let mut other = definition("game");
+        other.add_type(ty("game::Shared", IdlTypeDef::U64), other.namespace());
+        other.add_type(ty("game::Fleet", IdlTypeDef::U16), other.namespace());
+
+        let address = base.address;
+        let merged = merge(base.clone(), other)?;
+        assert_eq!(merged.address, address);
+        assert_eq!(
+            merged.types.keys().collect::<Vec<_>>(),
+            ["game::Fleet", "game::Player", "game::Shared"]
+        );
+
+        let mut conflicting = definition("game");
+        conflicting.add_type(ty("game::Shared", IdlTypeDef::U32), conflicting.namespace());
+        assert_eq!(
+            merge(base, conflicting).unwrap_err(),
+            MergeError::ConflictingDefinition {
+                kind: "type",
+                item: "game::Shared".to_string(),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_types() -> Result<()> {
+        let defined = |source: &str| {
+            IdlTypeDef::Defined(IdlTypeId {
+                source: source.to_string(),
+                namespace: None,
+                provided_generics: vec![],
+            })
+        };
+        let field = |path: &str, type_def| ty::IdlStructField {
+            path: Some(path.to_string()),
+            description: vec![],
+            type_def,
+        };
+        let mut game = definition("game");
+        game.add_type(ty("game::Player", IdlTypeDef::U8), game.namespace());
+        game.add_type(
+            ty(
+                "game::Crew",
+                IdlTypeDef::Option {
+                    ty: Box::new(defined("game::Player")),
+                    fixed: false,
+                },
+            ),
+            game.namespace(),
+        );
+        game.check_missing_types()?;
+
+        // `game::Ship` and `game::Carrier` are referenced but never added
+        game.add_type(
+            ty(
+                "game::Fleet",
+                IdlTypeDef::Struct(vec![
+                    field(
+                        "ships",
+                        IdlTypeDef::List {
+                            len_ty: Box::new(IdlTypeDef::U32),
+                            item_ty: Box::new(defined("game::Ship")),
+                        },
+                    ),
+                    field(
+                        "carriers",
+                        IdlTypeDef::Array(Box::new(defined("game::Carrier")), 2),
+                    ),
+                    field("crew", defined("game::Crew")),
+                ]),
+            ),
+            game.namespace(),
+        );
+        assert_eq!(game.missing_types(), ["game::Carrier", "game::Ship"]);
+        assert!(matches!(
+            game.check_missing_types(),
+            Err(Error::TypeNotFound(missing)) if missing == "game::Carrier, game::Ship"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_external_as_internal() -> Result<()> {
+        let mut counter = definition("counter");
+        counter.add_type(ty("counter::Counter", IdlTypeDef::U64), counter.namespace());
+        let counter_ref = IdlTypeDef::Defined(IdlTypeId {
+            source: "counter::Counter".to_string(),
+            namespace: Some(counter.namespace()),
+            provided_generics: vec![],
+        });
+
+        let mut game = definition("game");
+        game.add_type(ty("counter::Counter", IdlTypeDef::U64), counter.namespace());
+        game.add_required_definition(
+            counter.namespace(),
+            "counter::Counter",
+            IdlDefinitionReference {
+                version: Version::new(1, 0, 0),
+                address: counter.address,
+            },
+        );
+        game.add_type(
+            ty(
+                "game::Player",
+                IdlTypeDef::Struct(vec![ty::IdlStructField {
+                    path: Some("counter".to_string()),
+                    description: vec![],
+                    type_def: counter_ref,
+                }]),
+            ),
+            game.namespace(),
+        );
+
+        merge_external_as_internal(&mut game, counter);
+        assert!(game.external_types.is_empty());
+        assert!(game.metadata.required_idl_definitions.is_empty());
+        assert!(game.types.contains_key("counter::Counter"));
+        let mut namespaces = vec![];
+        game.visit_type_ids_mut(&mut |type_id| namespaces.push(type_id.namespace.clone()));
+        assert_eq!(namespaces, [None]);
+
+        // The merged IDL no longer depends on the `counter` IDL
+        #[cfg(feature = "verifier")]
+        verifier::verify_idl_definitions_strict([&game])?;
+        let program = ProgramNode::try_from(game)?;
+        assert!(!program.to_json()?.contains("programLinkNode"));
+        Ok(())
+    }
 }
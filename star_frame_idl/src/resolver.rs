@@ -0,0 +1,217 @@
+//! Resolves the external types of an [`IdlDefinition`] against the IDLs of the programs they come from.
+use crate::{Error, IdlDefinition, IdlNamespace, Result};
+use std::collections::BTreeMap;
+
+/// A set of known [`IdlDefinition`]s, by namespace, used to resolve external types with [`resolve_external_types`].
+#[derive(Debug, Clone, Default)]
+pub struct IdlRegistry {
+    definitions: BTreeMap<IdlNamespace, IdlDefinition>,
+}
+
+impl IdlRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `definition` to the registry, returning the previous definition with the same namespace.
+    pub fn insert(&mut self, definition: IdlDefinition) -> Option<IdlDefinition> {
+        self.definitions.insert(definition.namespace(), definition)
+    }
+
+    #[must_use]
+    pub fn get(&self, namespace: &str) -> Option<&IdlDefinition> {
+        self.definitions.get(namespace)
+    }
+}
+
+impl FromIterator<IdlDefinition> for IdlRegistry {
+    fn from_iter<T: IntoIterator<Item = IdlDefinition>>(iter: T) -> Self {
+        let mut registry = Self::new();
+        for definition in iter {
+            registry.insert(definition);
+        }
+        registry
+    }
+}
+
+/// Checks that every IDL in `idl`'s [`IdlMetadata::required_idl_definitions`](crate::IdlMetadata::required_idl_definitions)
+/// is in `registry` with a matching address and a compatible version, and that every external type matches its
+/// definition in one of those IDLs.
+pub fn resolve_external_types(idl: &IdlDefinition, registry: &IdlRegistry) -> Result<()> {
+    let mut required = Vec::new();
+    for (namespace, reference) in &idl.metadata.required_idl_definitions {
+        let definition = registry
+            .get(namespace)
+            .ok_or_else(|| Error::RequiredIdlNotFound(namespace.clone()))?;
+        let mismatch = |reason: String| Error::RequiredIdlMismatch {
+            namespace: namespace.clone(),
+            reason,
+        };
+        if definition.address != reference.address {
+            return Err(mismatch(format!(
+                "expected address {}, found {}",
+                reference.address, definition.address
+            )));
+        }
+        let version = &definition.metadata.crate_metadata.version;
+        if !reference.version_req().matches(version) {
+            return Err(mismatch(format!(
+                "expected a version compatible with {}, found {version}",
+                reference.version
+            )));
+        }
+        required.push(definition);
+    }
+
+    for (source, external) in &idl.external_types {
+        let defined = required
+            .iter()
+            .find_map(|definition| definition.types.get(source))
+            .ok_or_else(|| Error::TypeNotFound(source.clone()))?;
+        if defined.type_def != external.type_def {
+            return Err(Error::RequiredIdlMismatch {
+                namespace: idl.namespace(),
+                reason: format!("external type {source} does not match its definition"),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ty::{IdlType, IdlTypeDef},
+        CrateMetadata, IdlDefinitionReference, IdlMetadata, ItemInfo, Version,
+    };
+    use solana_pubkey::Pubkey;
+
+    fn definition(namespace: &str, version: Version) -> IdlDefinition {
+        IdlDefinition {
+            address: Pubkey::new_unique(),
+            metadata: IdlMetadata {
+                crate_metadata: CrateMetadata {
+                    name: namespace.to_string(),
+                    version,
+                    ..CrateMetadata::default()
+                },
+                ..IdlMetadata::default()
+            },
+            ..IdlDefinition::default()
+        }
+    }
+
+    fn counter_type(type_def: IdlTypeDef) -> IdlType {
+        IdlType {
+            info: ItemInfo {
+                name: "CounterAccount".to_string(),
+                source: "counter::CounterAccount".to_string(),
+                description: vec![],
+            },
+            generics: vec![],
+            type_def,
+        }
+    }
+
+    /// A `game` IDL that uses `counter::CounterAccount`, and the `counter` IDL it comes from.
+    fn definitions() -> (IdlDefinition, IdlDefinition) {
+        let mut counter = definition("counter", Version::new(1, 2, 0));
+        counter.add_type(counter_type(IdlTypeDef::U64), "counter".to_string());
+
+        let mut game = definition("game", Version::new(0, 1, 0));
+        let source = "counter::CounterAccount".to_string();
+        let namespace = game.add_type(counter_type(IdlTypeDef::U64), "counter".to_string());
+        assert_eq!(namespace.as_deref(), Some("counter"));
+        game.add_required_definition(
+            "counter".to_string(),
+            &source,
+            IdlDefinitionReference {
+                version: Version::new(1, 0, 0),
+                address: counter.address,
+            },
+        );
+        // Types from the program itself aren't required
+        game.add_required_definition(
+            "game".to_string(),
+            &source,
+            IdlDefinitionReference {
+                version: Version::new(0, 1, 0),
+                address: game.address,
+            },
+        );
+        (game, counter)
+    }
+
+    #[test]
+    fn test_required_definitions() {
+        let (game, counter) = definitions();
+        assert_eq!(
+            game.metadata
+                .required_idl_definitions
+                .keys()
+                .collect::<Vec<_>>(),
+            ["counter"]
+        );
+        assert_eq!(
+            game.metadata.required_idl_definitions["counter"].address,
+            counter.address
+        );
+
+        // External program IDs override the associated program's ID
+        let mut overridden = definition("game", Version::new(0, 1, 0));
+        let address = Pubkey::new_unique();
+        overridden
+            .external_program_ids
+            .insert("counter::CounterAccount".to_string(), address);
+        overridden.add_required_definition(
+            "counter".to_string(),
+            "counter::CounterAccount",
+            IdlDefinitionReference {
+                version: Version::new(1, 0, 0),
+                address: counter.address,
+            },
+        );
+        assert_eq!(
+            overridden.metadata.required_idl_definitions["counter"].address,
+            address
+        );
+    }
+
+    #[test]
+    fn test_resolve_external_types() -> Result<()> {
+        let (game, counter) = definitions();
+        assert!(matches!(
+            resolve_external_types(&game, &IdlRegistry::new()),
+            Err(Error::RequiredIdlNotFound(namespace)) if namespace == "counter"
+        ));
+
+        resolve_external_types(&game, &IdlRegistry::from_iter([counter.clone()]))?;
+
+        let mut incompatible = counter.clone();
+        incompatible.metadata.crate_metadata.version = Version::new(2, 0, 0);
+        assert!(matches!(
+            resolve_external_types(&game, &IdlRegistry::from_iter([incompatible])),
+            Err(Error::RequiredIdlMismatch { .. })
+        ));
+
+        let mut moved = counter.clone();
+        moved.address = Pubkey::new_unique();
+        assert!(matches!(
+            resolve_external_types(&game, &IdlRegistry::from_iter([moved])),
+            Err(Error::RequiredIdlMismatch { .. })
+        ));
+
+        let mut changed = counter;
+        changed.types.insert(
+            "counter::CounterAccount".to_string(),
+            counter_type(IdlTypeDef::U32),
+        );
+        assert!(matches!(
+            resolve_external_types(&game, &IdlRegistry::from_iter([changed])),
+            Err(Error::RequiredIdlMismatch { .. })
+        ));
+        Ok(())
+    }
+}
@@ -12,18 +12,21 @@ pub struct IdlFindSeeds {
     pub program: Option<Pubkey>,
 }
 
-/// The only seeds we can reliably derive are ones that only rely on constants and account keys in
-/// an instruction.
+/// The only seeds we can reliably derive are ones that only rely on constants, account keys, and instruction
+/// arguments in an instruction.
 ///
 /// Using data from accounts would require fetching and parsing the account data, which
-/// we leave to the user to implement if they desire. Instruction data isn't super useful either, since finding
-/// seeds is done at the `AccountSet` level, which may not have access to the top level instruction data.
+/// we leave to the user to implement if they desire.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum IdlFindSeed {
     /// A constant seed
     Const(Vec<u8>),
-    /// A seed that is derived from an account. This is relative to the same AccountSet that the seeded account is in
+    /// A seed that is derived from an account. This is relative to the same AccountSet that the seeded account is in,
+    /// unless prefixed with a colon. Nested account sets are separated with a `.` (or a space).
     AccountPath(String),
+    /// A seed that is derived from a top-level field of the instruction arguments. Nested fields aren't supported,
+    /// since Codama can only reference whole instruction arguments.
+    ArgPath(String),
 }
 
 #[derive(Serialize, Deserialize, Deref, DerefMut, Clone, Debug, PartialEq, Eq)]
@@ -37,4 +40,40 @@ pub enum IdlSeed {
         description: ItemDescription,
         ty: IdlTypeDef,
     },
+    /// The raw UTF-8 bytes of a string
+    VariableString {
+        name: String,
+        description: ItemDescription,
+        /// If set, the bytes are truncated or zero-padded to this length
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        fixed_len: Option<usize>,
+    },
+}
+
+impl IdlSeed {
+    /// Prefixes the name of a variable seed with `{prefix}_`. Used when seeds are nested in a parent's seeds.
+    #[must_use]
+    pub fn with_name_prefix(self, prefix: &str) -> Self {
+        match self {
+            IdlSeed::Variable {
+                name,
+                description,
+                ty,
+            } => IdlSeed::Variable {
+                name: format!("{prefix}_{name}"),
+                description,
+                ty,
+            },
+            IdlSeed::VariableString {
+                name,
+                description,
+                fixed_len,
+            } => IdlSeed::VariableString {
+                name: format!("{prefix}_{name}"),
+                description,
+                fixed_len,
+            },
+            seed @ IdlSeed::Const(_) => seed,
+        }
+    }
 }
@@ -4,7 +4,7 @@ use crate::{
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct IdlType {
     #[serde(flatten)]
     pub info: ItemInfo,
@@ -54,6 +54,8 @@ pub enum IdlTypeDef {
     U128,
     I128,
     String,
+    /// A UTF-8 string of at most `N` bytes, stored as exactly `N` bytes padded with trailing zeros
+    FixedString(usize),
     Pubkey,
     FixedPoint {
         ty: Box<IdlTypeDef>,
@@ -89,6 +91,11 @@ pub enum IdlTypeDef {
         size: Box<IdlTypeDef>,
         variants: Vec<IdlEnumVariant>,
     },
+    /// A transparent wrapper with the same layout as `inner`, named so clients can tell it apart
+    Newtype {
+        name: String,
+        inner: Box<IdlTypeDef>,
+    },
 }
 
 impl IdlTypeId {
@@ -99,7 +106,185 @@ impl IdlTypeId {
     }
 }
 
+impl IdlTypeId {
+    /// Calls `f` with this type ID and every type ID in its provided generics.
+    pub fn visit_type_ids<'a>(&'a self, f: &mut impl FnMut(&'a IdlTypeId)) {
+        f(self);
+        for generic in &self.provided_generics {
+            generic.visit_type_ids(f);
+        }
+    }
+
+    /// Like [`Self::visit_type_ids`], but with mutable access to each type ID.
+    pub fn visit_type_ids_mut(&mut self, f: &mut impl FnMut(&mut IdlTypeId)) {
+        f(self);
+        for generic in &mut self.provided_generics {
+            generic.visit_type_ids_mut(f);
+        }
+    }
+}
+
 impl IdlTypeDef {
+    /// Calls `f` with every [`IdlTypeId`] referenced by this type definition, including nested and generic types.
+    pub fn visit_type_ids<'a>(&'a self, f: &mut impl FnMut(&'a IdlTypeId)) {
+        match self {
+            IdlTypeDef::Defined(type_id) => type_id.visit_type_ids(f),
+            IdlTypeDef::FixedPoint { ty, .. }
+            | IdlTypeDef::Option { ty, .. }
+            | IdlTypeDef::Newtype { inner: ty, .. }
+            | IdlTypeDef::Array(ty, _) => ty.visit_type_ids(f),
+            IdlTypeDef::List { len_ty, item_ty } | IdlTypeDef::Set { len_ty, item_ty } => {
+                len_ty.visit_type_ids(f);
+                item_ty.visit_type_ids(f);
+            }
+            IdlTypeDef::UnsizedList {
+                len_ty,
+                offset_ty,
+                item_ty,
+            } => {
+                len_ty.visit_type_ids(f);
+                offset_ty.visit_type_ids(f);
+                item_ty.visit_type_ids(f);
+            }
+            IdlTypeDef::Map {
+                len_ty,
+                key_ty,
+                value_ty,
+            } => {
+                len_ty.visit_type_ids(f);
+                key_ty.visit_type_ids(f);
+                value_ty.visit_type_ids(f);
+            }
+            IdlTypeDef::Struct(fields) => {
+                for field in fields {
+                    field.type_def.visit_type_ids(f);
+                }
+            }
+            IdlTypeDef::Enum { size, variants } => {
+                size.visit_type_ids(f);
+                for type_def in variants
+                    .iter()
+                    .filter_map(|variant| variant.type_def.as_ref())
+                {
+                    type_def.visit_type_ids(f);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Like [`Self::visit_type_ids`], but with mutable access to each type ID.
+    pub fn visit_type_ids_mut(&mut self, f: &mut impl FnMut(&mut IdlTypeId)) {
+        match self {
+            IdlTypeDef::Defined(type_id) => type_id.visit_type_ids_mut(f),
+            IdlTypeDef::FixedPoint { ty, .. }
+            | IdlTypeDef::Option { ty, .. }
+            | IdlTypeDef::Newtype { inner: ty, .. }
+            | IdlTypeDef::Array(ty, _) => ty.visit_type_ids_mut(f),
+            IdlTypeDef::List { len_ty, item_ty } | IdlTypeDef::Set { len_ty, item_ty } => {
+                len_ty.visit_type_ids_mut(f);
+                item_ty.visit_type_ids_mut(f);
+            }
+            IdlTypeDef::UnsizedList {
+                len_ty,
+                offset_ty,
+                item_ty,
+            } => {
+                len_ty.visit_type_ids_mut(f);
+                offset_ty.visit_type_ids_mut(f);
+                item_ty.visit_type_ids_mut(f);
+            }
+            IdlTypeDef::Map {
+                len_ty,
+                key_ty,
+                value_ty,
+            } => {
+                len_ty.visit_type_ids_mut(f);
+                key_ty.visit_type_ids_mut(f);
+                value_ty.visit_type_ids_mut(f);
+            }
+            IdlTypeDef::Struct(fields) => {
+                for field in fields {
+                    field.type_def.visit_type_ids_mut(f);
+                }
+            }
+            IdlTypeDef::Enum { size, variants } => {
+                size.visit_type_ids_mut(f);
+                for type_def in variants
+                    .iter_mut()
+                    .filter_map(|variant| variant.type_def.as_mut())
+                {
+                    type_def.visit_type_ids_mut(f);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces every [`IdlTypeDef::Newtype`] with its inner type, leaving only the layout.
+    pub fn strip_newtypes(&mut self) {
+        match self {
+            IdlTypeDef::Newtype { inner, .. } => {
+                *self = std::mem::take(inner.as_mut());
+                self.strip_newtypes();
+            }
+            IdlTypeDef::Defined(type_id) => {
+                for generic in &mut type_id.provided_generics {
+                    generic.strip_newtypes();
+                }
+            }
+            IdlTypeDef::FixedPoint { ty, .. }
+            | IdlTypeDef::Option { ty, .. }
+            | IdlTypeDef::Array(ty, _) => ty.strip_newtypes(),
+            IdlTypeDef::List { len_ty, item_ty } | IdlTypeDef::Set { len_ty, item_ty } => {
+                len_ty.strip_newtypes();
+                item_ty.strip_newtypes();
+            }
+            IdlTypeDef::UnsizedList {
+                len_ty,
+                offset_ty,
+                item_ty,
+            } => {
+                len_ty.strip_newtypes();
+                offset_ty.strip_newtypes();
+                item_ty.strip_newtypes();
+            }
+            IdlTypeDef::Map {
+                len_ty,
+                key_ty,
+                value_ty,
+            } => {
+                len_ty.strip_newtypes();
+                key_ty.strip_newtypes();
+                value_ty.strip_newtypes();
+            }
+            IdlTypeDef::Struct(fields) => {
+                for field in fields {
+                    field.type_def.strip_newtypes();
+                }
+            }
+            IdlTypeDef::Enum { size, variants } => {
+                size.strip_newtypes();
+                for type_def in variants
+                    .iter_mut()
+                    .filter_map(|variant| variant.type_def.as_mut())
+                {
+                    type_def.strip_newtypes();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns whether `self` and `other` have the same layout, ignoring [`IdlTypeDef::Newtype`] names.
+    #[must_use]
+    pub fn same_layout(&self, other: &IdlTypeDef) -> bool {
+        let (mut this, mut other) = (self.clone(), other.clone());
+        this.strip_newtypes();
+        other.strip_newtypes();
+        this == other
+    }
+
     pub fn assert_defined(&self) -> Result<&IdlTypeId> {
         match self {
             IdlTypeDef::Defined(ref type_id) => Ok(type_id),
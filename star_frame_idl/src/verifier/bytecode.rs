@@ -0,0 +1,230 @@
+//! Checks an [`IdlDefinition`] against a deployed program and its on-chain accounts.
+//!
+//! Unlike the structural verifier, these checks report every mismatch instead of failing on the first one,
+//! since a stale IDL usually differs from the program in more than one place.
+
+use super::verifier_err;
+use crate::{IdlDefinition, IdlDiscriminant, ItemSource, Result};
+use solana_pubkey::Pubkey;
+
+const RULE_INVALID_PROGRAM_DATA: &str = "SFIDL012";
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+/// The `lddw` opcode, which loads a 64-bit immediate split across two instruction slots.
+const LDDW_OPCODE: u8 = 0x18;
+const SEARCHED_DISCRIMINANT_LEN: usize = 8;
+
+/// The kind of IDL item a [`DiscriminantMismatch`] belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscriminantItem {
+    Instruction,
+    Account,
+}
+
+/// An IDL discriminant that does not appear in the program bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscriminantMismatch {
+    pub item: DiscriminantItem,
+    pub source: ItemSource,
+    pub discriminant: IdlDiscriminant,
+}
+
+/// An on-chain account whose data length differs from the `space` its IDL account declares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeMismatch {
+    pub address: Pubkey,
+    pub account: ItemSource,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+/// Returns the 8 byte instruction and account discriminants in `idl` that are missing from `program_data`.
+///
+/// `program_data` can be the program's ELF or the data of its upgradeable loader `ProgramData` account. Discriminants
+/// are searched for both as contiguous bytes (e.g., in `.rodata`) and as the immediate of an `lddw` instruction, which
+/// is how the compiler usually embeds the 8 byte comparisons. Discriminants of other lengths are skipped, since short
+/// discriminants are too likely to appear in the bytecode by chance.
+///
+/// A non-empty result usually means the program was redeployed with different discriminants than the IDL describes.
+pub fn verify_discriminants(
+    idl: &IdlDefinition,
+    program_data: &[u8],
+) -> Result<Vec<DiscriminantMismatch>> {
+    if find_subslice(program_data, ELF_MAGIC).is_none() {
+        return Err(verifier_err(
+            RULE_INVALID_PROGRAM_DATA,
+            "Program data does not contain an ELF",
+        ));
+    }
+    let instructions = idl.instructions.iter().map(|(source, instruction)| {
+        (
+            DiscriminantItem::Instruction,
+            source,
+            &instruction.discriminant,
+        )
+    });
+    let accounts = idl
+        .accounts
+        .iter()
+        .map(|(source, account)| (DiscriminantItem::Account, source, &account.discriminant));
+    Ok(instructions
+        .chain(accounts)
+        .filter(|(_, _, discriminant)| discriminant.len() == SEARCHED_DISCRIMINANT_LEN)
+        .filter(|(_, _, discriminant)| !contains_discriminant(program_data, discriminant))
+        .map(|(item, source, discriminant)| DiscriminantMismatch {
+            item,
+            source: source.clone(),
+            discriminant: discriminant.clone(),
+        })
+        .collect())
+}
+
+/// Returns the `accounts` whose data length differs from the fixed `space` of the IDL account they belong to.
+///
+/// Each account is matched to the IDL account with the longest discriminant prefixing its data. Accounts that match no
+/// IDL account, or match one without a fixed size, are skipped.
+pub fn verify_account_sizes(
+    idl: &IdlDefinition,
+    accounts: &[(Pubkey, Vec<u8>)],
+) -> Vec<SizeMismatch> {
+    accounts
+        .iter()
+        .filter_map(|(address, data)| {
+            let (source, account) = idl
+                .accounts
+                .iter()
+                .filter(|(_, account)| {
+                    !account.discriminant.is_empty() && data.starts_with(&account.discriminant)
+                })
+                .max_by_key(|(_, account)| account.discriminant.len())?;
+            let expected = account.space?;
+            (expected != data.len()).then(|| SizeMismatch {
+                address: *address,
+                account: source.clone(),
+                expected,
+                actual: data.len(),
+            })
+        })
+        .collect()
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn contains_discriminant(program_data: &[u8], discriminant: &[u8]) -> bool {
+    let (low, high) = discriminant.split_at(SEARCHED_DISCRIMINANT_LEN / 2);
+    find_subslice(program_data, discriminant).is_some()
+        || program_data.windows(16).any(|slots| {
+            slots[0] == LDDW_OPCODE
+                && slots[4..8] == *low
+                && slots[8..12] == [0; 4]
+                && slots[12..16] == *high
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        account::IdlAccount,
+        account_set::IdlAccountSetDef,
+        instruction::{IdlInstruction, IdlInstructionDef},
+        ty::IdlTypeId,
+    };
+
+    fn type_id(source: &str) -> IdlTypeId {
+        IdlTypeId {
+            source: source.to_string(),
+            namespace: None,
+            provided_generics: vec![],
+        }
+    }
+
+    fn definition() -> IdlDefinition {
+        let mut definition = IdlDefinition::default();
+        for (source, discriminant) in [("Run", [1u8; 8]), ("Stale", [2; 8])] {
+            definition.instructions.insert(
+                source.to_string(),
+                IdlInstruction {
+                    discriminant: discriminant.to_vec(),
+                    discriminant_namespace: None,
+                    definition: IdlInstructionDef {
+                        return_type: None,
+                        account_set: IdlAccountSetDef::empty_struct(),
+                        type_id: type_id(source),
+                    },
+                },
+            );
+        }
+        for (source, discriminant, space) in [
+            ("Counter", vec![3u8, 4, 5, 6, 7, 8, 9, 10], Some(16)),
+            ("Short", vec![11], None),
+        ] {
+            definition.accounts.insert(
+                source.to_string(),
+                IdlAccount {
+                    discriminant,
+                    type_id: type_id(source),
+                    seeds: None,
+                    space,
+                    version: None,
+                },
+            );
+        }
+        definition
+    }
+
+    #[test]
+    fn test_verify_discriminants() -> Result<()> {
+        let definition = definition();
+        let mut program_data = ELF_MAGIC.to_vec();
+        // `Run` is stored contiguously
+        program_data.extend_from_slice(&[0, 1, 1, 1, 1, 1, 1, 1, 1, 0]);
+        // `Counter` is loaded with `lddw`
+        program_data.extend_from_slice(&[LDDW_OPCODE, 1, 0, 0, 3, 4, 5, 6]);
+        program_data.extend_from_slice(&[0, 0, 0, 0, 7, 8, 9, 10]);
+
+        assert_eq!(
+            verify_discriminants(&definition, &program_data)?,
+            vec![DiscriminantMismatch {
+                item: DiscriminantItem::Instruction,
+                source: "Stale".to_string(),
+                discriminant: vec![2; 8],
+            }]
+        );
+        assert!(verify_discriminants(&definition, &[1; 32])
+            .unwrap_err()
+            .to_string()
+            .contains(RULE_INVALID_PROGRAM_DATA));
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_account_sizes() {
+        let definition = definition();
+        let counter = Pubkey::new_unique();
+        let mut counter_data = vec![3, 4, 5, 6, 7, 8, 9, 10];
+        counter_data.extend_from_slice(&[0; 12]);
+        let accounts = [
+            (counter, counter_data),
+            (
+                Pubkey::new_unique(),
+                [3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0, 0, 0].to_vec(),
+            ),
+            (Pubkey::new_unique(), vec![11; 40]),
+            (Pubkey::new_unique(), vec![12; 40]),
+        ];
+        assert_eq!(
+            verify_account_sizes(&definition, &accounts),
+            vec![SizeMismatch {
+                address: counter,
+                account: "Counter".to_string(),
+                expected: 16,
+                actual: 20,
+            }]
+        );
+    }
+}
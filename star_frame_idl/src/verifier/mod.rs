@@ -6,7 +6,13 @@
 //!
 //! Verification is fail-closed: invalid graphs return `Err` with stable rule identifiers
 //! (`SFIDL001`-`SFIDL011`) embedded in the diagnostic message.
+//!
+//! The [`verify_discriminants`] and [`verify_account_sizes`] checks compare a definition against a deployed program
+//! and its accounts instead.
+
+mod bytecode;
 
+pub use bytecode::*;
 use std::collections::BTreeMap;
 
 use crate::{
@@ -207,6 +213,15 @@ fn verify_definition<'a>(
             mode,
             &format!("{context} account_set"),
         )?;
+        if let Some(return_type) = &instruction.definition.return_type {
+            verify_type_def(
+                return_type,
+                definition,
+                namespace_index,
+                mode,
+                &format!("{context} return_type"),
+            )?;
+        }
     }
 
     Ok(())
@@ -504,7 +519,7 @@ fn verify_type_def<'a>(
         IdlTypeDef::Defined(type_id) => {
             verify_type_id(type_id, current, namespace_index, mode, context)?;
         }
-        IdlTypeDef::FixedPoint { ty, .. } => {
+        IdlTypeDef::FixedPoint { ty, .. } | IdlTypeDef::Newtype { inner: ty, .. } => {
             verify_type_def(ty, current, namespace_index, mode, context)?;
         }
         IdlTypeDef::Option { ty, .. } => {
@@ -579,6 +594,7 @@ fn verify_type_def<'a>(
         | IdlTypeDef::U128
         | IdlTypeDef::I128
         | IdlTypeDef::String
+        | IdlTypeDef::FixedString(_)
         | IdlTypeDef::Pubkey
         | IdlTypeDef::RemainingBytes => {}
     }
@@ -694,7 +710,9 @@ mod tests {
             source.to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     account_set: IdlAccountSetDef::empty_struct(),
                     type_id: type_id(type_source),
                 },
@@ -750,7 +768,9 @@ mod tests {
             "DoThing".to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     type_id: type_id("Args"),
                     account_set: IdlAccountSetDef::Defined(IdlAccountSetId {
                         source: "MissingSet".to_string(),
@@ -774,7 +794,9 @@ mod tests {
             "DoThing".to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     type_id: type_id("Args"),
                     account_set: IdlAccountSetDef::Many {
                         account_set: Box::new(IdlAccountSetDef::empty_struct()),
@@ -795,7 +817,9 @@ mod tests {
             "DoThing".to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     type_id: type_id("Args"),
                     account_set: IdlAccountSetDef::Or(vec![]),
                 },
@@ -812,7 +836,9 @@ mod tests {
             "DoThing".to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     type_id: IdlTypeId {
                         source: "GenericType".to_string(),
                         namespace: None,
@@ -837,7 +863,9 @@ mod tests {
             "DoThing".to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     type_id: type_id("Args"),
                     account_set: IdlAccountSetDef::Defined(IdlAccountSetId {
                         source: "GenericSet".to_string(),
@@ -862,7 +890,9 @@ mod tests {
             "DoThing".to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     type_id: type_id("Args"),
                     account_set: IdlAccountSetDef::Defined(IdlAccountSetId {
                         source: "GenericSet".to_string(),
@@ -901,7 +931,9 @@ mod tests {
             "DoThing".to_string(),
             IdlInstruction {
                 discriminant: vec![1],
+                discriminant_namespace: None,
                 definition: IdlInstructionDef {
+                    return_type: None,
                     type_id: type_id("Args"),
                     account_set: IdlAccountSetDef::Defined(IdlAccountSetId {
                         source: "SetWithAccount".to_string(),
@@ -923,6 +955,8 @@ mod tests {
                 discriminant: vec![7],
                 type_id: type_id_with_namespace("ExternalType", "external_program"),
                 seeds: None,
+                space: None,
+                version: None,
             },
         );
         assert_rule(
@@ -948,6 +982,8 @@ mod tests {
                 discriminant: vec![7],
                 type_id: type_id_with_namespace("ExternalType", "external_program"),
                 seeds: None,
+                space: None,
+                version: None,
             },
         );
         verify_idl_definitions([&definition]).expect("expected compatibility mode to pass");
@@ -970,6 +1006,8 @@ mod tests {
                 discriminant: vec![7],
                 type_id: type_id_with_namespace("ExternalType", "external_program"),
                 seeds: None,
+                space: None,
+                version: None,
             },
         );
         assert_rule(
@@ -987,6 +1025,8 @@ mod tests {
                 discriminant: vec![7],
                 type_id: type_id_with_namespace("ExternalType", "external_program"),
                 seeds: None,
+                space: None,
+                version: None,
             },
         );
 
@@ -29,6 +29,8 @@ struct AccountSetStructArgs {
     skip_default_cleanup: bool,
     #[argument(presence)]
     skip_default_idl: bool,
+    #[argument(presence)]
+    skip_duplicate_check: bool,
 }
 
 #[derive(ArgumentList, Debug, Clone, Default)]
@@ -1,8 +1,12 @@
 use crate::{
-    account_set::{generics::AccountSetGenerics, struct_impl::StepInput},
-    util::{new_generic, BetterGenerics, Paths},
+    account_set::{
+        generics::AccountSetGenerics,
+        struct_impl::{validate, StepInput},
+    },
+    util::{inline_attr, new_generic, uses_type_params, BetterGenerics, Paths},
 };
 use easy_proc::{find_attrs, ArgumentList};
+use itertools::Itertools;
 use proc_macro2::TokenStream;
 use proc_macro_error2::abort;
 use quote::quote;
@@ -27,6 +31,24 @@ struct CleanupFieldArgs {
     normalize_rent: bool,
 }
 
+/// Whether `arg` is `CloseAccount(())`, which closes the account to the cached recipient.
+fn closes_to_recipient(arg: &Expr) -> bool {
+    let Expr::Call(call) = arg else {
+        return false;
+    };
+    let Expr::Path(func) = &*call.func else {
+        return false;
+    };
+    func.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "CloseAccount")
+        && matches!(
+            call.args.iter().collect::<Vec<_>>().as_slice(),
+            [Expr::Tuple(tuple)] if tuple.elems.is_empty()
+        )
+}
+
 pub(super) fn cleanups(
     StepInput {
         paths,
@@ -80,7 +102,21 @@ pub(super) fn cleanups(
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
-    for field_cleanup in &field_cleanups {
+    for ((field, field_name), field_cleanup) in
+        fields.iter().zip_eq(field_name).zip_eq(&field_cleanups)
+    {
+        // `CloseAccount(())` closes to the cached recipient, which is this field if it's the recipient
+        if validate::recipient(paths, field) {
+            if let Some(arg) = field_cleanup
+                .iter()
+                .find_map(|(_, args)| args.arg.as_ref().filter(|arg| closes_to_recipient(arg)))
+            {
+                abort!(
+                    arg,
+                    "Field `{field_name}` cannot be both the recipient and closed with `CloseAccount`"
+                );
+            }
+        }
         let mut field_ids = HashSet::new();
         for (_, cleanup_field_arg) in field_cleanup {
             if !cleanup_ids.contains_key(&cleanup_field_arg.id.as_ref().map(LitStr::value)) {
@@ -105,6 +141,7 @@ pub(super) fn cleanups(
         let mut generics = main_generics.clone();
         let mut cleanup_type: Type = syn::parse_quote!(());
         let mut default_cleanup_arg: Expr = syn::parse_quote!(());
+        let infer_bounds = cleanup_struct_args.generics.is_none() && single_set_field.is_none();
         if let Some(extra_generics) = cleanup_struct_args.generics.map(|g| g.into_inner()) {
             generics.params.extend(extra_generics.params);
             if let Some(extra_where_clause) = extra_generics.where_clause {
@@ -125,10 +162,15 @@ pub(super) fn cleanups(
 
         let cleanup_args: Vec<Expr> = field_cleanups
             .iter()
-            .map(|f| {
+            .zip_eq(field_type.iter())
+            .map(|(f, field_type)| {
                 let found = f.iter()
                     .find(|(_, f)| f.id.as_ref().map(LitStr::value) == id);
 
+                if found.is_none() && infer_bounds && uses_type_params(field_type, main_generics) {
+                    // Fields using the struct's type parameters with the default arg need a bound to be cleaned up
+                    generics.make_where_clause().predicates.push(syn::parse_quote!(#field_type: #account_set_cleanup<()>));
+                }
                 match found {
                     Some((_, CleanupFieldArgs{ arg: Some(arg), normalize_rent: false, .. })) => arg.clone(),
                     Some((_, CleanupFieldArgs{ arg: None, normalize_rent: true, .. })) => syn::parse_quote!{ #normalize_rent::<()>(()) },
@@ -141,11 +183,7 @@ pub(super) fn cleanups(
         let (impl_generics, _, where_clause) = generics.split_for_impl();
         let extra_cleanup = cleanup_struct_args.extra_cleanup.map(|extra_validation| quote! {{ #extra_validation }?;});
 
-        let inline_attr = if cleanup_struct_args.inline_always {
-            quote!(#[inline(always)])
-        } else {
-            quote!(#[inline])
-        };
+        let inline_attr = inline_attr(cleanup_struct_args.inline_always);
 
         let handle_paths = field_name.iter().map(|field_name| if single_set_field.is_some() {
             quote! { res?; }
@@ -1,6 +1,6 @@
 use crate::{
     account_set::{generics::AccountSetGenerics, struct_impl::StepInput},
-    util::{new_generic, BetterGenerics, Paths},
+    util::{inline_attr, new_generic, uses_type_params, BetterGenerics, Paths},
 };
 use easy_proc::{find_attrs, ArgumentList};
 use itertools::Itertools;
@@ -8,7 +8,7 @@ use proc_macro2::TokenStream;
 use proc_macro_error2::abort;
 use quote::quote;
 use std::collections::{hash_map::Entry, HashMap, HashSet};
-use syn::{DataStruct, Expr, Fields, LitStr, Type};
+use syn::{DataStruct, Expr, Field, Fields, LitStr, Type};
 
 #[derive(ArgumentList, Default)]
 struct DecodeStructArgs {
@@ -22,12 +22,47 @@ struct DecodeStructArgs {
 #[derive(ArgumentList)]
 struct DecodeFieldArgs {
     id: Option<LitStr>,
-    arg: Expr,
+    arg: Option<Expr>,
+    skip: Option<Expr>,
+}
+
+/// Returns the initializer of a field marked with `#[decode(skip = ...)]`, which consumes no accounts when decoded.
+pub(super) fn decode_skip(paths: &Paths, field: &Field) -> Option<Expr> {
+    let field_decodes = find_attrs(&field.attrs, &paths.decode_ident)
+        .map(|a| (a, DecodeFieldArgs::parse_arguments(a)))
+        .collect::<Vec<_>>();
+    let mut skip = None;
+    for (attr, args) in &field_decodes {
+        match (&args.arg, &args.skip) {
+            (Some(_), Some(_)) => abort!(attr, "`arg` and `skip` cannot both be set"),
+            (None, None) => abort!(attr, "Expected either `arg` or `skip`"),
+            (None, Some(default)) => {
+                if let Some(id) = &args.id {
+                    abort!(
+                        id,
+                        "`skip` applies to every decode id and cannot have an `id`"
+                    );
+                }
+                if field_decodes.len() > 1 {
+                    abort!(
+                        attr,
+                        "`skip` cannot be combined with other `decode` attributes on the same field"
+                    );
+                }
+                skip = Some(default.clone());
+            }
+            (Some(_), None) => {}
+        }
+    }
+    skip
 }
 
 #[derive(Debug)]
 pub enum DecodeFieldTy<'a> {
     Type(&'a Type),
+    /// A field marked with `#[account_set(remaining)]`, which decodes all remaining accounts by default.
+    Remaining(&'a Type),
+    /// A field marked with `#[account_set(skip = ...)]` or `#[decode(skip = ...)]`, initialized without consuming accounts.
     Default(TokenStream),
 }
 
@@ -102,6 +137,7 @@ pub(super) fn decodes(
         .map(|f| {
             find_attrs(&f.attrs, decode_ident)
                 .map(DecodeFieldArgs::parse_arguments)
+                .filter(|args| args.skip.is_none())
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
@@ -130,6 +166,7 @@ pub(super) fn decodes(
         let mut generics = decode_generics.clone();
         let mut default_decode_arg: Expr = syn::parse_quote!(());
         let mut decode_type: Type = syn::parse_quote!(());
+        let infer_bounds = decode_struct_args.generics.is_none() && single_set_field.is_none();
         if let Some(extra_generics) = decode_struct_args.generics.map(|g| g.into_inner()) {
             generics.params.extend(extra_generics.params);
             if let Some(extra_where_clause) = extra_generics.where_clause {
@@ -147,22 +184,42 @@ pub(super) fn decodes(
             generics.make_where_clause().predicates.push(syn::parse_quote!(#single_ty: #account_set_decode<#decode_lifetime, #generic_arg> + #prelude::SingleAccountSet));
         }
         let decode_type = decode_struct_args.arg.unwrap_or(decode_type);
-        let decode_args: Vec<Expr> = field_decodes
+        let decode_args: Vec<Option<Expr>> = field_decodes
             .iter()
             .map(|f| {
                 f.iter()
                     .find(|f| f.id.as_ref().map(LitStr::value) == id)
-                    .map(|f| f.arg.clone())
-                    .unwrap_or_else(|| default_decode_arg.clone())
+                    .and_then(|f| f.arg.clone())
             }).collect();
+        // Fields using the struct's type parameters with the default arg need a bound to be decoded
+        if infer_bounds {
+            for (field_ty, _) in decode_field_ty.iter().zip_eq(&decode_args).filter(|(_, arg)| arg.is_none()) {
+                if let DecodeFieldTy::Type(field_ty) = field_ty {
+                    if uses_type_params(field_ty, main_generics) {
+                        generics.make_where_clause().predicates.push(syn::parse_quote!(#field_ty: #account_set_decode<#decode_lifetime, ()>));
+                    }
+                }
+            }
+        }
 
         let (impl_generics, _, where_clause) = generics.split_for_impl();
 
         let decode_inner = init(&mut decode_field_ty.iter().zip_eq(all_field_name).zip_eq(&decode_args).map(|((field_ty, field_name), decode_args)| {
             match &field_ty {
-                DecodeFieldTy::Type(field_type) => {
-                    let decode = quote! {
-                        <#field_type as #account_set_decode<#decode_lifetime, _>>::decode_accounts(accounts, #decode_args, ctx)
+                DecodeFieldTy::Type(field_type) | DecodeFieldTy::Remaining(field_type) => {
+                    let decode = match (field_ty, decode_args) {
+                        (DecodeFieldTy::Remaining(_), None) => quote! {
+                            {
+                                let remaining_len = accounts.len();
+                                <#field_type as #account_set_decode<#decode_lifetime, _>>::decode_accounts(accounts, remaining_len, ctx)
+                            }
+                        },
+                        (_, decode_args) => {
+                            let decode_args = decode_args.as_ref().unwrap_or(&default_decode_arg);
+                            quote! {
+                                <#field_type as #account_set_decode<#decode_lifetime, _>>::decode_accounts(accounts, #decode_args, ctx)
+                            }
+                        }
                     };
                     if single_set_field.is_some() {
                         quote! { #decode? }
@@ -179,11 +236,7 @@ pub(super) fn decodes(
             }
         }));
 
-        let inline_attr = if decode_struct_args.inline_always {
-            quote!(#[inline(always)])
-        } else {
-            quote!(#[inline])
-        };
+        let inline_attr = inline_attr(decode_struct_args.inline_always);
 
         quote! {
             #[automatically_derived]
@@ -1,13 +1,16 @@
 use crate::{
-    account_set::{generics::AccountSetGenerics, struct_impl::StepInput},
+    account_set::{
+        generics::AccountSetGenerics,
+        struct_impl::{validate::ValidateFieldArgs, StepInput},
+    },
     util,
-    util::{new_generic, BetterGenerics, Paths},
+    util::{new_generic, uses_type_params, BetterGenerics, Paths},
 };
 use easy_proc::{find_attrs, ArgumentList};
 use itertools::Itertools;
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Span, TokenStream, TokenTree};
 use proc_macro_error2::abort;
-use quote::quote;
+use quote::{quote, ToTokens};
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 use syn::{parse_quote, spanned::Spanned, Expr, LitStr, Type};
 
@@ -39,7 +42,7 @@ pub(super) fn idls(
 ) -> Vec<TokenStream> {
     let ident = &input.ident;
     let AccountSetGenerics { main_generics, .. } = account_set_generics;
-    Paths!(idl_ident, prelude);
+    Paths!(idl_ident, validate_ident, prelude);
 
     let mut idl_ids = HashMap::new();
     for idl_struct_args in find_attrs(&input.attrs, &idl_ident).map(IdlStructArgs::parse_arguments)
@@ -93,6 +96,25 @@ pub(super) fn idls(
         }
     }
 
+    // Owners and addresses from the default `#[validate(...)]` that can be evaluated without the account set
+    let (field_owners, field_addresses): (Vec<Option<Expr>>, Vec<Option<Expr>>) = fields
+        .iter()
+        .map(|f| {
+            find_attrs(&f.attrs, &validate_ident)
+                .map(ValidateFieldArgs::parse_arguments)
+                .find(|args| args.id.is_none())
+                .map(|args| {
+                    (
+                        args.owner
+                            .filter(|owner| is_static_expr(owner.to_token_stream())),
+                        args.address
+                            .filter(|address| is_static_expr(address.to_token_stream())),
+                    )
+                })
+                .unwrap_or_default()
+        })
+        .unzip();
+
     let struct_docs = &util::get_docs(&input.attrs);
     let ident_str = LitStr::new(&ident.to_string(), Span::call_site());
     let field_docs: Vec<Expr> = fields
@@ -119,6 +141,7 @@ pub(super) fn idls(
             let mut generics = main_generics.clone();
             let mut idl_type: Type = syn::parse_quote!(());
             let mut default_idl_arg: Expr = syn::parse_quote!(());
+            let infer_bounds = idl_struct_args.generics.is_none() && single_set_field.is_none();
             if let Some(extra_generics) = idl_struct_args.generics.map(|g| g.into_inner()) {
                 generics.params.extend(extra_generics.params);
                 if let Some(extra_where_clause) = extra_generics.where_clause {
@@ -145,6 +168,14 @@ pub(super) fn idls(
                         .unwrap_or_default()
                 })
                 .collect();
+            // Fields using the struct's type parameters with the default arg need a bound to be added to the IDL
+            if infer_bounds {
+                for (ty, _) in field_type.iter().zip_eq(&idl_args).filter(|(_, arg)| arg.is_none()) {
+                    if uses_type_params(ty, main_generics) {
+                        generics.make_where_clause().predicates.push(parse_quote!(#ty: #prelude::AccountSetToIdl<()>));
+                    }
+                }
+            }
             let idl_args: Vec<Expr> = idl_args.into_iter().map(|a| a.unwrap_or(default_idl_arg.clone())).collect();
             let (impl_generics, _, where_clause) = generics.split_for_impl();
 
@@ -156,15 +187,19 @@ pub(super) fn idls(
                 field_type
             };
 
-            let account_set_defs = relevant_field_types.iter().zip(idl_args).zip(idl_addresses).map(|((ty, idl_arg), idl_address)| {
-                let expression = quote! {
+            let account_set_defs = relevant_field_types.iter().zip(idl_args).zip(idl_addresses).zip(&field_owners).zip(&field_addresses).map(|((((ty, idl_arg), idl_address), owner), validate_address)| {
+                let mut expression = quote! {
                     <#ty as #prelude::AccountSetToIdl<_>>::account_set_to_idl(idl_definition, #idl_arg)
                 };
                 if let Some(address) = idl_address {
-                    quote! (#expression?.with_single_address(#address))
-                } else {
-                    expression
+                    expression = quote! (#expression?.with_single_address(#address));
+                } else if let Some(address) = validate_address {
+                    expression = quote! (#expression?.with_single_address(*::core::borrow::Borrow::<#prelude::Pubkey>::borrow(&(#address))));
+                }
+                if let Some(owner) = owner {
+                    expression = quote! (#expression?.with_single_owner(*::core::borrow::Borrow::<#prelude::Pubkey>::borrow(&(#owner))));
                 }
+                expression
             }).collect_vec();
 
             let inner = if account_set_defs.len() == 1 {
@@ -215,3 +250,15 @@ pub(super) fn idls(
         })
         .collect()
 }
+
+/// Whether the expression can be evaluated outside of validation, i.e. it doesn't reference `self`, `arg`, `ctx`, or
+/// `temp`.
+fn is_static_expr(tokens: TokenStream) -> bool {
+    tokens.into_iter().all(|token| match token {
+        TokenTree::Ident(ident) => {
+            !(ident == "self" || ident == "arg" || ident == "ctx" || ident == "temp")
+        }
+        TokenTree::Group(group) => is_static_expr(group.stream()),
+        _ => true,
+    })
+}
@@ -4,8 +4,8 @@ use crate::{
         SingleAccountSetFieldArgs, StrippedDeriveInput,
     },
     util::{
-        combine_gen, ignore_cfg_module, make_struct, new_generic, new_lifetime,
-        recurse_type_operator, GetGenerics, Paths,
+        combine_gen, ignore_cfg_module, inline_attr, make_struct, new_generic, new_lifetime,
+        recurse_type_operator, type_mentions_ident, GetGenerics, Paths,
     },
 };
 use easy_proc::{find_attr, ArgumentList};
@@ -19,7 +19,7 @@ use syn::{
     parse::{Parse, ParseStream},
     parse_quote,
     punctuated::Punctuated,
-    token, DataStruct, Field, Generics, Ident, Index, Lifetime, Token, Type,
+    token, DataStruct, Expr, ExprLit, Field, Generics, Ident, Index, Lifetime, Lit, Token, Type,
 };
 
 mod cleanup;
@@ -46,6 +46,37 @@ impl Parse for Requires {
 #[derive(ArgumentList, Debug, Clone, Default)]
 struct AccountSetFieldAttrs {
     skip: Option<TokenStream>,
+    #[argument(presence)]
+    remaining: bool,
+    #[argument(presence)]
+    client_skip: bool,
+    client_default: Option<Expr>,
+}
+
+/// How a field is filled in by the generated `ClientAccountSet`.
+enum ClientField {
+    /// The field is part of the `ClientAccounts` struct.
+    Provided,
+    /// The address is found from the field's default seeds.
+    Skip,
+    /// The field uses a constant default.
+    Default(Expr),
+}
+
+fn client_field(paths: &Paths, field: &Field) -> ClientField {
+    let Some(attr) = find_attr(&field.attrs, &paths.account_set_ident) else {
+        return ClientField::Provided;
+    };
+    let args = AccountSetFieldAttrs::parse_arguments(attr);
+    match (args.client_skip, args.client_default) {
+        (true, Some(default)) => abort!(
+            default,
+            "`client_skip` and `client_default` cannot both be used on the same field"
+        ),
+        (true, None) => ClientField::Skip,
+        (false, Some(default)) => ClientField::Default(default),
+        (false, None) => ClientField::Provided,
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -113,6 +144,48 @@ pub(super) fn derive_account_set_impl_struct(
         .map(|field| &field.ty)
         .collect::<Vec<_>>();
 
+    // Fields marked with `#[decode(skip = ...)]` take no account slot, so they are left out of the client, CPI, keys, and IDL impls
+    let decode_skips = data_struct
+        .fields
+        .iter()
+        .map(|field| decode::decode_skip(&paths, field))
+        .collect::<Vec<_>>();
+    let (slot_fields, slot_field_name, slot_field_type): (Vec<_>, Vec<_>, Vec<_>) = data_struct
+        .fields
+        .iter()
+        .enumerate()
+        .zip_eq(&decode_skips)
+        .filter(|((_, field), decode_skip)| filter_skip(field) && decode_skip.is_none())
+        .map(|((index, field), _)| (field, resolve_field_name((index, field)), &field.ty))
+        .multiunzip();
+    // Fields marked with `#[validate(read_only)]` are treated as `Immutable<T>` in the slot impls
+    let (slot_field_type, slot_field_ref): (Vec<Type>, Vec<TokenStream>) = slot_fields
+        .iter()
+        .zip_eq(&slot_field_name)
+        .zip_eq(slot_field_type)
+        .map(|((field, field_name), ty)| {
+            if validate::read_only(&paths, field) {
+                (
+                    parse_quote!(#prelude::Immutable<#ty>),
+                    quote!(#prelude::Immutable::from_ref(&self.#field_name)),
+                )
+            } else {
+                (ty.clone(), quote!(&self.#field_name))
+            }
+        })
+        .unzip();
+
+    if let Some(field_ty) = field_type
+        .iter()
+        .find(|field_ty| type_mentions_ident(field_ty, ident))
+    {
+        abort!(
+            field_ty,
+            "AccountSet `{}` cannot contain itself. Nested account sets are decoded recursively, so the set would never finish decoding",
+            ident
+        );
+    }
+
     let mut single_account_sets = fields
         .iter()
         .copied()
@@ -129,15 +202,25 @@ pub(super) fn derive_account_set_impl_struct(
         })
         .collect::<Vec<_>>();
 
-    skipped_fields.iter().for_each(|field| {
-        if let Some(attr) = find_attr(&field.attrs, &paths.single_account_set_ident) {
-            abort!(
-                attr,
-                "`{}` cannot be applied to skipped fields",
-                &paths.single_account_set_ident
-            );
-        }
-    });
+    skipped_fields
+        .iter()
+        .copied()
+        .chain(
+            data_struct
+                .fields
+                .iter()
+                .zip_eq(&decode_skips)
+                .filter_map(|(field, skip)| skip.as_ref().map(|_| field)),
+        )
+        .for_each(|field| {
+            if let Some(attr) = find_attr(&field.attrs, &paths.single_account_set_ident) {
+                abort!(
+                    attr,
+                    "`{}` cannot be applied to skipped fields",
+                    &paths.single_account_set_ident
+                );
+            }
+        });
 
     if single_account_sets.len() > 1 {
         abort!(
@@ -152,6 +235,7 @@ pub(super) fn derive_account_set_impl_struct(
 
     let (_, ty_generics, _) = main_generics.split_for_impl();
 
+    let inline_attr = inline_attr(false);
     let single_account_set_impls = single_account_set.map(|(field, field_name, args)| {
         if fields.len() > 1 {
             abort!(
@@ -199,6 +283,19 @@ pub(super) fn derive_account_set_impl_struct(
         let cpi_set_gen = combine_gen!(single_generics; where #field_ty: for<'__a> #prelude::CpiAccountSet + #prelude::SingleAccountSet);
         let (_, _, cpi_set_wc) = cpi_set_gen.split_for_impl();
 
+        // Sets with `skip_duplicate_check` leave their accounts out of the duplicate account check
+        let collect_single_key = if account_set_struct_args.skip_duplicate_check {
+            quote!(let _ = keys;)
+        } else {
+            quote! {
+                let meta = <Self as #prelude::SingleAccountSet>::meta();
+                keys.push(#prelude::AccountKey {
+                    pubkey: <Self as #prelude::SingleAccountSet>::pubkey(self),
+                    writable: meta.writable,
+                    signer: meta.signer,
+                });
+            }
+        };
         let cpi_set_impl = account_set_struct_args.skip_cpi_account_set.not().then(|| {
             let lt = new_lifetime(&cpi_set_gen, None);
             quote! {
@@ -208,7 +305,7 @@ pub(super) fn derive_account_set_impl_struct(
                     type ContainsOption = <#field_ty as #prelude::CpiAccountSet>::ContainsOption;
                     type AccountLen = #prelude::typenum::U1;
 
-                    #[inline]
+                    #inline_attr
                     fn to_cpi_accounts(&self) -> Self::CpiAccounts {
                         *self.account_info()
                     }
@@ -228,10 +325,11 @@ pub(super) fn derive_account_set_impl_struct(
                         index: &mut usize,
                         metas: &mut [#maybe_uninit<#prelude::PinocchioAccountMeta<#lt>>],
                     ) {
+                        let meta = <Self as #prelude::SingleAccountSet>::meta();
                         metas[*index] = #maybe_uninit::new(#prelude::PinocchioAccountMeta {
                             pubkey: accounts.key(),
-                            is_signer: <Self as #prelude::SingleAccountSet>::meta().signer,
-                            is_writable: <Self as #prelude::SingleAccountSet>::meta().writable,
+                            is_signer: meta.signer,
+                            is_writable: meta.writable,
                         });
                         *index += 1;
                     }
@@ -245,16 +343,17 @@ pub(super) fn derive_account_set_impl_struct(
                 impl #sg_impl #prelude::ClientAccountSet for #ident #ty_generics #client_set_wc {
                     type ClientAccounts = #prelude::Pubkey;
                     const MIN_LEN: usize = 1;
-                    #[inline]
+                    #inline_attr
                     fn extend_account_metas(
                         _program_id: &#prelude::Pubkey,
                         accounts: &Self::ClientAccounts,
                         metas: &mut Vec<#prelude::AccountMeta>,
                     ) {
+                        let meta = <Self as #prelude::SingleAccountSet>::meta();
                         metas.push(#prelude::AccountMeta {
                             pubkey: *accounts,
-                            is_signer: <Self as #prelude::SingleAccountSet>::meta().signer,
-                            is_writable: <Self as #prelude::SingleAccountSet>::meta().writable,
+                            is_signer: meta.signer,
+                            is_writable: meta.writable,
                         });
                     }
                 }
@@ -275,6 +374,14 @@ pub(super) fn derive_account_set_impl_struct(
                 }
             }
 
+            #[automatically_derived]
+            impl #sg_impl #prelude::AccountSetKeys for #ident #ty_generics #single_set_wc {
+                #inline_attr
+                fn collect_account_keys<'__a>(&'__a self, keys: &mut Vec<#prelude::AccountKey<'__a>>) {
+                    #collect_single_key
+                }
+            }
+
             #cpi_set_impl
             #client_set_impl
         };
@@ -285,7 +392,7 @@ pub(super) fn derive_account_set_impl_struct(
             quote! {
                 #[automatically_derived]
                 impl #sg_impl #prelude::SignedAccount for #ident #ty_generics #wc {
-                    #[inline]
+                    #inline_attr
                     fn signer_seeds(&self) -> Option<Vec<&[u8]>> {
                         <#field_ty as #prelude::SignedAccount>::signer_seeds(&self.#field_name)
                     }
@@ -346,7 +453,7 @@ pub(super) fn derive_account_set_impl_struct(
             quote! {
                 #[automatically_derived]
                 impl #impl_gen #prelude::CanInitSeeds<#new_generic> for #ident #ty_generics #wc {
-                    #[inline]
+                    #inline_attr
                     fn init_seeds(&mut self, arg: &#new_generic, ctx: &#prelude::Context) -> #result<()> {
                         <#field_ty as #prelude::CanInitSeeds<#new_generic>>::init_seeds(&mut self.#field_name, arg, ctx)
                     }
@@ -365,7 +472,7 @@ pub(super) fn derive_account_set_impl_struct(
             quote! {
                 #[automatically_derived]
                 impl #impl_gen #prelude::CanInitAccount<#init_gen> for #ident #ty_generics #wc {
-                    #[inline]
+                    #inline_attr
                     fn init_account<const #if_needed: bool>(
                         &mut self,
                         arg: #init_gen,
@@ -404,14 +511,14 @@ pub(super) fn derive_account_set_impl_struct(
         let where_clause = cpi_gen.make_where_clause();
         let cpi_set = quote!(#prelude::CpiAccountSet);
 
-        let new_fields: Vec<Field> = fields
+        let new_fields: Vec<Field> = slot_fields
             .iter()
-            .map(|field| {
+            .zip_eq(&slot_field_type)
+            .map(|(field, ty)| {
                 let Field {
                     vis,
                     ident,
                     colon_token,
-                    ty,
                     ..
                 } = field;
                 where_clause.predicates.push(parse_quote! {
@@ -432,7 +539,7 @@ pub(super) fn derive_account_set_impl_struct(
             contains_option,
             account_len,
             generics: cpi_gen,
-        } = create_cpi_clauses(field_type.as_slice(), &cpi_gen);
+        } = create_cpi_clauses(&slot_field_type.iter().collect::<Vec<_>>(), &cpi_gen);
 
         let (impl_gen, _, where_clause) = cpi_gen.split_for_impl();
 
@@ -447,10 +554,10 @@ pub(super) fn derive_account_set_impl_struct(
                 type ContainsOption = #contains_option;
                 type AccountLen = #prelude::typenum::Minimum<#account_len, #prelude::DynamicCpiAccountSetLen>;
 
-                #[inline]
+                #inline_attr
                 fn to_cpi_accounts(&self) -> Self::CpiAccounts {
                     Self::CpiAccounts {
-                        #(#struct_members: <#field_type as #cpi_set>::to_cpi_accounts(&self.#struct_members),)*
+                        #(#struct_members: <#slot_field_type as #cpi_set>::to_cpi_accounts(#slot_field_ref),)*
                     }
                 }
 
@@ -461,7 +568,7 @@ pub(super) fn derive_account_set_impl_struct(
                     index: &mut usize,
                     infos: &mut [#maybe_uninit<&#lt #prelude::AccountInfo>],
                 ) -> #prelude::Result<()> {
-                    #(<#field_type as #cpi_set>::write_account_infos(program, &accounts.#field_name, index, infos)?;)*
+                    #(<#slot_field_type as #cpi_set>::write_account_infos(program, &accounts.#slot_field_name, index, infos)?;)*
                     Ok(())
                 }
                 #[inline(always)]
@@ -471,7 +578,7 @@ pub(super) fn derive_account_set_impl_struct(
                     index: &mut usize,
                     metas: &mut [#maybe_uninit<#prelude::PinocchioAccountMeta<#lt>>],
                 ) {
-                    #(<#field_type as #cpi_set>::write_account_metas(program_id, &accounts.#field_name, index, metas);)*
+                    #(<#slot_field_type as #cpi_set>::write_account_metas(program_id, &accounts.#slot_field_name, index, metas);)*
                 }
             }
         }
@@ -485,23 +592,58 @@ pub(super) fn derive_account_set_impl_struct(
         let mut client_gen = main_generics.clone();
         let where_clause = client_gen.make_where_clause();
 
-        let new_fields: Vec<Field> = fields
+        let client_fields = slot_fields
             .iter()
-            .map(|field| {
+            .map(|field| client_field(&paths, field))
+            .collect::<Vec<_>>();
+
+        let new_fields: Vec<Field> = slot_fields
+            .iter()
+            .zip_eq(&slot_field_type)
+            .zip(&client_fields)
+            .filter_map(|((field, ty), client_field)| {
                 let Field {
                     vis,
                     ident,
                     colon_token,
-                    ty,
                     ..
                 } = field;
                 where_clause.predicates.push(parse_quote! {
                     #ty: #client_set
                 });
-                parse_quote!(#vis #ident #colon_token <#ty as #client_set>::ClientAccounts)
+                match client_field {
+                    ClientField::Provided => Some(parse_quote!(#vis #ident #colon_token <#ty as #client_set>::ClientAccounts)),
+                    ClientField::Skip => {
+                        where_clause.predicates.push(parse_quote!(#ty: #prelude::FindProgramAddress));
+                        where_clause.predicates.push(parse_quote!(<#ty as #prelude::HasSeeds>::Seeds: ::core::default::Default));
+                        where_clause.predicates.push(parse_quote!(<#ty as #client_set>::ClientAccounts: ::core::convert::From<#prelude::Pubkey>));
+                        None
+                    }
+                    ClientField::Default(_) => None,
+                }
             })
             .collect();
 
+        let extend_metas = slot_field_type
+            .iter()
+            .zip(&slot_field_name)
+            .zip(&client_fields)
+            .map(|((field_ty, field_name), client_field)| {
+                let accounts = match client_field {
+                    ClientField::Provided => quote!(&accounts.#field_name),
+                    ClientField::Skip => quote! {
+                        &::core::convert::From::from(
+                            <#field_ty as #prelude::FindProgramAddress>::find_program_address(
+                                &::core::default::Default::default(),
+                            ).0,
+                        )
+                    },
+                    ClientField::Default(default) => quote!(&::core::convert::Into::into(#default)),
+                };
+                quote!(<#field_ty as #client_set>::extend_account_metas(program_id, #accounts, metas);)
+            })
+            .collect::<Vec<_>>();
+
         let client_accounts_struct = make_struct(&client_accounts_ident, &new_fields, &client_gen);
 
 
@@ -514,28 +656,74 @@ pub(super) fn derive_account_set_impl_struct(
             #[automatically_derived]
             impl #impl_gen #client_set for #ident #ty_gen #where_clause {
                 type ClientAccounts = #client_accounts_ident #ty_gen;
-                const MIN_LEN: usize =  0#(+ <#field_type as #client_set>::MIN_LEN)*;
+                const MIN_LEN: usize =  0#(+ <#slot_field_type as #client_set>::MIN_LEN)*;
 
-                #[inline]
+                #inline_attr
                 fn extend_account_metas(
                     program_id: &#prelude::Pubkey,
                     accounts: &#client_accounts,
                     metas: &mut Vec<#prelude::AccountMeta>,
                 ) {
-                    #(<#field_type as #client_set>::extend_account_metas(program_id, &accounts.#field_name, metas);)*
+                    #(#extend_metas)*
                 }
             }
         }
     });
 
+    let account_set_keys_impl = single_account_set_impls.is_none().then(|| {
+        let account_set_keys = quote!(#prelude::AccountSetKeys);
+        let mut keys_gen = main_generics.clone();
+        let collect_keys = if account_set_struct_args.skip_duplicate_check {
+            quote!(let _ = keys;)
+        } else {
+            let where_clause = keys_gen.make_where_clause();
+            for ty in &slot_field_type {
+                where_clause.predicates.push(parse_quote!(#ty: #account_set_keys));
+            }
+            quote! {
+                #(<#slot_field_type as #account_set_keys>::collect_account_keys(#slot_field_ref, keys);)*
+            }
+        };
+        let (impl_gen, ty_gen, where_clause) = keys_gen.split_for_impl();
+        quote! {
+            #[automatically_derived]
+            impl #impl_gen #account_set_keys for #ident #ty_gen #where_clause {
+                #inline_attr
+                fn collect_account_keys<'__a>(&'__a self, keys: &mut Vec<#prelude::AccountKey<'__a>>) {
+                    #collect_keys
+                }
+            }
+        }
+    });
+
+    validate_array_fields(&data_struct);
+    let remaining_field = validate_remaining_field(&paths, &data_struct);
+
     let decode_types = data_struct
         .fields
         .iter()
-        .map(|field| {
-            find_attr(&field.attrs, &paths.account_set_ident)
+        .zip_eq(&decode_skips)
+        .enumerate()
+        .map(|(index, (field, decode_skip))| {
+            let account_set_skip = find_attr(&field.attrs, &paths.account_set_ident)
                 .map(AccountSetFieldAttrs::parse_arguments)
-                .and_then(|args| args.skip)
-                .map_or_else(|| DecodeFieldTy::Type(&field.ty), DecodeFieldTy::Default)
+                .and_then(|args| args.skip);
+            if let Some(decode_skip) = decode_skip {
+                if account_set_skip.is_some() {
+                    abort!(
+                        decode_skip,
+                        "`decode(skip)` cannot be used with `account_set(skip)`, which already skips decoding"
+                    );
+                }
+                if remaining_field == Some(index) {
+                    abort!(decode_skip, "`decode(skip)` cannot be used on the `remaining` field");
+                }
+                return DecodeFieldTy::Default(decode_skip.to_token_stream());
+            }
+            if remaining_field == Some(index) {
+                return DecodeFieldTy::Remaining(&field.ty);
+            }
+            account_set_skip.map_or_else(|| DecodeFieldTy::Type(&field.ty), DecodeFieldTy::Default)
         })
         .collect::<Vec<_>>();
 
@@ -553,7 +741,12 @@ pub(super) fn derive_account_set_impl_struct(
     let decodes = decode::decodes(step_input, &data_struct, &all_field_name, &decode_types);
     let validates = validate::validates(step_input);
     let cleanups = cleanup::cleanups(step_input);
-    let idls = idl::idls(step_input);
+    let idls = idl::idls(StepInput {
+        fields: &slot_fields,
+        field_name: &slot_field_name,
+        field_type: &slot_field_type.iter().collect::<Vec<_>>(),
+        ..step_input
+    });
 
     let idl_impls = ignore_cfg_module(
         ident,
@@ -571,11 +764,89 @@ pub(super) fn derive_account_set_impl_struct(
         #single_account_set_impls
         #cpi_account_set_impl
         #client_account_set_impl
+        #account_set_keys_impl
 
         #idl_impls
     }
 }
 
+/// Rejects array fields with a literal zero length. Non-literal lengths, such as const generics, are allowed.
+fn validate_array_fields(data_struct: &DataStruct) {
+    for field in &data_struct.fields {
+        let Type::Array(array) = &field.ty else {
+            continue;
+        };
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Int(len), ..
+        }) = &array.len
+        {
+            if len.base10_parse::<usize>().is_ok_and(|len| len == 0) {
+                abort!(len, "Account set arrays cannot be zero-length");
+            }
+        }
+    }
+}
+
+/// Finds the field marked with `#[account_set(remaining)]`, if any, and checks that it is a `Vec` and the
+/// last field of the struct.
+fn validate_remaining_field(paths: &Paths, data_struct: &DataStruct) -> Option<usize> {
+    let mut remaining_fields =
+        data_struct
+            .fields
+            .iter()
+            .enumerate()
+            .filter_map(|(index, field)| {
+                let attr = find_attr(&field.attrs, &paths.account_set_ident)?;
+                let args = AccountSetFieldAttrs::parse_arguments(attr);
+                if !args.remaining {
+                    return None;
+                }
+                if args.skip.is_some() {
+                    abort!(
+                        attr,
+                        "`remaining` and `skip` cannot both be used on the same field"
+                    );
+                }
+                Some((index, field, attr))
+            });
+
+    let (index, field, attr) = remaining_fields.next()?;
+    if let Some((_, _, attr)) = remaining_fields.next() {
+        abort!(
+            attr,
+            "Only one field can be marked as `{}(remaining)`",
+            &paths.account_set_ident
+        );
+    }
+    if index + 1 != data_struct.fields.len() {
+        abort!(
+            attr,
+            "The `{}(remaining)` field must be the last field in the struct",
+            &paths.account_set_ident
+        );
+    }
+    if let Some(attr) = find_attr(&field.attrs, &paths.single_account_set_ident) {
+        abort!(
+            attr,
+            "`{}` cannot be applied to a `remaining` field",
+            &paths.single_account_set_ident
+        );
+    }
+    let is_vec = matches!(
+        &field.ty,
+        Type::Path(type_path) if type_path.qself.is_none()
+            && type_path.path.segments.last().is_some_and(|segment| segment.ident == "Vec")
+    );
+    if !is_vec {
+        abort!(
+            field.ty,
+            "The `{}(remaining)` field must be a `Vec`",
+            &paths.account_set_ident
+        );
+    }
+    Some(index)
+}
+
 #[derive(Debug)]
 struct CpiClauseResult {
     generics: Generics,
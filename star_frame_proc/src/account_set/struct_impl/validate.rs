@@ -3,7 +3,7 @@ use crate::{
         generics::AccountSetGenerics,
         struct_impl::{Requires, StepInput},
     },
-    util::{new_generic, BetterGenerics, Paths},
+    util::{inline_attr, new_generic, respan, uses_type_params, BetterGenerics, Paths},
 };
 use daggy::Dag;
 use easy_proc::{find_attrs, ArgumentList};
@@ -11,8 +11,11 @@ use itertools::Itertools;
 use proc_macro2::{Span, TokenStream};
 use proc_macro_error2::abort;
 use quote::quote;
-use std::collections::{hash_map::Entry, HashMap, HashSet};
-use syn::{Expr, Ident, LitStr, Type};
+use std::collections::{hash_map::Entry, BTreeSet, HashMap, HashSet};
+use syn::{
+    spanned::Spanned, Expr, ExprLit, Field, GenericArgument, Ident, Lit, LitStr, PathArguments,
+    Type,
+};
 
 #[derive(ArgumentList, Default)]
 struct ValidateStructArgs {
@@ -21,27 +24,31 @@ struct ValidateStructArgs {
     generics: Option<BetterGenerics>,
     before_validation: Option<Expr>,
     extra_validation: Option<Expr>,
+    after_validation: Option<Expr>,
     #[argument(presence)]
     inline_always: bool,
 }
 
 #[derive(ArgumentList, Clone)]
-struct ValidateFieldArgs {
+pub(super) struct ValidateFieldArgs {
     /// The ident of the whole attribute, not required and can only be one
     #[argument(attr_ident)]
     attr_ident: Ident,
-    id: Option<LitStr>,
+    pub(super) id: Option<LitStr>,
     #[argument(presence)]
     funder: bool,
     #[argument(presence)]
     recipient: bool,
     #[argument(presence)]
     skip: bool,
+    #[argument(presence)]
+    read_only: bool,
     requires: Option<Requires>,
     arg: Option<Expr>,
     temp: Option<Expr>,
     arg_ty: Option<Type>,
     address: Option<Expr>,
+    pub(super) owner: Option<Expr>,
 }
 
 impl Default for ValidateFieldArgs {
@@ -52,15 +59,103 @@ impl Default for ValidateFieldArgs {
             funder: Default::default(),
             recipient: Default::default(),
             skip: Default::default(),
+            read_only: Default::default(),
             requires: Default::default(),
             arg: Default::default(),
             temp: Default::default(),
             arg_ty: Default::default(),
             address: Default::default(),
+            owner: Default::default(),
         }
     }
 }
 
+/// Returns whether a type is syntactically an `Option`.
+fn is_option(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Path(type_path) if type_path.qself.is_none()
+            && type_path.path.segments.last().is_some_and(|segment| segment.ident == "Option")
+    )
+}
+
+/// Returns whether a type is syntactically `Mut<_>` or `MaybeMut<true, _>`, which is checked to be writable.
+fn is_mut(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    match segment.ident.to_string().as_str() {
+        "Mut" => true,
+        "MaybeMut" => matches!(
+            &segment.arguments,
+            PathArguments::AngleBracketed(args) if matches!(
+                args.args.first(),
+                Some(GenericArgument::Const(Expr::Lit(ExprLit { lit: Lit::Bool(lit), .. }))) if lit.value
+            )
+        ),
+        _ => false,
+    }
+}
+
+/// Returns the inner type of a type that is syntactically `Immutable<_>`.
+fn immutable_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Immutable" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Returns whether a field is marked with `#[validate(read_only)]`, which treats it as `Immutable<T>` in the CPI,
+/// client, keys, and IDL impls.
+///
+/// A read-only `Mut` field would be passed as not writable but still be checked to be writable, so `read_only` on a
+/// `Mut` field and `Immutable<Mut<_>>` fields are rejected.
+pub(super) fn read_only(paths: &Paths, field: &Field) -> bool {
+    if immutable_inner(&field.ty).is_some_and(is_mut) {
+        abort!(
+            field.ty,
+            "`Immutable` can't wrap `Mut`, which is checked to be writable but is passed as not writable. Remove the `Mut`"
+        );
+    }
+    let field_validates = find_attrs(&field.attrs, &paths.validate_ident)
+        .map(ValidateFieldArgs::parse_arguments)
+        .filter(|args| args.read_only)
+        .collect::<Vec<_>>();
+    if let Some(id) = field_validates.iter().find_map(|args| args.id.as_ref()) {
+        abort!(
+            id,
+            "`read_only` applies to every validate id and cannot have an `id`"
+        );
+    }
+    if !field_validates.is_empty() && is_mut(&field.ty) {
+        abort!(
+            field.ty,
+            "`read_only` can't be used on a `Mut` field, which is checked to be writable but is passed as not writable. Remove the `Mut`"
+        );
+    }
+    !field_validates.is_empty()
+}
+
+/// Returns whether a field is marked with `#[validate(recipient)]` for any validate id.
+pub(super) fn recipient(paths: &Paths, field: &Field) -> bool {
+    find_attrs(&field.attrs, &paths.validate_ident)
+        .map(ValidateFieldArgs::parse_arguments)
+        .any(|args| args.recipient)
+}
+
 pub(super) fn validates(
     StepInput {
         paths,
@@ -140,6 +235,7 @@ pub(super) fn validates(
         let mut generics = main_generics.clone();
         let mut validate_type: Type = syn::parse_quote!(());
         let mut default_validate_arg: Expr = syn::parse_quote!(());
+        let infer_bounds = validate_struct_args.generics.is_none() && single_set_field.is_none();
         if let Some(extra_generics) = validate_struct_args.generics.map(|g| g.into_inner()) {
             generics.params.extend(extra_generics.params);
             if let Some(extra_where_clause) = extra_generics.where_clause {
@@ -159,24 +255,40 @@ pub(super) fn validates(
 
         validate_type = validate_struct_args.arg.unwrap_or(validate_type);
 
+        // Fields using the struct's type parameters with the default arg need a bound to be validated
+        if infer_bounds {
+            for (field_type, args) in field_type.iter().zip_eq(&relevant_field_validates) {
+                if !args.skip && args.arg.is_none() && args.arg_ty.is_none() && uses_type_params(field_type, main_generics) {
+                    generics.make_where_clause().predicates.push(syn::parse_quote!(#field_type: #account_set_validate<()>));
+                }
+            }
+        }
+
         // Cycle detection
         let mut field_id_map = HashMap::new();
         let mut validates_dag = Dag::<_, _, u32>::new();
         for field_name in field_name.iter().map(|f| f.to_string()) {
             field_id_map.insert(field_name, validates_dag.add_node(()));
         }
-        for (field_arg, field_name) in relevant_field_validates.iter().zip(field_name).filter_map(|(a, name)| a.requires.as_ref().map(|r| (r, name.to_string()))) {
+        // The indices of the fields each field requires
+        let mut required_indices = vec![Vec::new(); field_name.len()];
+        for (index, (field_arg, field_name)) in relevant_field_validates.iter().zip(field_name).enumerate().filter_map(|(index, (a, name))| a.requires.as_ref().map(|r| (index, (r, name.to_string())))) {
             for required in field_arg.required_fields.iter() {
+                if *required == field_name {
+                    abort!(required, "Field `{}` cannot require itself", required);
+                }
                 let from = field_id_map.get(&required.to_string()).unwrap_or_else(|| abort!(required, "Field `{:?}` not found", required));
                 let to = field_id_map.get(&field_name).unwrap();
                 if validates_dag.add_edge(*from, *to, ()).is_err() {
-                    abort!(required, "Cycle detected in `requires`")
+                    abort!(required, "Cycle detected in `requires`: `{}` already depends on `{}`", required, field_name)
                 }
+                required_indices[index].push(from.index());
             }
         }
+        let validate_order = validate_order(&required_indices);
 
         // set caches
-        let mut has_funder = false;
+        let mut has_required_funder = false;
         let mut has_recipient = false;
 
         // build the validate calls
@@ -196,12 +308,30 @@ pub(super) fn validates(
                     let temp = args.temp.as_ref();
                     let address_check = args.address.as_ref().map(|address| quote! {
                         #prelude::ErrorInfo::account_path(
-                            <#field_type as #prelude::CheckKey>::check_key(&self.#field_name, #address),
+                            <#field_type as #prelude::CheckKey>::check_key(
+                                &self.#field_name,
+                                ::core::borrow::Borrow::borrow(&(#address)),
+                            ),
                             ::std::stringify!(#field_name),
                         )?;
                     });
+                    let owner_check = args.owner.as_ref().map(|owner| quote! {
+                        #prelude::ErrorInfo::account_path(
+                            <#field_type as #prelude::SingleAccountSet>::check_owner(
+                                &self.#field_name,
+                                ::core::borrow::Borrow::borrow(&(#owner)),
+                            ),
+                            ::std::stringify!(#field_name),
+                        )?;
+                    });
+                    // `Result` temps are unwrapped, anything else is used as is
                     let temp = temp.as_ref().map(|temp| quote! {
-                        let temp = #temp;
+                        #[allow(unused_imports)]
+                        use #prelude::NoResultTemp as _;
+                        let temp = #prelude::ErrorInfo::account_path(
+                            #prelude::ValidateTemp(#temp).into_temp(),
+                            ::std::stringify!(#field_name),
+                        )?;
                     });
 
                     let handle_path = if single_set_field.is_some() {
@@ -210,28 +340,44 @@ pub(super) fn validates(
                         quote! { #prelude::ErrorInfo::account_path(res, ::std::stringify!(#field_name))?; }
                     };
 
+                    let validate_call = quote! {
+                        #prelude::_account_set_validate_reverse::<#field_type, #validate_ty>(
+                            __arg,
+                            &mut self.#field_name,
+                            ctx
+                        )
+                    };
+                    // Locate the call at the user's `arg` expression so mismatched arg types point at the attribute
+                    let validate_call = match &args.arg {
+                        Some(arg) => respan(validate_call, Span::call_site().located_at(arg.span())),
+                        None => validate_call,
+                    };
+
                     quote! {
                         {
-                            #address_check
                             #temp
+                            #owner_check
+                            #address_check
                             let __arg = #validate_arg;
-                            let res = #prelude::_account_set_validate_reverse::<#field_type, #validate_ty>(
-                                __arg,
-                                &mut self.#field_name,
-                                ctx
-                            );
+                            let res = #validate_call;
                             #handle_path
                         }
                     }
                 };
+                // Earlier `Option` funders take precedence, falling back to later funders when they are `None`
                 let funder = args.funder.then(|| {
-                    if has_funder {
-                        abort!(args.attr_ident, "Only one field can be marked as funder");
+                    if has_required_funder {
+                        abort!(
+                            args.attr_ident,
+                            "Only one field can be marked as funder unless the earlier funders are `Option`s"
+                        );
                     }
-                    has_funder = true;
+                    has_required_funder = !is_option(field_type);
                     quote! {
-                        if ctx.get_funder().is_none() {
-                            ctx.set_funder(Box::new(#clone::clone(&self.#field_name)));
+                        {
+                            #[allow(unused_imports)]
+                            use #prelude::CacheRequiredFunder as _;
+                            #prelude::CacheFunder(&self.#field_name).cache_funder(ctx);
                         }
                     }
                 });
@@ -253,37 +399,11 @@ pub(super) fn validates(
                 }
             }).collect_vec();
 
-        // Stores named validates in order
-        let mut out: Vec<(TokenStream, String)> = Vec::new();
-        // Map requires to vec of strings
-        let relevant_requires = relevant_field_validates
-            .iter()
-            .map(|f| f.requires
-                .as_ref()
-                .map(|r| &r.required_fields)
-                .map(|r| r.clone()
-                    .into_iter()
-                    .map(|r| r.to_string())
-                    .collect::<Vec<_>>()
-                )
-                .unwrap_or_default()
-            );
-        // Go backwards over validate calls paired with field name and what it requires
-        let iter = validates.into_iter()
-            .zip(relevant_requires)
-            .zip(field_name.iter().map(|f| f.to_string()))
-            .rev();
-        for ((validate, required), field_name) in iter {
-            let insert_index = out.iter()
-                .enumerate()
-                // find from the end
-                .rev()
-                .find(|(_, (_, name))| required.contains(name))
-                .map(|(index, _)| index + 1)
-                .unwrap_or(0);
-            out.insert(insert_index, (validate, field_name));
-        }
-        let validates = out.into_iter().map(|(validate, _)| validate);
+        let mut validates = validates.into_iter().map(Some).collect_vec();
+        let validates = validate_order
+            .into_iter()
+            .map(|index| validates[index].take().expect("Each field is validated once"))
+            .collect_vec();
 
         let (impl_generics, _, where_clause) = generics.split_for_impl();
         let before_validation = validate_struct_args.before_validation.map(|before_validation| quote! {
@@ -294,12 +414,12 @@ pub(super) fn validates(
             let res: #result<()> = { #extra_validation };
             res?;
         });
+        let after_validation = validate_struct_args.after_validation.map(|after_validation| quote! {
+            let res: #result<()> = { #after_validation };
+            res?;
+        });
 
-        let inline_attr = if validate_struct_args.inline_always {
-            quote!(#[inline(always)])
-        } else {
-            quote!(#[inline])
-        };
+        let inline_attr = inline_attr(validate_struct_args.inline_always);
 
         quote! {
             #[automatically_derived]
@@ -313,9 +433,57 @@ pub(super) fn validates(
                     #before_validation
                     #(#validates)*
                     #extra_validation
+                    #after_validation
                     Ok(())
                 }
             }
         }
     }).collect()
 }
+
+/// Orders the fields so each one comes after the fields it requires, keeping the declaration order otherwise.
+///
+/// `required_indices` must be acyclic, which is checked before this is called.
+fn validate_order(required_indices: &[Vec<usize>]) -> Vec<usize> {
+    let mut remaining = required_indices
+        .iter()
+        .map(|required| required.iter().unique().count())
+        .collect_vec();
+    let mut ready = (0..required_indices.len())
+        .filter(|index| remaining[*index] == 0)
+        .collect::<BTreeSet<_>>();
+    let mut order = Vec::with_capacity(required_indices.len());
+    while let Some(index) = ready.pop_first() {
+        order.push(index);
+        for (dependent, required) in required_indices.iter().enumerate() {
+            if required.contains(&index) {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_validate_order() {
+        // No requirements keeps the declaration order
+        assert_eq!(validate_order(&[vec![], vec![], vec![]]), vec![0, 1, 2]);
+        // `0` requires `2`, `1` requires `0`
+        assert_eq!(validate_order(&[vec![2], vec![0], vec![]]), vec![2, 0, 1]);
+        // Diamond: `0` requires `1` and `2`, which both require `3`
+        assert_eq!(
+            validate_order(&[vec![1, 2], vec![3], vec![3], vec![]]),
+            vec![3, 1, 2, 0]
+        );
+        // Duplicate requirements are only counted once
+        assert_eq!(validate_order(&[vec![1, 1], vec![]]), vec![1, 0]);
+    }
+}
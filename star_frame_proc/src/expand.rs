@@ -0,0 +1,213 @@
+use crate::{account_set, instruction_set};
+use proc_macro2::TokenStream;
+use proc_macro_error2::abort;
+use quote::{format_ident, quote};
+use std::path::{Path, PathBuf};
+use syn::{punctuated::Punctuated, DeriveInput, Item, ItemEnum, Path as SynPath, Token};
+
+/// The derives that `#[star_frame_expand]` can expand.
+const EXPANDABLE_DERIVES: &[&str] = &["AccountSet", "InstructionSet"];
+
+/// Expands the derives on `item`, writing the expansion to a file in the directory for the module in
+/// `source_file`.
+pub fn star_frame_expand_impl(item: Item, source_file: Option<PathBuf>) -> TokenStream {
+    let original = quote!(#item);
+    let (input, item_enum, derives) = expandable_derives(item);
+    let expansion = derive_expansion(&input, item_enum.as_ref(), &derives);
+
+    let path = expansion_dir()
+        .join(module_dir(
+            &std::env::var("CARGO_CRATE_NAME").unwrap_or_default(),
+            &PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default()),
+            source_file.as_deref(),
+        ))
+        .join(format!("{}.rs", input.ident));
+    let note = match write_expansion(&path, expansion) {
+        Ok(()) => format!("star_frame_expand: wrote expansion to {}", path.display()),
+        Err(e) => format!(
+            "star_frame_expand: failed to write expansion to {}: {e}",
+            path.display()
+        ),
+    };
+    // Stable proc macros can't emit notes, so a deprecated item is used to surface the path as a warning.
+    let note_ident = format_ident!("__StarFrameExpand{}", input.ident);
+    quote! {
+        #original
+
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct #note_ident;
+            let _ = #note_ident;
+        };
+    }
+}
+
+/// Splits `item` into its derive input, the enum if it is one, and the expandable derives on it.
+fn expandable_derives(item: Item) -> (DeriveInput, Option<ItemEnum>, Vec<String>) {
+    let (input, item_enum): (DeriveInput, _) = match item {
+        Item::Struct(item) => (item.into(), None),
+        Item::Enum(item) => (item.clone().into(), Some(item)),
+        item => abort!(
+            item,
+            "`star_frame_expand` can only be used on `AccountSet` structs or `InstructionSet` enums"
+        ),
+    };
+
+    let derives = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .flat_map(|attr| {
+            attr.parse_args_with(Punctuated::<SynPath, Token![,]>::parse_terminated)
+                .unwrap_or_else(|e| abort!(attr, "Failed to parse derive: {}", e))
+        })
+        .filter_map(|path| {
+            let ident = path.segments.last()?.ident.to_string();
+            EXPANDABLE_DERIVES
+                .contains(&ident.as_str())
+                .then_some(ident)
+        })
+        .collect::<Vec<_>>();
+    if derives.is_empty() {
+        abort!(
+            input.ident,
+            "`star_frame_expand` must be placed above `#[derive(AccountSet)]` or `#[derive(InstructionSet)]`"
+        );
+    }
+    (input, item_enum, derives)
+}
+
+fn derive_expansion(
+    input: &DeriveInput,
+    item_enum: Option<&ItemEnum>,
+    derives: &[String],
+) -> TokenStream {
+    derives
+        .iter()
+        .map(|derive| match derive.as_str() {
+            "AccountSet" => account_set::derive_account_set_impl(input.clone()),
+            _ => match item_enum {
+                Some(item_enum) => instruction_set::instruction_set_impl(item_enum.clone()),
+                None => abort!(
+                    input.ident,
+                    "`InstructionSet` can only be derived for enums"
+                ),
+            },
+        })
+        .collect()
+}
+
+/// Returns `target/star_frame_expand`, using `CARGO_TARGET_DIR` or the closest `target` directory above the crate.
+fn expansion_dir() -> PathBuf {
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let manifest_dir =
+                PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+            manifest_dir
+                .ancestors()
+                .map(|dir| dir.join("target"))
+                .find(|dir| dir.is_dir())
+                .unwrap_or_else(|| manifest_dir.join("target"))
+        });
+    target_dir.join("star_frame_expand")
+}
+
+/// Returns `<crate_name>/<module path>`, with the module path taken from `source_file` relative to the crate's
+/// `src` directory. Inline `mod` blocks aren't part of the file path, so they aren't included.
+fn module_dir(crate_name: &str, manifest_dir: &Path, source_file: Option<&Path>) -> PathBuf {
+    let mut dir = PathBuf::from(crate_name);
+    // Cargo passes source files relative to the workspace root, which is the compiler's working directory
+    let source_file = source_file.map(|file| {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(file))
+            .unwrap_or_else(|_| file.to_path_buf())
+    });
+    let Some(module_file) = source_file
+        .as_deref()
+        .and_then(|file| file.strip_prefix(manifest_dir.join("src")).ok())
+    else {
+        return dir;
+    };
+    let module_file = module_file.with_extension("");
+    let mut modules = module_file.iter().collect::<Vec<_>>();
+    if matches!(
+        modules.last().and_then(|module| module.to_str()),
+        Some("lib" | "main" | "mod")
+    ) {
+        modules.pop();
+    }
+    dir.extend(modules);
+    dir
+}
+
+fn write_expansion(path: &Path, expansion: TokenStream) -> std::io::Result<()> {
+    let formatted = match syn::parse2::<syn::File>(expansion.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        // Still write the tokens so a formatting failure doesn't hide the expansion
+        Err(_) => expansion.to_string(),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_expansion_parses() {
+        let items: [Item; 2] = [
+            parse_quote! {
+                #[derive(AccountSet)]
+                pub struct MyAccounts {
+                    pub authority: Signer,
+                    pub account: Mut<SystemAccount>,
+                }
+            },
+            parse_quote! {
+                #[derive(InstructionSet)]
+                pub enum MyInstructionSet {
+                    Run(Run),
+                    Close(Close),
+                }
+            },
+        ];
+        for item in items {
+            let (input, item_enum, derives) = expandable_derives(item);
+            let expansion = derive_expansion(&input, item_enum.as_ref(), &derives);
+            if let Err(e) = syn::parse2::<syn::File>(expansion) {
+                panic!("Expansion of {} failed to parse: {e}", input.ident);
+            }
+        }
+    }
+
+    #[test]
+    fn test_module_dir() {
+        let manifest_dir = Path::new("/workspace/my_program");
+        let cases = [
+            ("src/lib.rs", "my_program"),
+            ("src/main.rs", "my_program"),
+            ("src/instructions.rs", "my_program/instructions"),
+            ("src/instructions/mod.rs", "my_program/instructions"),
+            (
+                "src/instructions/place_order.rs",
+                "my_program/instructions/place_order",
+            ),
+        ];
+        for (file, expected) in cases {
+            assert_eq!(
+                module_dir("my_program", manifest_dir, Some(&manifest_dir.join(file))),
+                PathBuf::from(expected)
+            );
+        }
+        assert_eq!(
+            module_dir("my_program", manifest_dir, None),
+            PathBuf::from("my_program")
+        );
+    }
+}
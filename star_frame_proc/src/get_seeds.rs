@@ -1,9 +1,9 @@
 use crate::util::{get_docs, ignore_cfg_module, new_lifetime, Paths};
 use easy_proc::{find_attr, ArgumentList};
 use proc_macro2::TokenStream;
-use proc_macro_error2::abort;
+use proc_macro_error2::{abort, abort_call_site};
 use quote::{format_ident, quote};
-use syn::{parse_quote, Data, DeriveInput, Expr};
+use syn::{parse_quote, Data, DeriveInput, Expr, Ident, LitInt, LitStr};
 
 #[derive(Debug, ArgumentList, Default)]
 pub struct GetSeedsArgs {
@@ -11,6 +11,57 @@ pub struct GetSeedsArgs {
     pub seed_const: Option<Expr>,
     #[argument(presence)]
     pub skip_idl: bool,
+    pub idl_name: Option<LitStr>,
+}
+
+#[derive(Debug, ArgumentList, Default)]
+pub struct GetSeedsFieldArgs {
+    #[argument(presence)]
+    pub nested: bool,
+    #[argument(presence)]
+    pub as_bytes: bool,
+    pub fixed_len: Option<LitInt>,
+}
+
+/// How a field is turned into seeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeedKind {
+    /// `Seed::seed`
+    Plain,
+    /// `#[get_seeds(nested)]`, the field's own `GetSeeds`
+    Nested,
+    /// `#[get_seeds(as_bytes)]` or `#[get_seeds(fixed_len = ...)]`, the field's bytes, optionally truncated or
+    /// zero-padded to a fixed length
+    Bytes(Option<usize>),
+}
+
+/// The max length of a single seed
+const MAX_SEED_LEN: usize = 32;
+
+impl GetSeedsFieldArgs {
+    fn seed_kind(self) -> SeedKind {
+        let fixed_len = self.fixed_len.map(|lit| {
+            let len = lit
+                .base10_parse::<usize>()
+                .unwrap_or_else(|e| abort!(lit, e));
+            if len > MAX_SEED_LEN {
+                abort!(
+                    lit,
+                    "`fixed_len` cannot be more than {} bytes",
+                    MAX_SEED_LEN
+                );
+            }
+            len
+        });
+        match (self.nested, self.as_bytes || fixed_len.is_some()) {
+            (true, true) => {
+                abort_call_site!("`nested` cannot be combined with `as_bytes` or `fixed_len`")
+            }
+            (true, false) => SeedKind::Nested,
+            (false, true) => SeedKind::Bytes(fixed_len),
+            (false, false) => SeedKind::Plain,
+        }
+    }
 }
 
 pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
@@ -29,11 +80,19 @@ pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
     let GetSeedsArgs {
         skip_idl,
         seed_const,
+        idl_name,
     } = find_attr(&input.attrs, &get_seeds_ident)
         .map(GetSeedsArgs::parse_arguments)
         .unwrap_or_default();
 
     let ident = &input.ident;
+    let find_seeds_ident = match &idl_name {
+        Some(name) if skip_idl => abort!(name, "`idl_name` cannot be used with `skip_idl`"),
+        Some(name) => name
+            .parse::<Ident>()
+            .unwrap_or_else(|_| abort!(name, "`idl_name` must be a valid identifier")),
+        None => format_ident!("Find{ident}"),
+    };
     let wc_for = new_lifetime(&input.generics, Some("wc"));
     let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
 
@@ -44,6 +103,18 @@ pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
         );
     }
 
+    let seed_kinds = data_struct
+        .fields
+        .iter()
+        .map(|field| {
+            find_attr(&field.attrs, &get_seeds_ident)
+                .map(GetSeedsFieldArgs::parse_arguments)
+                .unwrap_or_default()
+                .seed_kind()
+        })
+        .collect::<Vec<_>>();
+    let all_plain = seed_kinds.iter().all(|kind| *kind == SeedKind::Plain);
+
     let idl_impl = (!skip_idl).then(|| {
         let seeds_to_idl = {
             let mut generics = input.generics.clone();
@@ -51,7 +122,8 @@ pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
             let field_seeds: Vec<_> = data_struct
                 .fields
                 .iter()
-                .map(|field| {
+                .zip(&seed_kinds)
+                .map(|(field, kind)| {
                     let ty = &field.ty;
                     let docs = get_docs(&field.attrs);
                     let ident = field
@@ -59,61 +131,106 @@ pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
                         .clone()
                         .expect("Field must have an identifier")
                         .to_string();
-                    where_clause.predicates.push(parse_quote! {
-                        #ty: for<#wc_for> #prelude::TypeToIdl
-                    });
-                    quote! {
-                        #prelude::IdlSeed::Variable {
-                            name: #ident.to_string(),
-                            description: #docs,
-                            ty: <#ty as #prelude::TypeToIdl>::type_to_idl(idl_definition)?,
+                    match kind {
+                        SeedKind::Nested => {
+                            where_clause.predicates.push(parse_quote! {
+                                #ty: #prelude::SeedsToIdl
+                            });
+                            quote! {
+                                seeds.extend(
+                                    <#ty as #prelude::SeedsToIdl>::seeds_to_idl(idl_definition)?
+                                        .0
+                                        .into_iter()
+                                        .map(|seed| seed.with_name_prefix(#ident)),
+                                );
+                            }
+                        }
+                        SeedKind::Bytes(fixed_len) => {
+                            let fixed_len = match fixed_len {
+                                Some(len) => quote!(Some(#len)),
+                                None => quote!(None),
+                            };
+                            quote! {
+                                seeds.push(#prelude::IdlSeed::VariableString {
+                                    name: #ident.to_string(),
+                                    description: #docs,
+                                    fixed_len: #fixed_len,
+                                });
+                            }
+                        }
+                        SeedKind::Plain => {
+                            where_clause.predicates.push(parse_quote! {
+                                #ty: for<#wc_for> #prelude::TypeToIdl
+                            });
+                            quote! {
+                                seeds.push(#prelude::IdlSeed::Variable {
+                                    name: #ident.to_string(),
+                                    description: #docs,
+                                    ty: <#ty as #prelude::TypeToIdl>::type_to_idl(idl_definition)?,
+                                });
+                            }
                         }
                     }
                 })
                 .collect();
-            let idl_seeds = seed_const
+            let const_seed = seed_const
                 .as_ref()
-                .map(|expr| quote!(#prelude::IdlSeed::Const(#expr.to_vec())))
-                .into_iter()
-                .chain(field_seeds);
+                .map(|expr| quote!(seeds.push(#prelude::IdlSeed::Const(#expr.to_vec()));));
 
             quote! {
                 #[cfg(all(feature = "idl", not(target_os = "solana")))]
                 #[automatically_derived]
                 impl #impl_generics #prelude::SeedsToIdl for #ident #type_generics #where_clause {
                     fn seeds_to_idl(idl_definition: &mut #prelude::IdlDefinition) -> #prelude::IdlResult<#prelude::IdlSeeds> {
-                        Ok(#prelude::IdlSeeds(vec![
-                            #(#idl_seeds),*
-                        ]))
+                        let mut seeds: Vec<#prelude::IdlSeed> = vec![];
+                        #const_seed
+                        #(#field_seeds)*
+                        Ok(#prelude::IdlSeeds(seeds))
                     }
                 }
             }
         };
 
         let find_seeds = {
-            let find_seeds_ident = format_ident!("Find{ident}");
-
             let field_find_seeds: Vec<_> = data_struct
                 .fields
                 .iter()
-                .map(|field| {
+                .zip(&seed_kinds)
+                .map(|(field, kind)| {
                     let ident = field.ident.as_ref().expect("Field must have an identifier");
-                    quote! {
-                        Into::into(&self.#ident)
+                    match kind {
+                        SeedKind::Nested => quote! {
+                            seeds.extend(#prelude::FindIdlSeeds::find_seeds(&self.#ident)?);
+                        },
+                        SeedKind::Bytes(fixed_len) => {
+                            let resize = fixed_len.map(|len| quote!(bytes.resize(#len, 0);));
+                            quote! {
+                                let mut bytes = ::core::convert::AsRef::<[u8]>::as_ref(&self.#ident).to_vec();
+                                #resize
+                                seeds.push(#prelude::IdlFindSeed::Const(bytes));
+                            }
+                        }
+                        SeedKind::Plain => quote! {
+                            seeds.push(Into::into(&self.#ident));
+                        },
                     }
                 })
                 .collect();
-            let find_seeds = seed_const
+            let const_find_seed = seed_const
                 .as_ref()
-                .map(|expr| parse_quote!(#prelude::IdlFindSeed::Const(#expr.to_vec())))
-                .into_iter()
-                .chain(field_find_seeds);
+                .map(|expr| quote!(seeds.push(#prelude::IdlFindSeed::Const(#expr.to_vec()));));
 
-            let find_fields = data_struct.fields.iter().map(|field| {
+            let find_fields = data_struct.fields.iter().zip(&seed_kinds).map(|(field, kind)| {
                 let mut field = field.clone();
                 let ty = &field.ty;
                 field.vis = parse_quote!(pub);
-                field.ty = parse_quote!(#prelude::FindSeed<#ty>);
+                field.attrs.retain(|attr| !attr.path().is_ident(&get_seeds_ident));
+                field.ty = match kind {
+                    SeedKind::Nested => parse_quote!(::std::vec::Vec<#prelude::IdlFindSeed>),
+                    // Byte seeds can't come from an account key, so they're always constant
+                    SeedKind::Bytes(_) => ty.clone(),
+                    SeedKind::Plain => parse_quote!(#prelude::FindSeed<#ty>),
+                };
                 field
             });
 
@@ -128,7 +245,10 @@ pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
                 #[automatically_derived]
                 impl #impl_generics #prelude::FindIdlSeeds for #find_seeds_ident #type_generics #where_clause {
                     fn find_seeds(&self) -> #prelude::IdlResult<Vec<#prelude::IdlFindSeed>> {
-                        Ok(vec![#(#find_seeds),*])
+                        let mut seeds: Vec<#prelude::IdlFindSeed> = vec![];
+                        #const_find_seed
+                        #(#field_find_seeds)*
+                        Ok(seeds)
                     }
                 }
             }
@@ -140,21 +260,77 @@ pub fn derive_get_seeds_impl(input: DeriveInput) -> TokenStream {
         })
     });
 
-    let field_seeds = data_struct.fields.iter().map(|field| {
-        let name = field.ident.as_ref().expect("Field must have an identifier");
-        parse_quote!(self.#name.seed())
-    });
-    let seeds = seed_const
-        .into_iter()
-        .chain(field_seeds)
-        .chain(std::iter::once(parse_quote!(&[])));
+    let field_seeds = data_struct
+        .fields
+        .iter()
+        .zip(&seed_kinds)
+        .map(|(field, kind)| {
+            let name = field.ident.as_ref().expect("Field must have an identifier");
+            match kind {
+                SeedKind::Nested => quote! {
+                    seeds.extend(#prelude::GetSeeds::seeds_without_bump(&self.#name));
+                },
+                SeedKind::Bytes(None) => quote! {
+                    #prelude::push_bytes_seed(
+                        &mut seeds,
+                        ::core::convert::AsRef::<[u8]>::as_ref(&self.#name),
+                    );
+                },
+                SeedKind::Bytes(Some(len)) => quote! {
+                    #prelude::push_fixed_len_seed(
+                        &mut seeds,
+                        ::core::convert::AsRef::<[u8]>::as_ref(&self.#name),
+                        #len,
+                    );
+                },
+                SeedKind::Plain => quote!(self.#name.seed()),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let seeds_body = |with_bump: bool| {
+        let bump = with_bump.then(|| quote!(&[]));
+        if all_plain {
+            let seeds = seed_const
+                .iter()
+                .map(|expr| quote!(#expr))
+                .chain(field_seeds.iter().cloned())
+                .chain(bump);
+            return quote!(vec![#(#seeds),*]);
+        }
+        let field_seeds = field_seeds
+            .iter()
+            .zip(&seed_kinds)
+            .map(|(seed, kind)| match kind {
+                SeedKind::Plain => quote!(seeds.push(#seed);),
+                _ => seed.clone(),
+            });
+        let const_seed = seed_const.as_ref().map(|expr| quote!(seeds.push(#expr);));
+        let bump = bump.map(|bump| quote!(seeds.push(#bump);));
+        quote! {
+            let mut seeds: Vec<&[u8]> = vec![];
+            #const_seed
+            #(#field_seeds)*
+            #bump
+            seeds
+        }
+    };
+    let seeds = seeds_body(true);
+    let seeds_without_bump = seeds_body(false);
 
     quote! {
         #[automatically_derived]
         impl #impl_generics #prelude::GetSeeds for #ident #type_generics #where_clause {
+            #[allow(unused_imports)]
             fn seeds(&self) -> Vec<&[u8]> {
                 use #prelude::Seed;
-                vec![#(#seeds),*]
+                #seeds
+            }
+
+            #[allow(unused_imports)]
+            fn seeds_without_bump(&self) -> Vec<&[u8]> {
+                use #prelude::Seed;
+                #seeds_without_bump
             }
         }
 
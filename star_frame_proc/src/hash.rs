@@ -3,7 +3,12 @@ use proc_macro2::TokenStream;
 use proc_macro_error2::abort;
 use sha2::{Digest, Sha256};
 use std::str::FromStr;
-use syn::{punctuated::Punctuated, token::Comma, LitStr};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token::Comma,
+    Ident, LitStr, Token,
+};
 
 pub const SIGHASH_GLOBAL_NAMESPACE: &str = "global";
 pub const SIGHASH_ACCOUNT_NAMESPACE: &str = "account";
@@ -22,10 +27,43 @@ pub fn hash_tts(hash: &[u8; 8]) -> TokenStream {
     TokenStream::from_str(&hash_tts).expect("Hash should be valid tts")
 }
 
-pub fn sighash_impl(args: Punctuated<LitStr, Comma>) -> TokenStream {
+/// The input to `sighash!`, with an optional leading `namespace: "<namespace>",`.
+pub struct SighashInput {
+    namespace: Option<LitStr>,
+    args: Punctuated<LitStr, Comma>,
+}
+
+impl Parse for SighashInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let namespace = if input.peek(Ident) && input.peek2(Token![:]) {
+            let ident: Ident = input.parse()?;
+            if ident != "namespace" {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "Expected `namespace` or a string literal",
+                ));
+            }
+            input.parse::<Token![:]>()?;
+            let namespace = input.parse()?;
+            if !input.is_empty() {
+                input.parse::<Comma>()?;
+            }
+            Some(namespace)
+        } else {
+            None
+        };
+        Ok(Self {
+            namespace,
+            args: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+pub fn sighash_impl(input: SighashInput) -> TokenStream {
+    let SighashInput { namespace, args } = input;
     if args.is_empty() {
         abort!(args, "sighash! requires at least one argument");
     }
-    let strings = args.iter().map(|s| s.value()).join(":");
+    let strings = namespace.iter().chain(&args).map(LitStr::value).join(":");
     hash_tts(&hash_str(&strings))
 }
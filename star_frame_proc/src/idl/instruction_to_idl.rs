@@ -20,6 +20,9 @@ pub fn derive_instruction_to_idl(input: &DeriveInput) -> TokenStream {
     where_clause.predicates.push(
         parse_quote!(<Self as #prelude::StarFrameInstruction>::Accounts<'decode, 'arg>: #prelude::AccountSetToIdl<#generic_arg>),
     );
+    where_clause.predicates.push(
+        parse_quote!(<Self as #prelude::StarFrameInstruction>::ReturnType: #prelude::TypeToIdl),
+    );
 
     ignore_cfg_module(
         ident,
@@ -32,9 +35,16 @@ pub fn derive_instruction_to_idl(input: &DeriveInput) -> TokenStream {
                     let account_set = <<#ident as #prelude::StarFrameInstruction>::Accounts<'decode, 'arg> as #prelude::AccountSetToIdl<#generic_arg>>::account_set_to_idl(idl_definition, arg)?;
                     let type_def = <#ident as #prelude::TypeToIdl>::type_to_idl(idl_definition)?;
                     let type_id = type_def.assert_defined()?.clone();
+                    type ReturnType = <#ident as #prelude::StarFrameInstruction>::ReturnType;
+                    let return_type = if ::core::mem::size_of::<ReturnType>() > 0 {
+                        Some(<ReturnType as #prelude::TypeToIdl>::type_to_idl(idl_definition)?)
+                    } else {
+                        None
+                    };
                     Ok(#prelude::IdlInstructionDef {
                         account_set,
                         type_id,
+                        return_type,
                     })
                 }
             }
@@ -49,7 +49,7 @@ pub fn derive_type_to_idl_inner(input: &DeriveInput, args: TypeToIdlArgs) -> Tok
     let ident_str = LitStr::new(&ident.to_string(), Span::call_site());
     let type_docs = &util::get_docs(&input.attrs);
     let type_def = match &input.data {
-        syn::Data::Struct(DataStruct { fields, .. }) => idl_struct_type_def(fields),
+        syn::Data::Struct(DataStruct { fields, .. }) => idl_struct_type_def(fields, true),
         syn::Data::Enum(data_enum) => idl_enum_type_def(data_enum, &input.attrs),
         syn::Data::Union(DataUnion { union_token, .. }) => {
             abort!(union_token, "Unions are not supported for TypeToIdl")
@@ -79,6 +79,13 @@ pub fn derive_type_to_idl_inner(input: &DeriveInput, args: TypeToIdlArgs) -> Tok
                             generics: vec![],
                         };
                         let namespace = idl_definition.add_type(idl_type, <Self::AssociatedProgram as #prelude::ProgramToIdl>::crate_metadata().name);
+                        if let Some(namespace) = &namespace {
+                            idl_definition.add_required_definition(
+                                namespace.clone(),
+                                &source,
+                                <Self::AssociatedProgram as #prelude::ProgramToIdl>::idl_definition_reference(),
+                            );
+                        }
                         Ok(#prelude::IdlTypeDef::Defined(#prelude::IdlTypeId {
                             namespace,
                             source,
@@ -90,7 +97,9 @@ pub fn derive_type_to_idl_inner(input: &DeriveInput, args: TypeToIdlArgs) -> Tok
     )
 }
 
-fn idl_struct_type_def(fields: &Fields) -> TokenStream {
+/// Tuple struct fields are named `field_0`, `field_1`, etc. when `name_tuple_fields` is set, so they can be
+/// represented as a struct in the IDL. Otherwise, they have no path.
+fn idl_struct_type_def(fields: &Fields, name_tuple_fields: bool) -> TokenStream {
     let Paths {
         type_to_idl_args_ident,
         prelude,
@@ -118,8 +127,12 @@ fn idl_struct_type_def(fields: &Fields) -> TokenStream {
     }
     let idl_fields: Vec<TokenStream> = idl_fields
         .iter()
-        .map(|f| {
-            let path: Expr = if tuple {
+        .enumerate()
+        .map(|(index, f)| {
+            let path: Expr = if tuple && name_tuple_fields {
+                let field_name = LitStr::new(&format!("field_{index}"), f.span());
+                parse_quote!(Some(#field_name.to_string()))
+            } else if tuple {
                 parse_quote!(None)
             } else {
                 let field_name = f
@@ -174,7 +187,7 @@ fn idl_enum_type_def(data_enum: &syn::DataEnum, attributes: &[Attribute]) -> Tok
             let type_def = if matches!(v.fields, Fields::Unit) {
                 quote!(None)
             } else {
-                let def = idl_struct_type_def(&v.fields);
+                let def = idl_struct_type_def(&v.fields, false);
                 quote!(Some(#def))
             };
             let discriminant = discriminant_vec(d, repr);
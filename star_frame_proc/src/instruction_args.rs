@@ -1,18 +1,19 @@
 use derive_more::Debug;
 use easy_proc::{find_attr, ArgumentList};
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use proc_macro_error2::abort;
 use quote::{quote, ToTokens as _};
 use syn::{
     parse::{Parse, ParseStream},
     parse_quote,
     punctuated::Punctuated,
-    DeriveInput, Expr, Ident, Index, Lifetime, Token, Type,
+    spanned::Spanned as _,
+    DeriveInput, Expr, Ident, Index, Lifetime, LitStr, Token, Type,
 };
 
 use crate::{
     idl::{derive_instruction_to_idl, derive_type_to_idl},
-    util::{ensure_data_struct, new_lifetime, reject_generics, Paths},
+    util::{ensure_data_struct, new_lifetime, respan, Paths},
 };
 
 #[derive(Debug)]
@@ -72,6 +73,34 @@ impl Parse for InstructionArg {
     }
 }
 
+/// An item in an `#[ix_args(...)]` attribute
+enum IxArgsItem {
+    Arg(InstructionArg),
+    /// `default = <expr>`, used when the field is missing from the instruction data
+    Default(Expr),
+    /// `optional`, uses [`Default::default`] when the field is missing from the instruction data
+    Optional,
+}
+
+impl Parse for IxArgsItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident) {
+            let fork = input.fork();
+            let ident: Ident = fork.parse()?;
+            if ident == "default" && fork.peek(Token![=]) {
+                input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+                return Ok(IxArgsItem::Default(input.parse()?));
+            }
+            if ident == "optional" {
+                input.parse::<Ident>()?;
+                return Ok(IxArgsItem::Optional);
+            }
+        }
+        Ok(IxArgsItem::Arg(input.parse()?))
+    }
+}
+
 impl InstructionArg {
     fn info(&self, attribute_type: AttributeType, lt: &Lifetime) -> ArgInfo {
         match attribute_type {
@@ -126,11 +155,6 @@ pub fn derive_instruction_args_impl(input: DeriveInput) -> TokenStream {
     Paths!(ix_args_ident, prelude);
     let ident = &input.ident;
 
-    reject_generics(
-        &input,
-        Some("Generics are not supported for InstructionArgs"),
-    );
-
     let data_struct = ensure_data_struct(
         &input,
         Some("InstructionArgs can only be derived for structs"),
@@ -143,18 +167,45 @@ pub fn derive_instruction_args_impl(input: DeriveInput) -> TokenStream {
     let mut validate: Vec<ArgInfo> = Vec::new();
     let mut run: Vec<ArgInfo> = Vec::new();
     let mut cleanup: Vec<ArgInfo> = Vec::new();
+    let mut borrowed_run = false;
 
     let mut handle_attrs = |attrs: &[syn::Attribute],
                             attribute_type: AttributeType,
-                            lt: &Lifetime| {
+                            lt: &Lifetime|
+     -> Option<(Expr, String)> {
         let attr = find_attr(attrs, &ix_args_ident);
+        let mut default = None;
         if let Some(args) = attr
             .map(|attr| {
-                attr.parse_args_with(Punctuated::<InstructionArg, Token![,]>::parse_terminated).unwrap_or_else(|_| {
-                    abort!(attr, "Attribute must be of the form `#[ix_args(decode, validate, run, cleanup)]`, optionaly with `&` or `&mut` to the argument. Any of the args can be provided.")
+                attr.parse_args_with(Punctuated::<IxArgsItem, Token![,]>::parse_terminated).unwrap_or_else(|_| {
+                    abort!(attr, "Attribute must be of the form `#[ix_args(decode, validate, run, cleanup)]`, optionaly with `&` or `&mut` to the argument. Any of the args can be provided. Fields can also have `default = <expr>` or `optional`.")
                 })
             }) {
-                for arg in args {
+                for item in args {
+                    let arg = match item {
+                        IxArgsItem::Arg(arg) => arg,
+                        IxArgsItem::Default(_) | IxArgsItem::Optional
+                            if matches!(attribute_type, AttributeType::Struct(_)) =>
+                        {
+                            abort!(attr, "`default` and `optional` can only be used on fields")
+                        }
+                        IxArgsItem::Default(expr) => {
+                            let display = expr.to_token_stream().to_string();
+                            if default.replace((expr, display)).is_some() {
+                                abort!(attr, "Only one of `default` or `optional` can be provided");
+                            }
+                            continue;
+                        }
+                        IxArgsItem::Optional => {
+                            if default.replace((parse_quote!(::core::default::Default::default()), "Default::default()".to_string())).is_some() {
+                                abort!(attr, "Only one of `default` or `optional` can be provided");
+                            }
+                            continue;
+                        }
+                    };
+                    if matches!(arg.arg_type, InstructionArgType::Run) && !matches!(arg.reference, RefKind::Owned) {
+                        borrowed_run = true;
+                    }
                     let info = arg.info(attribute_type, lt);
                     let arg_to_replace = match arg.arg_type {
                         InstructionArgType::Decode => &mut decode,
@@ -165,18 +216,55 @@ pub fn derive_instruction_args_impl(input: DeriveInput) -> TokenStream {
                     arg_to_replace.push(info);
                 }
             }
+        default
     };
 
     handle_attrs(&input.attrs, AttributeType::Struct(&input.ident), &lt);
 
+    let mut borsh_fields = Vec::new();
+    // The IDL documents the default of each trailing field
+    let mut idl_input = input.clone();
     for (i, field) in data_struct.fields.iter().enumerate() {
         let ident = field
             .ident
             .clone()
             .map(|ident| ident.into_token_stream())
             .unwrap_or_else(|| Index::from(i).into_token_stream());
-        handle_attrs(&field.attrs, AttributeType::Field(&ident, &field.ty), &lt);
+        let default = handle_attrs(&field.attrs, AttributeType::Field(&ident, &field.ty), &lt);
+        let skip = BorshAttrs::parse(&field.attrs).skip;
+        if default.is_none()
+            && !skip
+            && borsh_fields
+                .iter()
+                .any(|field: &BorshField| field.default.is_some())
+        {
+            abort!(
+                field,
+                "Fields with `default` or `optional` must come after all other fields"
+            );
+        }
+        if let Some((_, default)) = &default {
+            let doc = LitStr::new(
+                &format!(" Defaults to `{default}` when missing from the instruction data."),
+                field.span(),
+            );
+            let syn::Data::Struct(idl_struct) = &mut idl_input.data else {
+                unreachable!("Checked to be a struct above");
+            };
+            let idl_field = idl_struct.fields.iter_mut().nth(i).expect("Field exists");
+            idl_field.attrs.push(parse_quote!(#[doc = #doc]));
+        }
+        borsh_fields.push(BorshField {
+            ident,
+            ty: field.ty.clone(),
+            default: default.map(|(expr, _)| expr),
+            skip,
+        });
     }
+    let borsh_impl = borsh_fields
+        .iter()
+        .any(|field| field.default.is_some())
+        .then(|| borsh_deserialize_impl(&input, &borsh_fields));
 
     if decode.is_empty() {
         decode.push(default_type.clone());
@@ -197,12 +285,31 @@ pub fn derive_instruction_args_impl(input: DeriveInput) -> TokenStream {
     let (run_tys, run_exprs): SplitInfos = run.into_iter().unzip();
     let (cleanup_tys, cleanup_exprs): SplitInfos = cleanup.into_iter().unzip();
 
-    let idl_impl = idl_impl(&input);
+    let idl_impl = idl_impl(&idl_input);
+
+    // Stable proc macros can't emit warnings, so a deprecated item is used to surface the borrowed run arg. It is
+    // only deprecated with star_frame's `async` feature.
+    let borrowed_run_warning = borrowed_run.then(|| {
+        respan(
+            quote! { let _ = #prelude::BorrowedRunArg; },
+            Span::call_site().located_at(ident.span()),
+        )
+    });
 
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     quote! {
         #idl_impl
+        #borsh_impl
+
+        // Async instructions hold the args across await points
+        const _: () = {
+            #[allow(dead_code)]
+            fn __assert_async_args #impl_generics () #where_clause {
+                #prelude::assert_async_args::<#ident #ty_generics>();
+            }
+            #borrowed_run_warning
+        };
 
         impl #impl_generics #prelude::InstructionArgs for #ident #ty_generics #where_clause {
             type DecodeArg<#lt> = (#(#decode_tys),*);
@@ -222,3 +329,95 @@ pub fn derive_instruction_args_impl(input: DeriveInput) -> TokenStream {
         }
     }
 }
+
+struct BorshField {
+    ident: TokenStream,
+    ty: Type,
+    default: Option<Expr>,
+    skip: bool,
+}
+
+/// The `#[borsh(...)]` attributes the generated `BorshDeserialize` impl needs to match the derived `BorshSerialize`
+#[derive(Default)]
+struct BorshAttrs {
+    crate_path: Option<syn::Path>,
+    skip: bool,
+}
+
+impl BorshAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> Self {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("borsh")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    out.skip = true;
+                } else if meta.path.is_ident("crate") {
+                    out.crate_path = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<Expr>()?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    meta.parse_nested_meta(|nested| {
+                        nested.value()?.parse::<Expr>()?;
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            })
+            .unwrap_or_else(|e| abort!(attr, "Invalid `borsh` attribute: {}", e));
+        }
+        out
+    }
+}
+
+/// Deserializes each field in order, falling back to the field's default if the instruction data ends before it.
+/// Fields with `#[borsh(skip)]` are never read and use [`Default::default`].
+fn borsh_deserialize_impl(input: &DeriveInput, fields: &[BorshField]) -> TokenStream {
+    Paths!(prelude);
+    let ident = &input.ident;
+    let borsh = BorshAttrs::parse(&input.attrs)
+        .crate_path
+        .map_or_else(|| quote!(#prelude::borsh), |path| path.to_token_stream());
+
+    let mut generics = input.generics.clone();
+    if !generics.params.is_empty() {
+        let where_clause = generics.make_where_clause();
+        for field in fields {
+            let ty = &field.ty;
+            where_clause.predicates.push(if field.skip {
+                parse_quote!(#ty: ::core::default::Default)
+            } else {
+                parse_quote!(#ty: #borsh::BorshDeserialize)
+            });
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let field_values = fields.iter().map(|field| {
+        let field_ident = &field.ident;
+        match (&field.default, field.skip) {
+            (_, true) => quote! {
+                #field_ident: ::core::default::Default::default()
+            },
+            (None, false) => quote! {
+                #field_ident: #borsh::BorshDeserialize::deserialize_reader(reader)?
+            },
+            (Some(default), false) => quote! {
+                #field_ident: match #prelude::deserialize_trailing_arg(reader)? {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => #default,
+                }
+            },
+        }
+    });
+    quote! {
+        impl #impl_generics #borsh::BorshDeserialize for #ident #ty_generics #where_clause {
+            fn deserialize_reader<R: #borsh::io::Read>(
+                reader: &mut R,
+            ) -> #borsh::io::Result<Self> {
+                ::core::result::Result::Ok(Self {
+                    #(#field_values,)*
+                })
+            }
+        }
+    }
+}
@@ -3,12 +3,19 @@ use heck::ToSnakeCase;
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use proc_macro_error2::{abort, abort_call_site};
-use quote::quote;
-use syn::{parse_quote, Expr, Fields, FieldsUnnamed, ItemEnum, Type};
+use quote::{quote, ToTokens};
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    token, Expr, ExprLit, ExprUnary, Fields, FieldsUnnamed, Ident, ItemEnum, Lit, LitBool, LitStr,
+    Token, Type, UnOp, Variant,
+};
 
 use crate::{
     hash::SIGHASH_GLOBAL_NAMESPACE,
-    util::{enum_discriminants, get_repr, ignore_cfg_module, Paths},
+    util::{enum_discriminants, get_repr, ignore_cfg_module, IntegerRepr, Paths},
 };
 
 #[derive(Debug, ArgumentList, Clone, Default)]
@@ -16,15 +23,42 @@ pub struct InstructionSetStructArgs {
     #[argument(presence)]
     pub skip_idl: bool,
     #[argument(presence)]
+    pub skip_idl_output: bool,
+    #[argument(presence)]
+    pub skip_idl_types: bool,
+    #[argument(presence)]
     pub use_repr: bool,
+    pub repr: Option<Ident>,
+    pub fallback: Option<Expr>,
+    pub namespace: Option<LitStr>,
+    pub anchor_compat: Option<LitBool>,
 }
 
 #[derive(Debug, ArgumentList, Clone, Default)]
 pub struct InstructionSetFieldArgs {
+    pub flatten: Option<FlattenedInstructions>,
     pub idl_arg: Option<Expr>,
     pub idl_arg_ty: Option<Type>,
 }
 
+/// The instructions of a flattened sub-set, e.g. `[Ix1, Ix2]`.
+#[derive(Debug, Clone)]
+pub struct FlattenedInstructions {
+    #[allow(dead_code)]
+    bracket: token::Bracket,
+    instructions: Punctuated<Type, Token![,]>,
+}
+
+impl Parse for FlattenedInstructions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        Ok(Self {
+            bracket: bracketed!(content in input),
+            instructions: content.parse_terminated(Type::parse, Token![,])?,
+        })
+    }
+}
+
 pub fn instruction_set_impl(item: ItemEnum) -> TokenStream {
     Paths!(
         account_info,
@@ -43,17 +77,85 @@ pub fn instruction_set_impl(item: ItemEnum) -> TokenStream {
         .map(InstructionSetStructArgs::parse_arguments)
         .unwrap_or_default();
 
-    let discriminant_type: Type = if args.use_repr {
-        let repr = get_repr(&item.attrs);
-        repr.repr.as_integer().map_or_else(
-            || abort_call_site!("Invalid repr attribute for ix_set. Must use integer repr with `use_repr` enabled"),
-            |ty| parse_quote! { #ty },
-        )
-    } else {
-        parse_quote!([u8; 8])
+    let enum_repr = get_repr(&item.attrs).repr.as_integer();
+    let integer_repr = match (&args.repr, args.use_repr) {
+        (Some(repr_ident), use_repr) => {
+            if use_repr {
+                abort!(repr_ident, "`repr` and `use_repr` cannot both be specified");
+            }
+            let repr_str = repr_ident.to_string();
+            let repr = IntegerRepr::try_from(repr_str.as_str()).unwrap_or_else(|_| {
+                abort!(
+                    repr_ident,
+                    "Invalid ix_set repr `{}`. Must be an integer type",
+                    repr_str
+                )
+            });
+            if enum_repr.is_some_and(|enum_repr| enum_repr != repr) {
+                abort!(
+                    repr_ident,
+                    "ix_set `repr = {}` does not match the enum's `#[repr]`",
+                    repr_str
+                );
+            }
+            Some(repr)
+        }
+        (None, true) => Some(enum_repr.unwrap_or_else(|| {
+            abort_call_site!(
+                "Invalid repr attribute for ix_set. Must use integer repr with `use_repr` enabled"
+            )
+        })),
+        (None, false) => None,
     };
+    if let Some(repr) = integer_repr {
+        check_discriminant_range(item.variants.iter(), repr);
+    }
+
+    let sighash_namespace = sighash_namespace(&args);
+    if integer_repr.is_some() {
+        if let Some(namespace) = &args.namespace {
+            abort!(
+                namespace,
+                "`namespace` only applies to sighash discriminants and cannot be used with `repr` or `use_repr`"
+            );
+        }
+        if let Some(anchor_compat) = &args.anchor_compat {
+            abort!(
+                anchor_compat,
+                "`anchor_compat` only applies to sighash discriminants and cannot be used with `repr` or `use_repr`"
+            );
+        }
+    }
+
+    let discriminant_type: Type =
+        integer_repr.map_or_else(|| parse_quote!([u8; 8]), |ty| parse_quote! { #ty });
+
+    let variant_args = item
+        .variants
+        .iter()
+        .map(|v| {
+            let args = find_attr(&v.attrs, &instruction_set_args_ident)
+                .map(InstructionSetFieldArgs::parse_arguments)
+                .unwrap_or_default();
+            if args.flatten.is_some() {
+                if args.idl_arg.is_some() || args.idl_arg_ty.is_some() {
+                    abort!(
+                        v,
+                        "`idl_arg` and `idl_arg_ty` cannot be used on a `flatten` variant"
+                    );
+                }
+                if !item.generics.params.is_empty() {
+                    abort!(
+                        item.generics,
+                        "`flatten` cannot be used in a generic instruction set"
+                    );
+                }
+            }
+            args
+        })
+        .collect_vec();
 
-    let variant_tys = item
+    let all_variant_tys = item
         .variants
         .iter()
         .map(|v| {
@@ -69,68 +171,176 @@ pub fn instruction_set_impl(item: ItemEnum) -> TokenStream {
         })
         .collect_vec();
 
-    let ix_disc_values = if args.use_repr {
+    let all_disc_values: Vec<Expr> = if integer_repr.is_some() {
         enum_discriminants(item.variants.iter()).collect_vec()
     } else {
         item.variants
             .iter()
             .map(|v| {
                 let method_name = v.ident.to_string().to_snake_case();
-                parse_quote!(#prelude::sighash!(#SIGHASH_GLOBAL_NAMESPACE, #method_name))
+                parse_quote!(#prelude::sighash!(#sighash_namespace, #method_name))
             })
             .collect()
     };
 
-    let idl_impl = (!args.skip_idl).then( || {
-        let (idl_args, idl_arg_tys) = item
-            .variants
-            .iter()
-            .map(|v| {
-                let args = find_attr(&v.attrs, &instruction_set_args_ident)
-                    .map(InstructionSetFieldArgs::parse_arguments)
-                    .unwrap_or_default();
-                let idl_arg = args.idl_arg.unwrap_or_else(|| parse_quote!(()));
-                let idl_arg_ty = args.idl_arg_ty.unwrap_or_else(|| parse_quote!(_));
-                (idl_arg, idl_arg_ty)
-            })
-            .unzip::<_, _, Vec<_>, Vec<_>>();
+    let mut variant_tys = vec![];
+    let mut variants = vec![];
+    let mut ix_disc_values = vec![];
+    let mut flattened_tys = vec![];
+    let mut flattened_ixs = vec![];
+    for (((variant, ty), disc_value), args) in item
+        .variants
+        .iter()
+        .zip_eq(&all_variant_tys)
+        .zip_eq(all_disc_values)
+        .zip_eq(&variant_args)
+    {
+        if let Some(flatten) = &args.flatten {
+            if flatten.instructions.is_empty() {
+                abort!(
+                    variant,
+                    "`flatten` must list the instructions of the flattened set"
+                );
+            }
+            flattened_tys.push(*ty);
+            flattened_ixs.push(flatten.instructions.iter().collect_vec());
+        } else {
+            variant_tys.push(*ty);
+            variants.push(variant);
+            ix_disc_values.push(disc_value);
+        }
+    }
+
+    let direct_discriminants = quote! {
+        #(<#variant_tys as #prelude::InstructionDiscriminant<#ident #ty_generics>>::DISCRIMINANT,)*
+    };
+    let discriminants = if flattened_tys.is_empty() {
+        quote! { &[#direct_discriminants] }
+    } else {
+        let direct_count = variant_tys.len();
+        quote! {
+            &#prelude::concat_discriminants::<
+                #discriminant_type,
+                { #direct_count #(+ <#flattened_tys as #prelude::InstructionSet>::DISCRIMINANTS.len())* },
+            >(&[
+                &[#direct_discriminants],
+                #(<#flattened_tys as #prelude::InstructionSet>::DISCRIMINANTS,)*
+            ])
+        }
+    };
+
+    let idl_namespace = if integer_repr.is_some() {
+        quote!(None)
+    } else {
+        quote!(Some(#sighash_namespace.to_string()))
+    };
+
+    let idl_impl = (!args.skip_idl && !args.skip_idl_types).then(|| {
+        let idl_body = if args.skip_idl_output {
+            quote!(Ok(()))
+        } else {
+            let (idl_args, idl_arg_tys) = variant_args
+                .iter()
+                .filter(|args| args.flatten.is_none())
+                .map(|args| {
+                    let idl_arg = args.idl_arg.clone().unwrap_or_else(|| parse_quote!(()));
+                    let idl_arg_ty = args.idl_arg_ty.clone().unwrap_or_else(|| parse_quote!(_));
+                    (idl_arg, idl_arg_ty)
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+            quote! {
+                #({
+                    let definition =
+                        <#variant_tys as #prelude::InstructionToIdl<#idl_arg_tys>>::instruction_to_idl(idl_definition, #idl_args)?;
+                    let discriminant =
+                        <#variant_tys as #prelude::InstructionDiscriminant<Self>>::discriminant_bytes();
+                    idl_definition.add_instruction(definition, discriminant, #idl_namespace)?;
+                })*
+                #(
+                    <#flattened_tys as #prelude::InstructionSetToIdl>::instruction_set_to_idl(idl_definition)?;
+                )*
+                Ok(())
+            }
+        };
 
         ignore_cfg_module(ident, "_instruction_set_to_idl", quote! {
             #[cfg(all(feature = "idl", not(target_os = "solana")))]
             #[automatically_derived]
             impl #impl_generics #prelude::InstructionSetToIdl for #ident #ty_generics #where_clause {
-                #[allow(clippy::let_unit_value)]
+                #[allow(clippy::let_unit_value, unused_variables)]
                 fn instruction_set_to_idl(
                     idl_definition: &mut #prelude::IdlDefinition,
                 ) -> #prelude::IdlResult<()> {
-                    #({
-                        let definition =
-                            <#variant_tys as #prelude::InstructionToIdl<#idl_arg_tys>>::instruction_to_idl(idl_definition, #idl_args)?;
-                        let discriminant =
-                            <#variant_tys as #prelude::InstructionDiscriminant<Self>>::discriminant_bytes();
-                        idl_definition.add_instruction(definition, discriminant)?;
-                    })*
-                    Ok(())
+                    #idl_body
                 }
             }
         })
     });
-    let ix_message = item
-        .variants
+    let ix_message = variants
         .iter()
         .map(|v| format!("Instruction: {}", v.ident))
         .collect_vec();
 
-    let dispatch_body = if variant_tys.is_empty() {
+    let unknown_discriminant = match &args.fallback {
+        Some(fallback) => quote! {
+            _ => #fallback(full_instruction_data, accounts),
+        },
+        None => quote! {
+            x => #prelude::bail!(#prelude::ProgramError::InvalidInstructionData, "Invalid ix discriminant: {:?}", x),
+        },
+    };
+
+    // Data too short to hold a discriminant is unmatched input too
+    let short_data = args.fallback.as_ref().map(|fallback| {
+        quote! {
+            if maybe_discriminant_bytes.is_err() {
+                return #fallback(full_instruction_data, accounts);
+            }
+        }
+    });
+
+    let flattened_checks = (!flattened_tys.is_empty()).then(|| {
+        let flattened_messages = flattened_tys.iter().map(|ty| {
+            format!(
+                "`flatten` must list every instruction in `{}`",
+                ty.to_token_stream()
+            )
+        });
+        let flattened_counts = flattened_ixs.iter().map(Vec::len);
         quote! {
-            #prelude::bail!(#prelude::ProgramError::InvalidInstructionData, "No instructions in this instruction set")
+            const _: () = {
+                ::core::assert!(
+                    !#prelude::has_duplicate_discriminants(<#ident as #prelude::InstructionSet>::DISCRIMINANTS),
+                    "Instruction discriminants collide between flattened instruction sets"
+                );
+                #(
+                    ::core::assert!(
+                        <#flattened_tys as #prelude::InstructionSet>::DISCRIMINANTS.len() == #flattened_counts,
+                        #flattened_messages
+                    );
+                )*
+            };
+        }
+    });
+
+    let dispatch_body = if all_variant_tys.is_empty() {
+        match &args.fallback {
+            Some(fallback) => quote! {
+                #fallback(instruction_data, accounts)
+            },
+            None => quote! {
+                #prelude::bail!(#prelude::ProgramError::InvalidInstructionData, "No instructions in this instruction set")
+            },
         }
     } else {
         quote! {
+            #[allow(unused_variables)]
+            let full_instruction_data = instruction_data;
             let maybe_discriminant_bytes =
                 #prelude::Advance::try_advance(&mut instruction_data, ::core::mem::size_of::<#discriminant_type>());
+            #short_data
             let discriminant_bytes = #prelude::ErrorInfo::ctx(maybe_discriminant_bytes, "Failed to read instruction discriminant bytes")?;
-            let discriminant = *#bytemuck::try_from_bytes(discriminant_bytes)?;
+            let discriminant: #discriminant_type = *#bytemuck::try_from_bytes(discriminant_bytes)?;
             #[deny(unreachable_patterns)]
             match discriminant {
                 #(
@@ -143,7 +353,12 @@ pub fn instruction_set_impl(item: ItemEnum) -> TokenStream {
                         <#variant_tys as #instruction>::process_from_raw(program_id, accounts, instruction_data)
                     }
                 )*
-                x => #prelude::bail!(#prelude::ProgramError::InvalidInstructionData, "Invalid ix discriminant: {:?}", x),
+                #(
+                    #(<#flattened_ixs as #prelude::InstructionDiscriminant<#ident>>::DISCRIMINANT)|* => {
+                        <#flattened_tys as #prelude::InstructionSet>::dispatch(program_id, accounts, full_instruction_data)
+                    }
+                )*
+                #unknown_discriminant
             }
         }
     };
@@ -154,6 +369,8 @@ pub fn instruction_set_impl(item: ItemEnum) -> TokenStream {
         impl #impl_generics #prelude::InstructionSet for #ident #ty_generics #where_clause {
             type Discriminant = #discriminant_type;
 
+            const DISCRIMINANTS: &'static [#discriminant_type] = #discriminants;
+
             #[inline(always)]
             fn dispatch(
                 program_id: &'static #pubkey,
@@ -171,6 +388,98 @@ pub fn instruction_set_impl(item: ItemEnum) -> TokenStream {
             }
         )*
 
+        #(#(
+            #[automatically_derived]
+            impl #prelude::InstructionDiscriminant<#ident> for #flattened_ixs {
+                const DISCRIMINANT: #discriminant_type =
+                    <#flattened_ixs as #prelude::InstructionDiscriminant<#flattened_tys>>::DISCRIMINANT;
+            }
+        )*)*
+
+        #flattened_checks
+
         #idl_impl
     }
 }
+
+/// Returns the namespace sighash discriminants are hashed with.
+fn sighash_namespace(args: &InstructionSetStructArgs) -> String {
+    match (&args.namespace, &args.anchor_compat) {
+        (Some(namespace), Some(anchor_compat)) => abort!(
+            anchor_compat,
+            "`anchor_compat` cannot be used with `namespace`, which already sets the namespace for `{}`",
+            namespace.value()
+        ),
+        (Some(namespace), None) => {
+            if namespace.value().is_empty() {
+                abort!(namespace, "`namespace` cannot be empty");
+            }
+            namespace.value()
+        }
+        (None, Some(anchor_compat)) if !anchor_compat.value => std::env::var("CARGO_CRATE_NAME")
+            .unwrap_or_else(|_| {
+                abort!(
+                    anchor_compat,
+                    "`anchor_compat = false` requires `CARGO_CRATE_NAME` to be set by cargo"
+                )
+            }),
+        (None, _) => SIGHASH_GLOBAL_NAMESPACE.to_string(),
+    }
+}
+
+/// Aborts if a known discriminant doesn't fit in `repr`. Discriminants after a non-literal explicit discriminant
+/// are left for the compiler to check.
+fn check_discriminant_range<'a>(variants: impl Iterator<Item = &'a Variant>, repr: IntegerRepr) {
+    let (min, max) = integer_repr_range(repr);
+    let mut next = Some(0i128);
+    for variant in variants {
+        let value = match &variant.discriminant {
+            Some((_, discriminant)) => literal_discriminant(discriminant),
+            None => next,
+        };
+        if let Some(value) = value {
+            if value < min || value > max {
+                abort!(
+                    variant,
+                    "Discriminant `{}` of `{}` is out of range for `{}`",
+                    value,
+                    variant.ident,
+                    repr.to_token_stream()
+                );
+            }
+        }
+        next = value.and_then(|value| value.checked_add(1));
+    }
+}
+
+fn literal_discriminant(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => literal_discriminant(expr).and_then(i128::checked_neg),
+        Expr::Group(group) => literal_discriminant(&group.expr),
+        Expr::Paren(paren) => literal_discriminant(&paren.expr),
+        _ => None,
+    }
+}
+
+fn integer_repr_range(repr: IntegerRepr) -> (i128, i128) {
+    match repr {
+        IntegerRepr::U8 => (u8::MIN.into(), u8::MAX.into()),
+        IntegerRepr::I8 => (i8::MIN.into(), i8::MAX.into()),
+        IntegerRepr::U16 => (u16::MIN.into(), u16::MAX.into()),
+        IntegerRepr::I16 => (i16::MIN.into(), i16::MAX.into()),
+        IntegerRepr::U32 => (u32::MIN.into(), u32::MAX.into()),
+        IntegerRepr::I32 => (i32::MIN.into(), i32::MAX.into()),
+        // Solana targets are 64 bit
+        IntegerRepr::U64 | IntegerRepr::Usize => (u64::MIN.into(), u64::MAX.into()),
+        IntegerRepr::I64 | IntegerRepr::Isize => (i64::MIN.into(), i64::MAX.into()),
+        IntegerRepr::U128 => (0, i128::MAX),
+        IntegerRepr::I128 => (i128::MIN, i128::MAX),
+    }
+}
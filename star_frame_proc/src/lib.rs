@@ -1,6 +1,8 @@
 #![allow(clippy::let_and_return)]
 mod account_set;
 mod align1;
+#[cfg(feature = "expand")]
+mod expand;
 mod get_seeds;
 mod hash;
 mod idl;
@@ -16,10 +18,7 @@ mod util;
 mod zero_copy;
 
 use proc_macro_error2::proc_macro_error;
-use syn::{
-    parse::Nothing, parse_macro_input, punctuated::Punctuated, token::Comma, DeriveInput, Item,
-    ItemEnum, ItemFn, ItemImpl, LitStr,
-};
+use syn::{parse::Nothing, parse_macro_input, DeriveInput, Item, ItemEnum, ItemFn, ItemImpl};
 
 /// Derives `AccountSet` lifecycle traits and `AccountSetToIdl` for a struct.
 ///
@@ -43,9 +42,21 @@ use syn::{
 /// in field attributes should match the types available from the instruction's decode, validate,
 /// run, and cleanup argument types.
 ///
+/// # Generics
+///
+/// Type and const generics, with their bounds, are forwarded to every generated implementation. Fields that use a type
+/// parameter and the default `()` argument get the matching trait bound added automatically, e.g.
+/// `Account<M>: AccountSetDecode<'a, ()>` for `pub struct TokenAccounts<M: MintType> { mint: Account<M> }`. Fields
+/// with an explicit `arg`, or implementations with custom `generics`, need their bounds written out.
+///
+/// # Nesting
+///
+/// Fields can be other account sets, which decode from the same account slice in order. A set cannot contain itself
+/// (e.g., `Box<Self>` or `Option<Box<MySet>>`), since decoding it would never terminate; this is a compile error.
+///
 /// # Struct-level Attributes
 ///
-/// ## `#[account_set(skip_client_account_set, skip_cpi_account_set, skip_default_decode, skip_default_validate, skip_default_cleanup, skip_default_idl)]`
+/// ## `#[account_set(skip_client_account_set, skip_cpi_account_set, skip_default_decode, skip_default_validate, skip_default_cleanup, skip_default_idl, skip_duplicate_check)]`
 ///
 /// Controls which implementations are generated:
 /// - `skip_client_account_set` - Skips generating `ClientAccountSet` implementation
@@ -54,6 +65,8 @@ use syn::{
 /// - `skip_default_validate` - Skips generating default `AccountSetValidate` implementation
 /// - `skip_default_cleanup` - Skips generating default `AccountSetCleanup` implementation
 /// - `skip_default_idl` - Skips generating default IDL implementations
+/// - `skip_duplicate_check` - Leaves the set's accounts out of the instruction's duplicate account check, which flags
+///   two writable accounts, or a signer and a writable non-signer account, sharing a key
 ///
 /// ## `#[decode(id = <str>, arg = <type>, generics = <generics>, inline_always)]`
 ///
@@ -61,17 +74,22 @@ use syn::{
 /// - `id = <str>` - Unique identifier for this decode variant (optional, defaults to no id)
 /// - `arg = <type>` - Type of argument passed to decode functions
 /// - `generics = <generics>` - Additional generic parameters for this decode implementation
-/// - `inline_always` - Whether to add `#[inline(always)]` to the decode implementation (by default `#[inline]` is added)
+/// - `inline_always` - Whether to add `#[inline(always)]` to the decode implementation (by default `#[inline]` is added,
+///   or `#[inline(always)]` with star_frame's `aggressive_inline` feature)
 ///
-/// ## `#[validate(id = <str>, arg = <type>, generics = <generics>, before_validation = <expr>, extra_validation = <expr>, inline_always)]`
+/// ## `#[validate(id = <str>, arg = <type>, generics = <generics>, before_validation = <expr>, extra_validation = <expr>, after_validation = <expr>, inline_always)]`
 ///
 /// Define custom validation implementations:
 /// - `id = <str>` - Unique identifier for this validate variant (optional, defaults to no id)
 /// - `arg = <type>` - Type of argument passed to validate functions
 /// - `generics = <generics>` - Additional generic parameters for this validate implementation
-/// - `before_validation = <expr>` - Expression to execute before field validation
+/// - `before_validation = <expr>` - Expression to execute before field validation, e.g., to load shared state into
+///   `arg` or `ctx` once for every field's validate `arg` to use
 /// - `extra_validation = <expr>` - Expression to execute after field validation
-/// - `inline_always` - Whether to add `#[inline(always)]` to the validate implementation (by default `#[inline]` is added)
+/// - `after_validation = <expr>` - Expression to execute after `extra_validation`, once every field is validated and
+///   the funder and recipient are set
+/// - `inline_always` - Whether to add `#[inline(always)]` to the validate implementation (by default `#[inline]` is added,
+///   or `#[inline(always)]` with star_frame's `aggressive_inline` feature)
 ///
 /// ## `#[cleanup(id = <str>, generics = <generics>, arg = <type>, extra_cleanup = <expr>, inline_always)]`
 ///
@@ -80,7 +98,8 @@ use syn::{
 /// - `generics = <generics>` - Generic parameters for this cleanup implementation
 /// - `arg = <type>` - Type of argument passed to cleanup functions
 /// - `extra_cleanup = <expr>` - Cleanup expression to execute after field cleanup
-/// - `inline_always` - Whether to add `#[inline(always)]` to the cleanup implementation (by default `#[inline]` is added)
+/// - `inline_always` - Whether to add `#[inline(always)]` to the cleanup implementation (by default `#[inline]` is added,
+///   or `#[inline(always)]` with star_frame's `aggressive_inline` feature)
 ///
 /// ## `#[idl(id = <str>, arg = <type>, generics = <generics>)]`
 ///
@@ -94,6 +113,44 @@ use syn::{
 /// ## `#[account_set(skip = <TokenStream>)]`
 ///
 /// Skip this field during account set processing. The field will be initialized with the provided default value.
+/// Every lifecycle stage (decode, validate, cleanup, client, CPI, and IDL) ignores the field, so it doesn't need to
+/// be an account set. To only skip decoding, use `#[decode(skip = <expr>)]` instead.
+///
+/// ## `#[account_set(remaining)]`
+///
+/// Decode all remaining accounts into this field. The field must be a `Vec` and the last field in the struct,
+/// and only one field can be marked as `remaining`. An explicit `#[decode(arg = ...)]` on the field overrides
+/// the default of consuming every account left.
+///
+/// ## `#[account_set(client_skip)]`
+///
+/// Omit this field from the generated `ClientAccounts` struct, and find its address with
+/// `FindProgramAddress::find_program_address` using the default value of the field's seeds. The field must
+/// implement `HasSeeds` and `HasOwnerProgram` (e.g., a `Seeded` account), and its seeds must implement `Default`,
+/// so this is meant for PDAs whose seeds don't depend on other accounts or arguments, like a global config.
+///
+/// ## `#[account_set(client_default = <expr>)]`
+///
+/// Omit this field from the generated `ClientAccounts` struct, and use `<expr>` instead. The expression is
+/// converted into the field's `ClientAccounts` with `Into`, so `#[account_set(client_default = System::ID)]`
+/// works for a `Program<System>` field.
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// #[derive(AccountSet)]
+/// pub struct TransferAccounts {
+///     pub from: Signer<Mut<SystemAccount>>,
+///     pub to: Mut<SystemAccount>,
+///     #[account_set(client_default = System::ID)]
+///     pub system_program: Program<System>,
+/// }
+///
+/// // `system_program` no longer needs to be provided
+/// fn client_accounts(from: Pubkey, to: Pubkey) -> TransferClientAccounts {
+///     TransferClientAccounts { from, to }
+/// }
+/// # fn main() {}
+/// ```
 ///
 /// ## `#[single_account_set(signer, writable, meta = <expr>, skip_*)]`
 ///
@@ -103,7 +160,9 @@ use syn::{
 /// Options:
 /// - `signer` - Mark this account as a signer
 /// - `writable` - Mark this account as writable
-/// - `meta = <expr>` - Custom metadata expression
+/// - `meta = <expr>` - Override the account's `SingleSetMeta` with `<expr>`, instead of inheriting the field's meta.
+///   The expression is the body of `SingleAccountSet::meta`, so it is evaluated each time account metas are built
+///   for a CPI or client instruction, and can depend on runtime state. Cannot be combined with `signer` or `writable`
 /// - `skip_signed_account` - Skip `SignedAccount` trait implementation
 /// - `skip_writable_account` - Skip `WritableAccount` trait implementation
 /// - `skip_has_inner_type` - Skip `HasInnerType` trait implementation
@@ -112,23 +171,86 @@ use syn::{
 /// - `skip_can_init_seeds` - Skip `CanInitSeeds` trait implementation
 /// - `skip_can_init_account` - Skip `CanInitAccount` trait implementation
 ///
+/// A conditionally writable account:
+/// ```
+/// use star_frame::{account_set::single_set::SingleSetMeta, prelude::*};
+///
+/// fn counter_meta() -> SingleSetMeta {
+///     SingleSetMeta {
+///         writable: std::env::var_os("WRITABLE_COUNTER").is_some(),
+///         ..SystemAccount::meta()
+///     }
+/// }
+///
+/// #[derive(AccountSet)]
+/// pub struct WrappedCounter {
+///     #[single_account_set(meta = counter_meta())]
+///     pub counter: SystemAccount,
+/// }
+/// # fn main() {}
+/// ```
+///
 /// When a field is marked with `#[single_account_set]`, the generated AccountSet implementation will:
 /// - Implement `SingleAccountSet` and delegate to the marked field
 /// - Pass through `CpiAccountSet` and `ClientAccountSet` implementations
 /// - Forward trait implementations like `SignedAccount`, `WritableAccount`, `HasSeeds`, etc.
 ///
-/// ## `#[validate(id = <str>, funder, recipient, skip, requires = [<field>, ...], arg = <expr>, temp = <expr>, arg_ty = <type>, address = <expr>)]`
+/// ## `#[validate(id = <str>, funder, recipient, skip, read_only, requires = [<field>, ...], arg = <expr>, temp = <expr>, arg_ty = <type>, address = <expr>, owner = <expr>)]`
 ///
 /// Pass arguments to field validation:
 /// - `id = <str>` - Which validate variant this field participates in, to enable multiple `AccountSetValidate` implementations
-/// - `funder` - Mark this field as the funder for the Context cache (only one field can be marked as funder)
+/// - `funder` - Mark this field as the funder for the Context cache. Only one field can be marked as funder, unless the
+///   earlier funders are `Option`s, in which case the first one present is used (e.g., an optional payer falling back to
+///   a `Seeded` PDA)
 /// - `recipient` - Mark this field as the recipient for the Context cache (only one field can be marked as recipient)
 /// - `skip` - Skip validation for this field
+/// - `read_only` - Treat this single account field as `Immutable<T>` in the `CpiAccountSet`, `ClientAccountSet`, and
+///   IDL, so it is never passed as writable. Applies to every validate id, so it cannot be combined with `id`. Cannot
+///   be used on `Mut` fields, which are checked to be writable, and `Immutable<Mut<_>>` fields are rejected likewise
 /// - `requires = [<field>, ...]` - List of fields that must be validated before this field
 /// - `arg = <expr>` - Argument to pass to the field's `AccountSetValidate`` function
-/// - `temp = <expr>` - Temporary variable expression to use with `arg` (requires `arg` to be specified)
+/// - `temp = <expr>` - Computes a value once and binds it to `temp` for the field's `arg`, `address`, and `owner`
+///   expressions, so `arg` can borrow from it (requires `arg` to be specified). A `Result` is unwrapped with `?`.
+///   `temp` is only visible to the field it is declared on
 /// - `arg_ty = <type>` - Type of the validation argument. Usually inferred, but can be specified to get better error messages
-/// - `address = <expr>` - Check that the field's key matches this address, expr must return a `&Pubkey`
+/// - `address = <expr>` - Check that the field's key matches this address, expr must return a `Pubkey` or `&Pubkey`.
+///   Errors with `ErrorCode::AddressMismatch`. If the expr doesn't reference `self`, `arg`, `ctx`, or `temp`, the
+///   address is also recorded in the IDL unless `#[idl(address = ...)]` is set
+/// - `owner = <expr>` - Check that the field's account is owned by this program, expr must return a `Pubkey` or `&Pubkey`.
+///   Errors with `ErrorCode::OwnerMismatch`. If the expr doesn't reference `self`, `arg`, `ctx`, or `temp`, the owner
+///   is also recorded in the IDL
+///
+/// ### Validation order
+///
+/// After the duplicate account check, a struct's validation runs in this order:
+///
+/// `before_validation` → field validates → `extra_validation` → `after_validation`
+///
+/// Every field's `arg`, `address`, `owner`, and `temp` expressions are evaluated when that field is validated, so they
+/// can use anything `before_validation` set up. Within a field, `temp` is evaluated first, then the `owner` and
+/// `address` checks, then `arg` and the field's own validation. Fields are validated in declaration order, except that
+/// a field is always validated after the fields in its `requires`, including skipped fields and fields without an
+/// `arg`. A field requiring itself or a cycle of `requires` is a compile error:
+/// ```compile_fail
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// struct Cycle {
+///     #[validate(requires = [b])]
+///     a: AccountInfo,
+///     #[validate(requires = [a])]
+///     b: AccountInfo,
+/// }
+/// ```
+/// ```compile_fail
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// struct SelfRequired {
+///     #[validate(requires = [a])]
+///     a: AccountInfo,
+/// }
+/// ```
 ///
 /// ## `#[decode(id = <str>, arg = <expr>)]`
 ///
@@ -136,6 +258,33 @@ use syn::{
 /// - `id = <str>` - Which decode variant this field participates in, to enable multiple `AccountSetDecode` implementations
 /// - `arg = <expr>` - Argument to pass to the field's `AccountSetDecode` function
 ///
+/// ## `#[decode(skip = <expr>)]`
+///
+/// Initialize this field with `<expr>` instead of decoding it, so it consumes no accounts. The expression can use
+/// the decode `arg` and `ctx`, and is used for every decode id, so it can't be combined with other `#[decode]`
+/// attributes on the field. Unlike `#[account_set(skip)]`, the field is still validated and cleaned up as an
+/// account set (use `#[validate(skip)]` to opt out of validation), but it takes no slot in the client, CPI, and IDL
+/// account lists.
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// struct Computed {
+///     #[account_set(skip = 0)]
+///     value: u64,
+/// }
+///
+/// #[derive(AccountSet)]
+/// #[decode(arg = u64)]
+/// pub struct ComputedAccounts {
+///     pub authority: Signer,
+///     #[decode(skip = Computed { value: arg })]
+///     computed: Computed,
+/// }
+/// ```
+///
 /// ## `#[cleanup(id = <str>, arg = <expr>)]`
 ///
 /// Pass arguments to field cleanup:
@@ -212,6 +361,63 @@ use syn::{
 /// traits to the inner `Account<CounterAccount>`. The `signer` and `writable` flags modify
 /// the account's metadata for CPI and client usage. This will propagate all of the `account_set::modifier`
 /// marker traits from the inner account to the newtype.
+///
+/// ## Optional Accounts
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// pub struct OptionalAccounts {
+///     pub authority: Signer,
+///     // Only decoded and validated when the account is passed in
+///     pub fee_payer: Option<Signer<Mut<SystemAccount>>>,
+///     pub referrer: Option<SystemAccount>,
+/// }
+/// ```
+///
+/// Any account set can be wrapped in an `Option`. A `None` account is passed in as the executing
+/// program's id, and will still consume one account slot. The `ClientAccountSet` and `CpiAccountSet`
+/// accounts are `Option`s of the inner accounts, and the IDL marks the account as optional.
+/// Field attributes are only applied to the inner account set when the account is `Some`.
+///
+/// ## Remaining Accounts
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// pub struct RemainingAccounts {
+///     pub authority: Signer,
+///     #[account_set(remaining)]
+///     pub others: Vec<AccountInfo>,
+/// }
+/// ```
+///
+/// The `ClientAccountSet` takes a `Vec<Pubkey>` for the remaining field, which is appended after the
+/// other accounts, and the IDL emits it as remaining accounts.
+///
+/// ## Account Arrays
+///
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// #[derive(AccountSet)]
+/// pub struct ArrayAccounts<const N: usize> {
+///     pub authority: Signer,
+///     pub accounts: [Mut<SystemAccount>; 4],
+///     pub others: [SystemAccount; N],
+/// }
+/// ```
+///
+/// Array fields decode `N` consecutive accounts. An explicit `arg` in `#[validate]` or `#[cleanup]` is passed
+/// to the array as is: `arg = (<arg>,)` applies the same (`Clone`) arg to every element, and `arg = [<arg>, ...]`
+/// gives each element its own. A literal length must be non-zero, while non-literal lengths such as const
+/// generics are allowed. The `ClientAccountSet` takes an array of the inner client accounts (`[Pubkey; N]` for
+/// single accounts), and Codama emits each element as its own account, suffixed with its index.
 #[proc_macro_error]
 #[proc_macro_derive(
     AccountSet,
@@ -222,16 +428,49 @@ pub fn derive_account_set(input: proc_macro::TokenStream) -> proc_macro::TokenSt
     out.into()
 }
 
+/// Writes the expansion of the `AccountSet` or `InstructionSet` derive on an item to
+/// `target/star_frame_expand/<crate>/<module path>/<ItemName>.rs`, formatted with `prettyplease`. Requires the
+/// `expand` feature and Rust 1.88 or later.
+///
+/// The item itself is left unchanged. The path of the written file is reported as a deprecation warning, since
+/// proc macros can't emit notes on stable. This is meant for debugging derives and should be removed afterwards.
+///
+/// # Example
+/// ```ignore
+/// #[star_frame_expand]
+/// #[derive(AccountSet)]
+/// pub struct MyAccounts {
+///     pub authority: Signer,
+/// }
+/// ```
+#[cfg(feature = "expand")]
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn star_frame_expand(
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    parse_macro_input!(args as Nothing);
+    let out = expand::star_frame_expand_impl(
+        parse_macro_input!(item as Item),
+        proc_macro::Span::call_site().local_file(),
+    );
+    out.into()
+}
+
 /// Derives the `GetSeeds` trait for a struct.
 ///
 /// # Attributes
 ///
-/// ## 1. `#[get_seeds(seed_const = <expr>, skip_idl)]` (item level attribute)
+/// ## 1. `#[get_seeds(seed_const = <expr>, skip_idl, idl_name = <str>)]` (item level attribute)
 ///
 /// ### Syntax
 ///
 /// Attribute takes an `Expr` which resolves to a `&[u8]` seed for the account.
-/// If `skip_idl` is present, the `SeedsToIdl` trait and the `IdlFindSeed` struct will not be derived.
+/// If `skip_idl` is present, the `SeedsToIdl` trait and the `Find<TypeName>` struct will not be derived, even with
+/// the `idl` feature enabled. `GetSeeds` is always derived.
+/// `idl_name` overrides the name of the generated `Find<TypeName>` struct, for example
+/// `#[get_seeds(idl_name = "FindOrder")]` on `OrderAccountSeeds`. It cannot be combined with `skip_idl`.
 ///
 /// ### Usage
 ///
@@ -280,6 +519,55 @@ pub fn derive_account_set(input: proc_macro::TokenStream) -> proc_macro::TokenSt
 ///     key: Pubkey,
 /// }
 /// ```
+///
+/// ## 2. `#[get_seeds(nested)]` (field level attribute)
+///
+/// The field is itself a `GetSeeds` type, and its seeds are spliced into the parent's seeds instead of using the field
+/// as a single seed. In the IDL, the nested variable seeds are prefixed with the field name, and the field in the
+/// generated `Find` struct is a `Vec<IdlFindSeed>`, which can be made with the nested type's `find_seeds`.
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// #[derive(Debug, GetSeeds, Clone)]
+/// #[get_seeds(seed_const = b"market")]
+/// pub struct MarketSeeds {
+///     authority: Pubkey,
+/// }
+///
+/// // Resulting `order.seeds()` is `vec![b"market".as_ref(), order.market.authority.seed(), order.order_id.seed(), &[]];`
+/// #[derive(Debug, GetSeeds, Clone)]
+/// pub struct OrderSeeds {
+///     #[get_seeds(nested)]
+///     market: MarketSeeds,
+///     order_id: u64,
+/// }
+/// ```
+///
+/// ## 3. `#[get_seeds(as_bytes)]` and `#[get_seeds(fixed_len = <int>)]` (field level attribute)
+///
+/// The field's raw bytes are used as the seed via `AsRef<[u8]>`, for `String`, `&str` or byte fields. `as_bytes` seeds
+/// panic in `seeds()` if they are longer than 32 bytes. With
+/// `fixed_len`, the bytes are truncated or zero-padded to `<int>` bytes (at most 32) so names of different lengths
+/// derive deterministic PDAs. Padding is pushed as its own seed, which counts towards the 16 seed limit. In the IDL
+/// these are `VariableString` seeds, and the field in the generated `Find` struct keeps its type as a constant seed.
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// // Resulting `pool.seeds()` is `vec![b"pool".as_ref(), pool.name.as_bytes(), &[]];`
+/// #[derive(Debug, GetSeeds, Clone)]
+/// #[get_seeds(seed_const = b"pool")]
+/// pub struct PoolSeeds {
+///     #[get_seeds(as_bytes)]
+///     name: String,
+/// }
+///
+/// // Resulting `namespace.seeds()` is `vec![b"abc".as_ref(), &[0; 5], &[]];` for a namespace of `"abc"`
+/// #[derive(Debug, GetSeeds, Clone)]
+/// pub struct NamespaceSeeds<'a> {
+///     #[get_seeds(fixed_len = 8)]
+///     namespace: &'a str,
+/// }
+/// ```
 #[proc_macro_error]
 #[proc_macro_derive(GetSeeds, attributes(get_seeds))]
 pub fn derive_get_seeds(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -288,6 +576,31 @@ pub fn derive_get_seeds(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 }
 
 /// Derives `Align1` for a valid type.
+///
+/// Structs and unions require every field to be `Align1`, unless they are `#[repr(packed)]`. Enums must be
+/// `#[repr(u8)]`, which gives them an alignment of 1 when every variant is a unit variant. Non-generic enums with
+/// data variants are checked to have an alignment of 1 at compile time.
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// #[derive(Align1, Copy, Clone)]
+/// #[repr(u8)]
+/// pub enum RobotState {
+///     Idle,
+///     Working,
+///     Charging,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use star_frame::prelude::*;
+/// #[derive(Align1, Copy, Clone)]
+/// #[repr(u16)]
+/// pub enum RobotState {
+///     Idle,
+///     Working,
+/// }
+/// ```
 #[proc_macro_error]
 #[proc_macro_derive(Align1)]
 pub fn derive_align1(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -296,7 +609,7 @@ pub fn derive_align1(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
 /// Derives the `InstructionSet` trait for an enum of instructions.
 ///
-/// It uses a discriminant type of `[u8; 8]`, and derives each item discriminant by taking
+/// By default, it uses a discriminant type of `[u8; 8]`, and derives each item discriminant by taking
 /// the first 8 bytes of the sha256 hash in a compatible way with Anchor.
 ///
 /// # Example
@@ -321,42 +634,189 @@ pub fn derive_align1(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// pub struct CoolIx;
 /// # impl_blank_ix!(CoolIx);
 /// ```
-// todo: add this back once custom reprs are supported
+///
+/// # Attributes
+///
+/// ## `#[ix_set(skip_idl, skip_idl_output, skip_idl_types, repr = <int>, use_repr, fallback = <path>, namespace = <str>, anchor_compat = <bool>)]` (item level attribute)
+///
+/// - `skip_idl_output` - Generate an `InstructionSetToIdl` implementation that doesn't add any instructions to the
+///   `IdlDefinition`. The variants don't need to implement `InstructionToIdl`, but the set can still be used as the
+///   instruction set of a program that generates an IDL
+/// - `skip_idl_types` - Don't generate an `InstructionSetToIdl` implementation
+/// - `skip_idl` - Shorthand for both `skip_idl_output` and `skip_idl_types`
+/// - `namespace = <str>` - Hash sighash discriminants as `"<namespace>:<instruction_name>"` instead of Anchor's
+///   `"global:<instruction_name>"`. The namespace is recorded on each instruction in the IDL
+/// - `anchor_compat = false` - Use the crate name as the namespace, so the discriminants are specific to the program.
+///   Cannot be combined with `namespace`
+/// - `repr = <int>` - Use an integer discriminant of type `<int>` instead of the 8 byte sighash. Discriminants follow
+///   Rust enum semantics, starting at `0` and incrementing from the previous variant unless an explicit `= N` is given.
+///   Explicit values require a matching `#[repr(<int>)]` on the enum. Discriminants that don't fit in `<int>` are a compile error
+/// - `use_repr` - Same as `repr`, but reads the integer type from the enum's `#[repr]`
+/// - `fallback = <path>` - A `fn(data: &[u8], accounts: &[AccountInfo]) -> Result<()>` called when the discriminant
+///   doesn't match any variant or the data is too short to contain one, instead of returning
+///   `ProgramError::InvalidInstructionData`. `data` is the full instruction data, including the discriminant bytes
+///
+/// The `InstructionDiscriminant` implementations, and so each instruction's `DISCRIMINANT`, are always generated,
+/// regardless of the `skip_idl` flags and the `idl` feature.
+///
+/// Using a custom sighash namespace:
+/// ```
+/// use star_frame::impl_blank_ix;
+/// use star_frame::prelude::*;
+///
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl, namespace = "instruction")]
+/// pub enum CustomIxSet {
+///     Transfer(TransferIx),
+/// }
+///
+/// pub struct TransferIx;
+/// # impl_blank_ix!(TransferIx);
+///
+/// assert_eq!(TransferIx::DISCRIMINANT, sighash!(namespace: "instruction", "transfer"));
+/// ```
+///
+/// ## `#[ix_set(idl_arg = <expr>, idl_arg_ty = <ty>)]` (variant level attribute)
+///
+/// The argument (and optionally its type) passed to the variant's `InstructionToIdl` implementation.
+///
+/// ## `#[ix_set(flatten = [<instruction>, ...])]` (variant level attribute)
+///
+/// The variant's type is itself an `InstructionSet` with the same discriminant type, and the list names every
+/// instruction in it, including those of its own flattened sets. The listed discriminants are dispatched to it with the
+/// full instruction data, each instruction gets an `InstructionDiscriminant` implementation for this set so clients can
+/// build it against the parent program, and its instructions are added to the IDL. Discriminants that collide between
+/// the set and its flattened sub-sets, or a list that misses an instruction, are a compile error. `flatten` cannot be
+/// used in a generic instruction set.
+///
+/// ## `#[cfg(...)]` (variant level attribute)
+///
+/// Variants disabled by `#[cfg(...)]` are removed before the derive runs, so they have no discriminant, dispatch
+/// arm, or IDL entry. Sighash discriminants are derived from the variant name and stay the same either way. Integer
+/// discriminants follow the enabled variants, so give the variants after a gated one an explicit `= N` to keep them stable.
+///
+/// Using integer discriminants:
+/// ```
+/// use star_frame::impl_blank_ix;
+/// use star_frame::instruction::InstructionDiscriminant;
+/// use star_frame::prelude::*;
+///
+/// // Example Instructions (which implement `StarFrameInstruction`)
+/// pub struct CoolIx1 {}
+/// pub struct CoolIx2 {}
+/// pub struct CoolIx3 {}
+///
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl, repr = u8)]
+/// #[repr(u8)]
+/// pub enum CoolIxSetU8 {
+///     CoolInstruction1(CoolIx1),
+///     CoolInstruction2(CoolIx2),
+///     CoolInstruction3(CoolIx3) = 100,
+/// }
+/// assert_eq!(<CoolIx1 as InstructionDiscriminant<CoolIxSetU8>>::DISCRIMINANT, 0u8);
+/// assert_eq!(<CoolIx2 as InstructionDiscriminant<CoolIxSetU8>>::DISCRIMINANT, 1u8);
+/// assert_eq!(<CoolIx3 as InstructionDiscriminant<CoolIxSetU8>>::DISCRIMINANT, 100u8);
+///
+/// // The same instructions can be used in multiple instruction sets, since the
+/// // `InstructionDiscriminant` trait is generic over the instruction set.
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl, repr = i32)]
+/// #[repr(i32)]
+/// pub enum CoolIxSetI32 {
+///     CoolInstruction1(CoolIx1) = -999,
+///     CoolInstruction2(CoolIx2),
+///     CoolInstruction3(CoolIx3) = 9999,
+/// }
+/// assert_eq!(<CoolIx1 as InstructionDiscriminant<CoolIxSetI32>>::DISCRIMINANT, -999i32);
+/// assert_eq!(<CoolIx2 as InstructionDiscriminant<CoolIxSetI32>>::DISCRIMINANT, -998i32);
+/// assert_eq!(<CoolIx3 as InstructionDiscriminant<CoolIxSetI32>>::DISCRIMINANT, 9999i32);
+///
+/// # impl_blank_ix!(CoolIx1, CoolIx2, CoolIx3);
+/// ```
+///
+/// Handling unknown instructions:
+/// ```
+/// use star_frame::impl_blank_ix;
+/// use star_frame::prelude::*;
+///
+/// pub struct CoolIx1 {}
+///
+/// fn unknown_instruction(data: &[u8], _accounts: &[AccountInfo]) -> Result<()> {
+///     bail!(
+///         ProgramError::InvalidInstructionData,
+///         "Unknown instruction with data {:?}",
+///         data
+///     )
+/// }
+///
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl, repr = u8, fallback = unknown_instruction)]
+/// pub enum CoolIxSet {
+///     CoolInstruction1(CoolIx1),
+/// }
+///
+/// # impl_blank_ix!(CoolIx1);
+/// ```
+///
+/// Composing instruction sets:
+/// ```
+/// use star_frame::impl_blank_ix;
+/// use star_frame::instruction::InstructionDiscriminant;
+/// use star_frame::prelude::*;
+///
+/// pub struct AdminIx1 {}
+/// pub struct AdminIx2 {}
+/// pub struct UserIx {}
+///
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl)]
+/// pub enum AdminIxSet {
+///     AdminInstruction1(AdminIx1),
+///     AdminInstruction2(AdminIx2),
+/// }
+///
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl)]
+/// pub enum ProgramIxSet {
+///     UserInstruction(UserIx),
+///     #[ix_set(flatten = [AdminIx1, AdminIx2])]
+///     Admin(AdminIxSet),
+/// }
+///
+/// assert_eq!(<ProgramIxSet as InstructionSet>::DISCRIMINANTS.len(), 3);
+/// assert_eq!(
+///     <AdminIx2 as InstructionDiscriminant<ProgramIxSet>>::DISCRIMINANT,
+///     <AdminIx2 as InstructionDiscriminant<AdminIxSet>>::DISCRIMINANT,
+/// );
+/// # impl_blank_ix!(AdminIx1, AdminIx2, UserIx);
+/// ```
+///
+/// ```compile_fail
+/// use star_frame::impl_blank_ix;
+/// use star_frame::prelude::*;
+///
+/// pub struct AdminIx {}
+/// pub struct UserIx {}
+///
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl, repr = u8)]
+/// pub enum AdminIxSet {
+///     AdminInstruction(AdminIx),
+/// }
+///
+/// // `UserIx` and `AdminIx` both have the discriminant `0`
+/// #[derive(InstructionSet)]
+/// #[ix_set(skip_idl, repr = u8)]
+/// pub enum ProgramIxSet {
+///     UserInstruction(UserIx),
+///     #[ix_set(flatten = [AdminIx])]
+///     Admin(AdminIxSet),
+/// }
+///
+/// # impl_blank_ix!(AdminIx, UserIx);
+/// ```
 // todo: add docs for idl stuff
-// Using enum reprs as discriminants:
-// ```
-// use star_frame::impl_blank_ix;
-// use star_frame::prelude::*;
-//
-// // Example Instructions (which implement `StarFrameInstruction`)
-// pub struct CoolIx1 {}
-// pub struct CoolIx3 {}
-// pub struct CoolIx2 {}
-//
-// #[star_frame_instruction_set(u8)]
-// pub enum CoolIxSetU8 {
-//     CoolInstruction1(CoolIx1),
-//     CoolInstruction2(CoolIx2),
-//     CoolInstruction3(CoolIx3) = 100,
-// }
-// assert_eq!(<CoolIx1 as InstructionDiscriminant<CoolIxSetU8>>::DISCRIMINANT, 0u8);
-// assert_eq!(<CoolIx2 as InstructionDiscriminant<CoolIxSetU8>>::DISCRIMINANT, 1u8);
-// assert_eq!(<CoolIx3 as InstructionDiscriminant<CoolIxSetU8>>::DISCRIMINANT, 100u8);
-//
-// // The same instructions can be used in multiple instruction sets, since the
-// // `InstructionDiscriminant` trait is generic over the instruction set.
-// #[star_frame_instruction_set(i32)]
-// pub enum CoolIxSetU32 {
-//     CoolInstruction1(CoolIx1) = -999,
-//     CoolInstruction2(CoolIx2),
-//     CoolInstruction3(CoolIx3) = 9999,
-// }
-// assert_eq!(<CoolIx1 as InstructionDiscriminant<CoolIxSetU32>>::DISCRIMINANT, -999i32);
-// assert_eq!(<CoolIx2 as InstructionDiscriminant<CoolIxSetU32>>::DISCRIMINANT, -998i32);
-// assert_eq!(<CoolIx3 as InstructionDiscriminant<CoolIxSetU32>>::DISCRIMINANT, 9999i32);
-//
-// # impl_blank_ix!(CoolIx1, CoolIx2, CoolIx3);
-// ```
 #[proc_macro_error]
 #[proc_macro_derive(InstructionSet, attributes(ix_set))]
 pub fn star_frame_instruction_set(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -364,20 +824,44 @@ pub fn star_frame_instruction_set(item: proc_macro::TokenStream) -> proc_macro::
     out.into()
 }
 
-/// Derives `ProgramAccount` for a struct.
+/// Derives `ProgramAccount` for a struct or a `#[repr(u8)]` enum.
 ///
 /// This macro generates implementations for account-related traits and optionally `AccountToIdl` and `TypeToIdl`.
 ///
 /// # Attributes
 ///
-/// ## `#[program_account(skip_idl, program = <ty>, seeds = <ty>, discriminant = <expr>)]` (item level attribute)
+/// ## `#[program_account(skip_idl, program = <ty>, any_program, seeds = <ty>, discriminant = <expr>, space = <expr>, space_fn = <path>, version = <u8>, min_version = <u8>, max_version = <u8>, migrate_from = <ty>)]` (item level attribute)
 ///
 /// ### Arguments
 /// - `skip_idl` (presence) - If present, skips generating IDL implementations for this account
 /// - `program` (optional `Type`) - Specifies the program that owns this account type. Defaults to StarFrameDeclaredProgram at root of your crate
 ///    (Defined by the `#[derive(StarFrameProgram)]` macro)
+/// - `any_program` (presence) - If present, skips the owner check when validating the account, so it can be read from
+///   accounts owned by any program. The discriminant is still checked, and `program` still sets the discriminant type
+///   and the owner of newly created accounts
 /// - `seeds` (optional `Type`) - Specifies the seed type used to generate PDAs for this account
-/// - `discriminant` (optional `Expr`) - Custom discriminant value for the account type, overriding the Anchor style sighash
+/// - `discriminant` (optional `Expr`) - Custom discriminant value for the account type, overriding the Anchor style sighash.
+///   Useful for sharing a discriminant with an Anchor program. It is used for the account validation and the IDL, and array
+///   literals must have exactly as many bytes as the program's `account_discriminant`
+/// - `space` (optional `Expr`) - The fixed size of the account data after the discriminant. Defaults to `size_of::<Self>()`
+///   for zero-copy types. Borsh accounts are created with at least this much space
+/// - `space_fn` (optional `Path`) - A `const fn(usize) -> usize` computing the size of the account data after the
+///   discriminant from an init argument. Accounts are created with it using `Create(WithSizeFn(n))`. Cannot be used
+///   with `space`
+/// - `version` (optional `u8`) - Implements `VersionedAccount` with this as the current version, and records it in the
+///   IDL. The discriminant is not changed, so each version must be its own type. Versions above 1 require `migrate_from`
+/// - `min_version`, `max_version` (optional `u8`) - The range of versions accepted by the account, where `max_version`
+///   is the current version. Use instead of `version`. `min_version` defaults to the `min_version` of `migrate_from`,
+///   and setting it equal to `max_version` explicitly rejects older versions
+/// - `migrate_from` (optional `Type`) - The previous version of the account. Accounts stored as the previous version are
+///   migrated in place with `MigrateFrom::migrate_from` when an `Account` is validated. See
+///   `star_frame::account_set::versioned`
+///
+/// ### Generated items
+/// - `pub const SPACE: usize` - The total size of the account including the discriminant, for use with `create_account`.
+///   Using it on a type without a fixed size is a compile error
+/// - `pub const fn space_for(n: usize) -> usize` - The total size of the account for the init argument `n`, including the
+///   discriminant. Only generated with `space_fn`
 ///
 /// ### Usage
 /// ```
@@ -394,12 +878,87 @@ pub fn star_frame_instruction_set(item: proc_macro::TokenStream) -> proc_macro::
 /// pub struct MyAccount {
 ///     pub data: u64,
 /// }
+/// const _: () = assert!(MyAccount::SPACE == 8 + 8);
 ///
 /// #[derive(GetSeeds, Debug, Clone)]
 /// pub struct MyAccountSeeds {
 ///     pub key: Pubkey,
 /// }
 /// ```
+///
+/// # Custom discriminants
+///
+/// The `discriminant` replaces the sighash of `account:<TypeName>`, for example to match an existing Anchor account.
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// # #[derive(StarFrameProgram)]
+/// # #[program(instruction_set = (), id = System::ID, no_entrypoint)]
+/// # pub struct MyProgram;
+/// #[zero_copy(pod)]
+/// #[derive(ProgramAccount, Debug)]
+/// #[program_account(discriminant = [0x01, 0, 0, 0, 0, 0, 0, 0])]
+/// pub struct LegacyAccount {
+///     pub data: u64,
+/// }
+/// ```
+/// A discriminant of the wrong length is a compile error.
+/// ```compile_fail
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// # #[derive(StarFrameProgram)]
+/// # #[program(instruction_set = (), id = System::ID, no_entrypoint)]
+/// # pub struct MyProgram;
+/// #[zero_copy(pod)]
+/// #[derive(ProgramAccount, Debug)]
+/// #[program_account(discriminant = [0x01, 0, 0, 0])]
+/// pub struct LegacyAccount {
+///     pub data: u64,
+/// }
+/// ```
+///
+/// # Enums
+///
+/// Sized enums can be used in an `Account` to model state machines. The enum must be `#[repr(u8)]`, and its tag is
+/// stored directly after the account discriminant, followed by the variant's fields. The tag and variant data are
+/// checked with the enum's `CheckedBitPattern` implementation when the account is read.
+///
+/// For enums with data, `NoUninit` is implemented by this macro. Every field must be `NoUninit` and `Align1`, and
+/// every variant must have the same size, so the account data has no padding bytes. This is checked at compile time.
+/// ```
+/// # fn main() {}
+/// use star_frame::prelude::*;
+///
+/// # #[derive(StarFrameProgram)]
+/// # #[program(instruction_set = (), id = System::ID, no_entrypoint)]
+/// # pub struct MyProgram;
+///
+/// #[zero_copy(pod)]
+/// #[derive(Debug, TypeToIdl)]
+/// pub struct ActiveData {
+///     pub score: u64,
+/// }
+///
+/// #[zero_copy(pod)]
+/// #[derive(Debug, TypeToIdl)]
+/// pub struct ClosedData {
+///     pub closed_at: i64,
+/// }
+///
+/// #[derive(ProgramAccount, Align1, CheckedBitPattern, Copy, Clone, Debug)]
+/// #[repr(u8)]
+/// pub enum GameState {
+///     Active(ActiveData),
+///     Closed(ClosedData),
+/// }
+///
+/// #[derive(AccountSet)]
+/// pub struct GameAccounts {
+///     pub game: Account<GameState>,
+/// }
+/// ```
 #[proc_macro_error]
 #[proc_macro_derive(ProgramAccount, attributes(program_account, type_to_idl))]
 pub fn program_account(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -454,6 +1013,7 @@ pub fn program_account(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 ///     id = <expr>,
 ///     account_discriminant = <ty>,
 ///     closed_account_discriminant = <expr>,
+///     errors = <ty>,
 ///     no_entrypoint,
 ///     no_setup,
 ///     skip_idl
@@ -465,6 +1025,8 @@ pub fn program_account(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 /// or an expression that resolves to a `Pubkey`
 /// - `account_discriminant` - The `AccountDiscriminant` type used for the program. Defaults to `[u8; 8]` (similarly to Anchor)
 /// - `closed_account_discriminant` - The `AccountDiscriminant` value used for closed accounts. Defaults to `[u8::MAX; 8]`
+/// - `errors` - The `#[star_frame_error]` enum whose variants are added to the IDL `errors`. Use a tuple
+/// (`errors = (MyError, OtherError)`) if the program has more than one. Defaults to `()`, which adds no errors.
 /// - `no_entrypoint` - If present, the macro will not generate an entrypoint for the program.
 /// While the generated entrypoint is already feature gated, this may be useful in some cases where features aren't convenient.
 /// - `no_setup` - If present, the macro will not call the `program_setup!` macro. This is useful in libraries that may contain multiple programs.
@@ -492,6 +1054,7 @@ pub fn program(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///     skip_idl,
 ///     skip_phantom_generics,
 ///     skip_init_struct,
+///     serde,
 ///     program = <ty>,
 ///     seeds = <ty>,
 ///     discriminant = <expr>
@@ -503,12 +1066,18 @@ pub fn program(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// - `owned_from_ptr` - Override the function to convert from self to `UnsizedType::Owned`
 /// - `program_account` - Mark as a program account, deriving the `ProgramAccount` and `AccountToIdl` traits
 /// - `skip_idl` - Skips `TypeToIdl`/`AccountToIdl` generation
-/// - `skip_phantom_generics` - Skip phantom generic parameters in the generated Sized struct
+/// - `skip_phantom_generics` - Skip the `PhantomData` field over the struct's generics in the generated Sized struct.
+///   Only use this if every generic parameter is used by a sized field
 /// - `skip_init_struct` - Skip generating initialization struct for `UnsizedInit<MyStructInit>`
+/// - `serde` - Derive `serde::Serialize` and `serde::Deserialize` on the generated `Owned` type. Every owned field
+///   type must implement them. Cannot be used with `owned_type`
 /// - `program` - Override the program that owns this account type
 /// - `seeds` - Seed type for HasSeeds. Requires `program_account` to be present.
 /// - `discriminant` - Custom discriminant value, overrides the Anchor style sighash
 ///
+/// Generic structs are supported, and their bounds are forwarded to every generated item. The generated `Owned` type
+/// (e.g., `TypedListOwned<T>` for `TypedList<T>`) and Sized struct take the same generics.
+///
 /// # Example Struct
 ///
 /// ```
@@ -571,13 +1140,16 @@ pub fn program(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// let mut the_key = data.set_sized_pubkey(new_key)?;
 /// assert!(matches!(**the_key, new_key));
 ///
-/// let _unsized_inner = data.set_unsized(DefaultInit)?;
+/// // The setters return an exclusive wrapper over the new variant's inner type
+/// let mut unsized_inner = data.set_unsized(DefaultInit)?;
+/// unsized_inner.map().insert(new_key, 10)?;
 ///
-/// // You can also call `.get()` to get an exclusive wrapper version of the inner.
+/// // You can also call `.get()` to get an exclusive wrapper version of the current variant's inner.
 /// let MyEnumExclusive::Unsized(mut unsized_inner) = data.get() else {
 ///     panic!("Expected Unsized variant");
 /// };
-/// unsized_inner.map().insert(new_key, 10);
+/// unsized_inner.map().insert(Pubkey::new_unique(), 20)?;
+/// assert_eq!(unsized_inner.map.len(), 2);
 ///
 /// # Ok(())
 /// # }
@@ -605,7 +1177,8 @@ pub fn unsized_type(
 ///
 /// Only methods that take in `&mut self` or `self` directly are permitted. This generates a trait that is implemented on an ExclusiveWrapper around the given type. For multiple separate
 /// impl blocks, add `#[unsized_impl(tag = <str>)]` to avoid trait name collisions. The trait name for public methods is
-/// `<SelfTypeName>ExclusiveImpl<optional_tag>` and for private methods is `<SelfTypeName>ExclusiveImplPrivate<optional_tag>`.
+/// `<SelfTypeName>ExclusiveImpl<optional_tag>`, for `pub(crate)` methods is `<SelfTypeName>ExclusiveImplCrate<optional_tag>`,
+/// and for private methods is `<SelfTypeName>ExclusiveImplPrivate<optional_tag>`. Each trait has the visibility of its methods.
 ///
 /// # Example
 /// ```
@@ -670,10 +1243,28 @@ pub fn unsized_impl(
 ///
 /// If an argument type is not provided, the type will default to `()`.
 ///
+/// ## `#[ix_args(default = <expr>)]` and `#[ix_args(optional)]` (field level attribute)
+///
+/// Falls back to `<expr>` (or [`Default::default`] with `optional`) when the instruction data ends before the field,
+/// so new args can be added without breaking clients that send the older, shorter data. These fields must come after
+/// all other fields, and can be combined with the argument types above (e.g., `#[ix_args(run, optional)]`).
+///
+/// When any field uses these, `BorshDeserialize` is implemented by this macro and should not be derived. The impl
+/// follows the struct's generics, `#[borsh(crate = "...")]`, and fields with `#[borsh(skip)]`, and each default is
+/// added to the field's docs in the IDL.
+///
+/// Generic structs need `#[instruction_args(skip_idl)]`, since the IDL derives don't support generics.
+///
 /// ## `#[instruction_args(skip_idl)]` (item level attribute)
 ///
 /// If present, the macro will not generate a `InstructionToIdl` implementation for the type.
 ///
+/// # Async instructions
+///
+/// With the `async` feature of `star_frame` enabled, the struct is checked to be `Send + 'static` at compile time,
+/// since an `AsyncStarFrameInstruction` holds its args across await points. Borrowing the run arg with
+/// `#[ix_args(&run)]` emits a deprecation warning, since the borrow can't be held across await points either.
+///
 /// # Example
 /// ```
 /// use star_frame::prelude::*;
@@ -772,9 +1363,15 @@ pub fn derive_instruction_args(input: proc_macro::TokenStream) -> proc_macro::To
 ///
 /// # Attributes
 ///
-/// ## `#[type_to_idl(skip)]` (item level attribute)
+/// ## `#[type_to_idl(skip)]` (field level attribute)
+///
+/// If present, this field and all remaining fields will be skipped in the IDL definition. This also works on tuple
+/// struct fields, where the skipped fields are the ones at and after the marked position.
+///
+/// # Tuple Structs
 ///
-/// If present, this field and all remaining fields will be skipped in the IDL definition.
+/// Tuple struct fields are named by position (`field_0`, `field_1`, ...), so newtypes and other tuple structs are
+/// represented as structs in the IDL.
 ///
 /// # Example
 /// ```
@@ -831,11 +1428,21 @@ pub fn derive_instruction_to_idl(input: proc_macro::TokenStream) -> proc_macro::
 ///
 /// ## Arguments
 /// - `accounts: &mut <the account set>` (required) - The mutable reference to the account set to be set as `StarFrameInstruction::Accounts<'decode, 'arg>`
-/// - `run_arg: <the run argument type>` (optional) - The run argument for the instruction. Defaults to `_run_arg: Self::RunArg<'_>`
-/// - `ctx: &mut Context` (optional) - The context for the instruction. Defaults to `_ctx: &mut Context`
+/// - `run_arg: <the run argument type>` (optional) - The run argument for the instruction. Defaults to `_run_arg: Self::RunArg<'_>`.
+///   Any pattern can be used in place of `run_arg`, like `(distance, speed): (u64, u64)`, `Count { amount, .. }: Count`,
+///   or `_: u64` to ignore it. The pattern only binds the argument in the function body, the type must still be the
+///   full `RunArg` type
+/// - `ctx: &mut Context` (optional) - The context for the instruction. Defaults to `_ctx: &mut Context`. `Context` is a
+///   type, so `&mut impl Context` and `&mut dyn Context` error with the expected signature.
 ///
 /// ## Return Type
-/// - `Result<T>` (required) - The return type of the instruction. `T` will be set as `StarFrameInstruction::ReturnType`
+/// - `Result<T>` (required) - The return type of the instruction. `T` will be set as `StarFrameInstruction::ReturnType`,
+///   and is serialized with borsh as the solana return data if it is not empty.
+///
+/// ## Async Instructions
+/// An `async fn` takes `ctx: &mut dyn AsyncContext` instead, and also implements `AsyncStarFrameInstruction` off chain,
+/// so simulations can await async calls like `AsyncContext::fetch_account_data`. `StarFrameInstruction::process` runs
+/// the same body with a `BlockingContext`, where async calls return `ErrorCode::AsyncUnavailable`.
 ///
 /// # Example
 /// ```
@@ -907,7 +1514,8 @@ pub fn star_frame_instruction(
 ///
 /// ## `#[msg("My error message")]` (required variant level attribute)
 ///
-/// Used in the Display implementation and as the name for the IDL ErrorNode. The message must be a string literal.
+/// Used in the Display implementation and as the message for the IDL ErrorNode. The message must be a string literal.
+/// The IDL ErrorNode takes its name from the variant and its docs from the variant's doc comments.
 ///
 /// # Example
 /// ```
@@ -934,6 +1542,8 @@ pub fn star_frame_error(
 ///
 /// Takes in multiple string literals and returns the first 8 bytes of its sha256 hash.
 /// The strings will be concatenated with a `:` separator prior to hashing if multiple are passed in.
+/// A leading `namespace: "<namespace>"` is prepended the same way, which makes the namespace explicit at the call
+/// site.
 ///
 /// # Example
 /// ```
@@ -944,16 +1554,20 @@ pub fn star_frame_error(
 ///
 /// const NAMESPACE_HASH: [u8; 8] = [0x76, 0x03, 0x6f, 0xcc, 0x93, 0xdd, 0x73, 0x10];
 /// assert_eq!(sighash!("global", "other_stuff"), NAMESPACE_HASH);
+///
+/// // hash of "instruction:transfer"
+/// const CUSTOM_NAMESPACE_HASH: [u8; 8] = [0xf9, 0xd3, 0xd9, 0x2f, 0xf4, 0x85, 0x14, 0x04];
+/// assert_eq!(sighash!(namespace: "instruction", "transfer"), CUSTOM_NAMESPACE_HASH);
+/// assert_eq!(sighash!("instruction", "transfer"), CUSTOM_NAMESPACE_HASH);
 /// ```
 #[proc_macro]
 pub fn sighash(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    hash::sighash_impl(parse_macro_input!(input with Punctuated::<LitStr, Comma>::parse_terminated))
-        .into()
+    hash::sighash_impl(parse_macro_input!(input as hash::SighashInput)).into()
 }
 
 /// Convenience wrapper around the common `bytemuck` derives and `repr` attribute.
 ///
-/// Works with structs and enums, but omits the `repr` attribute on enums. This should be the first attribute on the item.
+/// Works with structs and enums. This should be the first attribute on the item.
 ///
 /// # Attributes
 ///
@@ -965,6 +1579,35 @@ pub fn sighash(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// - `skip_packed` - (struct only) skips the `packed` attribute. We still add the `Align1` derive,
 /// so all fields must be `Align1` if used.
 ///
+/// # Enums
+///
+/// Enums are `#[repr(u8)]`, which is added if the enum has no `repr`. Enums can have data variants, stored as the `u8`
+/// tag followed by the variant's fields. Every field must be `Align1`, and every variant must have the same size so the
+/// enum has no padding bytes, which is checked at compile time. The variant with tag `0` must be `Zeroable`.
+/// ```
+/// # use star_frame::prelude::*;
+/// #[zero_copy]
+/// #[derive(Debug, PartialEq, Eq)]
+/// enum Message {
+///     Amount(PackedValue<u64>),
+///     Bytes([u8; 8]),
+/// }
+///
+/// let message = Message::Bytes([1; 8]);
+/// let bytes = star_frame::bytemuck::bytes_of(&message);
+/// assert_eq!(bytes, &[1; 9]);
+/// assert_eq!(star_frame::bytemuck::checked::from_bytes::<Message>(bytes), &message);
+/// ```
+/// Variants of different sizes are a compile error.
+/// ```compile_fail
+/// # use star_frame::prelude::*;
+/// #[zero_copy]
+/// enum Message {
+///     Amount(PackedValue<u64>),
+///     Flag(bool),
+/// }
+/// ```
+///
 /// # Example
 /// ```
 /// # use star_frame::prelude::*;
@@ -1,20 +1,88 @@
 use crate::{
     hash::SIGHASH_ACCOUNT_NAMESPACE,
     idl::TypeToIdlArgs,
-    util::{ignore_cfg_module, reject_attributes, Paths},
+    util::{get_repr, ignore_cfg_module, reject_attributes, IntegerRepr, Paths},
 };
 use easy_proc::{find_attr, ArgumentList};
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Type, *};
+use proc_macro_error2::abort;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Type, *};
 
 #[derive(Debug, ArgumentList, Clone, Default)]
 pub struct ProgramAccountArgs {
     #[argument(presence)]
     pub skip_idl: bool,
     pub program: Option<Type>,
+    #[argument(presence)]
+    pub any_program: bool,
     pub seeds: Option<Type>,
     pub discriminant: Option<Expr>,
+    pub space: Option<Expr>,
+    pub space_fn: Option<Path>,
+    pub version: Option<LitInt>,
+    pub min_version: Option<LitInt>,
+    pub max_version: Option<LitInt>,
+    pub migrate_from: Option<Type>,
+}
+
+/// The resolved `version`, `min_version`, `max_version`, and `migrate_from` arguments.
+struct VersionArgs {
+    version: u8,
+    min_version: Option<u8>,
+    migrate_from: Option<Type>,
+}
+
+fn parse_version(lit: &LitInt) -> u8 {
+    lit.base10_parse()
+        .unwrap_or_else(|e| abort!(lit, "Versions must be a `u8`: {}", e))
+}
+
+fn version_args(args: &ProgramAccountArgs) -> Option<VersionArgs> {
+    let version = match (&args.version, &args.max_version) {
+        (Some(version), None) => {
+            let parsed = parse_version(version);
+            if parsed > 1 && args.migrate_from.is_none() {
+                abort!(
+                    version,
+                    "`version` above 1 requires `migrate_from` to migrate older versions. Use `min_version = {0}, max_version = {0}` to reject them instead",
+                    parsed
+                );
+            }
+            parsed
+        }
+        (None, Some(version)) => parse_version(version),
+        (Some(version), Some(_)) => {
+            abort!(version, "`version` and `max_version` cannot both be set")
+        }
+        (None, None) => {
+            if let Some(min_version) = &args.min_version {
+                abort!(min_version, "`min_version` requires `max_version`");
+            }
+            if let Some(migrate_from) = &args.migrate_from {
+                abort!(migrate_from, "`migrate_from` requires `version`");
+            }
+            return None;
+        }
+    };
+    let min_version = args.min_version.as_ref().map(|lit| {
+        let min_version = parse_version(lit);
+        if min_version > version {
+            abort!(lit, "`min_version` must not be greater than the current version");
+        }
+        if min_version < version && args.migrate_from.is_none() {
+            abort!(
+                lit,
+                "`min_version` below the current version requires `migrate_from` to migrate older versions"
+            );
+        }
+        min_version
+    });
+    Some(VersionArgs {
+        version,
+        min_version,
+        migrate_from: args.migrate_from.clone(),
+    })
 }
 
 pub fn program_account_impl(input: DeriveInput) -> TokenStream {
@@ -24,7 +92,154 @@ pub fn program_account_impl(input: DeriveInput) -> TokenStream {
         .map(ProgramAccountArgs::parse_arguments)
         .unwrap_or_default();
 
-    program_account_impl_inner(input, args)
+    let enum_impl = match &input.data {
+        Data::Enum(data_enum) => enum_layout_impl(&input, data_enum),
+        _ => None,
+    };
+    if let (Some(space), Some(_)) = (&args.space, &args.space_fn) {
+        abort!(space, "`space` and `space_fn` cannot both be set");
+    }
+    let space_impl = space_impl(&input, args.space_fn.as_ref());
+    let program_account_impl = program_account_impl_inner(input, args);
+
+    quote! {
+        #program_account_impl
+        #space_impl
+        #enum_impl
+    }
+}
+
+fn space_impl(input: &DeriveInput, space_fn: Option<&Path>) -> TokenStream {
+    Paths!(prelude, size_of);
+    let ident = &input.ident;
+    let (impl_gen, ty_gen, where_clause) = input.generics.split_for_impl();
+    let message = format!(
+        "`{ident}` does not have a fixed size. Set it with `#[program_account(space = <expr>)]`"
+    );
+    let discriminant_size = quote! {
+        #size_of::<<<Self as #prelude::HasOwnerProgram>::OwnerProgram as #prelude::StarFrameProgram>::AccountDiscriminant>()
+    };
+
+    let space_fn_impl = space_fn.map(|space_fn| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_gen #ident #ty_gen #where_clause {
+                /// The total size of the account for the init argument `n`, including the discriminant.
+                pub const fn space_for(n: usize) -> usize {
+                    #discriminant_size + #space_fn(n)
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_gen #prelude::ProgramAccountSpaceFn for #ident #ty_gen #where_clause {
+                #[inline]
+                fn data_space_for(n: usize) -> usize {
+                    #space_fn(n)
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[automatically_derived]
+        #[allow(dead_code)]
+        impl #impl_gen #ident #ty_gen #where_clause {
+            /// The total size of the account, including the discriminant.
+            pub const SPACE: usize = match <Self as #prelude::ProgramAccount>::DATA_SPACE {
+                Some(space) => #discriminant_size + space,
+                None => ::core::panic!(#message),
+            };
+        }
+
+        #space_fn_impl
+    }
+}
+
+/// Sized enums are stored as their `u8` tag followed by the variant's fields, directly after the account discriminant.
+///
+/// The tag and variant are checked by the enum's `CheckedBitPattern` implementation. Enums with data can't derive
+/// `NoUninit`, so it is implemented here after asserting that every variant fills the enum, leaving no padding bytes.
+fn enum_layout_impl(input: &DeriveInput, data_enum: &DataEnum) -> Option<TokenStream> {
+    Paths!(bytemuck, crate_name, size_of);
+
+    if get_repr(&input.attrs).repr.as_integer() != Some(IntegerRepr::U8) {
+        abort!(
+            input.ident,
+            "`ProgramAccount` enums must be `#[repr(u8)]`. The tag is stored after the account discriminant"
+        );
+    }
+    if data_enum
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit))
+    {
+        // Fieldless enums can derive `NoUninit` directly
+        return None;
+    }
+    if !input.generics.params.is_empty() {
+        abort!(
+            input.generics,
+            "`ProgramAccount` does not support generic enums with data"
+        );
+    }
+
+    let ident = &input.ident;
+    let variant_sizes = data_enum.variants.iter().map(|variant| {
+        let field_tys = variant.fields.iter().map(|field| &field.ty);
+        let message = format!(
+            "Variant `{}` of `{ident}` does not fill the enum. Every variant of a `ProgramAccount` enum with data must have the same size so the account has no padding bytes",
+            variant.ident,
+        );
+        quote! {
+            ::core::assert!(#size_of::<#ident>() == 1 #(+ #size_of::<#field_tys>())*, #message);
+        }
+    });
+    let field_tys = data_enum
+        .variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter().map(|field| &field.ty));
+
+    Some(quote! {
+        const _: () = {
+            #(#variant_sizes)*
+        };
+        #(
+            #crate_name::static_assertions::assert_impl_all!(#field_tys: #bytemuck::NoUninit, #crate_name::align1::Align1);
+        )*
+        // SAFETY:
+        // The enum is `#[repr(u8)]`, so each variant is laid out as a `#[repr(C)]` struct of the tag followed by its
+        // fields. All fields are `Align1` and `NoUninit`, and every variant's size is asserted to equal the enum's size,
+        // so there are no padding bytes.
+        #[automatically_derived]
+        unsafe impl #bytemuck::NoUninit for #ident {}
+    })
+}
+
+/// Checks that an array literal `discriminant` has one element per byte of the owner program's `AccountDiscriminant`,
+/// so a wrong length points at the attribute instead of only failing with a type mismatch.
+fn discriminant_len_check(
+    input: &DeriveInput,
+    owner_program: &Type,
+    discriminant: &Expr,
+) -> Option<TokenStream> {
+    Paths!(prelude, size_of);
+    let Expr::Array(array) = discriminant else {
+        return None;
+    };
+    // The check is a free constant, so it can't name the account's generics
+    if !input.generics.params.is_empty() {
+        return None;
+    }
+    let len = array.elems.len();
+    let message = format!(
+        "`discriminant` has {len} bytes, but it must be exactly the size of the program's `AccountDiscriminant`"
+    );
+    Some(quote_spanned! {discriminant.span()=>
+        const _: () = ::core::assert!(
+            #len == #size_of::<<#owner_program as #prelude::StarFrameProgram>::AccountDiscriminant>(),
+            #message
+        );
+    })
 }
 
 pub fn program_account_impl_inner(input: DeriveInput, args: ProgramAccountArgs) -> TokenStream {
@@ -45,16 +260,92 @@ pub fn program_account_impl_inner(input: DeriveInput, args: ProgramAccountArgs)
     };
 
     let account_ident_str = ident.to_string();
+    let discriminant_len_check = args
+        .discriminant
+        .as_ref()
+        .and_then(|discriminant| discriminant_len_check(&input, &owner_program, discriminant));
+    let version_args = version_args(&args);
     let discriminant = args.discriminant.unwrap_or_else(
         || parse_quote!(#prelude::sighash!(#SIGHASH_ACCOUNT_NAMESPACE, #account_ident_str)),
     );
+    let data_space = match &args.space {
+        Some(space) => quote! { Some(#space) },
+        None => quote! {
+            {
+                #[allow(unused_imports)]
+                use #prelude::UnknownAccountDataSpace as _;
+                #prelude::AccountDataSpace::<Self>::SPACE
+            }
+        },
+    };
+    let migrate_account_info = version_args
+        .as_ref()
+        .filter(|version_args| version_args.migrate_from.is_some())
+        .map(|_| {
+            quote! {
+                #[inline]
+                fn migrate_account_info(info: #prelude::AccountInfo) -> #prelude::Result<()> {
+                    #prelude::migrate_account::<Self>(info)
+                }
+            }
+        });
+    let any_owner = args
+        .any_program
+        .then(|| quote! { const ANY_OWNER: bool = true; });
     let program_account_impl = quote! {
         #[automatically_derived]
         impl #impl_gen #prelude::ProgramAccount for #ident #ty_gen #where_clause {
             const DISCRIMINANT: <Self::OwnerProgram as #prelude::StarFrameProgram>::AccountDiscriminant = #discriminant;
+            const DATA_SPACE: Option<usize> = #data_space;
+            #any_owner
+            #migrate_account_info
         }
+        #discriminant_len_check
     };
 
+    let versioned_account_impl = version_args.as_ref().map(|version_args| {
+        let version = version_args.version;
+        let min_version = match (version_args.min_version, &version_args.migrate_from) {
+            (Some(min_version), _) => quote! { #min_version },
+            (None, Some(previous)) => {
+                quote! { <#previous as #prelude::VersionedAccount>::MIN_VERSION }
+            }
+            (None, None) => quote! { #version },
+        };
+        let migrate_data = version_args.migrate_from.as_ref().map(|previous| {
+            quote! {
+                fn migrate_data(data: &[u8], min_version: u8) -> #prelude::Result<Option<Vec<u8>>> {
+                    #prelude::migrate_data_from::<Self, #previous>(data, min_version)
+                }
+            }
+        });
+        // The check is a free constant, so it can't name the account's generics
+        let previous_version_check = version_args
+            .migrate_from
+            .as_ref()
+            .filter(|_| input.generics.params.is_empty())
+            .map(|previous| {
+                let message = format!(
+                    "The version of `migrate_from` must be less than the version of `{ident}`"
+                );
+                quote_spanned! {previous.span()=>
+                    const _: () = ::core::assert!(
+                        <#previous as #prelude::VersionedAccount>::VERSION < #version,
+                        #message
+                    );
+                }
+            });
+        quote! {
+            #[automatically_derived]
+            impl #impl_gen #prelude::VersionedAccount for #ident #ty_gen #where_clause {
+                const VERSION: u8 = #version;
+                const MIN_VERSION: u8 = #min_version;
+                #migrate_data
+            }
+            #previous_version_check
+        }
+    });
+
     let has_seeds_impl = args.seeds.as_ref().map(|seeds| {
         quote! {
             #[automatically_derived]
@@ -64,6 +355,10 @@ pub fn program_account_impl_inner(input: DeriveInput, args: ProgramAccountArgs)
         }
     });
 
+    let idl_version = match &version_args {
+        Some(_) => quote! { Some(<Self as #prelude::VersionedAccount>::VERSION) },
+        None => quote! { None },
+    };
     let idl_impl =(!args.skip_idl).then( || {
         let type_args = TypeToIdlArgs {
             program: Some(owner_program.clone()),
@@ -89,6 +384,10 @@ pub fn program_account_impl_inner(input: DeriveInput, args: ProgramAccountArgs)
                         discriminant: <Self as #prelude::ProgramAccount>::discriminant_bytes(),
                         type_id,
                         seeds: #seeds,
+                        space: <Self as #prelude::ProgramAccount>::DATA_SPACE.map(|space| {
+                            space + ::core::mem::size_of::<<Self::OwnerProgram as #prelude::StarFrameProgram>::AccountDiscriminant>()
+                        }),
+                        version: #idl_version,
                     };
                     let namespace = idl_definition.add_account(idl_account, <Self::AssociatedProgram as #prelude::ProgramToIdl>::crate_metadata().name)?;
                     Ok(#prelude::IdlAccountId {
@@ -108,6 +407,7 @@ pub fn program_account_impl_inner(input: DeriveInput, args: ProgramAccountArgs)
     quote! {
         #owner_program_impl
         #program_account_impl
+        #versioned_account_impl
         #has_seeds_impl
         #idl_impl
     }
@@ -1,7 +1,10 @@
 use proc_macro2::TokenStream;
 use proc_macro_error2::abort;
-use quote::{format_ident, quote};
-use syn::{parse_quote, FnArg, ItemFn, ReturnType, Type, TypeReference};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, FnArg, ItemFn, PatType, ReturnType, Type, TypeImplTrait, TypeParamBound,
+    TypeReference, TypeTraitObject,
+};
 
 use crate::util::{reject_generics, Paths};
 
@@ -36,9 +39,16 @@ pub fn star_frame_instruction_impl(mut input: ItemFn) -> TokenStream {
     let run_arg = input_iter
         .next()
         .unwrap_or_else(|| parse_quote!(_run_arg: Self::RunArg<'_>));
-    let ctx = input_iter
-        .next()
-        .unwrap_or_else(|| parse_quote!(_ctx: &mut Context));
+    let is_async = input.sig.asyncness.is_some();
+    let ctx = input_iter.next().unwrap_or_else(|| {
+        if is_async {
+            parse_quote!(_ctx: &mut dyn #prelude::AsyncContext)
+        } else {
+            parse_quote!(_ctx: &mut Context)
+        }
+    });
+
+    check_ctx(&ctx, is_async);
 
     let Type::Reference(TypeReference {
         mutability: Some(_),
@@ -61,12 +71,101 @@ pub fn star_frame_instruction_impl(mut input: ItemFn) -> TokenStream {
         ident.set_span(joined_span);
     }
 
+    let process = if is_async {
+        async_process(&input, &account_set, &run_arg, &ctx)
+    } else {
+        quote!(#input)
+    };
+
+    let async_impl = is_async.then(|| {
+        input.sig.ident = format_ident!("process_async");
+        quote! {
+            #[cfg(not(target_os = "solana"))]
+            impl #prelude::AsyncStarFrameInstruction for #ident {
+                #input
+            }
+        }
+    });
+
     quote! {
         impl #prelude::#star_frame_instruction_ident for #ident {
             type ReturnType = <#return_type as #prelude::IxReturnType>::ReturnType;
             type Accounts<'decode, 'arg> = #account_set_type;
 
-            #input
+            #process
+        }
+
+        #async_impl
+    }
+}
+
+/// Suggests the expected ctx type when it is written as `impl Context` or `dyn Context` (or `impl AsyncContext` for
+/// async instructions), which don't match the `StarFrameInstruction` signature. Other types, including aliases of
+/// `Context`, are left for the compiler to check.
+fn check_ctx(ctx: &FnArg, is_async: bool) {
+    let (name, suggestion) = if is_async {
+        ("AsyncContext", "ctx: &mut dyn AsyncContext")
+    } else {
+        ("Context", "ctx: &mut Context")
+    };
+    let FnArg::Typed(PatType { ty, .. }) = ctx else {
+        abort!(ctx, "Expected the ctx argument to be `{}`", suggestion);
+    };
+    let Type::Reference(TypeReference { elem, .. }) = &**ty else {
+        return;
+    };
+    let bounds = match &**elem {
+        Type::ImplTrait(TypeImplTrait { bounds, .. }) => bounds,
+        // `dyn AsyncContext` is the expected async form
+        Type::TraitObject(TypeTraitObject { bounds, .. }) if !is_async => bounds,
+        _ => return,
+    };
+    let names_context = bounds.iter().any(|bound| {
+        matches!(bound, TypeParamBound::Trait(bound) if bound
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name))
+    });
+    if names_context {
+        abort!(ty, "Expected the ctx argument to be `{}`", suggestion);
+    }
+}
+
+/// The synchronous `process` of an `async fn` instruction, which runs the body with a `BlockingContext`.
+fn async_process(
+    input: &ItemFn,
+    account_set: &PatType,
+    run_arg: &FnArg,
+    ctx: &FnArg,
+) -> TokenStream {
+    Paths!(prelude);
+    let FnArg::Typed(PatType {
+        pat: ctx_pat,
+        ty: ctx_ty,
+        ..
+    }) = ctx
+    else {
+        abort!(ctx, "Expected ctx to be of type `&mut dyn AsyncContext`");
+    };
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let output = &sig.output;
+
+    quote! {
+        #(#attrs)*
+        #vis fn process(
+            #account_set,
+            #run_arg,
+            __ctx: &mut #prelude::Context,
+        ) #output {
+            let mut __blocking_ctx = #prelude::BlockingContext(__ctx);
+            let #ctx_pat: #ctx_ty = &mut __blocking_ctx;
+            #prelude::block_on_instruction(async move #block)
         }
     }
 }
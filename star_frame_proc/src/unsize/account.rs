@@ -15,6 +15,7 @@ pub fn account_impl(input: &DeriveInput, args: &UnsizedTypeArgs) -> TokenStream
                 program: args.program.clone(),
                 discriminant: args.discriminant.clone(),
                 seeds: args.seeds.clone(),
+                ..Default::default()
             },
         )
     } else if !args.skip_idl {
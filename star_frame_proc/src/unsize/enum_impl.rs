@@ -267,8 +267,11 @@ impl UnsizedEnumContext {
             }
         });
 
+        let serde_attributes = super::owned_serde_attributes(args);
+
         quote! {
             #(#[#additional_owned])*
+            #serde_attributes
             #[derive(#prelude::DeriveWhere)]
             #[derive_where(Debug, Copy, Clone, Eq, Hash, Ord, PartialEq, PartialOrd; #(for<#lt> <#filtered_variant_types as #prelude::UnsizedType>::Owned,)*)]
             #[repr(#integer_repr)]
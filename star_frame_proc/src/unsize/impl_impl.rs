@@ -1,7 +1,7 @@
 use crate::util::{combine_gen, new_generic, new_lifetime, strip_inner_attributes, Paths};
 use easy_proc::ArgumentList;
 use heck::ToUpperCamelCase;
-use itertools::{Either, Itertools};
+use itertools::Itertools;
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error2::abort;
 use quote::{format_ident, quote};
@@ -69,23 +69,43 @@ pub fn unsized_impl_impl(item: ItemImpl, args: TokenStream) -> TokenStream {
         })
         .collect_vec();
 
-    let (mut pub_exclusive_fns, mut priv_exclusive_fns): (Vec<_>, Vec<_>) =
-        impl_fns.into_iter().partition_map(|mut item_fn| {
-            if !matches!(item_fn.sig.inputs.first(), Some(FnArg::Receiver(Receiver { reference: Some(..), mutability: Some(..), .. } | Receiver { reference: None, .. }))) {
-                abort!(item_fn.sig, "`unsized_impl` requires all methods take self directly or a mutable reference to self argument, i.e., `fn foo(&mut self, ...)` or `fn foo(self, ...)`");
-            }
-            match item_fn.vis {
-                Visibility::Public(_) => {
-                    item_fn.vis = Visibility::Inherited;
-                    Either::Left(item_fn)
+    let mut pub_exclusive_fns = vec![];
+    let mut crate_exclusive_fns = vec![];
+    let mut priv_exclusive_fns = vec![];
+    for mut item_fn in impl_fns {
+        if !matches!(
+            item_fn.sig.inputs.first(),
+            Some(FnArg::Receiver(
+                Receiver {
+                    reference: Some(..),
+                    mutability: Some(..),
+                    ..
+                } | Receiver {
+                    reference: None,
+                    ..
                 }
-                Visibility::Restricted(_) => abort!(
-                    item_fn.vis,
-                    "`exclusive` functions can only have pub or inherited visibilities"
-                ),
-                Visibility::Inherited => Either::Right(item_fn),
+            ))
+        ) {
+            abort!(item_fn.sig, "`unsized_impl` requires all methods take self directly or a mutable reference to self argument, i.e., `fn foo(&mut self, ...)` or `fn foo(self, ...)`");
+        }
+        match &item_fn.vis {
+            Visibility::Public(_) => {
+                item_fn.vis = Visibility::Inherited;
+                pub_exclusive_fns.push(item_fn);
             }
-        });
+            Visibility::Restricted(restricted)
+                if restricted.in_token.is_none() && restricted.path.is_ident("crate") =>
+            {
+                item_fn.vis = Visibility::Inherited;
+                crate_exclusive_fns.push(item_fn);
+            }
+            Visibility::Restricted(_) => abort!(
+                item_fn.vis,
+                "`exclusive` functions can only have pub, pub(crate), or inherited visibilities"
+            ),
+            Visibility::Inherited => priv_exclusive_fns.push(item_fn),
+        }
+    }
 
     let parent_lt = new_lifetime(&item.generics, Some("parent"));
     let top_lt = new_lifetime(&item.generics, Some("top"));
@@ -98,6 +118,7 @@ pub fn unsized_impl_impl(item: ItemImpl, args: TokenStream) -> TokenStream {
     let impl_for = quote!(#prelude::ExclusiveWrapper<#parent_lt, #top_lt, #ptr_lt, #p>);
 
     let pub_exclusive_ident = format_ident!("{self_ident}ExclusiveImpl{tag_str}");
+    let crate_exclusive_ident = format_ident!("{self_ident}ExclusiveImplCrate{tag_str}");
     let priv_exclusive_ident = format_ident!("{self_ident}ExclusiveImplPrivate{tag_str}");
 
     let make_exclusive = |vis: Visibility, trait_ident: Ident, funcs: &mut [ImplItemFn]| {
@@ -133,6 +154,13 @@ pub fn unsized_impl_impl(item: ItemImpl, args: TokenStream) -> TokenStream {
             &mut pub_exclusive_fns,
         )
     });
+    let crate_exclusive = (!crate_exclusive_fns.is_empty()).then(|| {
+        make_exclusive(
+            parse_quote!(pub(crate)),
+            crate_exclusive_ident,
+            &mut crate_exclusive_fns,
+        )
+    });
     let priv_exclusive = (!priv_exclusive_fns.is_empty()).then(|| {
         make_exclusive(
             Visibility::Inherited,
@@ -143,6 +171,7 @@ pub fn unsized_impl_impl(item: ItemImpl, args: TokenStream) -> TokenStream {
 
     quote! {
         #pub_exclusive
+        #crate_exclusive
         #priv_exclusive
     }
 }
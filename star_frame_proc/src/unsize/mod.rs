@@ -1,9 +1,9 @@
-use crate::util::GetGenerics;
+use crate::util::{get_crate_name, GetGenerics};
 use easy_proc::ArgumentList;
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use proc_macro_error2::abort;
-use quote::ToTokens;
+use quote::{quote, ToTokens};
 use syn::{
     bracketed,
     parse::{Parse, ParseStream},
@@ -60,6 +60,8 @@ pub struct UnsizedTypeArgs {
     pub skip_phantom_generics: bool,
     #[argument(presence)]
     pub skip_init_struct: bool,
+    #[argument(presence)]
+    pub serde: bool,
     pub program: Option<Type>,
     pub seeds: Option<Type>,
     pub discriminant: Option<Expr>,
@@ -74,6 +76,12 @@ impl UnsizedTypeArgs {
                     "owned_attributes cannot be used with a custom owned_type"
                 )
             }
+            if self.serde {
+                abort!(
+                    owned_type,
+                    "serde cannot be used with a custom owned_type. Derive serde on the owned_type instead"
+                )
+            }
             if self.owned_from_ptr.is_none() {
                 abort!(
                     owned_type,
@@ -95,6 +103,18 @@ impl UnsizedTypeArgs {
     }
 }
 
+/// The serde derives for the generated `Owned` type when `serde` is set.
+fn owned_serde_attributes(args: &UnsizedTypeArgs) -> Option<TokenStream> {
+    args.serde.then(|| {
+        let crate_name = get_crate_name();
+        let serde_crate = quote!(#crate_name::serde).to_string().replace(' ', "");
+        quote! {
+            #[derive(#crate_name::serde::Serialize, #crate_name::serde::Deserialize)]
+            #[serde(crate = #serde_crate)]
+        }
+    })
+}
+
 pub fn reject_non_ty_gen(item: &impl GetGenerics) {
     let generics = item.get_generics();
     if !generics.lifetimes().collect_vec().is_empty() {
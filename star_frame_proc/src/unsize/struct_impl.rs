@@ -299,10 +299,13 @@ impl UnsizedStructContext {
         let owned_types = get_field_types(owned_fields).collect_vec();
         let lt = new_lifetime(&self.generics, None);
 
+        let serde_attributes = super::owned_serde_attributes(&self.args);
+
         let doc = format!("Owned type for [`{}`]", self.struct_ident);
         quote! {
             #(#[#additional_attributes])*
             #[doc = #doc]
+            #serde_attributes
             #[derive(#prelude::DeriveWhere)]
             #[derive_where(Debug, Copy, Clone, Default, Eq, Hash, Ord, PartialEq, PartialOrd; #(for<#lt> #owned_types,)*)]
             #vis struct #owned_ident #gen #where_clause {
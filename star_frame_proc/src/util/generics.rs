@@ -1,7 +1,7 @@
 use crate::util::Paths;
 use derive_more::{Deref, DerefMut};
 use itertools::Itertools;
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream, TokenTree};
 use proc_macro_error2::abort;
 use quote::{format_ident, quote, ToTokens};
 use syn::{
@@ -195,6 +195,38 @@ pub fn new_generic<G: GetGenerics>(generics: &G, generic_str: Option<&str>) -> I
     new_ident(generic_str, type_idents.chain(const_idents), false)
 }
 
+/// Returns whether `ty` mentions any of the type parameters in `generics`.
+pub fn uses_type_params(ty: &Type, generics: &Generics) -> bool {
+    fn contains(tokens: TokenStream, idents: &[&Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => idents.contains(&&ident),
+            TokenTree::Group(group) => contains(group.stream(), idents),
+            _ => false,
+        })
+    }
+    let idents = generics.type_params().map(|p| &p.ident).collect_vec();
+    !idents.is_empty() && contains(ty.to_token_stream(), &idents)
+}
+
+/// Returns whether `ty` refers to `ident` or `Self` through an unqualified path, e.g., `Box<Self>` or `Option<MySet>`.
+pub fn type_mentions_ident(ty: &Type, ident: &Ident) -> bool {
+    fn contains(tokens: TokenStream, ident: &Ident) -> bool {
+        let mut after_path_sep = false;
+        tokens.into_iter().any(|token| {
+            let found = match &token {
+                TokenTree::Ident(token_ident) => {
+                    !after_path_sep && (token_ident == ident || token_ident == "Self")
+                }
+                TokenTree::Group(group) => contains(group.stream(), ident),
+                _ => false,
+            };
+            after_path_sep = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ':');
+            found
+        })
+    }
+    contains(ty.to_token_stream(), ident)
+}
+
 pub fn reject_generics(item: &impl GetGenerics, error: Option<&str>) {
     let generics = item.get_generics();
     if !generics.params.is_empty() {
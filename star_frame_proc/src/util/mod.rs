@@ -10,7 +10,7 @@ use std::borrow::Borrow;
 use easy_proc::find_attr;
 use heck::ToSnakeCase;
 use itertools::Itertools;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
 use proc_macro_crate::{crate_name, FoundCrate};
 use proc_macro_error2::abort;
 use quote::{format_ident, quote, ToTokens};
@@ -34,37 +34,67 @@ pub fn get_crate_name() -> TokenStream {
     }
 }
 
+/// Collects the `///` and `/** */` doc comments in `attrs` as a `Vec<String>` expression, one entry per line.
+///
+/// Doc attributes that aren't string literals, like `#[doc(hidden)]` or `#[doc = include_str!(...)]`, are skipped.
 pub fn get_docs<'a>(attrs: impl IntoIterator<Item = &'a Attribute>) -> Expr {
     let doc_strings = attrs
         .into_iter()
         .filter(|a| a.path().is_ident("doc"))
-        .map(|a: &'a Attribute| {
-            if let Meta::NameValue(MetaNameValue {
+        .filter_map(|a: &'a Attribute| match &a.meta {
+            Meta::NameValue(MetaNameValue {
                 value:
                     Expr::Lit(ExprLit {
                         lit: Lit::Str(str), ..
                     }),
                 ..
-            }) = &a.meta
-            {
-                str
-            } else {
-                abort!(a, "Expected doc attribute to be a name value pair")
-            }
+            }) => Some(str.value()),
+            _ => None,
         })
-        .map(|s| {
-            let string = s.value();
-            string.trim().to_string()
+        .flat_map(|string| {
+            string
+                .split('\n')
+                .map(|line| line.trim().to_string())
+                .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
     parse_quote! { vec![#(#doc_strings.to_string()),*] }
 }
 
+/// Sets the span of every token in `tokens`, including within groups, to `span`.
+///
+/// Used to point errors in generated code at the user's input, e.g. with `Span::call_site().located_at(input_span)`.
+pub fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut new_group = Group::new(group.delimiter(), respan(group.stream(), span));
+                new_group.set_span(span);
+                token = TokenTree::Group(new_group);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
+
 pub fn is_doc_attribute(attribute: &impl Borrow<Attribute>) -> bool {
     attribute.borrow().path().is_ident("doc")
         && attribute.borrow().meta.require_name_value().is_ok()
 }
 
+/// Returns `#[inline(always)]` if `always` is set or star_frame's `aggressive_inline` feature is enabled, otherwise
+/// `#[inline]`.
+pub fn inline_attr(always: bool) -> TokenStream {
+    if always || cfg!(feature = "aggressive_inline") {
+        quote!(#[inline(always)])
+    } else {
+        quote!(#[inline])
+    }
+}
+
 pub fn get_doc_attributes(attrs: &[Attribute]) -> Vec<Attribute> {
     attrs.iter().filter(is_doc_attribute).cloned().collect_vec()
 }
@@ -373,6 +403,35 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn test_type_mentions_ident() {
+        let ident: Ident = parse_quote!(MySet);
+        let mentions: [Type; 6] = [
+            parse_quote!(MySet),
+            parse_quote!(Self),
+            parse_quote!(Box<MySet>),
+            parse_quote!(Mut<Option<Box<Self>>>),
+            parse_quote!((AccountInfo, MySet)),
+            parse_quote!([MySet; 2]),
+        ];
+        for ty in mentions {
+            assert!(type_mentions_ident(&ty, &ident), "{}", ty.to_token_stream());
+        }
+        let no_mentions: [Type; 4] = [
+            parse_quote!(AccountInfo),
+            parse_quote!(other::MySet),
+            parse_quote!(MySetAccounts),
+            parse_quote!(Vec<Signer>),
+        ];
+        for ty in no_mentions {
+            assert!(
+                !type_mentions_ident(&ty, &ident),
+                "{}",
+                ty.to_token_stream()
+            );
+        }
+    }
+
     #[test]
     fn test_strip_attributes_struct() {
         let mut struct_item: ItemStruct = parse_quote! {
@@ -2,9 +2,9 @@ use easy_proc::ArgumentList;
 use proc_macro2::TokenStream;
 use proc_macro_error2::abort;
 use quote::quote;
-use syn::{parse_quote, Data, DeriveInput};
+use syn::{parse_quote, punctuated::Punctuated, Data, DeriveInput, Fields, Path, Token};
 
-use crate::util::Paths;
+use crate::util::{get_repr, IntegerRepr, Paths, Repr};
 
 #[derive(ArgumentList, Default, Debug)]
 #[repr(C)]
@@ -31,7 +31,7 @@ pub fn zero_copy_impl(input: DeriveInput, args: TokenStream) -> TokenStream {
         if args.pod {
             abort!(
                 enum_data.enum_token,
-                "`#[zero_copy(pod)]` cannot be used on enums"
+                "`#[zero_copy(pod)]` cannot be used on enums, since not every tag is a valid variant"
             );
         }
         if args.skip_packed {
@@ -40,7 +40,14 @@ pub fn zero_copy_impl(input: DeriveInput, args: TokenStream) -> TokenStream {
                 "`#[zero_copy(skip_packed)]` cannot be used on enums"
             );
         }
-        quote!()
+        match get_repr(&input.attrs).repr {
+            Repr::Rust => quote! { #[repr(u8)] },
+            Repr::Integer(IntegerRepr::U8) => quote!(),
+            _ => abort!(
+                input.ident,
+                "`#[zero_copy]` enums must be `#[repr(u8)]`, which is added if no `repr` is set"
+            ),
+        }
     } else {
         let packed = (!args.skip_packed).then(|| quote! { packed, });
         quote! { #[repr(C, #packed)] }
@@ -48,13 +55,78 @@ pub fn zero_copy_impl(input: DeriveInput, args: TokenStream) -> TokenStream {
 
     let remaining_derives = if args.pod {
         quote! { #bytemuck::Pod }
+    } else if derives_program_account(&input) && has_data_variants(&input) {
+        // `ProgramAccount` implements `NoUninit` for enums with data itself
+        quote! { #bytemuck::CheckedBitPattern }
     } else {
         quote! { #bytemuck::CheckedBitPattern, #bytemuck::NoUninit }
     };
+    let variant_size_checks = variant_size_checks(&input);
 
     quote! {
         #[derive(#copy, #clone, #prelude::Align1, #bytemuck::Zeroable, #remaining_derives)]
         #repr
         #input
+        #variant_size_checks
+    }
+}
+
+fn has_data_variants(input: &DeriveInput) -> bool {
+    match &input.data {
+        Data::Enum(data_enum) => data_enum
+            .variants
+            .iter()
+            .any(|variant| !matches!(variant.fields, Fields::Unit)),
+        _ => false,
+    }
+}
+
+fn derives_program_account(input: &DeriveInput) -> bool {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| {
+            path.segments
+                .last()
+                .is_some_and(|segment| segment.ident == "ProgramAccount")
+        })
+}
+
+/// Asserts that every variant of an enum with data fills the enum, so `NoUninit` has no padding bytes to reject.
+///
+/// `NoUninit` would fail to compile either way, but this names the variant that is too small.
+fn variant_size_checks(input: &DeriveInput) -> Option<TokenStream> {
+    Paths!(size_of);
+    // `ProgramAccount` asserts the same for its enums
+    if !has_data_variants(input)
+        || !input.generics.params.is_empty()
+        || derives_program_account(input)
+    {
+        return None;
     }
+    let Data::Enum(data_enum) = &input.data else {
+        return None;
+    };
+    let ident = &input.ident;
+    let checks = data_enum.variants.iter().map(|variant| {
+        let field_tys = variant.fields.iter().map(|field| &field.ty);
+        let message = format!(
+            "Variant `{}` of `{ident}` does not fill the enum. Every variant of a `#[zero_copy]` enum must have the same size",
+            variant.ident,
+        );
+        quote! {
+            ::core::assert!(#size_of::<#ident>() == 1 #(+ #size_of::<#field_tys>())*, #message);
+        }
+    });
+    Some(quote! {
+        const _: () = {
+            #(#checks)*
+        };
+    })
 }
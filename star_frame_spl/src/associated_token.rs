@@ -30,8 +30,18 @@ impl AssociatedToken {
 
     /// Find the associated token address for the given wallet and mint, with a bump.
     pub fn find_address_with_bump(wallet: &Pubkey, mint: &KeyFor<MintAccount>) -> (Pubkey, u8) {
+        Self::find_address_for_token_program(wallet, mint.pubkey(), &Token::ID)
+    }
+
+    /// Find the associated token address and bump for the given wallet and mint, owned by `token_program`
+    /// (e.g. [`Token2022`](crate::token_2022::Token2022)).
+    pub fn find_address_for_token_program(
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> (Pubkey, u8) {
         Pubkey::find_program_address(
-            &[wallet.as_ref(), Token::ID.as_ref(), mint.pubkey().as_ref()],
+            &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
             &Self::ID,
         )
     }
@@ -49,11 +59,23 @@ impl StarFrameProgram for AssociatedToken {
     const ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
 }
 
+/// The IDL seeds of an associated token account, for use in `#[idl(arg = Seeds(FindAtaSeeds { .. }))]`.
+#[derive(Debug, Clone)]
+pub struct FindAssociatedTokenSeeds {
+    pub wallet: FindSeed<Pubkey>,
+    pub mint: FindSeed<Pubkey>,
+    /// The token program of the mint. Defaults to the [`Token`] program if `None`, use
+    /// `Some(seed_const(Token2022::ID))` or a path to the token program for other token programs.
+    pub token_program: Option<FindSeed<Pubkey>>,
+}
+
+pub type FindAtaSeeds = FindAssociatedTokenSeeds;
+
 #[cfg(all(feature = "idl", not(target_os = "solana")))]
 mod idl_impl {
     use super::*;
     use star_frame::{
-        idl::{FindIdlSeeds, FindSeed, SeedsToIdl},
+        idl::{FindIdlSeeds, SeedsToIdl},
         star_frame_idl::seeds::{IdlFindSeed, IdlSeed, IdlSeeds},
     };
 
@@ -68,7 +90,6 @@ mod idl_impl {
     }
 
     pub type AtaSeeds = AssociatedTokenSeeds;
-    pub type FindAtaSeeds = FindAssociatedTokenSeeds;
 
     impl GetSeeds for AssociatedTokenSeeds {
         fn seeds(&self) -> Vec<&[u8]> {
@@ -109,16 +130,15 @@ mod idl_impl {
         }
     }
 
-    #[derive(Debug, Clone)]
-    pub struct FindAssociatedTokenSeeds {
-        pub wallet: FindSeed<Pubkey>,
-        pub mint: FindSeed<Pubkey>,
-    }
     impl FindIdlSeeds for FindAssociatedTokenSeeds {
         fn find_seeds(&self) -> star_frame::IdlResult<Vec<IdlFindSeed>> {
+            let token_program = self.token_program.as_ref().map_or_else(
+                || IdlFindSeed::Const(Token::ID.as_ref().to_vec()),
+                Into::into,
+            );
             Ok(vec![
                 Into::into(&self.wallet),
-                IdlFindSeed::Const(Token::ID.as_ref().to_vec()),
+                token_program,
                 Into::into(&self.mint),
             ])
         }
@@ -287,6 +307,12 @@ pub mod state {
         pub mint: &'a KeyFor<MintAccount>,
     }
 
+    /// Creates the associated token account for `wallet` and `mint` with the associated token program.
+    ///
+    /// With [`CreateIfNeeded`], the create CPI is skipped if the account is already owned by the [`Token`]
+    /// program, and it is validated as the associated token account for `wallet` and `mint` instead. Anyone can
+    /// create an associated token account for any wallet, so prefer [`CreateIfNeeded`] when the account may
+    /// have been created ahead of time.
     #[derive(Debug, Clone, Copy)]
     pub struct InitAta<'a, WalletInfo, MintInfo>
     where
@@ -1,6 +1,26 @@
 #![allow(unexpected_cfgs)]
 #[cfg(feature = "token")]
 pub mod associated_token;
+#[cfg(feature = "metadata")]
+pub mod metadata;
 pub mod pod;
 #[cfg(feature = "token")]
 pub mod token;
+#[cfg(feature = "token")]
+pub mod token_2022;
+
+/// Common token program types.
+#[cfg(feature = "token")]
+pub mod prelude {
+    pub use crate::{
+        associated_token::AssociatedToken,
+        pod::PodOption,
+        token::{
+            state::{
+                AccountState, MintAccount, MintAccountData, TokenAccount, TokenAccountData,
+                ValidateMint, ValidateToken,
+            },
+            Token,
+        },
+    };
+}
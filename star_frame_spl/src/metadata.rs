@@ -0,0 +1,606 @@
+//! Accounts and seeds for the Metaplex Token Metadata program.
+//!
+//! Token Metadata accounts are borsh serialized and prefixed with a one byte [`Key`](state::Key), which is used as the
+//! [`ProgramAccount`] discriminant. The account wrappers in [`state`] validate the owner and key, and deserialize the
+//! data on demand.
+//!
+//! The `Find*Seeds` types are available on every target so they can be imported without a `cfg`, but they are only
+//! used by the IDL.
+use borsh::{BorshDeserialize, BorshSerialize};
+use star_frame::prelude::*;
+
+/// The Metaplex Token Metadata program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+pub struct TokenMetadata;
+
+/// The first seed of every Token Metadata PDA.
+pub const METADATA_PREFIX: &[u8] = b"metadata";
+/// The last seed of master edition and edition PDAs.
+pub const EDITION_SUFFIX: &[u8] = b"edition";
+
+impl TokenMetadata {
+    /// Find the metadata address and bump for the given mint.
+    pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&MetadataSeeds { mint: *mint }.seeds(), &Self::ID)
+    }
+
+    /// Find the master edition address and bump for the given mint. This is also the edition address of a print.
+    pub fn find_master_edition_address(mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&MasterEditionSeeds { mint: *mint }.seeds(), &Self::ID)
+    }
+}
+
+impl StarFrameProgram for TokenMetadata {
+    type InstructionSet = ();
+    type AccountDiscriminant = u8;
+    /// See `mpl_token_metadata::ID`.
+    const ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+/// The seeds of a [`MetadataAccount`](state::MetadataAccount), for use with `Seeded<_, _, TokenMetadata>`.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct MetadataSeeds {
+    pub mint: Pubkey,
+}
+
+impl GetSeeds for MetadataSeeds {
+    fn seeds(&self) -> Vec<&[u8]> {
+        vec![
+            METADATA_PREFIX,
+            TokenMetadata::ID.as_ref(),
+            self.mint.seed(),
+        ]
+    }
+}
+
+/// The seeds of a [`MasterEditionAccount`](state::MasterEditionAccount) or
+/// [`EditionAccount`](state::EditionAccount), for use with `Seeded<_, _, TokenMetadata>`.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct MasterEditionSeeds {
+    pub mint: Pubkey,
+}
+
+impl GetSeeds for MasterEditionSeeds {
+    fn seeds(&self) -> Vec<&[u8]> {
+        vec![
+            METADATA_PREFIX,
+            TokenMetadata::ID.as_ref(),
+            self.mint.seed(),
+            EDITION_SUFFIX,
+        ]
+    }
+}
+
+/// The IDL seeds of a metadata account, for use in `#[idl(arg = Seeds(FindMetadataSeeds { .. }))]`.
+#[derive(Debug, Clone)]
+pub struct FindMetadataSeeds {
+    pub mint: FindSeed<Pubkey>,
+}
+
+/// The IDL seeds of a master edition or edition account, for use in
+/// `#[idl(arg = Seeds(FindMasterEditionSeeds { .. }))]`.
+#[derive(Debug, Clone)]
+pub struct FindMasterEditionSeeds {
+    pub mint: FindSeed<Pubkey>,
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+mod idl_impl {
+    use super::*;
+    use star_frame::{
+        idl::{FindIdlSeeds, SeedsToIdl},
+        star_frame_idl::{
+            seeds::{IdlFindSeed, IdlSeed, IdlSeeds},
+            IdlDefinition,
+        },
+    };
+
+    fn mint_seed(idl_definition: &mut IdlDefinition) -> star_frame::IdlResult<IdlSeed> {
+        Ok(IdlSeed::Variable {
+            name: "mint".to_string(),
+            description: vec![],
+            ty: <Pubkey as TypeToIdl>::type_to_idl(idl_definition)?,
+        })
+    }
+
+    impl SeedsToIdl for MetadataSeeds {
+        fn seeds_to_idl(idl_definition: &mut IdlDefinition) -> star_frame::IdlResult<IdlSeeds> {
+            Ok(IdlSeeds(vec![
+                IdlSeed::Const(METADATA_PREFIX.to_vec()),
+                IdlSeed::Const(TokenMetadata::ID.as_ref().to_vec()),
+                mint_seed(idl_definition)?,
+            ]))
+        }
+    }
+
+    impl SeedsToIdl for MasterEditionSeeds {
+        fn seeds_to_idl(idl_definition: &mut IdlDefinition) -> star_frame::IdlResult<IdlSeeds> {
+            Ok(IdlSeeds(vec![
+                IdlSeed::Const(METADATA_PREFIX.to_vec()),
+                IdlSeed::Const(TokenMetadata::ID.as_ref().to_vec()),
+                mint_seed(idl_definition)?,
+                IdlSeed::Const(EDITION_SUFFIX.to_vec()),
+            ]))
+        }
+    }
+
+    impl FindIdlSeeds for FindMetadataSeeds {
+        fn find_seeds(&self) -> star_frame::IdlResult<Vec<IdlFindSeed>> {
+            Ok(vec![
+                IdlFindSeed::Const(METADATA_PREFIX.to_vec()),
+                IdlFindSeed::Const(TokenMetadata::ID.as_ref().to_vec()),
+                Into::into(&self.mint),
+            ])
+        }
+    }
+
+    impl FindIdlSeeds for FindMasterEditionSeeds {
+        fn find_seeds(&self) -> star_frame::IdlResult<Vec<IdlFindSeed>> {
+            Ok(vec![
+                IdlFindSeed::Const(METADATA_PREFIX.to_vec()),
+                IdlFindSeed::Const(TokenMetadata::ID.as_ref().to_vec()),
+                Into::into(&self.mint),
+                IdlFindSeed::Const(EDITION_SUFFIX.to_vec()),
+            ])
+        }
+    }
+
+    impl ProgramToIdl for TokenMetadata {
+        type Errors = ();
+        fn crate_metadata() -> star_frame::star_frame_idl::CrateMetadata {
+            star_frame::star_frame_idl::CrateMetadata {
+                version: star_frame::star_frame_idl::Version::new(5, 1, 0),
+                name: "mpl_token_metadata".to_string(),
+                docs: vec![],
+                description: None,
+                homepage: None,
+                license: None,
+                repository: None,
+            }
+        }
+    }
+}
+
+pub mod state {
+    use super::*;
+    use star_frame::account_set::modifiers::{HasInnerType, HasOwnerProgram, HasSeeds};
+
+    /// See `mpl_token_metadata::types::Key`. The first byte of every Token Metadata account.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    #[repr(u8)]
+    pub enum Key {
+        Uninitialized,
+        EditionV1,
+        MasterEditionV1,
+        ReservationListV1,
+        MetadataV1,
+        ReservationListV2,
+        MasterEditionV2,
+        EditionMarker,
+        UseAuthorityRecord,
+        CollectionAuthorityRecord,
+        TokenOwnedEscrow,
+        TokenRecord,
+        MetadataDelegate,
+        EditionMarkerV2,
+        HolderDelegate,
+    }
+
+    /// See `mpl_token_metadata::types::Creator`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub struct Creator {
+        pub address: Pubkey,
+        pub verified: bool,
+        /// The creator's share of royalties, in percent.
+        pub share: u8,
+    }
+
+    /// See `mpl_token_metadata::types::Data`.
+    #[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub struct Data {
+        pub name: String,
+        pub symbol: String,
+        pub uri: String,
+        pub seller_fee_basis_points: u16,
+        pub creators: Option<Vec<Creator>>,
+    }
+
+    /// See `mpl_token_metadata::types::TokenStandard`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub enum TokenStandard {
+        NonFungible,
+        FungibleAsset,
+        Fungible,
+        NonFungibleEdition,
+        ProgrammableNonFungible,
+        ProgrammableNonFungibleEdition,
+    }
+
+    /// See `mpl_token_metadata::types::Collection`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub struct Collection {
+        pub verified: bool,
+        pub key: Pubkey,
+    }
+
+    /// See `mpl_token_metadata::types::UseMethod`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub enum UseMethod {
+        Burn,
+        Multiple,
+        Single,
+    }
+
+    /// See `mpl_token_metadata::types::Uses`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub struct Uses {
+        pub use_method: UseMethod,
+        pub remaining: u64,
+        pub total: u64,
+    }
+
+    /// See `mpl_token_metadata::types::CollectionDetails`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub enum CollectionDetails {
+        V1 { size: u64 },
+        V2 { padding: [u8; 8] },
+    }
+
+    /// See `mpl_token_metadata::types::ProgrammableConfig`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+    pub enum ProgrammableConfig {
+        V1 { rule_set: Option<Pubkey> },
+    }
+
+    /// See `mpl_token_metadata::accounts::Metadata`, without the leading [`Key`].
+    ///
+    /// Metadata accounts are allocated at their maximum size and zero padded, so the optional fields added in later
+    /// versions of the program deserialize as `None` for older accounts.
+    #[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, ProgramAccount)]
+    #[program_account(skip_idl, program = TokenMetadata, discriminant = Key::MetadataV1 as u8)]
+    pub struct Metadata {
+        pub update_authority: Pubkey,
+        pub mint: Pubkey,
+        pub data: Data,
+        pub primary_sale_happened: bool,
+        pub is_mutable: bool,
+        pub edition_nonce: Option<u8>,
+        pub token_standard: Option<TokenStandard>,
+        pub collection: Option<Collection>,
+        pub uses: Option<Uses>,
+        pub collection_details: Option<CollectionDetails>,
+        pub programmable_config: Option<ProgrammableConfig>,
+    }
+
+    impl Metadata {
+        /// Deserializes metadata from the account data after the [`Key`], like `mpl_token_metadata`'s
+        /// `safe_deserialize`.
+        ///
+        /// The optional fields were appended in later versions of the Token Metadata program, so accounts can end
+        /// before them or hold stale bytes in their place. Trailing optional fields that are missing or fail to
+        /// deserialize are read as `None`, and any bytes after the last field are ignored.
+        pub fn deserialize_lenient(mut data: &[u8]) -> Result<Self> {
+            /// Reads an optional field, reading it and every later field as `None` if it doesn't deserialize.
+            fn optional<T: BorshDeserialize>(data: &mut &[u8]) -> Option<T> {
+                Option::<T>::deserialize(data).unwrap_or_else(|_| {
+                    *data = &[];
+                    None
+                })
+            }
+
+            let data = &mut data;
+            Ok(Self {
+                update_authority: Pubkey::deserialize(data)?,
+                mint: Pubkey::deserialize(data)?,
+                data: Data::deserialize(data)?,
+                primary_sale_happened: bool::deserialize(data)?,
+                is_mutable: bool::deserialize(data)?,
+                edition_nonce: optional(data),
+                token_standard: optional(data),
+                collection: optional(data),
+                uses: optional(data),
+                collection_details: optional(data),
+                programmable_config: optional(data),
+            })
+        }
+
+        /// The name, without the null padding added by the Token Metadata program.
+        #[must_use]
+        pub fn name(&self) -> &str {
+            self.data.name.trim_end_matches('\0')
+        }
+
+        /// The symbol, without the null padding added by the Token Metadata program.
+        #[must_use]
+        pub fn symbol(&self) -> &str {
+            self.data.symbol.trim_end_matches('\0')
+        }
+
+        /// The uri, without the null padding added by the Token Metadata program.
+        #[must_use]
+        pub fn uri(&self) -> &str {
+            self.data.uri.trim_end_matches('\0')
+        }
+    }
+
+    /// See `mpl_token_metadata::accounts::MasterEdition` (`MasterEditionV2`), without the leading [`Key`].
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize, ProgramAccount,
+    )]
+    #[program_account(skip_idl, program = TokenMetadata, discriminant = Key::MasterEditionV2 as u8)]
+    pub struct MasterEdition {
+        pub supply: u64,
+        pub max_supply: Option<u64>,
+    }
+
+    /// See `mpl_token_metadata::accounts::Edition`, without the leading [`Key`].
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize, ProgramAccount,
+    )]
+    #[program_account(skip_idl, program = TokenMetadata, discriminant = Key::EditionV1 as u8)]
+    pub struct Edition {
+        /// The master edition this edition was printed from.
+        pub parent: Pubkey,
+        pub edition: u64,
+    }
+
+    impl MasterEdition {
+        /// Deserializes a master edition from the account data after the [`Key`], ignoring any trailing bytes.
+        pub fn deserialize_lenient(mut data: &[u8]) -> Result<Self> {
+            Ok(Self::deserialize(&mut data)?)
+        }
+    }
+
+    impl Edition {
+        /// Deserializes an edition from the account data after the [`Key`], ignoring any trailing bytes.
+        pub fn deserialize_lenient(mut data: &[u8]) -> Result<Self> {
+            Ok(Self::deserialize(&mut data)?)
+        }
+    }
+
+    macro_rules! metadata_account {
+        ($(#[$meta:meta])* $account:ident, $data:ident, $seeds:ident) => {
+            $(#[$meta])*
+            #[derive(AccountSet, Debug, Clone)]
+            #[validate(extra_validation = self.validate())]
+            pub struct $account {
+                #[single_account_set(
+                    skip_can_init_account,
+                    skip_has_owner_program,
+                    skip_has_inner_type,
+                    skip_has_seeds
+                )]
+                info: AccountInfo,
+            }
+
+            impl HasOwnerProgram for $account {
+                type OwnerProgram = TokenMetadata;
+            }
+
+            impl HasInnerType for $account {
+                type Inner = $account;
+            }
+
+            impl HasSeeds for $account {
+                type Seeds = $seeds;
+            }
+
+            impl $account {
+                /// Checks that the account is owned by the [`TokenMetadata`] program and has the expected [`Key`].
+                #[inline]
+                pub fn validate(&self) -> Result<()> {
+                    $data::validate_account_info(self.info)
+                }
+
+                /// Deserializes the account data after the [`Key`], ignoring any trailing bytes.
+                #[inline]
+                pub fn data(&self) -> Result<$data> {
+                    $data::deserialize_lenient(&self.account_data()?[core::mem::size_of::<u8>()..])
+                }
+            }
+        };
+    }
+
+    metadata_account!(
+        /// A wrapper around `AccountInfo` for a [`Metadata`] account.
+        ///
+        /// Validate it with `Seeded<MetadataAccount, MetadataSeeds, TokenMetadata>` and
+        /// `#[validate(arg = Seeds(MetadataSeeds { mint: *self.mint.pubkey() }))]` to also check its address.
+        MetadataAccount,
+        Metadata,
+        MetadataSeeds
+    );
+    metadata_account!(
+        /// A wrapper around `AccountInfo` for a [`MasterEdition`] account.
+        MasterEditionAccount,
+        MasterEdition,
+        MasterEditionSeeds
+    );
+    metadata_account!(
+        /// A wrapper around `AccountInfo` for an [`Edition`] account. Editions share the seeds of master editions.
+        EditionAccount,
+        Edition,
+        MasterEditionSeeds
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{state::*, *};
+
+    /// The maximum size of a metadata account, which the Token Metadata program allocates up front.
+    const MAX_METADATA_LEN: usize = 679;
+
+    fn push_padded_string(bytes: &mut Vec<u8>, value: &str, len: usize) {
+        bytes.extend_from_slice(&(len as u32).to_le_bytes());
+        bytes.extend_from_slice(value.as_bytes());
+        bytes.resize(bytes.len() + len - value.len(), 0);
+    }
+
+    /// Lays out a metadata account the way the Token Metadata program writes it, up to `is_mutable`.
+    fn metadata_bytes(update_authority: Pubkey, mint: Pubkey, creator: Pubkey) -> Vec<u8> {
+        let mut bytes = vec![Key::MetadataV1 as u8];
+        bytes.extend_from_slice(update_authority.as_ref());
+        bytes.extend_from_slice(mint.as_ref());
+        push_padded_string(&mut bytes, "Star Atlas #1", 32);
+        push_padded_string(&mut bytes, "SA", 10);
+        push_padded_string(&mut bytes, "https://example.com/1.json", 200);
+        bytes.extend_from_slice(&500u16.to_le_bytes());
+        bytes.extend_from_slice(&[1, 1, 0, 0, 0]);
+        bytes.extend_from_slice(creator.as_ref());
+        bytes.extend_from_slice(&[1, 100]);
+        // `primary_sale_happened` and `is_mutable`
+        bytes.extend_from_slice(&[1, 1]);
+        bytes
+    }
+
+    #[test]
+    fn test_metadata_layout() -> Result<()> {
+        let update_authority = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+
+        let mut bytes = metadata_bytes(update_authority, mint, creator);
+        // `edition_nonce`, `token_standard`, `collection`, `uses`, `collection_details`, and `programmable_config`
+        bytes.extend_from_slice(&[1, 254, 1, 4, 1, 1]);
+        bytes.extend_from_slice(collection.as_ref());
+        bytes.extend_from_slice(&[0, 0, 1, 0, 0]);
+        bytes.resize(MAX_METADATA_LEN, 0);
+
+        assert_eq!(bytes[0], Metadata::DISCRIMINANT);
+        let metadata = Metadata::deserialize_lenient(&bytes[1..])?;
+        assert_eq!(metadata, Metadata::deserialize(&mut &bytes[1..])?);
+        assert_eq!(metadata.update_authority, update_authority);
+        assert_eq!(metadata.mint, mint);
+        assert_eq!(metadata.name(), "Star Atlas #1");
+        assert_eq!(metadata.symbol(), "SA");
+        assert_eq!(metadata.uri(), "https://example.com/1.json");
+        assert_eq!(metadata.data.seller_fee_basis_points, 500);
+        assert_eq!(
+            metadata.data.creators,
+            Some(vec![Creator {
+                address: creator,
+                verified: true,
+                share: 100,
+            }])
+        );
+        assert!(metadata.primary_sale_happened && metadata.is_mutable);
+        assert_eq!(metadata.edition_nonce, Some(254));
+        assert_eq!(
+            metadata.token_standard,
+            Some(TokenStandard::ProgrammableNonFungible)
+        );
+        assert_eq!(
+            metadata.collection,
+            Some(Collection {
+                verified: true,
+                key: collection,
+            })
+        );
+        assert_eq!(metadata.uses, None);
+        assert_eq!(metadata.collection_details, None);
+        assert_eq!(
+            metadata.programmable_config,
+            Some(ProgrammableConfig::V1 { rule_set: None })
+        );
+
+        // Accounts written before the optional fields existed are zero padded
+        let mut bytes = metadata_bytes(update_authority, mint, creator);
+        bytes.resize(MAX_METADATA_LEN, 0);
+        let metadata = Metadata::deserialize_lenient(&bytes[1..])?;
+        assert_eq!(metadata.name(), "Star Atlas #1");
+        assert_eq!(metadata.edition_nonce, None);
+        assert_eq!(metadata.token_standard, None);
+        assert_eq!(metadata.collection, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_lenient() -> Result<()> {
+        let update_authority = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+
+        // Accounts can end before the optional fields, which strict borsh rejects
+        let mut bytes = metadata_bytes(update_authority, mint, creator);
+        bytes.push(1);
+        assert!(Metadata::deserialize(&mut &bytes[1..]).is_err());
+        let metadata = Metadata::deserialize_lenient(&bytes[1..])?;
+        assert_eq!(metadata.mint, mint);
+        assert_eq!(metadata.edition_nonce, None);
+        assert_eq!(metadata.programmable_config, None);
+
+        // An invalid optional field is read as `None` along with every field after it
+        let mut bytes = metadata_bytes(update_authority, mint, creator);
+        bytes.extend_from_slice(&[1, 7, 1, 200]);
+        bytes.resize(MAX_METADATA_LEN, 0xff);
+        let metadata = Metadata::deserialize_lenient(&bytes[1..])?;
+        assert_eq!(metadata.edition_nonce, Some(7));
+        assert_eq!(metadata.token_standard, None);
+        assert_eq!(metadata.collection, None);
+        assert_eq!(metadata.programmable_config, None);
+
+        // The required fields are still checked
+        let bytes = metadata_bytes(update_authority, mint, creator);
+        assert!(Metadata::deserialize_lenient(&bytes[1..bytes.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_edition_layouts() -> Result<()> {
+        let mut bytes = vec![Key::MasterEditionV2 as u8];
+        bytes.extend_from_slice(&3u64.to_le_bytes());
+        bytes.push(1);
+        bytes.extend_from_slice(&10u64.to_le_bytes());
+        assert_eq!(bytes[0], MasterEdition::DISCRIMINANT);
+        assert_eq!(
+            MasterEdition::deserialize(&mut &bytes[1..])?,
+            MasterEdition {
+                supply: 3,
+                max_supply: Some(10),
+            }
+        );
+
+        let parent = Pubkey::new_unique();
+        let mut bytes = vec![Key::EditionV1 as u8];
+        bytes.extend_from_slice(parent.as_ref());
+        bytes.extend_from_slice(&2u64.to_le_bytes());
+        assert_eq!(bytes[0], Edition::DISCRIMINANT);
+        assert_eq!(
+            Edition::deserialize(&mut &bytes[1..])?,
+            Edition { parent, edition: 2 }
+        );
+        // Trailing padding is ignored
+        bytes.resize(bytes.len() + 8, 0);
+        assert_eq!(
+            Edition::deserialize_lenient(&bytes[1..])?,
+            Edition { parent, edition: 2 }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_seeds() {
+        let mint = Pubkey::new_unique();
+        assert_eq!(
+            TokenMetadata::find_metadata_address(&mint),
+            Pubkey::find_program_address(
+                &[b"metadata", TokenMetadata::ID.as_ref(), mint.as_ref()],
+                &TokenMetadata::ID
+            )
+        );
+        assert_eq!(
+            TokenMetadata::find_master_edition_address(&mint),
+            Pubkey::find_program_address(
+                &[
+                    b"metadata",
+                    TokenMetadata::ID.as_ref(),
+                    mint.as_ref(),
+                    b"edition"
+                ],
+                &TokenMetadata::ID
+            )
+        );
+    }
+}
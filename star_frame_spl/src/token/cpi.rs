@@ -0,0 +1,255 @@
+//! Validated CPI helpers for the [`Token`] and [`Token2022`] programs.
+//!
+//! Each helper checks that the passed accounts belong together before building the instruction, and
+//! targets the token program that owns the accounts. The returned [`CpiBuilder`] can be invoked with
+//! [`CpiBuilder::invoke`], [`CpiBuilder::invoke_signed`], or [`CpiBuilder::invoke_signed_by`].
+//!
+//! # Example
+//! ```ignore
+//! cpi::transfer(&accounts.source, &accounts.destination, &accounts.authority, amount)?.invoke()?;
+//! ```
+use crate::{
+    token::{
+        instructions::*,
+        state::{MintAccount, MintAccountData, TokenAccount, TokenAccountData},
+        Token,
+    },
+    token_2022::{
+        state::{Mint2022Account, Token2022Account},
+        Token2022,
+    },
+};
+use star_frame::{cpi::CpiBuilder, prelude::*};
+
+/// Marker for account types with the [`TokenAccountData`] layout.
+pub trait TokenAccountLayout {}
+impl TokenAccountLayout for TokenAccount {}
+impl TokenAccountLayout for Token2022Account {}
+
+/// Marker for account types with the [`MintAccountData`] layout.
+pub trait MintAccountLayout {}
+impl MintAccountLayout for MintAccount {}
+impl MintAccountLayout for Mint2022Account {}
+
+/// A [`SingleAccountSet`] wrapping a [`TokenAccount`] or [`Token2022Account`], such as `Mut<TokenAccount>` or
+/// an `AssociatedTokenAccount`.
+pub trait TokenAccountSet: SingleAccountSet {
+    /// Returns the base token account data, ignoring any Token-2022 extensions.
+    fn token_data(&self) -> Result<Ref<'_, TokenAccountData>> {
+        Ref::try_map(self.account_data()?, |data| {
+            bytemuck::checked::try_from_bytes::<TokenAccountData>(
+                &data[..TokenAccount::LEN.min(data.len())],
+            )
+        })
+        .map_err(|e| e.1.into())
+    }
+}
+
+impl<T> TokenAccountSet for T
+where
+    T: SingleAccountSet + HasInnerType,
+    T::Inner: TokenAccountLayout,
+{
+}
+
+/// A [`SingleAccountSet`] wrapping a [`MintAccount`] or [`Mint2022Account`].
+pub trait MintAccountSet: SingleAccountSet {
+    /// Returns the base mint data, ignoring any Token-2022 extensions.
+    fn mint_data(&self) -> Result<Ref<'_, MintAccountData>> {
+        Ref::try_map(self.account_data()?, |data| {
+            bytemuck::checked::try_from_bytes::<MintAccountData>(
+                &data[..MintAccount::LEN.min(data.len())],
+            )
+        })
+        .map_err(|e| e.1.into())
+    }
+}
+
+impl<T> MintAccountSet for T
+where
+    T: SingleAccountSet + HasInnerType,
+    T::Inner: MintAccountLayout,
+{
+}
+
+/// Returns the program override for accounts owned by `owner`, erroring if it is not a token program.
+fn program_override(owner: &Pubkey, key: &Pubkey) -> Result<Option<&'static Pubkey>> {
+    if *owner == Token::ID {
+        Ok(None)
+    } else if *owner == Token2022::ID {
+        Ok(Some(&Token2022::ID))
+    } else {
+        bail!(
+            ProgramError::InvalidAccountOwner,
+            "Account {} is owned by {}, which is not a token program",
+            key,
+            owner
+        );
+    }
+}
+
+/// Returns the token program owning both `a` and `b`, erroring if they differ.
+fn shared_program(
+    a: &impl SingleAccountSet,
+    b: &impl SingleAccountSet,
+) -> Result<Option<&'static Pubkey>> {
+    let a_owner = a.owner_pubkey();
+    let b_owner = b.owner_pubkey();
+    if a_owner != b_owner {
+        bail!(
+            ProgramError::IncorrectProgramId,
+            "Account {} is owned by {}, but account {} is owned by {}",
+            a.pubkey(),
+            a_owner,
+            b.pubkey(),
+            b_owner
+        );
+    }
+    program_override(&a_owner, a.pubkey())
+}
+
+/// Errors if `token` is not an account for `mint`.
+fn check_mint(token: &impl TokenAccountSet, mint: &Pubkey) -> Result<()> {
+    let token_mint = token.token_data()?.mint;
+    if token_mint.pubkey() != mint {
+        bail!(
+            ProgramError::InvalidAccountData,
+            "TokenAccount {} has mint {}, expected {}",
+            token.pubkey(),
+            token_mint,
+            mint
+        );
+    }
+    Ok(())
+}
+
+/// Builds a [`Transfer`] of `amount` tokens from `from` to `to`, checking that both accounts share a mint and token program.
+pub fn transfer(
+    from: &impl TokenAccountSet,
+    to: &impl TokenAccountSet,
+    authority: &impl SingleAccountSet,
+    amount: u64,
+) -> Result<CpiBuilder<'static, Token, Transfer, TransferAccounts>> {
+    let program = shared_program(from, to)?;
+    check_mint(to, from.token_data()?.mint.pubkey())?;
+    Ok(Token::cpi(
+        Transfer { amount },
+        TransferCpiAccounts {
+            source: *from.account_info(),
+            destination: *to.account_info(),
+            owner: *authority.account_info(),
+        },
+        program,
+    ))
+}
+
+/// Builds a [`TransferChecked`] of `amount` tokens from `from` to `to`, checking that both accounts belong to `mint`
+/// and that `mint` has `decimals` decimals.
+pub fn transfer_checked(
+    from: &impl TokenAccountSet,
+    mint: &impl MintAccountSet,
+    to: &impl TokenAccountSet,
+    authority: &impl SingleAccountSet,
+    amount: u64,
+    decimals: u8,
+) -> Result<CpiBuilder<'static, Token, TransferChecked, TransferCheckedAccounts>> {
+    let program = shared_program(from, to)?;
+    shared_program(from, mint)?;
+    check_mint(from, mint.pubkey())?;
+    check_mint(to, mint.pubkey())?;
+    let mint_decimals = mint.mint_data()?.decimals;
+    if mint_decimals != decimals {
+        bail!(
+            ProgramError::InvalidArgument,
+            "MintAccount {} has {} decimals, expected {}",
+            mint.pubkey(),
+            mint_decimals,
+            decimals
+        );
+    }
+    Ok(Token::cpi(
+        TransferChecked { amount, decimals },
+        TransferCheckedCpiAccounts {
+            source: *from.account_info(),
+            mint: *mint.account_info(),
+            destination: *to.account_info(),
+            owner: *authority.account_info(),
+        },
+        program,
+    ))
+}
+
+/// Builds a [`MintTo`] of `amount` tokens of `mint` into `to`, checking that `to` belongs to `mint`.
+pub fn mint_to(
+    mint: &impl MintAccountSet,
+    to: &impl TokenAccountSet,
+    mint_authority: &impl SingleAccountSet,
+    amount: u64,
+) -> Result<CpiBuilder<'static, Token, MintTo, MintToAccounts>> {
+    let program = shared_program(mint, to)?;
+    check_mint(to, mint.pubkey())?;
+    Ok(Token::cpi(
+        MintTo { amount },
+        MintToCpiAccounts {
+            mint: *mint.account_info(),
+            account: *to.account_info(),
+            mint_authority: *mint_authority.account_info(),
+        },
+        program,
+    ))
+}
+
+/// Builds a [`Burn`] of `amount` tokens from `from`, checking that `from` belongs to `mint`.
+pub fn burn(
+    from: &impl TokenAccountSet,
+    mint: &impl MintAccountSet,
+    authority: &impl SingleAccountSet,
+    amount: u64,
+) -> Result<CpiBuilder<'static, Token, Burn, BurnAccounts>> {
+    let program = shared_program(from, mint)?;
+    check_mint(from, mint.pubkey())?;
+    Ok(Token::cpi(
+        Burn { amount },
+        BurnCpiAccounts {
+            account: *from.account_info(),
+            mint: *mint.account_info(),
+            owner: *authority.account_info(),
+        },
+        program,
+    ))
+}
+
+/// Builds an [`Approve`] letting `delegate` spend up to `amount` tokens from `source`.
+pub fn approve(
+    source: &impl TokenAccountSet,
+    delegate: &impl SingleAccountSet,
+    authority: &impl SingleAccountSet,
+    amount: u64,
+) -> Result<CpiBuilder<'static, Token, Approve, ApproveAccounts>> {
+    let program = program_override(&source.owner_pubkey(), source.pubkey())?;
+    Ok(Token::cpi(
+        Approve { amount },
+        ApproveCpiAccounts {
+            source: *source.account_info(),
+            delegate: *delegate.account_info(),
+            owner: *authority.account_info(),
+        },
+        program,
+    ))
+}
+
+/// Builds a [`Revoke`] clearing the delegate of `source`.
+pub fn revoke(
+    source: &impl TokenAccountSet,
+    authority: &impl SingleAccountSet,
+) -> Result<CpiBuilder<'static, Token, Revoke, RevokeAccounts>> {
+    let program = program_override(&source.owner_pubkey(), source.pubkey())?;
+    Ok(Token::cpi(
+        Revoke,
+        RevokeCpiAccounts {
+            source: *source.account_info(),
+            owner: *authority.account_info(),
+        },
+        program,
+    ))
+}
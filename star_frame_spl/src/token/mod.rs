@@ -1,3 +1,4 @@
+pub mod cpi;
 pub mod instructions;
 pub mod state;
 
@@ -34,3 +35,17 @@ impl ProgramToIdl for Token {
         }
     }
 }
+
+/// The programs implementing the [`Token`] interface, for use with [`Interface`].
+///
+/// ```
+/// # use star_frame::prelude::*;
+/// # use star_frame_spl::{token::{Token, TokenInterface}, token_2022::Token2022};
+/// assert_eq!(TokenInterface::VALID_PROGRAM_IDS, &[Token::ID, Token2022::ID]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+pub struct TokenInterface;
+
+impl ProgramInterface for TokenInterface {
+    const VALID_PROGRAM_IDS: &'static [Pubkey] = &[Token::ID, crate::token_2022::Token2022::ID];
+}
@@ -7,6 +7,7 @@ use crate::{
         },
         Token,
     },
+    token_2022::state::Tk22ExtensionInit,
 };
 use star_frame::{
     account_set::{
@@ -116,46 +117,70 @@ impl MintAccount {
 
     #[inline]
     pub fn validate_mint(&self, validate_mint: ValidateMint) -> Result<()> {
-        let data = self.data()?;
+        self.data()?.validate_mint(self.pubkey(), validate_mint)
+    }
+}
+
+impl MintAccountData {
+    /// Creates initialized mint data.
+    #[must_use]
+    pub fn new(
+        supply: u64,
+        decimals: u8,
+        mint_authority: Option<Pubkey>,
+        freeze_authority: Option<Pubkey>,
+    ) -> Self {
+        Self {
+            mint_authority: mint_authority.into(),
+            supply,
+            decimals,
+            is_initialized: true,
+            freeze_authority: freeze_authority.into(),
+        }
+    }
+
+    /// Validates the mint data against `validate_mint`, using `key` for error messages.
+    #[inline]
+    pub fn validate_mint(&self, key: &Pubkey, validate_mint: ValidateMint) -> Result<()> {
         if let Some(decimals) = validate_mint.decimals {
-            if data.decimals != decimals {
+            if self.decimals != decimals {
                 bail!(
                     ProgramError::InvalidAccountData,
                     "MintAccount {} has decimals {}, expected {}",
-                    self.pubkey(),
-                    data.decimals,
+                    key,
+                    self.decimals,
                     decimals
                 );
             }
         }
         if let Some(authority) = validate_mint.authority {
-            if data.mint_authority != PodOption::some(*authority) {
+            if self.mint_authority != PodOption::some(*authority) {
                 bail!(
                     ProgramError::InvalidAccountData,
                     "MintAccount {} has mint authority {:?}, expected {:?}",
-                    self.pubkey(),
-                    data.mint_authority,
+                    key,
+                    self.mint_authority,
                     authority
                 );
             }
         }
         match validate_mint.freeze_authority {
             FreezeAuthority::None => {
-                if data.freeze_authority.is_some() {
+                if self.freeze_authority.is_some() {
                     bail!(
                         ProgramError::InvalidAccountData,
                         "MintAccount {} has a freeze authority but expected none",
-                        self.pubkey()
+                        key
                     );
                 }
             }
             FreezeAuthority::Some(authority) => {
-                if data.freeze_authority != PodOption::some(*authority) {
+                if self.freeze_authority != PodOption::some(*authority) {
                     bail!(
                         ProgramError::InvalidAccountData,
                         "MintAccount {} has freeze authority {:?}, expected {:?}",
-                        self.pubkey(),
-                        data.freeze_authority,
+                        key,
+                        self.freeze_authority,
                         authority
                     );
                 }
@@ -192,6 +217,9 @@ pub struct InitMint<'a> {
     pub decimals: u8,
     pub mint_authority: &'a Pubkey,
     pub freeze_authority: Option<&'a Pubkey>,
+    /// The Token-2022 extensions to initialize. Must be empty for [`MintAccount`], see
+    /// [`Mint2022Account`](crate::token_2022::state::Mint2022Account).
+    pub extensions: &'a [Tk22ExtensionInit],
 }
 
 impl<'a> From<InitMint<'a>> for ValidateMint<'a> {
@@ -236,6 +264,13 @@ where
         ctx: &Context,
     ) -> Result<bool> {
         let (init_mint, funder) = arg;
+        if !init_mint.extensions.is_empty() {
+            bail!(
+                ProgramError::InvalidArgument,
+                "MintAccount {} does not support Token-2022 extensions",
+                self.pubkey()
+            );
+        }
         if IF_NEEDED && self.owner_pubkey() == Token::ID {
             self.validate()?;
             self.validate_mint(init_mint.into())?;
@@ -380,25 +415,75 @@ impl TokenAccount {
 
     #[inline]
     pub fn validate_token(&self, validate_token: ValidateToken) -> Result<()> {
-        let data = self.data()?;
+        self.data()?.validate_token(self.pubkey(), validate_token)
+    }
+}
+
+impl TokenAccountData {
+    /// Creates initialized token account data with no delegate or close authority.
+    #[must_use]
+    pub fn new(mint: KeyFor<MintAccount>, owner: Pubkey, amount: u64) -> Self {
+        Self {
+            mint,
+            owner,
+            amount,
+            delegate: PodOption::none(),
+            state: AccountState::Initialized,
+            is_native: PodOption::none(),
+            delegated_amount: 0,
+            close_authority: PodOption::none(),
+        }
+    }
+
+    /// The number of tokens in the account.
+    #[must_use]
+    pub fn balance(&self) -> u64 {
+        self.amount
+    }
+
+    #[must_use]
+    pub fn is_frozen(&self) -> bool {
+        self.state == AccountState::Frozen
+    }
+
+    /// Whether the account holds wrapped SOL.
+    #[must_use]
+    pub fn is_native(&self) -> bool {
+        self.is_native.is_some()
+    }
+
+    #[must_use]
+    pub fn delegate(&self) -> Option<Pubkey> {
+        self.delegate.into_option()
+    }
+
+    /// The amount the delegate is approved for, if the account has a delegate.
+    #[must_use]
+    pub fn delegated_amount(&self) -> Option<u64> {
+        self.delegate.is_some().then_some(self.delegated_amount)
+    }
+
+    /// Validates the token account data against `validate_token`, using `key` for error messages.
+    #[inline]
+    pub fn validate_token(&self, key: &Pubkey, validate_token: ValidateToken) -> Result<()> {
         if let Some(mint) = validate_token.mint {
-            if data.mint != mint {
+            if self.mint != mint {
                 bail!(
                     ProgramError::InvalidAccountData,
                     "TokenAccount {} has mint {}, expected {}",
-                    self.pubkey(),
-                    data.mint,
+                    key,
+                    self.mint,
                     mint
                 );
             }
         }
         if let Some(owner) = validate_token.owner {
-            if data.owner != owner {
+            if self.owner != owner {
                 bail!(
                     ProgramError::IncorrectAuthority,
                     "TokenAccount {} has owner {}, expected {}",
-                    self.pubkey(),
-                    data.owner,
+                    key,
+                    self.owner,
                     owner
                 );
             }
@@ -590,4 +675,41 @@ mod tests {
         // TODO: Figure out how to actually test this
         Ok(())
     }
+
+    #[test]
+    fn test_data_helpers() -> Result<()> {
+        let authority = Pubkey::new_unique();
+        let mint = MintAccountData::new(1_000, 6, Some(authority), None);
+        assert!(mint.is_initialized);
+        assert_eq!(mint.mint_authority.into_option(), Some(authority));
+        assert!(mint.freeze_authority.is_none());
+        assert_eq!({ mint.supply }, 1_000);
+
+        let mint_key = KeyFor::new(Pubkey::new_unique());
+        let owner = Pubkey::new_unique();
+        let mut account = TokenAccountData::new(mint_key, owner, 69);
+        assert_eq!(account.balance(), 69);
+        assert!(!account.is_frozen());
+        assert!(!account.is_native());
+        assert_eq!(account.delegate(), None);
+        assert_eq!(account.delegated_amount(), None);
+        account.validate_token(
+            &Pubkey::new_unique(),
+            ValidateToken {
+                mint: Some(mint_key),
+                owner: Some(owner),
+            },
+        )?;
+
+        let delegate = Pubkey::new_unique();
+        account.delegate = PodOption::some(delegate);
+        account.delegated_amount = 42;
+        account.state = AccountState::Frozen;
+        account.is_native = PodOption::some(2_039_280);
+        assert_eq!(account.delegate(), Some(delegate));
+        assert_eq!(account.delegated_amount(), Some(42));
+        assert!(account.is_frozen());
+        assert!(account.is_native());
+        Ok(())
+    }
 }
@@ -0,0 +1,150 @@
+use crate::token::instructions::*;
+use borsh::{BorshDeserialize, BorshSerialize};
+use star_frame::{empty_star_frame_instruction, prelude::*};
+
+/// The Token-2022 instructions. The classic [`TokenInstructionSet`] instructions keep their discriminants,
+/// and extension instructions are prefixed with their extension's discriminant.
+#[derive(Copy, Debug, Clone, PartialEq, Eq, InstructionSet)]
+#[ix_set(use_repr, skip_idl)]
+#[repr(u8)]
+pub enum Token2022InstructionSet {
+    InitializeMint(InitializeMint),
+    InitializeAccount(InitializeAccount),
+    InitializeMultisig(InitializeMultisig),
+    Transfer(Transfer),
+    Approve(Approve),
+    Revoke(Revoke),
+    SetAuthority(SetAuthority),
+    MintTo(MintTo),
+    Burn(Burn),
+    CloseAccount(CloseAccount),
+    FreezeAccount(FreezeAccount),
+    ThawAccount(ThawAccount),
+    TransferChecked(TransferChecked),
+    ApproveChecked(ApproveChecked),
+    MintToChecked(MintToChecked),
+    BurnChecked(BurnChecked),
+    InitializeAccount2(InitializeAccount2),
+    SyncNative(SyncNative),
+    InitializeAccount3(InitializeAccount3),
+    InitializeMultisig2(InitializeMultisig2),
+    InitializeMint2(InitializeMint2),
+    GetAccountDataSize(GetAccountDataSize),
+    InitializeImmutableOwner(InitializeImmutableOwner),
+    AmountToUiAmount(AmountToUiAmount),
+    TransferFeeExtension(InitializeTransferFeeConfig) = 26,
+    InterestBearingMintExtension(InitializeInterestBearingConfig) = 33,
+}
+
+/// Implements borsh for an extension instruction, prefixing its fields with the extension's sub-instruction discriminant.
+macro_rules! extension_instruction_borsh {
+    ($ty:ident = $sub:literal { $($field:ident),* $(,)? }) => {
+        impl BorshSerialize for $ty {
+            fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+                BorshSerialize::serialize(&$sub, writer)?;
+                $(BorshSerialize::serialize(&self.$field, writer)?;)*
+                Ok(())
+            }
+        }
+
+        impl BorshDeserialize for $ty {
+            fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+                let sub_discriminant = u8::deserialize_reader(reader)?;
+                if sub_discriminant != $sub {
+                    return Err(borsh::io::Error::new(
+                        borsh::io::ErrorKind::InvalidData,
+                        concat!("Invalid extension instruction discriminant for ", stringify!($ty)),
+                    ));
+                }
+                Ok(Self {
+                    $($field: BorshDeserialize::deserialize_reader(reader)?,)*
+                })
+            }
+        }
+    };
+}
+
+// initialize transfer fee config
+/// See `spl_token_2022::extension::transfer_fee::instruction::TransferFeeInstruction::InitializeTransferFeeConfig`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, InstructionArgs)]
+#[instruction_args(skip_idl)]
+pub struct InitializeTransferFeeConfig {
+    pub transfer_fee_config_authority: Option<Pubkey>,
+    pub withdraw_withheld_authority: Option<Pubkey>,
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+}
+extension_instruction_borsh!(InitializeTransferFeeConfig = 0u8 {
+    transfer_fee_config_authority,
+    withdraw_withheld_authority,
+    transfer_fee_basis_points,
+    maximum_fee,
+});
+/// Accounts for the [`InitializeTransferFeeConfig`] instruction.
+#[derive(Debug, Clone, AccountSet)]
+pub struct InitializeTransferFeeConfigAccounts {
+    pub mint: Mut<AccountInfo>,
+}
+empty_star_frame_instruction!(
+    InitializeTransferFeeConfig,
+    InitializeTransferFeeConfigAccounts
+);
+
+// initialize interest bearing config
+/// See `spl_token_2022::extension::interest_bearing_mint::instruction::InterestBearingMintInstruction::Initialize`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, InstructionArgs)]
+#[instruction_args(skip_idl)]
+pub struct InitializeInterestBearingConfig {
+    pub rate_authority: OptionalPubkey,
+    pub rate: i16,
+}
+extension_instruction_borsh!(InitializeInterestBearingConfig = 0u8 { rate_authority, rate });
+/// Accounts for the [`InitializeInterestBearingConfig`] instruction.
+#[derive(Debug, Clone, AccountSet)]
+pub struct InitializeInterestBearingConfigAccounts {
+    pub mint: Mut<AccountInfo>,
+}
+empty_star_frame_instruction!(
+    InitializeInterestBearingConfig,
+    InitializeInterestBearingConfigAccounts
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use star_frame::client::star_frame_instruction_data;
+
+    #[test]
+    fn test_extension_instruction_data() -> Result<()> {
+        let authority = Pubkey::new_unique();
+        let data = star_frame_instruction_data::<Token2022InstructionSet, _>(
+            &InitializeTransferFeeConfig {
+                transfer_fee_config_authority: Some(authority),
+                withdraw_withheld_authority: None,
+                transfer_fee_basis_points: 50,
+                maximum_fee: 1_000,
+            },
+        )?;
+        let mut expected = vec![26, 0, 1];
+        expected.extend_from_slice(authority.as_ref());
+        expected.push(0);
+        expected.extend_from_slice(&50u16.to_le_bytes());
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        assert_eq!(data, expected);
+
+        let data = star_frame_instruction_data::<Token2022InstructionSet, _>(
+            &InitializeInterestBearingConfig {
+                rate_authority: OptionalPubkey::NONE,
+                rate: -25,
+            },
+        )?;
+        let mut expected = vec![33, 0];
+        expected.extend_from_slice(&[0; 32]);
+        expected.extend_from_slice(&(-25i16).to_le_bytes());
+        assert_eq!(data, expected);
+
+        // Wrong sub-instruction discriminant
+        assert!(InitializeTransferFeeConfig::try_from_slice(&[1; 13]).is_err());
+        Ok(())
+    }
+}
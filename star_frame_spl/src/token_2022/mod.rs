@@ -0,0 +1,32 @@
+pub mod instructions;
+pub mod state;
+
+// Avoid name collisions with glob
+use star_frame::prelude::*;
+
+/// The Token-2022 program. It shares the classic [`Token`](crate::token::Token) instructions and adds extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+pub struct Token2022;
+
+impl StarFrameProgram for Token2022 {
+    type InstructionSet = instructions::Token2022InstructionSet;
+    type AccountDiscriminant = ();
+    /// See `spl_token_2022::ID`.
+    const ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
+#[cfg(all(feature = "idl", not(target_os = "solana")))]
+impl ProgramToIdl for Token2022 {
+    type Errors = ();
+    fn crate_metadata() -> star_frame::star_frame_idl::CrateMetadata {
+        star_frame::star_frame_idl::CrateMetadata {
+            version: star_frame::star_frame_idl::Version::new(9, 0, 0),
+            name: "spl_token_2022".to_string(),
+            docs: vec![],
+            description: None,
+            homepage: None,
+            license: None,
+            repository: None,
+        }
+    }
+}
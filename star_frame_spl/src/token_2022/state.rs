@@ -0,0 +1,675 @@
+use crate::{
+    token::{
+        instructions::{
+            InitializeAccount3, InitializeAccount3CpiAccounts, InitializeMint2,
+            InitializeMint2CpiAccounts,
+        },
+        state::{
+            AccountState, InitMint, InitToken, MintAccount, MintAccountData, TokenAccount,
+            TokenAccountData, ValidateMint, ValidateToken,
+        },
+    },
+    token_2022::{
+        instructions::{
+            InitializeInterestBearingConfig, InitializeInterestBearingConfigCpiAccounts,
+            InitializeTransferFeeConfig, InitializeTransferFeeConfigCpiAccounts,
+        },
+        Token2022,
+    },
+};
+use star_frame::{
+    account_set::{
+        modifiers::{CanInitAccount, HasInnerType, HasOwnerProgram},
+        CanFundRent, CanSystemCreateAccount as _,
+    },
+    bytemuck,
+    errors::ErrorCode,
+    pinocchio::account_info::Ref,
+    prelude::*,
+};
+use std::ops::Range;
+
+/// The offset of the account type in accounts with extensions. The base mint is padded to the size of a token account.
+const ACCOUNT_TYPE_OFFSET: usize = TokenAccount::LEN;
+/// The size of the type and length header before each extension.
+const TLV_HEADER_LEN: usize = 4;
+/// The size of a multisig account. Mints with extensions are padded so they never have this size.
+const MULTISIG_LEN: usize = 355;
+
+/// See `spl_token_2022::extension::AccountType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum AccountType {
+    Mint = 1,
+    Account = 2,
+}
+
+/// A Token-2022 extension, stored after the base mint or token account data.
+pub trait Tk22Extension: Pod + Align1 {
+    /// See `spl_token_2022::extension::ExtensionType`.
+    const EXTENSION_TYPE: u16;
+}
+
+/// See `spl_token_2022::extension::transfer_fee::TransferFee`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Pod, Zeroable, Align1)]
+#[repr(C, packed)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_points: u16,
+}
+
+/// See `spl_token_2022::extension::transfer_fee::TransferFeeConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Pod, Zeroable, Align1)]
+#[repr(C, packed)]
+pub struct TransferFeeConfig {
+    pub transfer_fee_config_authority: OptionalPubkey,
+    pub withdraw_withheld_authority: OptionalPubkey,
+    pub withheld_amount: u64,
+    pub older_transfer_fee: TransferFee,
+    pub newer_transfer_fee: TransferFee,
+}
+
+impl Tk22Extension for TransferFeeConfig {
+    const EXTENSION_TYPE: u16 = 1;
+}
+
+impl TransferFeeConfig {
+    /// Returns the transfer fee that applies in `epoch`.
+    #[must_use]
+    pub fn transfer_fee(&self, epoch: u64) -> TransferFee {
+        let newer_transfer_fee = self.newer_transfer_fee;
+        if epoch >= { newer_transfer_fee.epoch } {
+            newer_transfer_fee
+        } else {
+            self.older_transfer_fee
+        }
+    }
+}
+
+/// See `spl_token_2022::extension::transfer_fee::TransferFeeAmount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Pod, Zeroable, Align1)]
+#[repr(C, packed)]
+pub struct TransferFeeAmount {
+    pub withheld_amount: u64,
+}
+
+impl Tk22Extension for TransferFeeAmount {
+    const EXTENSION_TYPE: u16 = 2;
+}
+
+/// See `spl_token_2022::extension::interest_bearing_mint::InterestBearingConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Pod, Zeroable, Align1)]
+#[repr(C, packed)]
+pub struct InterestBearingConfig {
+    pub rate_authority: OptionalPubkey,
+    pub initialization_timestamp: i64,
+    pub pre_update_average_rate: i16,
+    pub last_update_timestamp: i64,
+    pub current_rate: i16,
+}
+
+impl Tk22Extension for InterestBearingConfig {
+    const EXTENSION_TYPE: u16 = 10;
+}
+
+/// A mint extension initialized by [`Mint2022Account`] through [`InitMint::extensions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tk22ExtensionInit {
+    /// Initializes a [`TransferFeeConfig`]. See [`InitializeTransferFeeConfig`].
+    TransferFeeConfig {
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    /// Initializes an [`InterestBearingConfig`]. See [`InitializeInterestBearingConfig`].
+    InterestBearingConfig {
+        rate_authority: Option<Pubkey>,
+        rate: i16,
+    },
+}
+
+impl Tk22ExtensionInit {
+    /// The [`Tk22Extension::EXTENSION_TYPE`] of the initialized extension.
+    #[must_use]
+    pub fn extension_type(&self) -> u16 {
+        match self {
+            Self::TransferFeeConfig { .. } => TransferFeeConfig::EXTENSION_TYPE,
+            Self::InterestBearingConfig { .. } => InterestBearingConfig::EXTENSION_TYPE,
+        }
+    }
+
+    /// The size of the initialized extension, excluding its header.
+    #[must_use]
+    pub fn extension_len(&self) -> usize {
+        match self {
+            Self::TransferFeeConfig { .. } => size_of::<TransferFeeConfig>(),
+            Self::InterestBearingConfig { .. } => size_of::<InterestBearingConfig>(),
+        }
+    }
+
+    fn initialize(&self, mint: &AccountInfo, account_seeds: &[&[&[u8]]]) -> Result<()> {
+        match *self {
+            Self::TransferFeeConfig {
+                transfer_fee_config_authority,
+                withdraw_withheld_authority,
+                transfer_fee_basis_points,
+                maximum_fee,
+            } => Token2022::cpi(
+                InitializeTransferFeeConfig {
+                    transfer_fee_config_authority,
+                    withdraw_withheld_authority,
+                    transfer_fee_basis_points,
+                    maximum_fee,
+                },
+                InitializeTransferFeeConfigCpiAccounts { mint: *mint },
+                None,
+            )
+            .invoke_signed(account_seeds)?,
+            Self::InterestBearingConfig {
+                rate_authority,
+                rate,
+            } => Token2022::cpi(
+                InitializeInterestBearingConfig {
+                    rate_authority: OptionalPubkey::new(rate_authority.unwrap_or_default()),
+                    rate,
+                },
+                InitializeInterestBearingConfigCpiAccounts { mint: *mint },
+                None,
+            )
+            .invoke_signed(account_seeds)?,
+        }
+        Ok(())
+    }
+}
+
+/// Finds the range of the value of the extension with `extension_type` in Token-2022 account data.
+fn find_extension(
+    data: &[u8],
+    account_type: AccountType,
+    extension_type: u16,
+) -> Result<Option<Range<usize>>> {
+    if data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(None);
+    }
+    if data[ACCOUNT_TYPE_OFFSET] != account_type as u8 {
+        bail!(
+            ProgramError::InvalidAccountData,
+            "Expected Token-2022 account type {:?}, found {}",
+            account_type,
+            data[ACCOUNT_TYPE_OFFSET]
+        );
+    }
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + TLV_HEADER_LEN <= data.len() {
+        let ty = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let len = usize::from(u16::from_le_bytes([data[offset + 2], data[offset + 3]]));
+        // Uninitialized extensions are only used as padding at the end of the account
+        if ty == 0 {
+            break;
+        }
+        let start = offset + TLV_HEADER_LEN;
+        let end = start + len;
+        if end > data.len() {
+            bail!(
+                ProgramError::InvalidAccountData,
+                "Token-2022 extension {} overflows the account data",
+                ty
+            );
+        }
+        if ty == extension_type {
+            return Ok(Some(start..end));
+        }
+        offset = end;
+    }
+    Ok(None)
+}
+
+fn extension_ref<E: Tk22Extension>(
+    data: Ref<'_, [u8]>,
+    account_type: AccountType,
+) -> Result<Option<Ref<'_, E>>> {
+    let Some(range) = find_extension(&data, account_type, E::EXTENSION_TYPE)? else {
+        return Ok(None);
+    };
+    Ref::try_map(data, |data| {
+        bytemuck::checked::try_from_bytes::<E>(&data[range])
+    })
+    .map(Some)
+    .map_err(|e| e.1.into())
+}
+
+/// A wrapper around `AccountInfo` for a Token-2022 mint, which may have extensions.
+/// It validates the account data on validate and provides the same accessors as [`MintAccount`],
+/// along with [`Mint2022Account::extension`].
+#[derive(AccountSet, Debug, Clone)]
+#[validate(extra_validation = self.validate())]
+#[validate(
+    id = "validate_mint", arg = ValidateMint<'a>, generics = [<'a>],
+    extra_validation = {
+        self.validate()?;
+        self.validate_mint(arg)
+    }
+)]
+pub struct Mint2022Account {
+    #[single_account_set(skip_can_init_account, skip_has_owner_program, skip_has_inner_type)]
+    info: AccountInfo,
+}
+
+impl HasOwnerProgram for Mint2022Account {
+    type OwnerProgram = Token2022;
+}
+
+impl HasInnerType for Mint2022Account {
+    type Inner = Mint2022Account;
+}
+
+impl Mint2022Account {
+    /// Returns the size of a mint account with `extensions`.
+    #[must_use]
+    pub fn space_for(extensions: &[Tk22ExtensionInit]) -> usize {
+        if extensions.is_empty() {
+            return MintAccount::LEN;
+        }
+        let len = ACCOUNT_TYPE_OFFSET
+            + 1
+            + extensions
+                .iter()
+                .map(|extension| TLV_HEADER_LEN + extension.extension_len())
+                .sum::<usize>();
+        if len == MULTISIG_LEN {
+            len + TLV_HEADER_LEN
+        } else {
+            len
+        }
+    }
+
+    #[inline]
+    pub fn validate(&self) -> Result<()> {
+        if self.owner_pubkey() != Token2022::ID {
+            bail!(
+                ProgramError::InvalidAccountOwner,
+                "Mint2022Account owner {} does not match expected Token-2022 program ID {}",
+                self.owner_pubkey(),
+                Token2022::ID
+            );
+        }
+        let data = self.account_data()?;
+        let has_extensions = data.len() > ACCOUNT_TYPE_OFFSET
+            && data[ACCOUNT_TYPE_OFFSET] == AccountType::Mint as u8;
+        if data.len() != MintAccount::LEN && !has_extensions {
+            bail!(
+                ProgramError::InvalidAccountData,
+                "Mint2022Account {} has invalid data length {} or account type",
+                self.pubkey(),
+                data.len()
+            );
+        }
+        drop(data);
+        if !self.data_unchecked()?.is_initialized {
+            bail!(
+                ProgramError::UninitializedAccount,
+                "Mint2022Account {} is not initialized",
+                self.pubkey()
+            );
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn data_unchecked(&self) -> Result<Ref<'_, MintAccountData>> {
+        Ref::try_map(self.account_data()?, |data| {
+            bytemuck::checked::try_from_bytes::<MintAccountData>(
+                &data[..MintAccount::LEN.min(data.len())],
+            )
+        })
+        .map_err(|e| e.1.into())
+    }
+
+    #[inline]
+    pub fn data(&self) -> Result<Ref<'_, MintAccountData>> {
+        if self.is_writable() {
+            self.validate()?;
+        }
+        self.data_unchecked()
+    }
+
+    #[inline]
+    pub fn validate_mint(&self, validate_mint: ValidateMint) -> Result<()> {
+        self.data()?.validate_mint(self.pubkey(), validate_mint)
+    }
+
+    /// Returns the extension `E`, or `None` if the mint does not have it.
+    #[inline]
+    pub fn extension<E: Tk22Extension>(&self) -> Result<Option<Ref<'_, E>>> {
+        if self.is_writable() {
+            self.validate()?;
+        }
+        extension_ref(self.account_data()?, AccountType::Mint)
+    }
+}
+
+impl<'a> CanInitAccount<InitMint<'a>> for Mint2022Account {
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: InitMint<'a>,
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let funder = ctx.get_funder().ok_or_else(|| {
+            error!(
+                ErrorCode::EmptyFunderCache,
+                "Missing tagged `funder` for Mint2022Account `init_account`"
+            )
+        })?;
+        self.init_account::<IF_NEEDED>((arg, funder), account_seeds, ctx)
+    }
+}
+
+/// Creates the mint, initializes [`InitMint::extensions`] in order, then initializes the mint.
+///
+/// If the mint already exists with `IF_NEEDED`, its extensions are not checked.
+impl<Funder> CanInitAccount<(InitMint<'_>, &Funder)> for Mint2022Account
+where
+    Funder: CanFundRent + ?Sized,
+{
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: (InitMint, &Funder),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let (init_mint, funder) = arg;
+        if IF_NEEDED && self.owner_pubkey() == Token2022::ID {
+            self.validate()?;
+            self.validate_mint(init_mint.into())?;
+            return Ok(false);
+        }
+        self.check_writable()?;
+        self.system_create_account(
+            funder,
+            Token2022::ID,
+            Self::space_for(init_mint.extensions),
+            account_seeds,
+            ctx,
+        )?;
+        let account_seeds: &[&[&[u8]]] = match &account_seeds {
+            Some(seeds) => &[seeds],
+            None => &[],
+        };
+        // Extensions must be initialized before the mint
+        for extension in init_mint.extensions {
+            extension.initialize(self.account_info(), account_seeds)?;
+        }
+        Token2022::cpi(
+            InitializeMint2 {
+                decimals: init_mint.decimals,
+                mint_authority: *init_mint.mint_authority,
+                freeze_authority: init_mint.freeze_authority.cloned(),
+            },
+            InitializeMint2CpiAccounts {
+                mint: *self.account_info(),
+            },
+            None,
+        )
+        .invoke_signed(account_seeds)?;
+        Ok(true)
+    }
+}
+
+/// A wrapper around `AccountInfo` for a Token-2022 token account, which may have extensions.
+/// It validates the account data on validate and provides the same accessors as [`TokenAccount`],
+/// along with [`Token2022Account::extension`].
+#[derive(AccountSet, Debug, Clone)]
+#[validate(extra_validation = self.validate())]
+#[validate(
+    id = "validate_token",
+    arg = ValidateToken,
+    generics = [],
+    extra_validation = {
+        self.validate()?;
+        self.validate_token(arg)
+    }
+)]
+pub struct Token2022Account {
+    #[single_account_set(skip_can_init_account, skip_has_owner_program, skip_has_inner_type)]
+    info: AccountInfo,
+}
+
+impl HasOwnerProgram for Token2022Account {
+    type OwnerProgram = Token2022;
+}
+
+impl HasInnerType for Token2022Account {
+    type Inner = Token2022Account;
+}
+
+impl Token2022Account {
+    /// Returns the size of a token account for the mint with `mint_data`, including the account extensions
+    /// required by the mint's [`TransferFeeConfig`].
+    pub fn space_for_mint(mint_data: &[u8]) -> Result<usize> {
+        if find_extension(
+            mint_data,
+            AccountType::Mint,
+            TransferFeeConfig::EXTENSION_TYPE,
+        )?
+        .is_some()
+        {
+            Ok(ACCOUNT_TYPE_OFFSET + 1 + TLV_HEADER_LEN + size_of::<TransferFeeAmount>())
+        } else {
+            Ok(TokenAccount::LEN)
+        }
+    }
+
+    #[inline]
+    pub fn validate(&self) -> Result<()> {
+        if self.owner_pubkey() != Token2022::ID {
+            bail!(
+                ProgramError::InvalidAccountOwner,
+                "Token2022Account owner {} does not match expected Token-2022 program ID {}",
+                self.owner_pubkey(),
+                Token2022::ID
+            );
+        }
+        let data = self.account_data()?;
+        let has_extensions = data.len() > ACCOUNT_TYPE_OFFSET
+            && data[ACCOUNT_TYPE_OFFSET] == AccountType::Account as u8;
+        if data.len() != TokenAccount::LEN && !has_extensions {
+            bail!(
+                ProgramError::InvalidAccountData,
+                "Token2022Account {} has invalid data length {} or account type",
+                self.pubkey(),
+                data.len()
+            );
+        }
+        drop(data);
+        if self.data_unchecked()?.state == AccountState::Uninitialized {
+            bail!(
+                ProgramError::UninitializedAccount,
+                "Token2022Account {} is not initialized",
+                self.pubkey()
+            );
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn data_unchecked(&self) -> Result<Ref<'_, TokenAccountData>> {
+        Ref::try_map(self.account_data()?, |data| {
+            bytemuck::checked::try_from_bytes::<TokenAccountData>(
+                &data[..TokenAccount::LEN.min(data.len())],
+            )
+        })
+        .map_err(|e| e.1.into())
+    }
+
+    #[inline]
+    pub fn data(&self) -> Result<Ref<'_, TokenAccountData>> {
+        if self.is_writable() {
+            self.validate()?;
+        }
+        self.data_unchecked()
+    }
+
+    #[inline]
+    pub fn validate_token(&self, validate_token: ValidateToken) -> Result<()> {
+        self.data()?.validate_token(self.pubkey(), validate_token)
+    }
+
+    /// Returns the extension `E`, or `None` if the token account does not have it.
+    #[inline]
+    pub fn extension<E: Tk22Extension>(&self) -> Result<Option<Ref<'_, E>>> {
+        if self.is_writable() {
+            self.validate()?;
+        }
+        extension_ref(self.account_data()?, AccountType::Account)
+    }
+}
+
+impl<MintInfo> CanInitAccount<InitToken<'_, MintInfo>> for Token2022Account
+where
+    MintInfo: SingleAccountSet,
+{
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: InitToken<MintInfo>,
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        let funder = ctx.get_funder().ok_or_else(|| {
+            error!(
+                ErrorCode::EmptyFunderCache,
+                "Missing tagged `funder` for Token2022Account `init_account`"
+            )
+        })?;
+        self.init_account::<IF_NEEDED>((arg, funder), account_seeds, ctx)
+    }
+}
+
+impl<MintInfo, Funder> CanInitAccount<(InitToken<'_, MintInfo>, &Funder)> for Token2022Account
+where
+    MintInfo: SingleAccountSet,
+    Funder: CanFundRent + ?Sized,
+{
+    fn init_account<const IF_NEEDED: bool>(
+        &mut self,
+        arg: (InitToken<MintInfo>, &Funder),
+        account_seeds: Option<&[&[u8]]>,
+        ctx: &Context,
+    ) -> Result<bool> {
+        if IF_NEEDED && self.owner_pubkey() == Token2022::ID {
+            self.validate()?;
+            self.validate_token(arg.0.into())?;
+            return Ok(false);
+        }
+        self.check_writable()?;
+        let (init_token, funder) = arg;
+        let space = Self::space_for_mint(&init_token.mint.account_data()?)?;
+        self.system_create_account(funder, Token2022::ID, space, account_seeds, ctx)?;
+        let account_seeds: &[&[&[u8]]] = match account_seeds {
+            Some(seeds) => &[seeds],
+            None => &[],
+        };
+        Token2022::cpi(
+            InitializeAccount3 {
+                owner: init_token.owner,
+            },
+            InitializeAccount3CpiAccounts {
+                account: *self.account_info(),
+                mint: *init_token.mint.account_info(),
+            },
+            None,
+        )
+        .invoke_signed(account_seeds)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_extension<E: Tk22Extension>(data: &mut [u8], offset: usize, extension: &E) -> usize {
+        data[offset..offset + 2].copy_from_slice(&E::EXTENSION_TYPE.to_le_bytes());
+        data[offset + 2..offset + 4].copy_from_slice(&(size_of::<E>() as u16).to_le_bytes());
+        let start = offset + TLV_HEADER_LEN;
+        data[start..start + size_of::<E>()].copy_from_slice(bytemuck::bytes_of(extension));
+        start + size_of::<E>()
+    }
+
+    #[test]
+    fn test_extension_sizes() {
+        // See `spl_token_2022::extension::ExtensionType::try_calculate_account_len`
+        assert_eq!(size_of::<TransferFeeConfig>(), 108);
+        assert_eq!(size_of::<InterestBearingConfig>(), 52);
+        assert_eq!(size_of::<TransferFeeAmount>(), 8);
+
+        let transfer_fee = Tk22ExtensionInit::TransferFeeConfig {
+            transfer_fee_config_authority: None,
+            withdraw_withheld_authority: None,
+            transfer_fee_basis_points: 10,
+            maximum_fee: 100,
+        };
+        let interest_bearing = Tk22ExtensionInit::InterestBearingConfig {
+            rate_authority: None,
+            rate: 5,
+        };
+        assert_eq!(Mint2022Account::space_for(&[]), MintAccount::LEN);
+        assert_eq!(Mint2022Account::space_for(&[transfer_fee]), 278);
+        assert_eq!(
+            Mint2022Account::space_for(&[transfer_fee, interest_bearing]),
+            334
+        );
+    }
+
+    #[test]
+    fn test_find_extension() -> Result<()> {
+        let rate_authority = Pubkey::new_unique();
+        let transfer_fee_config = TransferFeeConfig {
+            withheld_amount: 42,
+            ..Default::default()
+        };
+        let interest_bearing_config = InterestBearingConfig {
+            rate_authority: OptionalPubkey::new(rate_authority),
+            current_rate: 7,
+            ..Default::default()
+        };
+        let mut data = vec![0; 400];
+        data[ACCOUNT_TYPE_OFFSET] = AccountType::Mint as u8;
+        let offset = write_extension(&mut data, ACCOUNT_TYPE_OFFSET + 1, &transfer_fee_config);
+        write_extension(&mut data, offset, &interest_bearing_config);
+
+        let range = find_extension(
+            &data,
+            AccountType::Mint,
+            InterestBearingConfig::EXTENSION_TYPE,
+        )?
+        .expect("Interest bearing config exists");
+        let found: &InterestBearingConfig = bytemuck::from_bytes(&data[range]);
+        assert_eq!(found, &interest_bearing_config);
+        assert_eq!(found.rate_authority.pubkey(), Some(&rate_authority));
+
+        let range = find_extension(&data, AccountType::Mint, TransferFeeConfig::EXTENSION_TYPE)?
+            .expect("Transfer fee config exists");
+        let found: &TransferFeeConfig = bytemuck::from_bytes(&data[range]);
+        assert_eq!({ found.withheld_amount }, 42);
+
+        assert_eq!(
+            find_extension(&data, AccountType::Mint, TransferFeeAmount::EXTENSION_TYPE)?,
+            None
+        );
+        assert!(find_extension(&data, AccountType::Account, 1).is_err());
+        assert_eq!(
+            find_extension(&[0; MintAccount::LEN], AccountType::Mint, 1)?,
+            None
+        );
+
+        assert_eq!(Token2022Account::space_for_mint(&data)?, 178);
+        assert_eq!(
+            Token2022Account::space_for_mint(&[0; MintAccount::LEN])?,
+            TokenAccount::LEN
+        );
+        Ok(())
+    }
+}